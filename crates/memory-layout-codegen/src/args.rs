@@ -0,0 +1,562 @@
+use proc_macro2::{TokenStream, TokenTree};
+use quote::ToTokens;
+use syn::{
+  parse::{Parse, ParseStream},
+  Error as SynError, Expr, ExprLit, Ident, Lit, LitInt, LitStr, Result as SynResult, Token, Type
+};
+
+use crate::{
+  dependency_crate,
+  offset::{FieldOffset, Offset, PointerWidthValue, VersionedValue}
+};
+
+mod kw {
+  syn::custom_keyword!(strict);
+  syn::custom_keyword!(builder);
+  syn::custom_keyword!(sparse);
+  syn::custom_keyword!(sort);
+  syn::custom_keyword!(verify);
+  syn::custom_keyword!(volatile);
+  syn::custom_keyword!(debug);
+  syn::custom_keyword!(eq);
+  syn::custom_keyword!(hash);
+  syn::custom_keyword!(default);
+  syn::custom_keyword!(zerocopy);
+  syn::custom_keyword!(serde);
+  syn::custom_keyword!(size);
+  syn::custom_keyword!(align);
+  syn::custom_keyword!(base);
+  syn::custom_keyword!(name);
+  syn::custom_keyword!(offset);
+  syn::custom_keyword!(ty);
+  syn::custom_keyword!(ro);
+  syn::custom_keyword!(wo);
+  syn::custom_keyword!(rw);
+  syn::custom_keyword!(big);
+  syn::custom_keyword!(little);
+  syn::custom_keyword!(versions);
+  syn::custom_keyword!(cfg);
+  syn::custom_keyword!(rip_relative);
+  syn::custom_keyword!(operand);
+  syn::custom_keyword!(reflect);
+  syn::custom_keyword!(pad);
+  syn::custom_keyword!(uninit);
+  syn::custom_keyword!(zeroed);
+  syn::custom_keyword!(pad_fill);
+  syn::custom_keyword!(deny_gaps);
+  syn::custom_keyword!(round_to);
+}
+
+/// Arguments of the `memory_layout` attribute.
+#[derive(Clone, Default)]
+pub struct LayoutArgs {
+  /// Desired size of the struct, either a literal or a constant expression.
+  pub size:               Option<Offset>,
+  /// Whether trailing padding has to be covered by an explicit field.
+  pub strict:             bool,
+  /// Whether every byte between fields has to be covered by an explicit field, as well as trailing
+  /// padding.
+  pub deny_gaps:          bool,
+  /// Whether a type-state builder should be generated.
+  pub builder:            bool,
+  /// Whether padding should be left uninitialized, through `sparse` or `pad = uninit`.
+  pub sparse:             bool,
+  /// Byte padding is filled with by constructors and `to_bytes`, rather than zero.
+  pub pad_fill:           Option<u8>,
+  /// Whether fields should be ordered by their offset rather than their declaration.
+  pub sort:               bool,
+  /// Whether the natural `repr(C)` layout should only be checked against the offsets, without
+  /// inserting padding.
+  pub verify:             bool,
+  /// Alignment of the struct, in which case it's `repr(C, align(n))` rather than packed.
+  pub align:              Option<usize>,
+  /// Whether every field is accessed with volatile reads and writes.
+  pub volatile:           bool,
+  /// Whether a `Debug` implementation reading fields through their getters should be generated.
+  pub debug:              bool,
+  /// Whether `PartialEq` and `Eq` implementations ignoring padding should be generated.
+  pub eq:                 bool,
+  /// Whether a `Hash` implementation ignoring padding should be generated.
+  pub hash:               bool,
+  /// Whether a `FIELDS` table describing every field and its type should be generated.
+  pub reflect:            bool,
+  /// Whether a `Default` implementation zeroing padding should be generated.
+  pub default:            bool,
+  /// Whether zerocopy's traits should be derived.
+  pub zerocopy:           bool,
+  /// Whether serde's traits should be implemented.
+  pub serde:              bool,
+  /// Type embedded at offset 0, which the struct dereferences to.
+  pub base:               Option<Type>,
+  /// Desired size depending on the pointer width, `x86 = 0x40, x64 = 0x80`.
+  pub pointer_width_size: Option<PointerWidthValue>,
+  /// Boundary the end of the last field is rounded up to for the size, `round_to = 0x10`.
+  pub round_to:           Option<usize>,
+  /// Versions of the target with their own offsets, `versions(v1_58, v1_63)`.
+  pub versions:           Vec<Version>,
+  /// Desired size per version, `size(v1_58 = 0x200, v1_63 = 0x210)`.
+  pub versioned_size:     Option<VersionedValue>
+}
+
+/// A version of the target, selected by a cargo feature of the same name or by a `cfg` predicate.
+#[derive(Clone)]
+pub struct Version {
+  pub name: Ident,
+  pub cfg:  TokenStream
+}
+
+impl Parse for Version {
+  fn parse(input: ParseStream) -> SynResult<Self> {
+    let name = input.parse::<Ident>()?;
+    let cfg = if input.peek(Token![=]) {
+      input.parse::<Token![=]>()?;
+      input.parse::<kw::cfg>()?;
+      let content;
+      syn::parenthesized!(content in input);
+      content.parse::<TokenStream>()?
+    } else {
+      let feature = LitStr::new(&name.to_string(), name.span());
+      quote::quote!(feature = #feature)
+    };
+    Ok(Version { name, cfg })
+  }
+}
+
+impl Parse for LayoutArgs {
+  fn parse(input: ParseStream) -> SynResult<Self> {
+    let mut args = LayoutArgs::default();
+
+    while !input.is_empty() {
+      if input.peek(kw::strict) && is_last(input) {
+        input.parse::<kw::strict>()?;
+        args.strict = true;
+      } else if input.peek(kw::deny_gaps) && is_last(input) {
+        input.parse::<kw::deny_gaps>()?;
+        args.deny_gaps = true;
+      } else if input.peek(kw::builder) && is_last(input) {
+        input.parse::<kw::builder>()?;
+        args.builder = true;
+      } else if input.peek(kw::sparse) && is_last(input) {
+        input.parse::<kw::sparse>()?;
+        args.sparse = true;
+      } else if input.peek(kw::sort) && is_last(input) {
+        input.parse::<kw::sort>()?;
+        args.sort = true;
+      } else if input.peek(kw::verify) && is_last(input) {
+        input.parse::<kw::verify>()?;
+        args.verify = true;
+      } else if input.peek(kw::debug) && is_last(input) {
+        input.parse::<kw::debug>()?;
+        args.debug = true;
+      } else if input.peek(kw::eq) && is_last(input) {
+        input.parse::<kw::eq>()?;
+        args.eq = true;
+      } else if input.peek(kw::hash) && is_last(input) {
+        input.parse::<kw::hash>()?;
+        args.hash = true;
+      } else if input.peek(kw::reflect) && is_last(input) {
+        input.parse::<kw::reflect>()?;
+        args.reflect = true;
+      } else if input.peek(kw::default) && is_last(input) {
+        input.parse::<kw::default>()?;
+        args.default = true;
+      } else if input.peek(kw::zerocopy) && is_last(input) {
+        let keyword = input.parse::<kw::zerocopy>()?;
+        if !cfg!(feature = "zerocopy") {
+          return Err(SynError::new(
+            keyword.span,
+            "zerocopy requires the `zerocopy` feature of memory-layout."
+          ));
+        }
+        if dependency_crate("zerocopy").is_none() {
+          return Err(SynError::new(
+            keyword.span,
+            "zerocopy requires a dependency on the `zerocopy` crate with its `derive` feature."
+          ));
+        }
+        args.zerocopy = true;
+      } else if input.peek(kw::serde) && is_last(input) {
+        let keyword = input.parse::<kw::serde>()?;
+        if !cfg!(feature = "serde") {
+          return Err(SynError::new(
+            keyword.span,
+            "serde requires the `serde` feature of memory-layout."
+          ));
+        }
+        if dependency_crate("serde").is_none() {
+          return Err(SynError::new(
+            keyword.span,
+            "serde requires a dependency on the `serde` crate with its `derive` feature."
+          ));
+        }
+        args.serde = true;
+      } else if input.peek(kw::volatile) && is_last(input) {
+        input.parse::<kw::volatile>()?;
+        args.volatile = true;
+      } else if input.peek(kw::pad) && input.peek2(Token![=]) {
+        input.parse::<kw::pad>()?;
+        input.parse::<Token![=]>()?;
+        let lookahead = input.lookahead1();
+        if lookahead.peek(kw::uninit) {
+          input.parse::<kw::uninit>()?;
+          args.sparse = true;
+        } else if lookahead.peek(kw::zeroed) {
+          input.parse::<kw::zeroed>()?;
+        } else {
+          return Err(lookahead.error());
+        }
+      } else if input.peek(kw::pad_fill) && input.peek2(Token![=]) {
+        let span = input.span();
+        input.parse::<kw::pad_fill>()?;
+        input.parse::<Token![=]>()?;
+        let lit = input.parse::<LitInt>()?;
+        let fill = lit
+          .base10_parse::<u8>()
+          .map_err(|_| SynError::new(lit.span(), "Padding fill must be a byte."))?;
+        if args.pad_fill.is_some() {
+          return Err(SynError::new(span, "Padding fill is specified twice."));
+        }
+        args.pad_fill = Some(fill);
+      } else if input.peek(kw::round_to) && input.peek2(Token![=]) {
+        let span = input.span();
+        input.parse::<kw::round_to>()?;
+        input.parse::<Token![=]>()?;
+        let lit = input.parse::<LitInt>()?;
+        let boundary = lit
+          .base10_parse::<usize>()
+          .ok()
+          .filter(|boundary| *boundary > 0)
+          .ok_or_else(|| SynError::new(lit.span(), "Rounding boundary must not be zero."))?;
+        if args.round_to.is_some() {
+          return Err(SynError::new(span, "Rounding boundary is specified twice."));
+        }
+        args.round_to = Some(boundary);
+      } else if input.peek(kw::align) && input.peek2(Token![=]) {
+        let span = input.span();
+        input.parse::<kw::align>()?;
+        input.parse::<Token![=]>()?;
+        let lit = input.parse::<LitInt>()?;
+        let align = lit
+          .base10_parse::<usize>()
+          .ok()
+          .filter(|align| align.is_power_of_two())
+          .ok_or_else(|| SynError::new(lit.span(), "Alignment must be a power of two."))?;
+        if args.align.is_some() {
+          return Err(SynError::new(span, "Alignment is specified twice."));
+        }
+        args.align = Some(align);
+      } else if PointerWidthValue::peek(input) {
+        let span = input.span();
+        let size = input.parse::<PointerWidthValue>()?;
+        if args.size.is_some() || args.pointer_width_size.is_some() || args.versioned_size.is_some()
+        {
+          return Err(SynError::new(span, "Desired size is specified twice."));
+        }
+        args.pointer_width_size = Some(size);
+      } else if input.peek(kw::versions) && input.peek2(syn::token::Paren) {
+        let span = input.span();
+        input.parse::<kw::versions>()?;
+        let content;
+        syn::parenthesized!(content in input);
+        let versions = content.parse_terminated(Version::parse, Token![,])?;
+        if !args.versions.is_empty() {
+          return Err(SynError::new(span, "Versions are specified twice."));
+        }
+        if versions.is_empty() {
+          return Err(SynError::new(span, "At least one version is required."));
+        }
+        for (i, version) in versions.iter().enumerate() {
+          if versions
+            .iter()
+            .take(i)
+            .any(|other| other.name == version.name)
+          {
+            return Err(SynError::new(
+              version.name.span(),
+              "Version is specified twice."
+            ));
+          }
+        }
+        args.versions = versions.into_iter().collect();
+      } else if input.peek(kw::size) && input.peek2(syn::token::Paren) {
+        let span = input.span();
+        input.parse::<kw::size>()?;
+        let content;
+        syn::parenthesized!(content in input);
+        let size = content.parse::<VersionedValue>()?;
+        if !content.is_empty() {
+          return Err(content.error("Expected a version selector."));
+        }
+        if args.size.is_some() || args.pointer_width_size.is_some() || args.versioned_size.is_some()
+        {
+          return Err(SynError::new(span, "Desired size is specified twice."));
+        }
+        args.versioned_size = Some(size);
+      } else if input.peek(kw::base) && input.peek2(Token![=]) {
+        let span = input.span();
+        input.parse::<kw::base>()?;
+        input.parse::<Token![=]>()?;
+        if args.base.is_some() {
+          return Err(SynError::new(span, "Base is specified twice."));
+        }
+        args.base = Some(input.parse::<Type>()?);
+      } else {
+        let span = input.span();
+        if input.peek(kw::size) && input.peek2(Token![=]) {
+          input.parse::<kw::size>()?;
+          input.parse::<Token![=]>()?;
+        }
+        let size = parse_size(input)?;
+        if args.size.is_some() || args.pointer_width_size.is_some() || args.versioned_size.is_some()
+        {
+          return Err(SynError::new(span, "Desired size is specified twice."));
+        }
+        args.size = Some(size);
+      }
+
+      if !input.is_empty() {
+        input.parse::<Token![,]>()?;
+      }
+    }
+
+    Ok(args)
+  }
+}
+
+/// Whether the next token is the last one of the current argument.
+fn is_last(input: ParseStream) -> bool {
+  let fork = input.fork();
+  fork.parse::<TokenTree>().is_ok() && (fork.is_empty() || fork.peek(Token![,]))
+}
+
+/// Parses a size given as an integer literal or a constant expression. The expression is parsed as
+/// a whole, so commas inside it, such as in `size_of::<HashMap<K, V>>()`, don't end the argument.
+fn parse_size(input: ParseStream) -> SynResult<Offset> {
+  if input.is_empty() || input.peek(Token![,]) {
+    return Err(input.error(
+      "Expected a size, `align`, `base`, `pad`, `pad_fill`, `round_to`, `strict`, `deny_gaps`, \
+       `builder`, `sparse`, `sort`, `verify`, `volatile`, `debug`, `eq`, `hash`, `default`, \
+       `reflect`, `zerocopy`, `serde` or `versions`."
+    ));
+  }
+
+  match input.parse::<Expr>()? {
+    Expr::Lit(ExprLit {
+      lit: Lit::Int(lit), ..
+    }) => {
+      let size = lit
+        .base10_parse::<usize>()
+        .map_err(|_| SynError::new(lit.span(), "Desired size must be a valid usize"))?;
+      Ok(Offset::Known(size))
+    }
+    expr => Ok(Offset::Expr(expr.into_token_stream()))
+  }
+}
+
+/// Arguments of the `accessor` field attribute.
+pub struct AccessorArgs {
+  /// Name used for the getter, the setter is named `set_<name>`.
+  pub name: Ident
+}
+
+impl Parse for AccessorArgs {
+  fn parse(input: ParseStream) -> SynResult<Self> {
+    input.parse::<kw::name>()?;
+    input.parse::<Token![=]>()?;
+    let lit = input.parse::<LitStr>()?;
+    let name = lit
+      .parse::<Ident>()
+      .map_err(|_| SynError::new(lit.span(), "Accessor name must be a valid identifier."))?;
+    Ok(AccessorArgs { name })
+  }
+}
+
+/// Access mode given through the `access` field attribute, `#[access(ro)]`.
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum Access {
+  /// Getters and setters are generated.
+  #[default]
+  ReadWrite,
+  /// Only getters are generated.
+  ReadOnly,
+  /// Only setters are generated.
+  WriteOnly
+}
+
+impl Access {
+  pub fn readable(self) -> bool {
+    self != Access::WriteOnly
+  }
+
+  pub fn writable(self) -> bool {
+    self != Access::ReadOnly
+  }
+}
+
+impl Parse for Access {
+  fn parse(input: ParseStream) -> SynResult<Self> {
+    let lookahead = input.lookahead1();
+    if lookahead.peek(kw::ro) {
+      input.parse::<kw::ro>()?;
+      Ok(Access::ReadOnly)
+    } else if lookahead.peek(kw::wo) {
+      input.parse::<kw::wo>()?;
+      Ok(Access::WriteOnly)
+    } else if lookahead.peek(kw::rw) {
+      input.parse::<kw::rw>()?;
+      Ok(Access::ReadWrite)
+    } else {
+      Err(lookahead.error())
+    }
+  }
+}
+
+/// Arguments of the `nested` field attribute, `#[nested(size = 0x40)]`.
+#[derive(Default)]
+pub struct NestedArgs {
+  /// Size the nested struct is expected to have, either a literal or a constant expression.
+  pub size: Option<Offset>
+}
+
+impl Parse for NestedArgs {
+  fn parse(input: ParseStream) -> SynResult<Self> {
+    input.parse::<kw::size>()?;
+    input.parse::<Token![=]>()?;
+    let tokens = input.parse::<TokenStream>()?;
+    if let Ok(lit) = syn::parse2::<LitInt>(tokens.clone()) {
+      return Ok(NestedArgs {
+        size: Some(Offset::Known(lit.base10_parse()?))
+      });
+    }
+    if tokens.is_empty() {
+      return Err(input.error("Expected a size."));
+    }
+    Ok(NestedArgs {
+      size: Some(Offset::Expr(tokens))
+    })
+  }
+}
+
+/// Arguments of the `signature` field attribute, `#[signature("8B 81 ?? ?? ?? ??", operand = 2)]`.
+pub struct SignatureArgs {
+  pub pattern:      LitStr,
+  /// Position of the operand in the pattern, the first wildcard if not given.
+  pub operand:      Option<LitInt>,
+  pub rip_relative: bool
+}
+
+impl Parse for SignatureArgs {
+  fn parse(input: ParseStream) -> SynResult<Self> {
+    let pattern = input.parse::<LitStr>()?;
+    let value = pattern.value();
+    let tokens = value.split_ascii_whitespace().collect::<Vec<_>>();
+    if tokens.is_empty()
+      || tokens.iter().any(|token| {
+        !matches!(*token, "?" | "??")
+          && (token.len() != 2 || !token.bytes().all(|byte| byte.is_ascii_hexdigit()))
+      })
+    {
+      return Err(SynError::new(
+        pattern.span(),
+        "Signatures are hex bytes and `??` wildcards separated by spaces."
+      ));
+    }
+
+    let mut args = SignatureArgs {
+      pattern,
+      operand: None,
+      rip_relative: false
+    };
+    while !input.is_empty() {
+      input.parse::<Token![,]>()?;
+      if input.is_empty() {
+        break;
+      }
+
+      let lookahead = input.lookahead1();
+      if lookahead.peek(kw::rip_relative) {
+        input.parse::<kw::rip_relative>()?;
+        args.rip_relative = true;
+      } else if lookahead.peek(kw::operand) {
+        input.parse::<kw::operand>()?;
+        input.parse::<Token![=]>()?;
+        args.operand = Some(input.parse()?);
+      } else {
+        return Err(lookahead.error());
+      }
+    }
+
+    Ok(args)
+  }
+}
+
+/// Byte order given through the `endian` field attribute, `#[endian(big)]`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Endian {
+  Big,
+  Little
+}
+
+impl Parse for Endian {
+  fn parse(input: ParseStream) -> SynResult<Self> {
+    let lookahead = input.lookahead1();
+    if lookahead.peek(kw::big) {
+      input.parse::<kw::big>()?;
+      Ok(Endian::Big)
+    } else if lookahead.peek(kw::little) {
+      input.parse::<kw::little>()?;
+      Ok(Endian::Little)
+    } else {
+      Err(lookahead.error())
+    }
+  }
+}
+
+/// Arguments of the `discriminant` attribute on enums.
+pub struct DiscriminantArgs {
+  /// Offset of the discriminant.
+  pub offset: FieldOffset,
+  /// Integer type the discriminant is stored as.
+  pub ty:     Type
+}
+
+impl Parse for DiscriminantArgs {
+  fn parse(input: ParseStream) -> SynResult<Self> {
+    input.parse::<kw::offset>()?;
+    input.parse::<Token![=]>()?;
+    let offset = input.parse::<FieldOffset>()?;
+    input.parse::<Token![,]>()?;
+    input.parse::<kw::ty>()?;
+    input.parse::<Token![=]>()?;
+    let ty = input.parse::<Type>()?;
+    if input.peek(Token![,]) {
+      input.parse::<Token![,]>()?;
+    }
+    Ok(DiscriminantArgs { offset, ty })
+  }
+}
+
+/// Arguments of the `bit_offset` field attribute, `#[bit_offset(byte, bit, width)]`.
+pub struct BitOffsetArgs {
+  /// Offset of the backing integer in bytes.
+  pub byte:  usize,
+  /// Index of the lowest bit within the backing integer.
+  pub bit:   usize,
+  /// Number of bits.
+  pub width: usize
+}
+
+impl Parse for BitOffsetArgs {
+  fn parse(input: ParseStream) -> SynResult<Self> {
+    let byte = input.parse::<LitInt>()?.base10_parse()?;
+    input.parse::<Token![,]>()?;
+    let bit = input.parse::<LitInt>()?.base10_parse()?;
+    input.parse::<Token![,]>()?;
+    let lit = input.parse::<LitInt>()?;
+    let width = lit.base10_parse()?;
+    if width == 0 {
+      return Err(SynError::new(lit.span(), "Bit width must be at least 1."));
+    }
+    Ok(BitOffsetArgs { byte, bit, width })
+  }
+}
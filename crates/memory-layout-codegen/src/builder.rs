@@ -0,0 +1,184 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{parse_quote, GenericParam, Generics};
+
+use crate::{runtime_crate, setter_ident, FieldKind, StructInfo};
+
+/// Generic arguments referring to each of the parameters, in declaration order.
+fn generic_arguments(generics: &Generics) -> Vec<TokenStream> {
+  generics
+    .params
+    .iter()
+    .map(|param| {
+      match param {
+        GenericParam::Lifetime(param) => {
+          let lifetime = &param.lifetime;
+          quote!(#lifetime)
+        }
+        GenericParam::Type(param) => {
+          let ident = &param.ident;
+          quote!(#ident)
+        }
+        GenericParam::Const(param) => {
+          let ident = &param.ident;
+          quote!(#ident)
+        }
+      }
+    })
+    .collect()
+}
+
+/// Generates `<Name>Builder`, which tracks which fields have been set in its type so that
+/// `build()` is only available once every field is set.
+pub fn generate_builder(struct_info: &StructInfo) -> TokenStream {
+  let name = &struct_info.derived.ident;
+  let vis = &struct_info.derived.vis;
+  let builder = format_ident!("{}Builder", name);
+
+  // Fields that depend on a `cfg` aren't tracked, leaving them zeroed unless they're set. `build()`
+  // requires them to be `Zeroable` for that reason.
+  let fields = struct_info
+    .fields
+    .iter()
+    .filter(|field| matches!(field.kind, FieldKind::Data) && field.cfg.is_none())
+    .collect::<Vec<_>>();
+  let optional_fields = struct_info
+    .fields
+    .iter()
+    .filter(|field| matches!(field.kind, FieldKind::Data) && field.cfg.is_some())
+    .collect::<Vec<_>>();
+  let states = (0..fields.len())
+    .map(|i| format_ident!("__SET{}", i))
+    .collect::<Vec<_>>();
+
+  let mut builder_generics = struct_info.derived.generics.clone();
+  for state in &states {
+    builder_generics
+      .params
+      .push(parse_quote!(const #state: bool = false));
+  }
+  let mut impl_generics = struct_info.derived.generics.clone();
+  for state in &states {
+    impl_generics.params.push(parse_quote!(const #state: bool));
+  }
+  let (struct_impl_generics, struct_ty_generics, where_clause) =
+    struct_info.derived.generics.split_for_impl();
+  let (impl_generics, ..) = impl_generics.split_for_impl();
+
+  let arguments = generic_arguments(&struct_info.derived.generics);
+  let unset = states.iter().map(|_| quote!(false)).collect::<Vec<_>>();
+  let set = states.iter().map(|_| quote!(true)).collect::<Vec<_>>();
+
+  let setters = fields.iter().enumerate().map(|(i, field)| {
+    let ident = field.field.ident.as_ref().unwrap();
+    let setter = setter_ident(field.accessor_ident());
+    let ty = &field.field.ty;
+    let field_vis = &field.field.vis;
+    let next_states =
+      states.iter().enumerate().map(
+        |(j, state)| {
+          if i == j {
+            quote!(true)
+          } else {
+            quote!(#state)
+          }
+        }
+      );
+    let doc = format!("Sets `{}`.", ident);
+    let value = field.stored_value(quote!(value));
+    quote! {
+      #[doc = #doc]
+      #[inline]
+      #field_vis fn #setter(mut self, value: #ty) -> #builder<#(#arguments,)* #(#next_states),*> {
+        unsafe {
+          ::core::ptr::addr_of_mut!((*self.inner.as_mut_ptr()).#ident).write_unaligned(#value)
+        }
+        #builder { inner: self.inner }
+      }
+    }
+  });
+
+  let optional_setters = optional_fields.iter().map(|field| {
+    let ident = field.field.ident.as_ref().unwrap();
+    let setter = setter_ident(field.accessor_ident());
+    let ty = &field.field.ty;
+    let field_vis = &field.field.vis;
+    let cfg = field.cfg_attr();
+    let doc = format!("Sets `{}`.", ident);
+    let value = field.stored_value(quote!(value));
+    quote! {
+      #cfg
+      #[doc = #doc]
+      #[inline]
+      #field_vis fn #setter(mut self, value: #ty) -> Self {
+        unsafe {
+          ::core::ptr::addr_of_mut!((*self.inner.as_mut_ptr()).#ident).write_unaligned(#value)
+        }
+        self
+      }
+    }
+  });
+
+  let runtime = runtime_crate();
+  let zeroable_checks = optional_fields.iter().map(|field| {
+    let ty = &field.field.ty;
+    let cfg = field.cfg_attr();
+    quote! {
+      #cfg
+      {
+        fn zeroable<T: ?::core::marker::Sized + #runtime::Zeroable>() {}
+        zeroable::<#ty>();
+      }
+    }
+  });
+
+  let init = if struct_info.args.sparse && optional_fields.is_empty() {
+    quote!(::core::mem::MaybeUninit::uninit())
+  } else {
+    quote!(::core::mem::MaybeUninit::zeroed())
+  };
+
+  let init = match struct_info.fill_padding(quote!(inner.as_mut_ptr())) {
+    Some(fill) => {
+      quote! {{
+        let mut inner: ::core::mem::MaybeUninit<Self> = #init;
+        #fill
+        inner
+      }}
+    }
+    None => init
+  };
+
+  let doc = format!(
+    "Builder for [`{}`], `build()` becomes available once every field has been set.",
+    name
+  );
+
+  quote! {
+    #[doc = #doc]
+    #vis struct #builder #builder_generics #where_clause {
+      inner: ::core::mem::MaybeUninit<#name #struct_ty_generics>
+    }
+
+    impl #struct_impl_generics #name #struct_ty_generics #where_clause {
+      /// Creates a builder with every field unset and all padding zeroed, unless the struct is
+      /// sparse or sets `pad_fill`.
+      pub fn builder() -> #builder<#(#arguments,)* #(#unset),*> {
+        #builder { inner: #init }
+      }
+    }
+
+    impl #impl_generics #builder<#(#arguments,)* #(#states),*> #where_clause {
+      #(#setters)*
+      #(#optional_setters)*
+    }
+
+    impl #struct_impl_generics #builder<#(#arguments,)* #(#set),*> #where_clause {
+      /// Builds the struct, fields that depend on a `cfg` are left zeroed unless they were set.
+      pub fn build(self) -> #name #struct_ty_generics {
+        #(#zeroable_checks)*
+        unsafe { self.inner.assume_init() }
+      }
+    }
+  }
+}
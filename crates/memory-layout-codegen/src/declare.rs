@@ -0,0 +1,144 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+  parse::{Parse, ParseStream},
+  Attribute, DeriveInput, Error as SynError, FieldsNamed, Meta, Result as SynResult, Token, Type,
+  Visibility
+};
+
+use crate::{args::LayoutArgs, check_message, expand_attribute};
+
+/// Input of `declare_layout!`, a struct describing the layout of a `target` type.
+pub struct DeclareLayout {
+  attrs:  Vec<Attribute>,
+  vis:    Visibility,
+  ident:  syn::Ident,
+  target: Type,
+  fields: FieldsNamed
+}
+
+impl Parse for DeclareLayout {
+  fn parse(input: ParseStream) -> SynResult<Self> {
+    let attrs = input.call(Attribute::parse_outer)?;
+    let vis = input.parse()?;
+    input.parse::<Token![struct]>()?;
+    let ident = input.parse()?;
+    input.parse::<Token![for]>()?;
+    let target = input.parse()?;
+    let fields = input.parse()?;
+    Ok(DeclareLayout {
+      attrs,
+      vis,
+      ident,
+      target,
+      fields
+    })
+  }
+}
+
+/// Whether `ty` is known to be unsized, a slice, `str` or a trait object, whose size can't be
+/// compared against the declared layout.
+fn is_unsized(ty: &Type) -> bool {
+  match ty {
+    Type::Slice(_) | Type::TraitObject(_) => true,
+    Type::Path(path) => path.qself.is_none() && path.path.is_ident("str"),
+    Type::Paren(paren) => is_unsized(&paren.elem),
+    Type::Group(group) => is_unsized(&group.elem),
+    _ => false
+  }
+}
+
+/// Expands the struct as if it was annotated with its `memory_layout` attribute, and adds casts
+/// from the target type to it.
+pub fn generate_declared_layout(input: DeclareLayout) -> SynResult<TokenStream> {
+  let DeclareLayout {
+    mut attrs,
+    vis,
+    ident,
+    target,
+    fields
+  } = input;
+  let args = match attrs
+    .iter()
+    .position(|attr| attr.path().is_ident("memory_layout"))
+  {
+    Some(index) => {
+      match attrs.remove(index).meta {
+        Meta::Path(_) => LayoutArgs::default(),
+        Meta::List(list) => list.parse_args()?,
+        meta => {
+          return Err(SynError::new_spanned(
+            meta,
+            "Expected `#[memory_layout(..)]`."
+          ))
+        }
+      }
+    }
+    None => LayoutArgs::default()
+  };
+
+  let derive_input: DeriveInput = syn::parse_quote! {
+    #(#attrs)*
+    #vis struct #ident #fields
+  };
+  let layout = expand_attribute(derive_input, args)?;
+  let target_name = quote!(#target).to_string();
+  let ref_doc = format!(
+    "Views a `{}` through this layout.\n\n# Safety\n\nThe memory of `value` has to be laid out \
+     as declared, spanning at least `size_of::<Self>()` bytes.",
+    target_name
+  );
+  let mut_doc = format!(
+    "Views a `{}` mutably through this layout.\n\n# Safety\n\nThe memory of `value` has to be \
+     laid out as declared, spanning at least `size_of::<Self>()` bytes, and writing the fields \
+     must keep it a valid `{}`.",
+    target_name, target_name
+  );
+  let size_check = (!is_unsized(&target)).then(|| {
+    let message = check_message(format!(
+      "The layout `{}` is larger than `{}`, which it describes.",
+      ident, target_name
+    ));
+    quote! {
+      const _: () = assert!(
+        ::core::mem::size_of::<#ident>() <= ::core::mem::size_of::<#target>(),
+        #message
+      );
+    }
+  });
+  let ptr_doc = format!(
+    "Casts a pointer to a `{}` to this layout, for reading it through the generated functions.",
+    target_name
+  );
+
+  Ok(quote! {
+    #layout
+    #size_check
+
+    impl #ident {
+      #[doc = #ref_doc]
+      #[inline]
+      pub unsafe fn from_target(value: &#target) -> &Self {
+        &*(value as *const #target as *const Self)
+      }
+
+      #[doc = #mut_doc]
+      #[inline]
+      pub unsafe fn from_target_mut(value: &mut #target) -> &mut Self {
+        &mut *(value as *mut #target as *mut Self)
+      }
+
+      #[doc = #ptr_doc]
+      #[inline]
+      pub fn from_target_ptr(ptr: *const #target) -> *const Self {
+        ptr as *const Self
+      }
+
+      #[doc = #ptr_doc]
+      #[inline]
+      pub fn from_target_ptr_mut(ptr: *mut #target) -> *mut Self {
+        ptr as *mut Self
+      }
+    }
+  })
+}
@@ -0,0 +1,167 @@
+use proc_macro2::{Span, TokenStream, TokenTree};
+use quote::ToTokens;
+use syn::{
+  GenericArgument, GenericParam, Generics, Ident, Lifetime, PathArguments, TraitBoundModifier,
+  Type, TypeParamBound, WherePredicate
+};
+
+/// Rewrites `ty` so it can be named in constants, where the generic parameters of the struct
+/// aren't available, without changing its size or alignment.
+///
+/// Lifetimes become `'static`, and type parameters behind pointers, references, `PhantomData` and
+/// `NonNull` become `()`, as long as they're `Sized`. Any other use of a type or const parameter
+/// decides the size of the type, that parameter is returned as error.
+pub fn const_type(ty: &Type, generics: &Generics) -> Result<Type, Ident> {
+  let mut ty = ty.clone();
+  erase(&mut ty, generics, false)?;
+  Ok(ty)
+}
+
+/// `name` with `'static` for each of its lifetimes, or `None` when it has type or const
+/// parameters, which constants can't name.
+pub fn const_self_type(name: &Ident, generics: &Generics) -> Option<Type> {
+  if generics.params.is_empty() {
+    return Some(syn::parse_quote!(#name));
+  }
+  let lifetimes = generics
+    .params
+    .iter()
+    .map(|param| {
+      match param {
+        GenericParam::Lifetime(_) => Some(static_lifetime()),
+        _ => None
+      }
+    })
+    .collect::<Option<Vec<_>>>()?;
+  Some(syn::parse_quote!(#name<#(#lifetimes),*>))
+}
+
+fn static_lifetime() -> Lifetime {
+  Lifetime::new("'static", Span::call_site())
+}
+
+fn erase(ty: &mut Type, generics: &Generics, behind_pointer: bool) -> Result<(), Ident> {
+  match ty {
+    Type::Reference(reference) => {
+      if reference.lifetime.is_some() {
+        reference.lifetime = Some(static_lifetime());
+      }
+      erase(&mut reference.elem, generics, true)
+    }
+    Type::Ptr(pointer) => erase(&mut pointer.elem, generics, true),
+    Type::Slice(slice) => erase(&mut slice.elem, generics, behind_pointer),
+    Type::Array(array) => {
+      check_tokens(&array.len, generics)?;
+      erase(&mut array.elem, generics, behind_pointer)
+    }
+    Type::Tuple(tuple) => {
+      tuple
+        .elems
+        .iter_mut()
+        .try_for_each(|elem| erase(elem, generics, behind_pointer))
+    }
+    Type::Paren(paren) => erase(&mut paren.elem, generics, behind_pointer),
+    Type::Group(group) => erase(&mut group.elem, generics, behind_pointer),
+    Type::Path(path) if path.qself.is_none() => {
+      if let Some(param) = path
+        .path
+        .get_ident()
+        .and_then(|ident| type_param(ident, generics))
+      {
+        if behind_pointer && is_sized(&param, generics) {
+          *ty = syn::parse_quote!(());
+          return Ok(());
+        }
+        return Err(param);
+      }
+      if let Some(first) = path.path.segments.first() {
+        if path.path.segments.len() > 1 && type_param(&first.ident, generics).is_some() {
+          // Associated types of a parameter, like `T::Output`.
+          return Err(first.ident.clone());
+        }
+      }
+      let pointer = path.path.segments.last().map_or(false, |segment| {
+        segment.ident == "PhantomData" || segment.ident == "NonNull"
+      });
+      for segment in &mut path.path.segments {
+        let PathArguments::AngleBracketed(arguments) = &mut segment.arguments else {
+          check_tokens(&segment.arguments, generics)?;
+          continue;
+        };
+        for argument in &mut arguments.args {
+          match argument {
+            GenericArgument::Lifetime(lifetime) => *lifetime = static_lifetime(),
+            GenericArgument::Type(ty) => erase(ty, generics, behind_pointer || pointer)?,
+            argument => check_tokens(argument, generics)?
+          }
+        }
+      }
+      Ok(())
+    }
+    ty => check_tokens(ty, generics)
+  }
+}
+
+/// The first type or const parameter used in `tokens`, which can't be part of constants.
+pub fn used_param(tokens: &impl ToTokens, generics: &Generics) -> Option<Ident> {
+  check_tokens(tokens, generics).err()
+}
+
+/// Finds a type or const parameter used anywhere in `tokens`.
+fn check_tokens(tokens: &impl ToTokens, generics: &Generics) -> Result<(), Ident> {
+  fn find(tokens: TokenStream, generics: &Generics) -> Result<(), Ident> {
+    for token in tokens {
+      match token {
+        TokenTree::Ident(ident) => {
+          let used = generics.params.iter().any(|param| {
+            match param {
+              GenericParam::Type(param) => param.ident == ident,
+              GenericParam::Const(param) => param.ident == ident,
+              GenericParam::Lifetime(_) => false
+            }
+          });
+          if used {
+            return Err(ident);
+          }
+        }
+        TokenTree::Group(group) => find(group.stream(), generics)?,
+        _ => {}
+      }
+    }
+    Ok(())
+  }
+  find(tokens.to_token_stream(), generics)
+}
+
+fn type_param(ident: &Ident, generics: &Generics) -> Option<Ident> {
+  generics
+    .type_params()
+    .find(|param| param.ident == *ident)
+    .map(|param| param.ident.clone())
+}
+
+/// Whether `bound` is `?Sized`.
+fn is_maybe(bound: &TypeParamBound) -> bool {
+  match bound {
+    TypeParamBound::Trait(bound) => matches!(bound.modifier, TraitBoundModifier::Maybe(_)),
+    _ => false
+  }
+}
+
+/// Whether `param` is `Sized`, so pointers to it are thin.
+fn is_sized(param: &Ident, generics: &Generics) -> bool {
+  let declared = generics
+    .type_params()
+    .filter(|declared| declared.ident == *param)
+    .any(|declared| declared.bounds.iter().any(is_maybe));
+  let predicated = generics
+    .where_clause
+    .iter()
+    .flat_map(|clause| &clause.predicates)
+    .any(|predicate| {
+      matches!(predicate, WherePredicate::Type(predicate)
+        if matches!(&predicate.bounded_ty, Type::Path(path) if path.path.is_ident(param))
+          && predicate.bounds.iter().any(is_maybe))
+    });
+  !declared && !predicated
+}
@@ -1,128 +1,4866 @@
 use proc_macro::TokenStream;
 use proc_macro2::Span;
-use quote::{quote, quote_spanned};
+use proc_macro_crate::{crate_name, FoundCrate};
+use quote::{format_ident, quote, ToTokens};
 use syn::{
-  parse::Parse, parse_macro_input, Attribute, Data, DataStruct, DeriveInput, Error as SynError,
-  Field, LitInt, Result as SynResult, Type
+  ext::IdentExt, parse::Parse, parse_macro_input, punctuated::Punctuated, Attribute, Data,
+  DataStruct, DeriveInput, Error as SynError, Field, Fields, LitInt, Meta, Result as SynResult,
+  Token, Type
 };
 
+mod args;
+mod builder;
+mod declare;
+mod generics;
+mod offset;
+mod pointer_width;
+mod tagged;
+mod tuple;
+mod union;
+mod versions;
+mod vtable;
+
+use args::{Access, AccessorArgs, BitOffsetArgs, Endian, LayoutArgs, NestedArgs, SignatureArgs};
+use offset::{FieldOffset, Offset};
+
+enum FieldKind {
+  /// A regular field placed at its `field_offset`.
+  Data,
+  /// A `gap` pseudo-field reserving the bytes up to an absolute offset.
+  Gap
+}
+
+/// Parses the value of an attribute written as either `#[name(value)]` or `#[name = value]`.
+fn parse_attribute_value<T: Parse>(attr: &Attribute) -> SynResult<T> {
+  match &attr.meta {
+    Meta::NameValue(meta) => syn::parse2(meta.value.to_token_stream()),
+    _ => attr.parse_args()
+  }
+}
+
+/// Field attributes consumed by the macro.
+const HELPER_ATTRIBUTES: &[&str] = &[
+  "field_offset",
+  "gap",
+  "field_align",
+  "field_size",
+  "computed",
+  "accessor",
+  "bit_offset",
+  "overlaps",
+  "volatile",
+  "access",
+  "endian",
+  "nested",
+  "stride",
+  "signature",
+  "marker"
+];
+
+/// Whether the attribute marks a field that takes up no space of its own.
+fn is_virtual_attribute(attr: &Attribute) -> bool {
+  ["computed", "bit_offset", "overlaps"]
+    .iter()
+    .any(|name| attr.path().is_ident(name))
+    || is_runtime_offset(attr)
+}
+
+/// Whether the field is a zero-sized `marker`, which shares the offset of the next field.
+fn is_marker(field: &Field) -> bool {
+  field
+    .attrs
+    .iter()
+    .any(|attr| attr.path().is_ident("marker"))
+}
+
+/// Whether the field is a trailing unsized array, like `entries: [Entry]`.
+fn is_flexible(field: &Field) -> bool {
+  matches!(field.ty, Type::Slice(_))
+}
+
+/// Whether the attribute is `#[field_offset(runtime)]`.
+fn is_runtime_offset(attr: &Attribute) -> bool {
+  attr.path().is_ident("field_offset")
+    && matches!(
+      parse_attribute_value::<FieldOffset>(attr),
+      Ok(FieldOffset::Runtime)
+    )
+}
+
+fn is_helper_attribute(attr: &Attribute) -> bool {
+  HELPER_ATTRIBUTES
+    .iter()
+    .any(|helper| attr.path().is_ident(helper))
+}
+
 struct FieldInfo {
   field:           Field,
+  kind:            FieldKind,
+  /// Offset of the previous field, or the end of the previous gap.
+  previous_offset: Offset,
   previous_type:   Option<Type>,
-  relative_offset: usize,
-  absolute_offset: usize
+  /// Offset of the field, or the end of the gap.
+  offset:          Offset,
+  /// Alignment required by `field_align`.
+  align:           Option<usize>,
+  /// Bytes the field occupies given through `field_size`, instead of the size of its type.
+  size:            Option<usize>,
+  /// Combined predicate of the field's `cfg` attributes.
+  cfg:             Option<proc_macro2::TokenStream>,
+  /// Name given through `accessor`.
+  accessor:        Option<syn::Ident>,
+  /// Whether the accessors use volatile reads and writes.
+  volatile:        bool,
+  /// Which accessors are generated.
+  access:          Access,
+  /// Byte order the field is stored in, native if not given.
+  endian:          Option<Endian>,
+  /// Given through `nested` for fields whose type is itself a `#[memory_layout]` struct.
+  nested:          Option<NestedArgs>,
+  /// Whether this is the hidden field holding the `base` of the struct.
+  base:            bool
+}
+
+impl FieldInfo {
+  /// Whether the field is a `Strided` array given through `stride`.
+  fn is_strided(&self) -> bool {
+    self
+      .field
+      .attrs
+      .iter()
+      .any(|attr| attr.path().is_ident("stride"))
+  }
+
+  /// Type the field occupies the size of, a byte array of its `field_size` if it has one.
+  fn size_type(&self) -> Type {
+    match self.size {
+      Some(size) => syn::parse_quote!([u8; #size]),
+      None => self.field.ty.clone()
+    }
+  }
+
+  /// Whether the getter borrows the field rather than copying it.
+  fn is_borrowed(&self) -> bool {
+    is_byte_array(&self.field.ty) || self.is_strided() || is_cstr_array(&self.field.ty)
+  }
+
+  /// Whether getters, setters and constants are generated for the field.
+  fn has_accessors(&self) -> bool {
+    matches!(self.kind, FieldKind::Data) && !self.base
+  }
+
+  /// Name of the generated getter, setters and the builder setter are derived from it.
+  fn accessor_ident(&self) -> &syn::Ident {
+    self
+      .accessor
+      .as_ref()
+      .unwrap_or_else(|| self.field.ident.as_ref().unwrap())
+  }
+
+  /// Converts `value` read from the field to the native byte order.
+  fn native_value(&self, value: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    let ty = &self.field.ty;
+    match self.endian {
+      Some(Endian::Big) => quote!(<#ty>::from_be(#value)),
+      Some(Endian::Little) => quote!(<#ty>::from_le(#value)),
+      None => value
+    }
+  }
+
+  /// Converts `value` to the byte order the field is stored in.
+  fn stored_value(&self, value: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    let ty = &self.field.ty;
+    match self.endian {
+      Some(Endian::Big) => quote!(<#ty>::to_be(#value)),
+      Some(Endian::Little) => quote!(<#ty>::to_le(#value)),
+      None => value
+    }
+  }
+
+  /// `#[cfg]` attribute to apply to items generated for this field.
+  fn cfg_attr(&self) -> Option<proc_macro2::TokenStream> {
+    self.cfg.as_ref().map(|cfg| quote!(#[cfg(#cfg)]))
+  }
+
+  /// Size of the padding between the previous field and this one.
+  fn padding_size(&self) -> proc_macro2::TokenStream {
+    self
+      .previous_offset
+      .padding_to(self.previous_type.as_ref(), &self.offset)
+  }
+}
+
+/// A `computed` field, which only generates a getter evaluating its expression.
+struct ComputedField {
+  field: Field,
+  expr:  proc_macro2::TokenStream
+}
+
+/// A field annotated with `bit_offset`, which occupies bits of an integer stored at `byte`.
+struct BitField {
+  field: Field,
+  args:  BitOffsetArgs
+}
+
+/// A field annotated with `overlaps`, another typed view of the bytes of `target`.
+struct OverlayField {
+  field:  Field,
+  target: syn::Ident
+}
+
+/// A field annotated with `#[field_offset(runtime)]`, whose offset is set through `set_offsets`.
+struct RuntimeField {
+  field:     Field,
+  /// Given through `signature` to find the offset with `scan_offsets`.
+  signature: Option<SignatureArgs>
+}
+
+/// A zero-sized field annotated with `marker`, declared in front of the field at index `before`.
+struct MarkerField {
+  field:  Field,
+  /// Given through `field_offset`, which has to match the offset of the next field.
+  offset: Option<Offset>,
+  cfg:    Option<proc_macro2::TokenStream>,
+  /// Index of the field following the marker, the number of fields for trailing markers.
+  before: usize
+}
+
+/// A trailing unsized array, like `entries: [Entry]`, which isn't stored in the struct but read
+/// from the memory following it.
+struct FlexibleField {
+  field:  Field,
+  offset: Offset
 }
 
 struct StructInfo {
-  derived: DeriveInput,
-  fields:  Vec<FieldInfo>
+  derived:   DeriveInput,
+  args:      LayoutArgs,
+  /// Whether the struct is `repr(C, packed)`, rather than a user specified `repr(C)`.
+  packed:    bool,
+  fields:    Vec<FieldInfo>,
+  computed:  Vec<ComputedField>,
+  bitfields: Vec<BitField>,
+  overlays:  Vec<OverlayField>,
+  runtime:   Vec<RuntimeField>,
+  markers:   Vec<MarkerField>,
+  flexible:  Option<FlexibleField>,
+  /// Offset and type of the last field, the type is `None` when the struct ends in a gap or a
+  /// `cfg` dependent field.
+  tail:      (Offset, Option<Type>)
 }
 
 impl StructInfo {
-  fn get_data_struct(input: &DeriveInput) -> SynResult<&DataStruct> {
-    match &input.data {
-      Data::Struct(data) => Ok(data),
-      Data::Enum(data) => {
-        Err(SynError::new_spanned(
-          data.enum_token,
-          "Expected struct but found enum."
-        ))
+  /// Type of a padding field of `size` bytes.
+  fn padding_type(&self, size: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    if self.args.sparse {
+      quote!([::core::mem::MaybeUninit<u8>; #size])
+    } else {
+      quote!([u8; #size])
+    }
+  }
+
+  /// Statements filling the padding and gaps of the struct `target` points to with the
+  /// `pad_fill` byte, `None` without one. `target` doesn't have to be aligned.
+  fn fill_padding(&self, target: proc_macro2::TokenStream) -> Option<proc_macro2::TokenStream> {
+    let fill = self.args.pad_fill?;
+    let mut regions = self
+      .fields
+      .iter()
+      .enumerate()
+      .map(|(i, field)| {
+        let ident = field.field.ident.as_ref().unwrap();
+        let cfg = field.cfg_attr();
+        match field.kind {
+          FieldKind::Data => {
+            let pad_ident = syn::Ident::new(&format!("__pad{}", i), ident.span());
+            let size_padding = self.size_padding(i, field).map(
+              |(size_ident, _)| quote!(#cfg fill(::core::ptr::addr_of_mut!((*target).#size_ident));)
+            );
+            quote! {
+              #cfg fill(::core::ptr::addr_of_mut!((*target).#pad_ident));
+              #size_padding
+            }
+          }
+          FieldKind::Gap => quote!(#cfg fill(::core::ptr::addr_of_mut!((*target).#ident));)
+        }
+      })
+      .collect::<Vec<_>>();
+    if self.args.size.is_some() {
+      let pad_ident = syn::Ident::new(&format!("__pad{}", self.fields.len()), Span::call_site());
+      regions.push(quote!(fill(::core::ptr::addr_of_mut!((*target).#pad_ident));));
+    }
+
+    Some(quote! {
+      unsafe fn fill<T>(padding: *mut T) {
+        ::core::ptr::write_bytes(padding as *mut u8, #fill, ::core::mem::size_of::<T>())
       }
-      Data::Union(data) => {
-        Err(SynError::new_spanned(
-          data.union_token,
-          "Expected struct but found union."
-        ))
+      let target: *mut Self = #target;
+      unsafe {
+        #(#regions)*
+      }
+    })
+  }
+
+  fn new(input: DeriveInput, mut args: LayoutArgs) -> SynResult<Self> {
+    let data = Self::get_data_struct(&input)?;
+    let packed = Self::get_packed(&input)? && !args.verify && args.align.is_none();
+    if args.verify && args.sparse {
+      return Err(SynError::new_spanned(
+        &input.ident,
+        "verify inserts no padding, so it can't be combined with sparse."
+      ));
+    }
+    if args.verify && args.pad_fill.is_some() {
+      return Err(SynError::new_spanned(
+        &input.ident,
+        "verify inserts no padding, so it can't be combined with pad_fill."
+      ));
+    }
+    if args.zerocopy && (args.verify || args.sparse) {
+      return Err(SynError::new_spanned(
+        &input.ident,
+        "zerocopy requires initialized padding, so it can't be combined with verify or sparse."
+      ));
+    }
+    let computed = Self::get_computed_fields(data)?;
+    let bitfields = Self::get_bit_fields(data)?;
+    let overlays = Self::get_overlay_fields(data)?;
+    let runtime = Self::get_runtime_fields(data)?;
+    if let Some(field) = runtime.first().filter(|_| args.pad_fill.is_some()) {
+      return Err(SynError::new_spanned(
+        &field.field,
+        "A field with a runtime offset may be stored in padding, so it can't be combined with \
+         pad_fill."
+      ));
+    }
+    if let Some(field) = runtime.first().filter(|_| args.eq || args.hash) {
+      return Err(SynError::new_spanned(
+        &field.field,
+        "A field with a runtime offset may be stored in padding, which eq and hash ignore."
+      ));
+    }
+    let declared = if args.sort {
+      Self::sort_fields(data, args.size.as_ref())?
+    } else {
+      data.fields.iter().collect()
+    };
+    if args.builder && args.base.is_some() {
+      return Err(SynError::new_spanned(
+        &input.ident,
+        "A struct with a base can't have a builder."
+      ));
+    }
+    let (mut fields, mut tail) =
+      Self::get_fields(&declared, args.size.as_ref(), args.base.as_ref())?;
+    let markers = Self::get_marker_fields(&declared, args.size.as_ref(), args.base.is_some())?;
+    if let Some(field) = fields
+      .iter()
+      .find(|field| args.verify && field.size.is_some())
+    {
+      return Err(SynError::new_spanned(
+        &field.field,
+        "verify inserts no padding, so it can't be combined with field_size."
+      ));
+    }
+    Self::erase_generics(&input.generics, &args, data, &mut fields, &mut tail)?;
+    if let Some(boundary) = args.round_to {
+      if args.size.is_some() {
+        return Err(SynError::new_spanned(
+          &input.ident,
+          "round_to derives the size from the last field, so it can't be combined with a size."
+        ));
+      }
+      args.size = Some(Self::round_size(&tail, boundary));
+    }
+    if args.volatile {
+      fields
+        .iter_mut()
+        .filter(|field| field.has_accessors())
+        .for_each(|field| field.volatile = true);
+    }
+    if let Some(field) = fields.iter().find(|field| packed && field.volatile) {
+      return Err(SynError::new_spanned(
+        &field.field,
+        "Volatile accesses require aligned fields, add `#[repr(C)]` or an `align` to the struct."
+      ));
+    }
+    Self::check_constant_names(&fields)?;
+    if let Some(field) = fields.iter().find(|field| {
+      args.reflect
+        && field.has_accessors()
+        && field.field.ident.as_ref().unwrap().unraw() == "fields"
+    }) {
+      return Err(SynError::new_spanned(
+        &field.field,
+        "The handle constant of `fields` collides with the `FIELDS` table of reflect."
+      ));
+    }
+    let flexible = Self::get_flexible_field(data, args.size.as_ref(), &fields, &tail)?;
+    Self::check_accessor_names(
+      &fields,
+      &computed,
+      &bitfields,
+      &overlays,
+      &runtime,
+      flexible.as_ref()
+    )?;
+    Self::check_overlay_targets(&fields, &overlays)?;
+    if let Some(gap) = fields
+      .iter()
+      .find(|field| args.verify && matches!(field.kind, FieldKind::Gap))
+    {
+      return Err(SynError::new_spanned(
+        &gap.field,
+        "Gaps insert padding, which verify doesn't do."
+      ));
+    }
+    if fields.is_empty() && args.size.is_none() && args.base.is_none() {
+      return Err(SynError::new_spanned(
+        &input.ident,
+        "memory_layout requires at least one field or a struct size."
+      ));
+    }
+
+    Ok(StructInfo {
+      derived: input,
+      args,
+      packed,
+      fields,
+      computed,
+      bitfields,
+      overlays,
+      runtime,
+      markers,
+      flexible,
+      tail
+    })
+  }
+
+  /// A user specified `repr(C)` replaces the default `repr(C, packed)`, any other `repr` conflicts
+  /// with the layout generated by the macro.
+  fn get_packed(input: &DeriveInput) -> SynResult<bool> {
+    let mut packed = true;
+    for attr in input
+      .attrs
+      .iter()
+      .filter(|attr| attr.path().is_ident("repr"))
+    {
+      let reprs = attr.parse_args_with(Punctuated::<syn::Ident, Token![,]>::parse_terminated)?;
+      if reprs.len() != 1 || reprs[0] != "C" {
+        return Err(SynError::new_spanned(
+          attr,
+          "Only `repr(C)` can be combined with memory_layout."
+        ));
       }
+      packed = false;
     }
+
+    Ok(packed)
   }
 
-  fn get_field_offset_value(attr: &Attribute) -> SynResult<usize> {
-    attr
-      .parse_args::<LitInt>()
-      .and_then(|lit| lit.base10_parse::<usize>())
-      .map_err(|_| SynError::new_spanned(attr, "Field offset must be an integer literal."))
+  /// Rejects fields that would generate identically named constants.
+  fn check_constant_names(fields: &[FieldInfo]) -> SynResult<()> {
+    let idents = fields
+      .iter()
+      .filter(|field| field.has_accessors())
+      .filter_map(|field| field.field.ident.as_ref())
+      .collect::<Vec<_>>();
+    let constants = |ident: &syn::Ident| {
+      let upper = ident.unraw().to_string().to_uppercase();
+      ["OFFSET_", "SIZE_", "END_", ""].map(|prefix| format!("{}{}", prefix, upper))
+    };
+    for (i, ident) in idents.iter().enumerate() {
+      for other in &idents[..i] {
+        let others = constants(other);
+        if let Some(constant) = constants(ident)
+          .into_iter()
+          .find(|constant| others.contains(constant))
+        {
+          return Err(SynError::new_spanned(
+            ident,
+            format!(
+              "`{}` and `{}` both generate the constant `{}`.",
+              other, ident, constant
+            )
+          ));
+        }
+      }
+    }
+
+    Ok(())
   }
 
-  fn get_fields(data: &DataStruct) -> SynResult<Vec<FieldInfo>> {
-    let mut result = Vec::<FieldInfo>::new();
+  /// Rejects fields that would generate identically named accessors.
+  fn check_accessor_names(
+    fields: &[FieldInfo],
+    computed: &[ComputedField],
+    bitfields: &[BitField],
+    overlays: &[OverlayField],
+    runtime: &[RuntimeField],
+    flexible: Option<&FlexibleField>
+  ) -> SynResult<()> {
+    let accessors = fields
+      .iter()
+      .filter(|field| field.has_accessors())
+      .map(|field| (field.field.ident.as_ref().unwrap(), field.accessor_ident()))
+      .chain(
+        computed
+          .iter()
+          .map(|computed| &computed.field)
+          .chain(bitfields.iter().map(|bitfield| &bitfield.field))
+          .chain(overlays.iter().map(|overlay| &overlay.field))
+          .chain(runtime.iter().map(|runtime| &runtime.field))
+          .chain(flexible.map(|flexible| &flexible.field))
+          .map(|field| {
+            let ident = field.ident.as_ref().unwrap();
+            (ident, ident)
+          })
+      )
+      .collect::<Vec<_>>();
+    for (i, (ident, accessor)) in accessors.iter().enumerate() {
+      if let Some((other, _)) = accessors[..i]
+        .iter()
+        .find(|(_, other)| other.unraw() == accessor.unraw())
+      {
+        return Err(SynError::new_spanned(
+          accessor,
+          format!(
+            "`{}` and `{}` both generate the accessor `{}`.",
+            other,
+            ident,
+            accessor.unraw()
+          )
+        ));
+      }
+    }
 
-    let mut current_offset = 0usize;
-    let mut previous_type: Option<Type> = None;
+    Ok(())
+  }
+
+  /// Name given to a field's accessors through `#[accessor(name = "...")]`.
+  fn get_accessor(field: &Field) -> SynResult<Option<syn::Ident>> {
+    field
+      .attrs
+      .iter()
+      .find(|attr| attr.path().is_ident("accessor"))
+      .map(|attr| Ok(attr.parse_args::<AccessorArgs>()?.name))
+      .transpose()
+  }
+
+  /// Access mode given through `#[access(..)]`, fields are readable and writable by default.
+  fn get_access(field: &Field) -> SynResult<Access> {
+    field
+      .attrs
+      .iter()
+      .find(|attr| attr.path().is_ident("access"))
+      .map_or(Ok(Access::ReadWrite), |attr| attr.parse_args::<Access>())
+  }
+
+  /// Byte order given through `#[endian(..)]`, only integer fields can be byte-swapped.
+  fn get_endian(field: &Field) -> SynResult<Option<Endian>> {
+    let Some(attr) = field
+      .attrs
+      .iter()
+      .find(|attr| attr.path().is_ident("endian"))
+    else {
+      return Ok(None);
+    };
+
+    let endian = attr.parse_args::<Endian>()?;
+    if !is_integer(&field.ty) {
+      return Err(SynError::new_spanned(
+        &field.ty,
+        "Only integer fields can have an endian."
+      ));
+    }
+    Ok(Some(endian))
+  }
+
+  /// Arguments of `#[nested]` or `#[nested(size = ..)]`.
+  fn get_nested(field: &Field) -> SynResult<Option<NestedArgs>> {
+    field
+      .attrs
+      .iter()
+      .find(|attr| attr.path().is_ident("nested"))
+      .map(|attr| {
+        match attr.meta {
+          syn::Meta::Path(_) => Ok(NestedArgs::default()),
+          _ => attr.parse_args::<NestedArgs>()
+        }
+      })
+      .transpose()
+  }
+
+  fn get_computed_fields(data: &DataStruct) -> SynResult<Vec<ComputedField>> {
+    let mut result = Vec::new();
     for field in &data.fields {
-      let field_offset = field
+      let Some(attr) = field
         .attrs
         .iter()
-        .find(|attr| attr.path().is_ident("field_offset"));
+        .find(|attr| attr.path().is_ident("computed"))
+      else {
+        continue;
+      };
 
-      let offset = field_offset
-        .ok_or_else(|| SynError::new_spanned(field, "Field is missing a field_offset."))
-        .and_then(Self::get_field_offset_value)?;
+      if let Some(conflict) = field.attrs.iter().find(|attr| {
+        attr.path().is_ident("field_offset")
+          || attr.path().is_ident("gap")
+          || attr.path().is_ident("accessor")
+      }) {
+        return Err(SynError::new_spanned(
+          conflict,
+          "A computed field can't have a field_offset or accessor, or be a gap."
+        ));
+      }
+
+      result.push(ComputedField {
+        field: field.clone(),
+        expr:  attr.parse_args::<proc_macro2::TokenStream>()?
+      });
+    }
+
+    Ok(result)
+  }
 
-      if current_offset > offset {
+  fn get_bit_fields(data: &DataStruct) -> SynResult<Vec<BitField>> {
+    let mut result = Vec::new();
+    for field in &data.fields {
+      let Some(attr) = field
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("bit_offset"))
+      else {
+        continue;
+      };
+
+      if let Some(conflict) = field
+        .attrs
+        .iter()
+        .find(|attr| is_helper_attribute(attr) && !attr.path().is_ident("bit_offset"))
+      {
         return Err(SynError::new_spanned(
-          field_offset,
-          "Field offset can't be lower than its predecessor."
+          conflict,
+          "A bit field can't be combined with other memory_layout attributes."
         ));
       }
 
-      result.push(FieldInfo {
-        field:           field.clone(),
-        previous_type:   previous_type.clone(),
-        relative_offset: offset - current_offset,
-        absolute_offset: offset
+      if !is_unsigned_integer(&field.ty) {
+        return Err(SynError::new_spanned(
+          &field.ty,
+          "Bit fields must have an unsigned integer type."
+        ));
+      }
+
+      result.push(BitField {
+        field: field.clone(),
+        args:  attr.parse_args::<BitOffsetArgs>()?
       });
+    }
+
+    Ok(result)
+  }
+
+  fn get_overlay_fields(data: &DataStruct) -> SynResult<Vec<OverlayField>> {
+    let mut result = Vec::new();
+    for field in &data.fields {
+      let Some(attr) = field
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("overlaps"))
+      else {
+        continue;
+      };
+
+      if let Some(conflict) = field
+        .attrs
+        .iter()
+        .find(|attr| is_helper_attribute(attr) && !attr.path().is_ident("overlaps"))
+      {
+        return Err(SynError::new_spanned(
+          conflict,
+          "An overlapping field can't be combined with other memory_layout attributes."
+        ));
+      }
 
-      previous_type = Some(field.ty.clone());
-      current_offset = offset
+      result.push(OverlayField {
+        field:  field.clone(),
+        target: attr.parse_args::<syn::Ident>()?
+      });
     }
 
     Ok(result)
   }
-}
 
-impl Parse for StructInfo {
-  fn parse(input: syn::parse::ParseStream) -> SynResult<Self> {
-    let input: DeriveInput = input.parse()?;
+  fn get_runtime_fields(data: &DataStruct) -> SynResult<Vec<RuntimeField>> {
+    let mut result = Vec::new();
+    for field in &data.fields {
+      let signature = field
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("signature"));
+      if !field.attrs.iter().any(is_runtime_offset) {
+        if let Some(signature) = signature {
+          return Err(SynError::new_spanned(
+            signature,
+            "Signatures can only be given for fields with `#[field_offset(runtime)]`."
+          ));
+        }
+        continue;
+      }
 
-    let data = Self::get_data_struct(&input)?;
-    let fields = Self::get_fields(data)?;
+      if let Some(conflict) = field.attrs.iter().find(|attr| {
+        is_helper_attribute(attr)
+          && !attr.path().is_ident("field_offset")
+          && !attr.path().is_ident("signature")
+      }) {
+        return Err(SynError::new_spanned(
+          conflict,
+          "A field with a runtime offset can't be combined with other memory_layout attributes."
+        ));
+      }
 
-    Ok(StructInfo {
-      derived: input,
-      fields
-    })
+      result.push(RuntimeField {
+        field:     field.clone(),
+        signature: signature
+          .map(|attr| attr.parse_args::<SignatureArgs>())
+          .transpose()?
+      });
+    }
+
+    if let Some(field) = result.iter().find(|field| field.signature.is_none()) {
+      if result.iter().any(|field| field.signature.is_some()) {
+        return Err(SynError::new_spanned(
+          &field.field,
+          "Every field with a runtime offset needs a signature once one of them has one."
+        ));
+      }
+    }
+
+    Ok(result)
   }
-}
 
-#[cfg(feature = "offset_of")]
-fn generate_field_offset_checks(struct_info: &StructInfo) -> Vec<proc_macro2::TokenStream> {
-  let struct_ident = &struct_info.derived.ident;
-  struct_info
-    .fields
-    .iter()
-    .map(|field| {
-      let Some(ident) = field.field.ident.clone() else {
-      todo!()
+  /// The trailing unsized array, placed at its `field_offset` or at the end of the struct.
+  fn get_flexible_field(
+    data: &DataStruct,
+    desired_size: Option<&Offset>,
+    fields: &[FieldInfo],
+    tail: &(Offset, Option<Type>)
+  ) -> SynResult<Option<FlexibleField>> {
+    let Some((index, field)) = data
+      .fields
+      .iter()
+      .enumerate()
+      .find(|(_, field)| is_flexible(field))
+    else {
+      return Ok(None);
     };
-      let offset = field.absolute_offset;
-      quote! {
-        const _:() = assert!(::core::mem::offset_of!(#struct_ident, #ident) == #offset);
+
+    if index + 1 != data.fields.len() {
+      return Err(SynError::new_spanned(
+        field,
+        "An unsized array has to be the last field."
+      ));
+    }
+    if let Some(conflict) = field
+      .attrs
+      .iter()
+      .find(|attr| is_helper_attribute(attr) && !attr.path().is_ident("field_offset"))
+    {
+      return Err(SynError::new_spanned(
+        conflict,
+        "An unsized array can only have a field_offset."
+      ));
+    }
+
+    let (tail_offset, tail_type) = tail;
+    let end = match tail_type {
+      Some(ty) => tail_offset.after(ty),
+      None => tail_offset.clone()
+    };
+    let field_offset = field
+      .attrs
+      .iter()
+      .find(|attr| attr.path().is_ident("field_offset"));
+    let offset = match field_offset {
+      Some(attr) => Self::get_field_offset_value(attr, desired_size, fields)?,
+      None => desired_size.cloned().unwrap_or(end)
+    };
+    let last_end = tail_offset
+      .known()
+      .zip(tail_type.as_ref().and_then(known_size))
+      .map(|(offset, size)| offset + size);
+    if let (Some(end), Some(offset)) = (last_end, offset.known()) {
+      if offset < end {
+        return Err(SynError::new_spanned(
+          field_offset.map_or_else(|| field.to_token_stream(), |attr| attr.to_token_stream()),
+          format!(
+            "Unsized array `{}` at 0x{:X} overlaps the previous field, which ends at 0x{:X}.",
+            field.ident.as_ref().unwrap(),
+            offset,
+            end
+          )
+        ));
       }
-    })
-    .collect::<Vec<_>>()
-}
+    }
 
-#[cfg(not(feature = "offset_of"))]
-fn generate_field_offset_checks(_: &StructInfo) -> Vec<proc_macro2::TokenStream> {
-  vec![]
-}
+    Ok(Some(FlexibleField {
+      field: field.clone(),
+      offset
+    }))
+  }
 
-/// Allows for `field_offset`s to be defined in the struct.
-/// All fields in the struct have to be annotated with a `field_offset` attribute, and must be defined in-order.
-/// A `field_offset` attribute has to include a int literal, which indicates the offset the field should have.
+  fn check_overlay_targets(fields: &[FieldInfo], overlays: &[OverlayField]) -> SynResult<()> {
+    for overlay in overlays {
+      if !fields.iter().any(|field| {
+        matches!(field.kind, FieldKind::Data) && field.field.ident.as_ref() == Some(&overlay.target)
+      }) {
+        return Err(SynError::new_spanned(
+          &overlay.target,
+          format!(
+            "`{}` is not a field that can be overlapped.",
+            overlay.target
+          )
+        ));
+      }
+    }
+
+    Ok(())
+  }
+
+  /// The field an overlapping field shares its bytes with.
+  fn overlay_target(&self, overlay: &OverlayField) -> &FieldInfo {
+    self
+      .fields
+      .iter()
+      .find(|field| field.field.ident.as_ref() == Some(&overlay.target))
+      .unwrap()
+  }
+
+  /// Size of a struct whose last field ends at `tail`, rounded up to a multiple of `boundary`.
+  fn round_size(tail: &(Offset, Option<Type>), boundary: usize) -> Offset {
+    match tail {
+      (Offset::Known(end), None) => Offset::Known((end + boundary - 1) / boundary * boundary),
+      (offset, ty) => {
+        let end = match ty {
+          Some(ty) => offset.after(ty),
+          None => offset.clone()
+        };
+        Offset::Expr(quote!((#end + #boundary - 1) / #boundary * #boundary))
+      }
+    }
+  }
+
+  fn get_data_struct(input: &DeriveInput) -> SynResult<&DataStruct> {
+    match &input.data {
+      Data::Struct(data) => Ok(data),
+      Data::Enum(data) => {
+        Err(SynError::new_spanned(
+          data.enum_token,
+          "Expected struct but found enum."
+        ))
+      }
+      Data::Union(data) => {
+        Err(SynError::new_spanned(
+          data.union_token,
+          "Expected struct but found union."
+        ))
+      }
+    }
+  }
+
+  fn get_field_offset_value(
+    attr: &Attribute,
+    desired_size: Option<&Offset>,
+    previous: &[FieldInfo]
+  ) -> SynResult<Offset> {
+    let offset = parse_attribute_value::<FieldOffset>(attr).map_err(|_| {
+      SynError::new_spanned(
+        attr,
+        "Field offset must be an integer literal or a constant expression."
+      )
+    })?;
+
+    match offset {
+      FieldOffset::Absolute(offset) => Ok(Offset::Known(offset)),
+      FieldOffset::Expr(expr) => Ok(Offset::Expr(expr)),
+      FieldOffset::PointerWidth(_) => unreachable!("pointer width selectors are resolved first"),
+      FieldOffset::Runtime => {
+        Err(SynError::new_spanned(
+          attr,
+          "Runtime offsets are only supported on struct fields."
+        ))
+      }
+      FieldOffset::Versioned(_) => {
+        Err(SynError::new_spanned(
+          attr,
+          "Versioned offsets require a `versions(..)` argument on the struct."
+        ))
+      }
+      FieldOffset::FromEnd(from_end) => {
+        let size = desired_size.ok_or_else(|| {
+          SynError::new_spanned(attr, "Offsets relative to the end require a struct size.")
+        })?;
+        match size {
+          Offset::Known(size) => {
+            size
+              .checked_sub(from_end)
+              .map(Offset::Known)
+              .ok_or_else(|| {
+                SynError::new_spanned(
+                  attr,
+                  "Offset relative to the end is larger than the struct size."
+                )
+              })
+          }
+          Offset::Expr(_) => Ok(Offset::Expr(quote!(#size - #from_end)))
+        }
+      }
+      FieldOffset::After(ident, extra) => {
+        let field = previous
+          .iter()
+          .find(|field| field.field.ident.as_ref() == Some(&ident))
+          .ok_or_else(|| {
+            SynError::new_spanned(&ident, format!("`{}` is not a preceding field.", ident))
+          })?;
+        let end = match field.kind {
+          FieldKind::Data => field.offset.after(&field.size_type()),
+          FieldKind::Gap => field.offset.clone()
+        };
+        Ok(end.add(extra))
+      }
+    }
+  }
+
+  /// Replaces the type of an array annotated with `#[stride(..)]` by a `Strided` array.
+  fn apply_stride(field: &Field) -> SynResult<Field> {
+    let Some(attr) = field
+      .attrs
+      .iter()
+      .find(|attr| attr.path().is_ident("stride"))
+    else {
+      return Ok(field.clone());
+    };
+
+    let stride = parse_attribute_value::<syn::Expr>(attr)?;
+    let Type::Array(array) = &field.ty else {
+      return Err(SynError::new_spanned(
+        &field.ty,
+        "A stride can only be given for array fields."
+      ));
+    };
+    // Literals and paths can be used as const arguments directly, which keeps braces out of the
+    // type names in check messages.
+    let const_argument = |expr: &syn::Expr| {
+      match expr {
+        syn::Expr::Lit(_) | syn::Expr::Path(_) => quote!(#expr),
+        _ => quote!({ #expr })
+      }
+    };
+    let runtime = runtime_crate();
+    let elem = &array.elem;
+    let stride = const_argument(&stride);
+    let len = const_argument(&array.len);
+    let mut field = field.clone();
+    field.ty = syn::parse_quote!(#runtime::Strided<#elem, #stride, #len>);
+    Ok(field)
+  }
+
+  /// Bytes the field occupies through `field_size`, which can't be fewer than the size of its
+  /// type when that's known while expanding the macro.
+  fn get_field_size(field: &Field) -> SynResult<Option<usize>> {
+    let Some(attr) = field
+      .attrs
+      .iter()
+      .find(|attr| attr.path().is_ident("field_size"))
+    else {
+      return Ok(None);
+    };
+
+    let size = parse_attribute_value::<LitInt>(attr)
+      .and_then(|lit| lit.base10_parse::<usize>())
+      .map_err(|_| SynError::new_spanned(attr, "Field size must be an integer literal."))?;
+
+    if let Some(type_size) = known_size(&field.ty).filter(|type_size| *type_size > size) {
+      return Err(SynError::new_spanned(
+        attr,
+        format!(
+          "Field `{}` takes {} bytes, which doesn't fit in its field_size of {} bytes.",
+          field.ident.as_ref().unwrap(),
+          type_size,
+          size
+        )
+      ));
+    }
+    Ok(Some(size))
+  }
+
+  fn get_field_align(field: &Field, offset: &Offset) -> SynResult<Option<usize>> {
+    let Some(attr) = field
+      .attrs
+      .iter()
+      .find(|attr| attr.path().is_ident("field_align"))
+    else {
+      return Ok(None);
+    };
+
+    let align = parse_attribute_value::<LitInt>(attr)
+      .and_then(|lit| lit.base10_parse::<usize>())
+      .map_err(|_| SynError::new_spanned(attr, "Field alignment must be an integer literal."))?;
+
+    if !align.is_power_of_two() {
+      return Err(SynError::new_spanned(
+        attr,
+        "Field alignment must be a power of two."
+      ));
+    }
+
+    if let Some(offset) = offset.known() {
+      if offset % align != 0 {
+        return Err(SynError::new_spanned(
+          attr,
+          format!(
+            "Field offset 0x{:X} is not aligned to 0x{:X} bytes.",
+            offset, align
+          )
+        ));
+      }
+    }
+
+    Ok(Some(align))
+  }
+
+  fn get_gap_end_value(attr: &Attribute) -> SynResult<usize> {
+    parse_attribute_value::<LitInt>(attr)
+      .and_then(|lit| lit.base10_parse::<usize>())
+      .map_err(|_| SynError::new_spanned(attr, "Gap end must be an integer literal."))
+  }
+
+  fn get_gap(field: &Field, gap: &Attribute) -> SynResult<usize> {
+    if let Some(attr) = field
+      .attrs
+      .iter()
+      .find(|attr| attr.path().is_ident("field_offset"))
+    {
+      return Err(SynError::new_spanned(
+        attr,
+        "A gap can't also have a field_offset."
+      ));
+    }
+
+    if let Some(attr) = field
+      .attrs
+      .iter()
+      .find(|attr| attr.path().is_ident("accessor"))
+    {
+      return Err(SynError::new_spanned(
+        attr,
+        "A gap has no accessors to rename."
+      ));
+    }
+
+    match &field.ty {
+      Type::Tuple(tuple) if tuple.elems.is_empty() => Self::get_gap_end_value(gap),
+      ty => {
+        Err(SynError::new_spanned(
+          ty,
+          "A gap must have the unit type `()`."
+        ))
+      }
+    }
+  }
+
+  /// Combines the predicates of all `cfg` attributes on a field.
+  fn get_cfg(field: &Field) -> SynResult<Option<proc_macro2::TokenStream>> {
+    let predicates = field
+      .attrs
+      .iter()
+      .filter(|attr| attr.path().is_ident("cfg"))
+      .map(|attr| attr.parse_args::<proc_macro2::TokenStream>())
+      .collect::<SynResult<Vec<_>>>()?;
+
+    Ok(match predicates.len() {
+      0 => None,
+      1 => predicates.into_iter().next(),
+      _ => Some(quote!(all(#(#predicates),*)))
+    })
+  }
+
+  /// Offset the next field starts at when a `cfg` dependent field is left out or not.
+  fn cfg_cursor(
+    cfg: &proc_macro2::TokenStream,
+    present: Offset,
+    current_offset: &Offset,
+    previous_type: Option<&Type>
+  ) -> Offset {
+    let absent = match previous_type {
+      Some(ty) => current_offset.after(ty),
+      None => current_offset.clone()
+    };
+    Offset::Expr(quote!(if cfg!(#cfg) { #present } else { #absent }))
+  }
+
+  /// Collects the `marker` fields, each placed in front of the field declared after it.
+  fn get_marker_fields(
+    declared: &[&Field],
+    desired_size: Option<&Offset>,
+    base: bool
+  ) -> SynResult<Vec<MarkerField>> {
+    let mut markers = Vec::new();
+    let mut before = usize::from(base);
+    for field in declared.iter().copied() {
+      if !is_marker(field) {
+        if !field.attrs.iter().any(is_virtual_attribute) && !is_flexible(field) {
+          before += 1;
+        }
+        continue;
+      }
+
+      if let Some(conflict) = field.attrs.iter().find(|attr| {
+        is_helper_attribute(attr)
+          && !attr.path().is_ident("marker")
+          && !attr.path().is_ident("field_offset")
+      }) {
+        return Err(SynError::new_spanned(
+          conflict,
+          "A marker can only have a field_offset."
+        ));
+      }
+      let offset = field
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("field_offset"))
+        .map(|attr| Self::get_field_offset_value(attr, desired_size, &[]))
+        .transpose()?;
+
+      markers.push(MarkerField {
+        field: field.clone(),
+        offset,
+        cfg: Self::get_cfg(field)?,
+        before
+      });
+    }
+    Ok(markers)
+  }
+
+  /// Orders the fields by their offset for `sort`, which requires every offset to be known while
+  /// expanding the macro.
+  fn sort_fields<'a>(
+    data: &'a DataStruct,
+    desired_size: Option<&Offset>
+  ) -> SynResult<Vec<&'a Field>> {
+    let mut keyed = Vec::new();
+    // Markers without an offset stay in front of the field declared after them.
+    let mut pending = Vec::new();
+    for field in &data.fields {
+      if let Some(gap) = field.attrs.iter().find(|attr| attr.path().is_ident("gap")) {
+        return Err(SynError::new_spanned(
+          gap,
+          "Gaps can't be combined with sort, as they depend on the previous field."
+        ));
+      }
+      if field.attrs.iter().any(is_virtual_attribute) || is_flexible(field) {
+        keyed.push(((0, true), field));
+        continue;
+      }
+      let field_offset = field
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("field_offset"));
+      if is_marker(field) && field_offset.is_none() {
+        pending.push(field);
+        continue;
+      }
+
+      let offset = field_offset
+        .map(|attr| Self::get_field_offset_value(attr, desired_size, &[]))
+        .transpose()?
+        .and_then(|offset| offset.known())
+        .ok_or_else(|| {
+          SynError::new_spanned(
+            field,
+            "Fields of a sorted struct require an integer literal field_offset."
+          )
+        })?;
+      if is_marker(field) {
+        keyed.push(((offset, false), field));
+        continue;
+      }
+      keyed.extend(pending.drain(..).map(|marker| ((offset, false), marker)));
+      keyed.push(((offset, true), field));
+    }
+    keyed.extend(
+      pending
+        .into_iter()
+        .map(|marker| ((usize::MAX, false), marker))
+    );
+
+    keyed.sort_by_key(|(key, _)| *key);
+    Ok(keyed.into_iter().map(|(_, field)| field).collect())
+  }
+
+  /// Rejects a field placed at `offset` that overlaps the previous field at `current`, when the
+  /// size of the previous field is known while expanding the macro.
+  fn check_overlap(
+    previous: &[FieldInfo],
+    previous_type: Option<&Type>,
+    current: usize,
+    field: &Field,
+    offset: usize,
+    span: proc_macro2::TokenStream
+  ) -> SynResult<()> {
+    let previous = previous.last().zip(previous_type.and_then(known_size));
+    let Some((previous, size)) = previous.filter(|(_, size)| current + size > offset) else {
+      return Ok(());
+    };
+
+    let ident = field.ident.as_ref().unwrap();
+    let previous_ident = previous.field.ident.as_ref().unwrap();
+    let mut error = SynError::new_spanned(
+      span,
+      format!(
+        "Field `{}` at 0x{:X} overlaps field `{}` (0x{:X} + {} bytes), the lowest valid offset \
+         is 0x{:X}.",
+        ident,
+        offset,
+        previous_ident,
+        current,
+        size,
+        current + size
+      )
+    );
+    error.combine(SynError::new_spanned(
+      previous_ident,
+      format!("`{}` is declared here.", previous_ident)
+    ));
+    Err(error)
+  }
+
+  #[allow(clippy::type_complexity)]
+  fn get_fields(
+    fields: &[&Field],
+    desired_size: Option<&Offset>,
+    base: Option<&Type>
+  ) -> SynResult<(Vec<FieldInfo>, (Offset, Option<Type>))> {
+    let mut result = Vec::<FieldInfo>::new();
+
+    let mut current_offset = Offset::Known(0);
+    let mut previous_type: Option<Type> = None;
+    if let Some(base) = base {
+      // The base is a hidden field at offset 0, following fields are placed after it.
+      result.push(FieldInfo {
+        field:           Field {
+          attrs:       vec![syn::parse_quote!(#[doc(hidden)])],
+          vis:         syn::Visibility::Inherited,
+          mutability:  syn::FieldMutability::None,
+          ident:       Some(syn::Ident::new("__base", Span::call_site())),
+          colon_token: Some(Default::default()),
+          ty:          base.clone()
+        },
+        kind:            FieldKind::Data,
+        previous_offset: Offset::Known(0),
+        previous_type:   None,
+        offset:          Offset::Known(0),
+        align:           None,
+        size:            None,
+        cfg:             None,
+        accessor:        None,
+        volatile:        false,
+        access:          Access::ReadWrite,
+        endian:          None,
+        nested:          None,
+        base:            true
+      });
+      previous_type = Some(base.clone());
+    }
+    for field in fields.iter().copied() {
+      if field.attrs.iter().any(is_virtual_attribute) || is_flexible(field) || is_marker(field) {
+        continue;
+      }
+
+      let cfg = Self::get_cfg(field)?;
+
+      if let Some(gap) = field.attrs.iter().find(|attr| attr.path().is_ident("gap")) {
+        let end = Self::get_gap(field, gap)?;
+
+        if let Some(current) = current_offset.known() {
+          if current > end {
+            return Err(SynError::new_spanned(
+              gap,
+              "Gap end can't be lower than its predecessor."
+            ));
+          }
+          Self::check_overlap(
+            &result,
+            previous_type.as_ref(),
+            current,
+            field,
+            end,
+            gap.to_token_stream()
+          )?;
+        }
+
+        result.push(FieldInfo {
+          field:           field.clone(),
+          kind:            FieldKind::Gap,
+          previous_offset: current_offset.clone(),
+          previous_type:   previous_type.clone(),
+          offset:          Offset::Known(end),
+          align:           None,
+          size:            None,
+          cfg:             cfg.clone(),
+          accessor:        None,
+          volatile:        false,
+          access:          Access::ReadWrite,
+          endian:          None,
+          nested:          None,
+          base:            false
+        });
+
+        current_offset = match &cfg {
+          Some(cfg) => {
+            Self::cfg_cursor(
+              cfg,
+              Offset::Known(end),
+              &current_offset,
+              previous_type.as_ref()
+            )
+          }
+          None => Offset::Known(end)
+        };
+        previous_type = None;
+        continue;
+      }
+
+      let field = &Self::apply_stride(field)?;
+      let field_offset = field
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("field_offset"));
+
+      // Fields without a `field_offset` directly follow the previous field.
+      let offset = match field_offset {
+        Some(attr) => Self::get_field_offset_value(attr, desired_size, &result)?,
+        None => {
+          match &previous_type {
+            Some(ty) => current_offset.after(ty),
+            None => current_offset.clone()
+          }
+        }
+      };
+
+      if let (Some(current), Some(offset)) = (current_offset.known(), offset.known()) {
+        if current > offset {
+          return Err(SynError::new_spanned(
+            field_offset,
+            "Field offset can't be lower than its predecessor."
+          ));
+        }
+
+        let span = field_offset.map_or_else(
+          || field.ident.to_token_stream(),
+          |attr| attr.to_token_stream()
+        );
+        Self::check_overlap(
+          &result,
+          previous_type.as_ref(),
+          current,
+          field,
+          offset,
+          span
+        )?;
+      }
+
+      let align = Self::get_field_align(field, &offset)?;
+      let size = Self::get_field_size(field)?;
+
+      result.push(FieldInfo {
+        field: field.clone(),
+        kind: FieldKind::Data,
+        previous_offset: current_offset.clone(),
+        previous_type: previous_type.clone(),
+        offset: offset.clone(),
+        align,
+        size,
+        cfg: cfg.clone(),
+        accessor: Self::get_accessor(field)?,
+        volatile: field
+          .attrs
+          .iter()
+          .any(|attr| attr.path().is_ident("volatile")),
+        access: Self::get_access(field)?,
+        endian: Self::get_endian(field)?,
+        nested: Self::get_nested(field)?,
+        base: false
+      });
+
+      // Following fields are placed after the `field_size` rather than the size of the type.
+      let size_type = result.last().unwrap().size_type();
+      match &cfg {
+        Some(cfg) => {
+          current_offset = Self::cfg_cursor(
+            cfg,
+            offset.after(&size_type),
+            &current_offset,
+            previous_type.as_ref()
+          );
+          previous_type = None;
+        }
+        None => {
+          current_offset = offset;
+          previous_type = Some(size_type);
+        }
+      }
+    }
+
+    Ok((result, (current_offset, previous_type)))
+  }
+
+  /// Rewrites the types padding is computed from so they can be named in constants, which don't
+  /// have the generic parameters of the struct. Padding can't depend on a type parameter, so
+  /// fields whose size depends on one have to be the last, in a struct without a size.
+  fn erase_generics(
+    generics: &syn::Generics,
+    args: &LayoutArgs,
+    data: &DataStruct,
+    fields: &mut [FieldInfo],
+    tail: &mut (Offset, Option<Type>)
+  ) -> SynResult<()> {
+    if generics.params.is_empty() {
+      return Ok(());
+    }
+    if let Some(param) = args
+      .size
+      .as_ref()
+      .and_then(|size| generics::used_param(size, generics))
+    {
+      return Err(SynError::new(
+        param.span(),
+        format!(
+          "The size can't depend on the generic parameter `{}`, as the trailing padding would \
+           have to be computed from it. Declare the varying bytes as a last field of type \
+           `[u8; {}]` instead, without a size.",
+          param, param
+        )
+      ));
+    }
+    if let Some((field, param)) = fields
+      .iter()
+      .find_map(|field| generics::used_param(&field.offset, generics).map(|param| (field, param)))
+    {
+      return Err(SynError::new(
+        param.span(),
+        format!(
+          "The offset of `{}` can't depend on the generic parameter `{}`, as the padding before \
+           it would have to be computed from it.",
+          field.field.ident.as_ref().unwrap().unraw(),
+          param
+        )
+      ));
+    }
+    let message = |param: syn::Ident| {
+      format!(
+        "The size of this field depends on the generic parameter `{}`, which padding can't \
+         depend on. Only the last field of a struct without a size can.",
+        param
+      )
+    };
+
+    if let Some((field, param)) = fields.iter().find_map(|field| {
+      let param = generics::const_type(&field.field.ty, generics).err()?;
+      field.size.map(|_| (field, param))
+    }) {
+      return Err(SynError::new_spanned(
+        &field.field.ty,
+        format!(
+          "The size of this field depends on the generic parameter `{}`, so the padding up to its \
+           field_size can't be computed.",
+          param
+        )
+      ));
+    }
+    for field in fields.iter_mut() {
+      let Some(ty) = field.previous_type.take() else {
+        continue;
+      };
+      field.previous_type = match generics::const_type(&ty, generics) {
+        Ok(ty) => Some(ty),
+        // Without padding only the overlap checks use the type.
+        Err(_) if args.verify => None,
+        Err(param) => return Err(SynError::new_spanned(ty, message(param)))
+      };
+    }
+
+    let derived = args.round_to.is_some() || data.fields.iter().any(is_flexible);
+    if let Some(ty) = &tail.1 {
+      tail.1 = match generics::const_type(ty, generics) {
+        Ok(ty) => Some(ty),
+        // Only the checks against the size use the type, which are skipped.
+        Err(_) if args.verify && !derived => None,
+        Err(_) if args.size.is_none() && !derived => tail.1.take(),
+        Err(param) => return Err(SynError::new_spanned(ty, message(param)))
+      };
+    }
+    Ok(())
+  }
+
+  /// Name and type of the padding after the field at `index` filling up its `field_size`, `None`
+  /// without one.
+  fn size_padding(
+    &self,
+    index: usize,
+    field: &FieldInfo
+  ) -> Option<(syn::Ident, proc_macro2::TokenStream)> {
+    let size = field.size?;
+    let ty = self.const_type(&field.field.ty)?;
+    let ident = syn::Ident::new(
+      &format!("__size{}", index),
+      field.field.ident.as_ref().unwrap().span()
+    );
+    let padding_type =
+      self.padding_type(quote!(#size.saturating_sub(::core::mem::size_of::<#ty>())));
+    Some((ident, padding_type))
+  }
+
+  /// Whether the struct may hold uninitialized bytes, the padding of `sparse` and `verify`
+  /// structs, or padding the compiler appends to a generic `repr(C)` struct, whose size can't be
+  /// checked.
+  fn may_be_uninit(&self) -> bool {
+    self.args.sparse
+      || self.args.verify
+      || (!self.packed && self.args.align.is_none() && self.const_self_type().is_none())
+  }
+
+  /// `ty` rewritten to be named in constants, `None` if its size depends on a generic parameter.
+  fn const_type(&self, ty: &Type) -> Option<Type> {
+    generics::const_type(ty, &self.derived.generics).ok()
+  }
+
+  /// The struct as named in constants, `None` if it has type or const parameters.
+  fn const_self_type(&self) -> Option<Type> {
+    generics::const_self_type(&self.derived.ident, &self.derived.generics)
+  }
+}
+
+/// Path to the `memory-layout` runtime crate, taking renamed dependencies into account.
+fn runtime_crate() -> proc_macro2::TokenStream {
+  match crate_name("memory-layout") {
+    Ok(FoundCrate::Name(name)) => {
+      let ident = syn::Ident::new(&name, Span::call_site());
+      quote!(::#ident)
+    }
+    _ => quote!(::memory_layout)
+  }
+}
+
+/// Path to the crate `name` if the crate being expanded depends on it, taking renamed dependencies
+/// into account.
+fn dependency_crate(name: &str) -> Option<proc_macro2::TokenStream> {
+  match crate_name(name).ok()? {
+    FoundCrate::Itself => Some(quote!(crate)),
+    FoundCrate::Name(name) => {
+      let ident = syn::Ident::new(&name, Span::call_site());
+      Some(quote!(::#ident))
+    }
+  }
+}
+
+/// The `offset_of!` macro used for the offset checks, `memoffset::offset_of!` re-exported by the
+/// runtime crate works on compilers predating `core::mem::offset_of!`.
+fn offset_of_macro() -> proc_macro2::TokenStream {
+  if cfg!(feature = "memoffset") {
+    let runtime = runtime_crate();
+    quote!(#runtime::__private::offset_of)
+  } else {
+    quote!(::core::mem::offset_of)
+  }
+}
+
+/// Checks the offset of every field using `offset_of!`, which `verify` relies on as no padding is
+/// inserted.
+fn generate_field_offset_checks(struct_info: &StructInfo) -> Vec<proc_macro2::TokenStream> {
+  let enabled = cfg!(any(feature = "offset_of", feature = "memoffset")) || struct_info.args.verify;
+  // Type parameters can't be named in the free constants holding the checks.
+  let Some(struct_ty) = struct_info.const_self_type().filter(|_| enabled) else {
+    return vec![];
+  };
+
+  let offset_of = offset_of_macro();
+  struct_info
+    .fields
+    .iter()
+    .filter(|field| matches!(field.kind, FieldKind::Data))
+    .map(|field| {
+      // Tuple structs are expanded by `tuple::generate_tuple`, every field here is named.
+      let ident = field.field.ident.as_ref().unwrap();
+      let offset = &field.offset;
+      let cfg = field.cfg_attr();
+      quote! {
+        #cfg
+        const _:() = assert!(#offset_of!(#struct_ty, #ident) == #offset);
+      }
+    })
+    .collect::<Vec<_>>()
+}
+
+fn generate_byte_access() -> proc_macro2::TokenStream {
+  let runtime = runtime_crate();
+  let diff_bytes = generate_diff_bytes();
+  quote! {
+    /// Reinterprets `bytes` as the struct without copying, so fields can be edited in place.
+    /// Checks the same conditions as `view`. Only available when the struct is also `NoUninit`,
+    /// as writing a field that holds uninitialized bytes would leave them in `bytes`.
+    pub fn view_mut(bytes: &mut [u8]) -> ::core::result::Result<&mut Self, #runtime::LayoutError>
+    where
+      for<'__memory_layout> Self: #runtime::AnyBitPattern + #runtime::NoUninit
+    {
+      Self::view(bytes)?;
+      ::core::result::Result::Ok(unsafe { &mut *(bytes.as_mut_ptr() as *mut Self) })
+    }
+
+    /// Views the struct as its raw bytes, including padding. Only available when every field is
+    /// `NoUninit`.
+    pub fn as_bytes(&self) -> &[u8]
+    where
+      for<'__memory_layout> Self: #runtime::NoUninit
+    {
+      unsafe {
+        ::core::slice::from_raw_parts(
+          self as *const Self as *const u8,
+          ::core::mem::size_of::<Self>()
+        )
+      }
+    }
+
+    /// Iterates over the name, offset and raw bytes of every field, ordered by offset.
+    pub fn field_bytes(&self) -> impl ::core::iter::Iterator<Item = (&'static str, usize, &[u8])>
+    where
+      for<'__memory_layout> Self: #runtime::NoUninit
+    {
+      let bytes = self.as_bytes();
+      Self::layout()
+        .fields
+        .iter()
+        .map(move |field| (field.name, field.offset, &bytes[field.offset..field.end()]))
+    }
+
+    /// Raw bytes from the start of the field named `from` up to the end of the field named `to`.
+    ///
+    /// Returns `None` if either field doesn't exist or `to` is placed before `from`.
+    pub fn slice_between(&self, from: &str, to: &str) -> ::core::option::Option<&[u8]>
+    where
+      for<'__memory_layout> Self: #runtime::NoUninit
+    {
+      let layout = Self::layout();
+      let from = layout.field(from)?;
+      let to = layout.field(to)?;
+      if to.offset < from.offset {
+        return ::core::option::Option::None;
+      }
+      self.as_bytes().get(from.offset..to.end())
+    }
+
+    #diff_bytes
+  }
+}
+
+fn generate_checked_parse(struct_info: &StructInfo) -> proc_macro2::TokenStream {
+  let runtime = runtime_crate();
+  // Fields with a runtime offset may be stored in padding, their bytes aren't checked.
+  let stored = struct_info.runtime.iter().map(|field| {
+    let ident = field.field.ident.as_ref().unwrap();
+    let offset_fn = syn::Ident::new(&format!("{}_offset", ident.unraw()), ident.span());
+    let ty = &field.field.ty;
+    let cfg = field
+      .field
+      .attrs
+      .iter()
+      .filter(|attr| attr.path().is_ident("cfg"));
+    quote! {
+      #(#cfg)*
+      Self::#offset_fn().map(|offset| offset..offset + ::core::mem::size_of::<#ty>())
+    }
+  });
+  quote! {
+    /// Reinterprets `bytes` as the struct without copying, checking that the buffer has the size
+    /// and alignment of the struct. Only available when every field is `AnyBitPattern`.
+    pub fn view(bytes: &[u8]) -> ::core::result::Result<&Self, #runtime::LayoutError>
+    where
+      for<'__memory_layout> Self: #runtime::AnyBitPattern
+    {
+      if bytes.len() != ::core::mem::size_of::<Self>() {
+        return ::core::result::Result::Err(#runtime::LayoutError::InvalidLength {
+          expected: ::core::mem::size_of::<Self>(),
+          actual:   bytes.len()
+        });
+      }
+      if bytes.as_ptr() as usize % ::core::mem::align_of::<Self>() != 0 {
+        return ::core::result::Result::Err(#runtime::LayoutError::Misaligned {
+          align: ::core::mem::align_of::<Self>()
+        });
+      }
+      ::core::result::Result::Ok(unsafe { &*(bytes.as_ptr() as *const Self) })
+    }
+
+    /// Reads the struct from raw bytes, checking that the buffer has the size of the struct and
+    /// that all padding, including gaps, is zero. Bytes of fields with a runtime offset that has
+    /// been set aren't padding. Only available when every field is `AnyBitPattern`.
+    pub fn from_bytes_checked(
+      bytes: &[u8]
+    ) -> ::core::result::Result<Self, #runtime::LayoutError>
+    where
+      for<'__memory_layout> Self: #runtime::AnyBitPattern
+    {
+      let layout = Self::layout();
+      if bytes.len() != layout.size {
+        return ::core::result::Result::Err(#runtime::LayoutError::InvalidLength {
+          expected: layout.size,
+          actual:   bytes.len()
+        });
+      }
+      let stored: &[::core::option::Option<::core::ops::Range<usize>>] = &[#(#stored),*];
+      for offset in layout.padding().flatten() {
+        let field = stored.iter().flatten().any(|range| range.contains(&offset));
+        if bytes[offset] != 0 && !field {
+          return ::core::result::Result::Err(#runtime::LayoutError::NonZeroPadding { offset });
+        }
+      }
+      ::core::result::Result::Ok(unsafe { ::core::ptr::read_unaligned(bytes.as_ptr() as *const Self) })
+    }
+  }
+}
+
+/// `to_bytes` and `from_bytes`, converting between the struct and an owned array of its size. The
+/// size of generic structs can't be used as an array length, so they don't get these.
+fn generate_owned_bytes(struct_info: &StructInfo) -> Option<proc_macro2::TokenStream> {
+  if !struct_info.derived.generics.params.is_empty() {
+    return None;
+  }
+
+  let runtime = runtime_crate();
+  let name = &struct_info.derived.ident;
+  let to_bytes = (!struct_info.args.sparse && !struct_info.args.verify).then(|| {
+    if let Some(fill) =
+      struct_info.fill_padding(quote!(bytes.as_mut_ptr() as *mut Self))
+    {
+      return quote! {
+        /// Copies the raw bytes of the struct into an array, with padding set to the `pad_fill`
+        /// byte. Only available when every field is `NoUninit`.
+        pub fn to_bytes(&self) -> [u8; ::core::mem::size_of::<#name>()]
+        where
+          for<'__memory_layout> Self: #runtime::NoUninit
+        {
+          let mut bytes = unsafe {
+            ::core::ptr::read_unaligned(self as *const Self as *const [u8; ::core::mem::size_of::<#name>()])
+          };
+          #fill
+          bytes
+        }
+      };
+    }
+    quote! {
+      /// Copies the raw bytes of the struct, including padding, into an array. Only available when
+      /// every field is `NoUninit`.
+      pub fn to_bytes(&self) -> [u8; ::core::mem::size_of::<#name>()]
+      where
+        for<'__memory_layout> Self: #runtime::NoUninit
+      {
+        unsafe {
+          ::core::ptr::read_unaligned(self as *const Self as *const [u8; ::core::mem::size_of::<#name>()])
+        }
+      }
+    }
+  });
+  Some(quote! {
+    #to_bytes
+
+    /// Creates the struct from an array of its raw bytes. Only available when every field is
+    /// `AnyBitPattern`.
+    pub fn from_bytes(bytes: [u8; ::core::mem::size_of::<#name>()]) -> Self
+    where
+      for<'__memory_layout> Self: #runtime::AnyBitPattern
+    {
+      unsafe { ::core::mem::transmute(bytes) }
+    }
+  })
+}
+
+#[cfg(feature = "alloc")]
+fn generate_diff_bytes() -> proc_macro2::TokenStream {
+  let runtime = runtime_crate();
+  quote! {
+    /// Compares the raw bytes of the struct, including padding, against `other`.
+    ///
+    /// Only bytes up to the shorter of the two lengths are compared, a length mismatch is
+    /// reported separately.
+    pub fn diff_bytes(&self, other: &[u8]) -> #runtime::BytesDiff
+    where
+      for<'__memory_layout> Self: #runtime::NoUninit
+    {
+      #runtime::BytesDiff::between(self.as_bytes(), other)
+    }
+  }
+}
+
+#[cfg(not(feature = "alloc"))]
+fn generate_diff_bytes() -> proc_macro2::TokenStream {
+  quote!()
+}
+
+#[cfg(feature = "alloc")]
+pub(crate) fn generate_layout_diagram() -> proc_macro2::TokenStream {
+  let runtime = runtime_crate();
+  quote! {
+    /// Renders a byte map of the layout, see `memory_layout::Layout::diagram`.
+    pub fn layout_diagram() -> #runtime::__private::String {
+      Self::layout().diagram()
+    }
+  }
+}
+
+#[cfg(not(feature = "alloc"))]
+pub(crate) fn generate_layout_diagram() -> proc_macro2::TokenStream {
+  quote!()
+}
+
+/// Size of primitive types and arrays of them, which is known while expanding the macro. `usize`
+/// and `isize` are left out as they depend on the target.
+fn known_size(ty: &Type) -> Option<usize> {
+  match ty {
+    Type::Path(path) => {
+      match path.path.get_ident()?.to_string().as_str() {
+        "u8" | "i8" | "bool" => Some(1),
+        "u16" | "i16" => Some(2),
+        "u32" | "i32" | "f32" | "char" => Some(4),
+        "u64" | "i64" | "f64" => Some(8),
+        "u128" | "i128" => Some(16),
+        _ => None
+      }
+    }
+    Type::Array(array) => {
+      let syn::Expr::Lit(syn::ExprLit {
+        lit: syn::Lit::Int(len),
+        ..
+      }) = &array.len
+      else {
+        return None;
+      };
+      len
+        .base10_parse::<usize>()
+        .ok()?
+        .checked_mul(known_size(&array.elem)?)
+    }
+    Type::Paren(paren) => known_size(&paren.elem),
+    Type::Group(group) => known_size(&group.elem),
+    _ => None
+  }
+}
+
+/// Overlap checks for fields whose end isn't known while expanding the macro.
+fn generate_overlap_checks(struct_info: &StructInfo) -> Vec<proc_macro2::TokenStream> {
+  struct_info
+    .fields
+    .windows(2)
+    .filter_map(|pair| {
+      let [previous, field] = pair else {
+        unreachable!()
+      };
+      let ty = field.previous_type.as_ref()?;
+      if field.offset.known().is_some()
+        && field.previous_offset.known().is_some()
+        && known_size(ty).is_some()
+      {
+        return None;
+      }
+
+      let offset = &field.offset;
+      let previous_offset = &field.previous_offset;
+      let ident = field.field.ident.as_ref().unwrap();
+      let previous = if previous.base {
+        format!("the base `{}`", quote!(#ty))
+      } else {
+        format!("field `{}`", previous.field.ident.as_ref().unwrap())
+      };
+      let size = match known_size(ty) {
+        Some(size) => format!("{} bytes", size),
+        None => format!("`size_of::<{}>()` bytes", quote!(#ty))
+      };
+      let lowest = match (previous_offset.known(), known_size(ty)) {
+        (Some(previous), Some(size)) => {
+          format!(", the lowest valid offset is 0x{:X}", previous + size)
+        }
+        _ => String::new()
+      };
+      let message = check_message(format!(
+        "Field `{}` at {} overlaps {} ({} + {}){}.",
+        ident,
+        offset.describe(),
+        previous,
+        previous_offset.describe(),
+        size,
+        lowest
+      ));
+      let cfg = field.cfg_attr();
+      Some(quote! {
+        #cfg
+        const _: () = assert!(
+          #previous_offset + ::core::mem::size_of::<#ty>() <= #offset,
+          #message
+        );
+      })
+    })
+    .collect::<Vec<_>>()
+}
+
+/// Checks that an aligned type ends on a multiple of its alignment, which keeps the compiler from
+/// appending padding of its own.
+fn generate_size_align_check(
+  name: &syn::Ident,
+  size: &Offset,
+  align: Option<usize>
+) -> SynResult<Option<proc_macro2::TokenStream>> {
+  let Some(align) = align else {
+    return Ok(None);
+  };
+  if let Some(size) = size.known() {
+    if size % align != 0 {
+      return Err(SynError::new_spanned(
+        name,
+        format!(
+          "Size 0x{:X} of `{}` is not a multiple of its alignment {}.",
+          size, name, align
+        )
+      ));
+    }
+    return Ok(None);
+  }
+
+  let message = check_message(format!(
+    "Size {} of `{}` is not a multiple of its alignment {}.",
+    size.describe(),
+    name,
+    align
+  ));
+  Ok(Some(quote! {
+    const _: () = assert!(#size % #align == 0, #message);
+  }))
+}
+
+/// Checks that the last field ends before the desired struct size. Fields behind a `cfg` may be
+/// left out, so the fields before them are checked up to one that is always present.
+fn generate_tail_check(struct_info: &StructInfo) -> SynResult<Option<proc_macro2::TokenStream>> {
+  let Some(size) = &struct_info.args.size else {
+    return Ok(None);
+  };
+
+  let mut checks = Vec::new();
+  for field in struct_info.fields.iter().rev() {
+    let ident = field.field.ident.as_ref().unwrap();
+    let cfg = field.cfg_attr();
+    let offset = &field.offset;
+    match field.kind {
+      FieldKind::Data => {
+        if let (Some(size), Some(offset)) = (size.known(), offset.known()) {
+          if offset > size {
+            return Err(SynError::new_spanned(
+              ident,
+              format!(
+                "Struct size 0x{:X} is smaller than the offset 0x{:X} of field `{}`.",
+                size, offset, ident
+              )
+            ));
+          }
+          match known_size(&field.size_type()) {
+            Some(field_size) if offset + field_size > size && cfg.is_none() => {
+              return Err(SynError::new_spanned(
+                ident,
+                format!(
+                  "Struct size 0x{:X} is smaller than the end of field `{}` (0x{:X} + {} bytes).",
+                  size, ident, offset, field_size
+                )
+              ));
+            }
+            Some(_) if cfg.is_none() => break,
+            _ => {}
+          }
+        }
+        if let Some(ty) = struct_info.const_type(&field.size_type()) {
+          let message = check_message(format!(
+            "Struct size {} is smaller than the end of field `{}` ({} + `size_of::<{}>()` bytes).",
+            size.describe(),
+            ident,
+            offset.describe(),
+            quote!(#ty)
+          ));
+          checks.push(quote! {
+            #cfg
+            const _: () = assert!(#offset + ::core::mem::size_of::<#ty>() <= #size, #message);
+          });
+        }
+      }
+      FieldKind::Gap => {
+        if let (Some(size), Some(end)) = (size.known(), offset.known()) {
+          if end > size {
+            return Err(SynError::new_spanned(
+              ident,
+              format!(
+                "Struct size 0x{:X} is smaller than the end 0x{:X} of gap `{}`.",
+                size, end, ident
+              )
+            ));
+          }
+        } else {
+          let message = check_message(format!(
+            "Struct size {} is smaller than the end {} of gap `{}`.",
+            size.describe(),
+            offset.describe(),
+            ident
+          ));
+          checks.push(quote! {
+            #cfg
+            const _: () = assert!(#offset <= #size, #message);
+          });
+        }
+      }
+    }
+    if field.cfg.is_none() {
+      break;
+    }
+  }
+
+  Ok(Some(quote!(#(#checks)*)))
+}
+
+/// Ordering checks for offsets that aren't known while expanding the macro.
+fn generate_order_checks(struct_info: &StructInfo) -> Vec<proc_macro2::TokenStream> {
+  struct_info
+    .fields
+    .iter()
+    .filter(|field| field.offset.known().is_none() || field.previous_offset.known().is_none())
+    .map(|field| {
+      let offset = &field.offset;
+      let previous_offset = &field.previous_offset;
+      let message = check_message(format!(
+        "Offset of `{}` can't be lower than its predecessor.",
+        field.field.ident.as_ref().unwrap()
+      ));
+      let cfg = field.cfg_attr();
+      quote! {
+        #cfg
+        const _: () = assert!(#offset >= #previous_offset, #message);
+      }
+    })
+    .collect::<Vec<_>>()
+}
+
+/// Alignment checks for `field_align` offsets that aren't known while expanding the macro.
+fn generate_align_checks(struct_info: &StructInfo) -> Vec<proc_macro2::TokenStream> {
+  struct_info
+    .fields
+    .iter()
+    .filter(|field| field.offset.known().is_none())
+    .filter_map(|field| {
+      let align = field.align?;
+      let offset = &field.offset;
+      let message = check_message(format!(
+        "Offset of `{}` is not aligned to 0x{:X} bytes.",
+        field.field.ident.as_ref().unwrap(),
+        align
+      ));
+      let cfg = field.cfg_attr();
+      Some(quote! {
+        #cfg
+        const _: () = assert!(#offset % #align == 0, #message);
+      })
+    })
+    .collect::<Vec<_>>()
+}
+
+fn generate_aligned_getters(struct_info: &StructInfo) -> Vec<proc_macro2::TokenStream> {
+  struct_info
+    .fields
+    .iter()
+    .filter(|field| field.access.readable())
+    .filter_map(|field| {
+      let align = field.align?;
+      let ident = field.field.ident.as_ref().unwrap();
+      let accessor = field.accessor_ident();
+      let getter = syn::Ident::new(&format!("{}_ref", accessor.unraw()), accessor.span());
+      let ty = &field.field.ty;
+      let vis = &field.field.vis;
+      let doc = format!(
+        "Borrows `{}`, returns `None` if the field isn't aligned to 0x{:X} bytes in memory.",
+        ident, align
+      );
+      let cfg = field.cfg_attr();
+      Some(quote! {
+        #cfg
+        #[doc = #doc]
+        #[inline]
+        #vis fn #getter(&self) -> ::core::option::Option<&#ty> {
+          let ptr = ::core::ptr::addr_of!(self.#ident);
+          if ptr as usize % #align == 0 && ptr as usize % ::core::mem::align_of::<#ty>() == 0 {
+            ::core::option::Option::Some(unsafe { &*ptr })
+          } else {
+            ::core::option::Option::None
+          }
+        }
+      })
+    })
+    .collect::<Vec<_>>()
+}
+
+/// Checks that every field is naturally aligned when the struct isn't packed.
+fn generate_natural_align_checks(struct_info: &StructInfo) -> Vec<proc_macro2::TokenStream> {
+  if struct_info.packed {
+    return vec![];
+  }
+
+  struct_info
+    .fields
+    .iter()
+    .filter(|field| matches!(field.kind, FieldKind::Data))
+    .filter_map(|field| {
+      let offset = &field.offset;
+      let ty = struct_info.const_type(&field.field.ty)?;
+      let message = check_message(format!(
+        "`{}` is not naturally aligned, which `repr(C)` requires.",
+        field.field.ident.as_ref().unwrap()
+      ));
+      let cfg = field.cfg_attr();
+      Some(quote! {
+        #cfg
+        const _: () = assert!(#offset % ::core::mem::align_of::<#ty>() == 0, #message);
+      })
+    })
+    .collect::<Vec<_>>()
+}
+
+/// `Deref` and `DerefMut` implementations to the base of the struct, which is stored at offset 0.
+fn generate_base_deref(struct_info: &StructInfo) -> Option<proc_macro2::TokenStream> {
+  let base = struct_info.args.base.as_ref()?;
+  let name = &struct_info.derived.ident;
+  let (impl_generics, ty_generics, where_clause) = struct_info.derived.generics.split_for_impl();
+  let message = check_message(format!(
+    "`{}` is aligned to fewer bytes than its base `{}`, add `#[repr(C)]` or an `align` to the \
+     struct.",
+    name,
+    quote!(#base)
+  ));
+  // Generic structs can't be named in a free constant, so the check is also an associated constant
+  // that `deref` evaluates for every instantiation.
+  let align_check = struct_info.const_self_type().map(|struct_ty| {
+    quote! {
+      const _: () = assert!(
+        ::core::mem::align_of::<#struct_ty>() >= ::core::mem::align_of::<#base>(),
+        #message
+      );
+    }
+  });
+
+  Some(quote! {
+    impl #impl_generics #name #ty_generics #where_clause {
+      #[doc(hidden)]
+      const __BASE_ALIGN_CHECK: () = assert!(
+        ::core::mem::align_of::<Self>() >= ::core::mem::align_of::<#base>(),
+        #message
+      );
+    }
+
+    impl #impl_generics ::core::ops::Deref for #name #ty_generics #where_clause {
+      type Target = #base;
+
+      fn deref(&self) -> &#base {
+        let () = Self::__BASE_ALIGN_CHECK;
+        unsafe { &*(self as *const Self as *const #base) }
+      }
+    }
+
+    impl #impl_generics ::core::ops::DerefMut for #name #ty_generics #where_clause {
+      fn deref_mut(&mut self) -> &mut #base {
+        let () = Self::__BASE_ALIGN_CHECK;
+        unsafe { &mut *(self as *mut Self as *mut #base) }
+      }
+    }
+
+    #align_check
+  })
+}
+
+/// Checks that nested `#[memory_layout]` structs occupy their declared size, and the size the field
+/// expects if one is given.
+fn generate_nested_checks(struct_info: &StructInfo) -> Vec<proc_macro2::TokenStream> {
+  let runtime = runtime_crate();
+  struct_info
+    .fields
+    .iter()
+    .filter_map(|field| {
+      let nested = field.nested.as_ref()?;
+      let ty = struct_info.const_type(&field.field.ty)?;
+      let ident = field.field.ident.as_ref().unwrap();
+      let declared = quote!(<#ty as #runtime::MemoryLayout>::SIZE);
+      let message = check_message(format!(
+        "`{}` of `{}` doesn't occupy its declared size.",
+        ident,
+        quote!(#ty)
+      ));
+      let expected = nested.size.as_ref().map(|size| {
+        let message = check_message(format!(
+          "`{}` of `{}` is expected to be {} bytes in size.",
+          ident,
+          quote!(#ty),
+          size.describe()
+        ));
+        quote!(
+          const _: () = assert!(#declared == #size, #message);
+        )
+      });
+      let cfg = field.cfg_attr();
+      Some(quote! {
+        #cfg
+        const _: () = assert!(::core::mem::size_of::<#ty>() == #declared, #message);
+        #cfg
+        #expected
+      })
+    })
+    .collect::<Vec<_>>()
+}
+
+/// `repr(C, align(n))` for an explicit alignment, `repr(C)` otherwise.
+fn repr_attribute(align: Option<usize>) -> proc_macro2::TokenStream {
+  match align {
+    Some(align) => {
+      let align = proc_macro2::Literal::usize_unsuffixed(align);
+      quote!(#[repr(C, align(#align))])
+    }
+    None => quote!(#[repr(C)])
+  }
+}
+
+/// Whether the type is a primitive integer, which can be byte-swapped.
+fn is_integer(ty: &Type) -> bool {
+  const INTEGERS: &[&str] = &[
+    "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize"
+  ];
+  matches!(ty, Type::Path(path) if INTEGERS.iter().any(|int| path.path.is_ident(int)))
+}
+
+/// Whether the type is a primitive unsigned integer, which bit fields are masked out of.
+fn is_unsigned_integer(ty: &Type) -> bool {
+  const UNSIGNED: &[&str] = &["u8", "u16", "u32", "u64", "u128", "usize"];
+  matches!(ty, Type::Path(path) if UNSIGNED.iter().any(|int| path.path.is_ident(int)))
+}
+
+/// Whether the type is the runtime's `CStrArray`, whose getter borrows it.
+fn is_cstr_array(ty: &Type) -> bool {
+  matches!(
+    ty,
+    Type::Path(path) if path.path.segments.last().map_or(false, |segment| segment.ident == "CStrArray")
+  )
+}
+
+/// Whether the type is a `[u8; N]`, which can be borrowed from a packed struct because its
+/// alignment is 1.
+fn is_byte_array(ty: &Type) -> bool {
+  match ty {
+    Type::Array(array) => matches!(&*array.elem, Type::Path(path) if path.path.is_ident("u8")),
+    _ => false
+  }
+}
+
+fn generate_getters(struct_info: &StructInfo) -> Vec<proc_macro2::TokenStream> {
+  struct_info
+    .fields
+    .iter()
+    .filter(|field| field.has_accessors() && field.access.readable())
+    .map(|field| {
+      let ident = field.field.ident.as_ref().unwrap();
+      let getter = field.accessor_ident();
+      let ty = &field.field.ty;
+      let vis = &field.field.vis;
+      let cfg = field.cfg_attr();
+
+      if field.volatile {
+        let doc = format!("Reads `{}` using a volatile read.", ident);
+        let read = field.native_value(quote!(unsafe {
+          ::core::ptr::addr_of!(self.#ident).read_volatile()
+        }));
+        return quote! {
+          #cfg
+          #[doc = #doc]
+          #[inline]
+          #vis fn #getter(&self) -> #ty
+          where
+            for<'__memory_layout> #ty: ::core::marker::Copy
+          {
+            #read
+          }
+        };
+      }
+
+      // Borrowed types are aligned to a byte, which the compiler can't tell for a generic length.
+      // The check is an associated constant evaluated by the accessors, as a free constant can't
+      // name types depending on generic parameters.
+      if field.is_borrowed() {
+        let doc = format!("Borrows `{}`.", ident);
+        let align_check = constant_ident("__ALIGN_CHECK", ident);
+        let message = check_message(format!(
+          "`{}` is borrowed by its getter, so its type has to be aligned to a byte.",
+          ident
+        ));
+        let mutable = (!is_byte_array(ty) && field.access.writable()).then(|| {
+          let getter_mut = syn::Ident::new(&format!("{}_mut", getter.unraw()), getter.span());
+          let doc = format!("Mutably borrows `{}`.", ident);
+          quote! {
+            #cfg
+            #[doc = #doc]
+            #[inline]
+            #vis fn #getter_mut(&mut self) -> &mut #ty {
+              let () = Self::#align_check;
+              unsafe { &mut *::core::ptr::addr_of_mut!(self.#ident) }
+            }
+          }
+        });
+        return quote! {
+          #cfg
+          #[doc(hidden)]
+          const #align_check: () = assert!(::core::mem::align_of::<#ty>() == 1, #message);
+
+          #cfg
+          #[doc = #doc]
+          #[inline]
+          #vis fn #getter(&self) -> &#ty {
+            let () = Self::#align_check;
+            unsafe { &*::core::ptr::addr_of!(self.#ident) }
+          }
+
+          #mutable
+        };
+      }
+
+      let doc = format!("Reads `{}` without requiring it to be aligned.", ident);
+      let read = field.native_value(quote!(unsafe {
+        ::core::ptr::addr_of!(self.#ident).read_unaligned()
+      }));
+      // The higher-ranked bound keeps the getter from failing to compile for non-`Copy` fields,
+      // the getter simply can't be called for those.
+      quote! {
+        #cfg
+        #[doc = #doc]
+        #[inline]
+        #vis fn #getter(&self) -> #ty
+        where
+          for<'__memory_layout> #ty: ::core::marker::Copy
+        {
+          #read
+        }
+      }
+    })
+    .collect::<Vec<_>>()
+}
+
+fn generate_pointer_accessors(struct_info: &StructInfo) -> Vec<proc_macro2::TokenStream> {
+  struct_info
+    .fields
+    .iter()
+    .filter(|field| field.has_accessors())
+    .map(|field| {
+      let ident = field.field.ident.as_ref().unwrap();
+      let accessor = field.accessor_ident();
+      let ptr = syn::Ident::new(&format!("{}_ptr", accessor.unraw()), accessor.span());
+      let ptr_mut = syn::Ident::new(&format!("{}_ptr_mut", accessor.unraw()), accessor.span());
+      let ty = &field.field.ty;
+      let vis = &field.field.vis;
+      let doc = format!(
+        "Pointer to `{}`, which may be unaligned. Doesn't create an intermediate reference.",
+        ident
+      );
+      let doc_mut = format!(
+        "Mutable pointer to `{}`, which may be unaligned. Doesn't create an intermediate \
+         reference.",
+        ident
+      );
+      let cfg = field.cfg_attr();
+      let mutable = field.access.writable().then(|| {
+        quote! {
+          #cfg
+          #[doc = #doc_mut]
+          #[inline]
+          #vis fn #ptr_mut(&mut self) -> *mut #ty {
+            ::core::ptr::addr_of_mut!(self.#ident)
+          }
+        }
+      });
+      quote! {
+        #cfg
+        #[doc = #doc]
+        #[inline]
+        #vis fn #ptr(&self) -> *const #ty {
+          ::core::ptr::addr_of!(self.#ident)
+        }
+
+        #mutable
+      }
+    })
+    .collect::<Vec<_>>()
+}
+
+fn generate_computed_getters(struct_info: &StructInfo) -> Vec<proc_macro2::TokenStream> {
+  struct_info
+    .computed
+    .iter()
+    .map(|computed| {
+      let ident = computed.field.ident.as_ref().unwrap();
+      let ty = &computed.field.ty;
+      let vis = &computed.field.vis;
+      let expr = &computed.expr;
+      let attrs = computed
+        .field
+        .attrs
+        .iter()
+        .filter(|attr| !is_helper_attribute(attr));
+      quote! {
+        #(#attrs)*
+        #[inline]
+        #vis fn #ident(&self) -> #ty {
+          #expr
+        }
+      }
+    })
+    .collect::<Vec<_>>()
+}
+
+/// Accessors viewing `count` elements of the trailing unsized array through pointer arithmetic.
+fn generate_flexible_accessors(struct_info: &StructInfo) -> Option<proc_macro2::TokenStream> {
+  let flexible = struct_info.flexible.as_ref()?;
+  let ident = flexible.field.ident.as_ref().unwrap();
+  let Type::Slice(slice) = &flexible.field.ty else {
+    unreachable!("unsized arrays are slices");
+  };
+  let element = &slice.elem;
+  let vis = &flexible.field.vis;
+  let offset = &flexible.offset;
+  let attrs = flexible
+    .field
+    .attrs
+    .iter()
+    .filter(|attr| !is_helper_attribute(attr))
+    .collect::<Vec<_>>();
+  let offset_constant = constant_ident("OFFSET", ident);
+  let mut_ident = format_ident!("{}_mut", ident.unraw());
+  let size_ident = format_ident!("size_with_{}", ident.unraw());
+  let offset_doc = format!("Offset of the unsized array `{}` in bytes.", ident);
+  let getter_doc = format!(
+    "Borrows the first `count` elements of the unsized array `{}` following the struct.",
+    ident
+  );
+  let safety_doc = " # Safety\n\n The memory following the struct must hold `count` valid \
+                    elements, aligned for their type, and `self` must be borrowed from memory \
+                    covering them.";
+  let size_doc = format!(
+    "Size of the struct followed by `count` elements of `{}`, for allocating it.",
+    ident
+  );
+  Some(quote! {
+    #[doc = #offset_doc]
+    pub const #offset_constant: usize = #offset;
+
+    #(#attrs)*
+    #[doc = #getter_doc]
+    ///
+    #[doc = #safety_doc]
+    #[inline]
+    #vis unsafe fn #ident(&self, count: usize) -> &[#element] {
+      let ptr = (self as *const Self as *const u8).add(Self::#offset_constant) as *const #element;
+      debug_assert!(ptr as usize % ::core::mem::align_of::<#element>() == 0);
+      ::core::slice::from_raw_parts(ptr, count)
+    }
+
+    #(#attrs)*
+    #[doc = #getter_doc]
+    ///
+    #[doc = #safety_doc]
+    #[inline]
+    #vis unsafe fn #mut_ident(&mut self, count: usize) -> &mut [#element] {
+      let ptr = (self as *mut Self as *mut u8).add(Self::#offset_constant) as *mut #element;
+      debug_assert!(ptr as usize % ::core::mem::align_of::<#element>() == 0);
+      ::core::slice::from_raw_parts_mut(ptr, count)
+    }
+
+    #[doc = #size_doc]
+    #vis const fn #size_ident(count: usize) -> usize {
+      let end = Self::#offset_constant + count * ::core::mem::size_of::<#element>();
+      if end > ::core::mem::size_of::<Self>() {
+        end
+      } else {
+        ::core::mem::size_of::<Self>()
+      }
+    }
+  })
+}
+
+/// Evaluates the checks of bit fields where the struct is defined, if it can be named there. Checks
+/// of generic structs are evaluated by the accessors.
+fn generate_bit_checks(struct_info: &StructInfo) -> Vec<proc_macro2::TokenStream> {
+  let Some(struct_ty) = struct_info.const_self_type() else {
+    return Vec::new();
+  };
+  struct_info
+    .bitfields
+    .iter()
+    .map(|bitfield| {
+      let check = constant_ident("__BIT_CHECK", bitfield.field.ident.as_ref().unwrap());
+      quote!(const _: () = #struct_ty::#check;)
+    })
+    .collect::<Vec<_>>()
+}
+
+/// Bound the accessors of a bit field need for its backing integer to be read and written as a
+/// whole. Only the type of the field the integer lies within has to be `AnyBitPattern + NoUninit`
+/// when that field is known while expanding the macro, otherwise the whole struct has to be.
+fn bit_backing_bound(struct_info: &StructInfo, bitfield: &BitField) -> proc_macro2::TokenStream {
+  let runtime = runtime_crate();
+  let start = bitfield.args.byte;
+  let backing = known_size(&bitfield.field.ty).and_then(|size| {
+    struct_info.fields.iter().find(|field| {
+      let Some((offset, field_size)) = field.offset.known().zip(known_size(&field.field.ty)) else {
+        return false;
+      };
+      field.has_accessors()
+        && field.cfg.is_none()
+        && offset <= start
+        && start + size <= offset + field_size
+    })
+  });
+  match backing {
+    Some(field) => {
+      let ty = &field.field.ty;
+      quote!(for<'__memory_layout> #ty: #runtime::AnyBitPattern + #runtime::NoUninit)
+    }
+    None => quote!(for<'__memory_layout> Self: #runtime::AnyBitPattern + #runtime::NoUninit)
+  }
+}
+
+/// Masked getters and setters for bit fields, the backing integer is read and written as a whole.
+/// They evaluate an associated constant checking that the bits fit in the backing integer, and
+/// that it lies within a field. Padding isn't part of the value of the struct, it may be
+/// uninitialized or overwritten by `pad_fill`, and `eq` and `hash` skip it.
+fn generate_bit_accessors(struct_info: &StructInfo) -> Vec<proc_macro2::TokenStream> {
+  struct_info
+    .bitfields
+    .iter()
+    .map(|bitfield| {
+      let ident = bitfield.field.ident.as_ref().unwrap();
+      let setter = setter_ident(ident);
+      let check = constant_ident("__BIT_CHECK", ident);
+      let ty = &bitfield.field.ty;
+      let vis = &bitfield.field.vis;
+      let BitOffsetArgs { byte, bit, width } = bitfield.args;
+      let attrs = bitfield
+        .field
+        .attrs
+        .iter()
+        .filter(|attr| !is_helper_attribute(attr));
+      let getter_doc = format!(
+        "Reads bits {}..{} of the integer at 0x{:X}.",
+        bit,
+        bit + width,
+        byte
+      );
+      let setter_doc = format!(
+        "Writes bits {}..{} of the integer at 0x{:X}.",
+        bit,
+        bit + width,
+        byte
+      );
+      let bits_message = check_message(format!(
+        "Bits {}..{} of `{}` don't fit in its backing integer.",
+        bit,
+        bit + width,
+        ident
+      ));
+      let byte_message = check_message(format!(
+        "The backing integer of `{}` extends past the end of the struct.",
+        ident
+      ));
+      let covered_message = check_message(format!(
+        "The backing integer of `{}` isn't within a field.",
+        ident
+      ));
+      let covers = struct_info
+        .fields
+        .iter()
+        .filter(|field| field.has_accessors())
+        .map(|field| {
+          let field_ident = field.field.ident.as_ref().unwrap();
+          let offset_constant = constant_ident("OFFSET", field_ident);
+          let end_constant = constant_ident("END", field_ident);
+          let cfg = field.cfg_attr();
+          quote! {
+            #cfg
+            {
+              covered |= Self::#offset_constant <= #byte
+                && #byte + ::core::mem::size_of::<#ty>() <= Self::#end_constant;
+            }
+          }
+        });
+      let bound = bit_backing_bound(struct_info, bitfield);
+      let end = (bit + width) as u32;
+      let width = width as u32;
+      let bit = bit as u32;
+      quote! {
+        #[doc(hidden)]
+        #[allow(clippy::int_plus_one)]
+        const #check: () = {
+          assert!(#end <= <#ty>::BITS, #bits_message);
+          assert!(
+            #byte + ::core::mem::size_of::<#ty>() <= ::core::mem::size_of::<Self>(),
+            #byte_message
+          );
+          let mut covered = false;
+          #(#covers)*
+          assert!(covered, #covered_message);
+        };
+
+        #(#attrs)*
+        #[doc = #getter_doc]
+        #[inline]
+        #vis fn #ident(&self) -> #ty
+        where
+          #bound
+        {
+          let () = Self::#check;
+          let raw = unsafe {
+            ((self as *const Self as *const u8).add(#byte) as *const #ty).read_unaligned()
+          };
+          (raw >> #bit) & (<#ty>::MAX >> (<#ty>::BITS - #width))
+        }
+
+        #[doc = #setter_doc]
+        #[inline]
+        #vis fn #setter(&mut self, value: #ty)
+        where
+          #bound
+        {
+          let () = Self::#check;
+          let mask = (<#ty>::MAX >> (<#ty>::BITS - #width)) << #bit;
+          unsafe {
+            let ptr = (self as *mut Self as *mut u8).add(#byte) as *mut #ty;
+            ptr.write_unaligned((ptr.read_unaligned() & !mask) | ((value << #bit) & mask));
+          }
+        }
+      }
+    })
+    .collect::<Vec<_>>()
+}
+
+/// Evaluates the checks of overlapping fields where the struct is defined, if it can be named
+/// there. Checks of generic structs are evaluated by the accessors.
+fn generate_overlay_checks(struct_info: &StructInfo) -> Vec<proc_macro2::TokenStream> {
+  let Some(struct_ty) = struct_info.const_self_type() else {
+    return Vec::new();
+  };
+  struct_info
+    .overlays
+    .iter()
+    .map(|overlay| {
+      let cfg = struct_info.overlay_target(overlay).cfg_attr();
+      let check = constant_ident("__OVERLAY_CHECK", overlay.field.ident.as_ref().unwrap());
+      quote! {
+        #cfg
+        const _: () = #struct_ty::#check;
+      }
+    })
+    .collect::<Vec<_>>()
+}
+
+/// Getters, setters and constants for overlapping fields, which read and write the bytes of the
+/// field they overlap. They evaluate an associated constant checking that the overlapping field
+/// fits within the field it overlaps.
+fn generate_overlay_accessors(struct_info: &StructInfo) -> Vec<proc_macro2::TokenStream> {
+  let runtime = runtime_crate();
+  struct_info
+    .overlays
+    .iter()
+    .map(|overlay| {
+      let target = struct_info.overlay_target(overlay);
+      let target_ident = &overlay.target;
+      let target_ty = &target.field.ty;
+      let offset = &target.offset;
+      let cfg = target.cfg_attr();
+      let ident = overlay.field.ident.as_ref().unwrap();
+      let setter = setter_ident(ident);
+      let offset_constant = constant_ident("OFFSET", ident);
+      let size_constant = constant_ident("SIZE", ident);
+      let end_constant = constant_ident("END", ident);
+      let check = constant_ident("__OVERLAY_CHECK", ident);
+      let ty = &overlay.field.ty;
+      let vis = &overlay.field.vis;
+      let message = check_message(format!(
+        "`{}` is larger than `{}`, which it overlaps.",
+        ident, target_ident
+      ));
+      let attrs = overlay
+        .field
+        .attrs
+        .iter()
+        .filter(|attr| !is_helper_attribute(attr));
+      let getter_doc = format!(
+        "Reads the bytes of `{}` as `{}`. Only available when `{}` is `AnyBitPattern` and the \
+         struct is `NoUninit`.",
+        target_ident,
+        ident,
+        quote!(#ty)
+      );
+      let setter_doc = format!(
+        "Writes `{}` over the bytes of `{}`. Only available when `{}` is `NoUninit` and `{}` is \
+         `AnyBitPattern`.",
+        ident,
+        target_ident,
+        quote!(#ty),
+        quote!(#target_ty)
+      );
+      let offset_doc = format!("Offset of `{}` in bytes.", ident);
+      let size_doc = format!("Size of `{}` in bytes.", ident);
+      let end_doc = format!("Offset of the first byte after `{}`.", ident);
+      quote! {
+        #cfg
+        #[doc = #offset_doc]
+        pub const #offset_constant: usize = #offset;
+        #cfg
+        #[doc = #size_doc]
+        pub const #size_constant: usize = ::core::mem::size_of::<#ty>();
+        #cfg
+        #[doc = #end_doc]
+        pub const #end_constant: usize = Self::#offset_constant + Self::#size_constant;
+
+        #cfg
+        #[doc(hidden)]
+        const #check: () = assert!(
+          ::core::mem::size_of::<#ty>() <= ::core::mem::size_of::<#target_ty>(),
+          #message
+        );
+
+        #cfg
+        #(#attrs)*
+        #[doc = #getter_doc]
+        #[inline]
+        #vis fn #ident(&self) -> #ty
+        where
+          for<'__memory_layout> #ty: ::core::marker::Copy + #runtime::AnyBitPattern,
+          for<'__memory_layout> Self: #runtime::NoUninit
+        {
+          let () = Self::#check;
+          unsafe { (::core::ptr::addr_of!(self.#target_ident) as *const #ty).read_unaligned() }
+        }
+
+        #cfg
+        #[doc = #setter_doc]
+        #[inline]
+        #vis fn #setter(&mut self, value: #ty)
+        where
+          for<'__memory_layout> #ty: ::core::marker::Copy + #runtime::NoUninit,
+          for<'__memory_layout> #target_ty: #runtime::AnyBitPattern
+        {
+          let () = Self::#check;
+          unsafe {
+            (::core::ptr::addr_of_mut!(self.#target_ident) as *mut #ty).write_unaligned(value)
+          }
+        }
+      }
+    })
+    .collect::<Vec<_>>()
+}
+
+/// Functions copying the struct or single fields from and to the memory of a `RemoteProcess`.
+fn generate_remote_accessors(struct_info: &StructInfo) -> proc_macro2::TokenStream {
+  let runtime = runtime_crate();
+  let fields = struct_info
+    .fields
+    .iter()
+    .filter(|field| field.has_accessors())
+    .map(|field| {
+      let ident = field.field.ident.as_ref().unwrap();
+      let accessor = field.accessor_ident().unraw();
+      let offset = constant_ident("OFFSET", ident);
+      let ty = &field.field.ty;
+      let vis = &field.field.vis;
+      let cfg = field.cfg_attr();
+      let read = field.access.readable().then(|| {
+        let read_fn = syn::Ident::new(&format!("read_{}_from", accessor), ident.span());
+        let doc = format!("Reads `{}` of the struct at `address` in `process`.", ident);
+        let value = field.native_value(quote!(value));
+        quote! {
+          #cfg
+          #[doc = #doc]
+          #vis fn #read_fn<P: #runtime::remote::RemoteProcess + ?::core::marker::Sized>(
+            process: &P,
+            address: usize
+          ) -> ::core::result::Result<#ty, P::Error>
+          where
+            for<'__memory_layout> #ty: #runtime::AnyBitPattern
+          {
+            let mut value = ::core::mem::MaybeUninit::<#ty>::zeroed();
+            process.read_bytes(address + Self::#offset, unsafe {
+              ::core::slice::from_raw_parts_mut(
+                value.as_mut_ptr() as *mut u8,
+                ::core::mem::size_of::<#ty>()
+              )
+            })?;
+            let value = unsafe { value.assume_init() };
+            ::core::result::Result::Ok(#value)
+          }
+        }
+      });
+      let write = field.access.writable().then(|| {
+        let write_fn = syn::Ident::new(&format!("write_{}_to", accessor), ident.span());
+        let doc = format!(
+          "Writes `{}` of the struct at `address` in `process`. Only available when the field type \
+           is `NoUninit`.",
+          ident
+        );
+        let value = field.stored_value(quote!(value));
+        quote! {
+          #cfg
+          #[doc = #doc]
+          #vis fn #write_fn<P: #runtime::remote::RemoteProcess + ?::core::marker::Sized>(
+            process: &P,
+            address: usize,
+            value: #ty
+          ) -> ::core::result::Result<(), P::Error>
+          where
+            for<'__memory_layout> #ty: ::core::marker::Copy + #runtime::NoUninit
+          {
+            let value = #value;
+            process.write_bytes(address + Self::#offset, unsafe {
+              ::core::slice::from_raw_parts(
+                &value as *const #ty as *const u8,
+                ::core::mem::size_of::<#ty>()
+              )
+            })
+          }
+        }
+      });
+      quote!(#read #write)
+    });
+  // Sparse and verified structs have uninitialized padding, so they can't be written as bytes.
+  let write = (!struct_info.args.sparse && !struct_info.args.verify).then(|| {
+    quote! {
+      /// Writes the struct, including padding, to `address` in `process`. Only available when
+      /// every field is `NoUninit`.
+      pub fn write_to<P: #runtime::remote::RemoteProcess + ?::core::marker::Sized>(
+        &self,
+        process: &P,
+        address: usize
+      ) -> ::core::result::Result<(), P::Error>
+      where
+        for<'__memory_layout> Self: #runtime::NoUninit
+      {
+        process.write_bytes(address, self.as_bytes())
+      }
+    }
+  });
+
+  quote! {
+    /// Reads the struct at `address` in `process`. The field types must be valid for any bit
+    /// pattern.
+    pub fn read_from<P: #runtime::remote::RemoteProcess + ?::core::marker::Sized>(
+      process: &P,
+      address: usize
+    ) -> ::core::result::Result<Self, P::Error>
+    where
+      for<'__memory_layout> Self: #runtime::AnyBitPattern
+    {
+      let mut value = ::core::mem::MaybeUninit::<Self>::zeroed();
+      process.read_bytes(address, unsafe {
+        ::core::slice::from_raw_parts_mut(
+          value.as_mut_ptr() as *mut u8,
+          ::core::mem::size_of::<Self>()
+        )
+      })?;
+      ::core::result::Result::Ok(unsafe { value.assume_init() })
+    }
+
+    #write
+
+    #(#fields)*
+  }
+}
+
+/// `set_offsets` and accessors for fields with runtime offsets, which are stored in a static table
+/// shared by every instance of the struct.
+fn generate_runtime_accessors(struct_info: &StructInfo) -> Option<proc_macro2::TokenStream> {
+  if struct_info.runtime.is_empty() {
+    return None;
+  }
+
+  let runtime = runtime_crate();
+  let count = struct_info.runtime.len();
+  let cfgs = struct_info
+    .runtime
+    .iter()
+    .map(|field| {
+      field
+        .field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("cfg"))
+        .collect::<Vec<_>>()
+    })
+    .collect::<Vec<_>>();
+  let params = struct_info.runtime.iter().zip(&cfgs).map(|(field, cfg)| {
+    let ident = field.field.ident.as_ref().unwrap();
+    quote!(#(#cfg)* #ident: usize)
+  });
+  let stores = struct_info
+    .runtime
+    .iter()
+    .zip(&cfgs)
+    .enumerate()
+    .map(|(i, (field, cfg))| {
+      let ident = field.field.ident.as_ref().unwrap();
+      let name = ident.unraw().to_string();
+      let ty = &field.field.ty;
+      quote! {
+        #(#cfg)*
+        {
+          let size = ::core::mem::size_of::<#ty>();
+          if #ident.checked_add(size).map_or(true, |end| end > ::core::mem::size_of::<Self>()) {
+            return ::core::result::Result::Err(::core::convert::From::from(
+              #runtime::LayoutError::FieldOutOfBounds {
+                field:  #name,
+                offset: #ident,
+                size
+              }
+            ));
+          }
+          offsets[#i] = #ident;
+        }
+      }
+    })
+    .collect::<Vec<_>>();
+  let scan = generate_scan_offsets(struct_info, &cfgs, &stores);
+  let accessors = struct_info
+    .runtime
+    .iter()
+    .zip(&cfgs)
+    .enumerate()
+    .map(|(i, (field, cfg))| {
+      let ident = field.field.ident.as_ref().unwrap();
+      let offset_fn = syn::Ident::new(&format!("{}_offset", ident.unraw()), ident.span());
+      let setter = setter_ident(ident);
+      let ty = &field.field.ty;
+      let vis = &field.field.vis;
+      let attrs = field
+        .field
+        .attrs
+        .iter()
+        .filter(|attr| !is_helper_attribute(attr));
+      let offset_doc = format!(
+        "Offset of `{}` in bytes, `None` until it's set through `set_offsets`.",
+        ident
+      );
+      let getter_doc = format!(
+        "Reads `{}` at its runtime offset. Only available when the field type is `AnyBitPattern` \
+         and the struct is `NoUninit`, as the offset may point anywhere in the struct.
+
+# Panics
+
+Panics if the offset hasn't been set.",
+        ident
+      );
+      let setter_doc = format!(
+        "Writes `{}` at its runtime offset. Only available when the field type is `NoUninit` and \
+         the struct is `AnyBitPattern`, as the offset may point anywhere in the struct.
+
+# Panics
+
+Panics if the offset hasn't been set.",
+        ident
+      );
+      let message = format!("The offset of `{}` hasn't been set.", ident);
+      quote! {
+        #(#cfg)*
+        #[doc = #offset_doc]
+        #[inline]
+        #vis fn #offset_fn() -> ::core::option::Option<usize> {
+          let offset = Self::__memory_layout_runtime_offsets()[#i]
+            .load(::core::sync::atomic::Ordering::Relaxed);
+          (offset != usize::MAX).then(|| offset)
+        }
+
+        #(#attrs)*
+        #[doc = #getter_doc]
+        #[inline]
+        #vis fn #ident(&self) -> #ty
+        where
+          for<'__memory_layout> #ty: ::core::marker::Copy + #runtime::AnyBitPattern,
+          for<'__memory_layout> Self: #runtime::NoUninit
+        {
+          let offset = Self::#offset_fn().expect(#message);
+          unsafe { ((self as *const Self as *const u8).add(offset) as *const #ty).read_unaligned() }
+        }
+
+        #(#cfg)*
+        #[doc = #setter_doc]
+        #[inline]
+        #vis fn #setter(&mut self, value: #ty)
+        where
+          for<'__memory_layout> #ty: ::core::marker::Copy + #runtime::NoUninit,
+          for<'__memory_layout> Self: #runtime::AnyBitPattern
+        {
+          let offset = Self::#offset_fn().expect(#message);
+          unsafe { ((self as *mut Self as *mut u8).add(offset) as *mut #ty).write_unaligned(value) }
+        }
+      }
+    });
+
+  Some(quote! {
+    #[doc(hidden)]
+    fn __memory_layout_runtime_offsets() -> &'static [::core::sync::atomic::AtomicUsize; #count] {
+      #[allow(clippy::declare_interior_mutable_const)]
+      const UNSET: ::core::sync::atomic::AtomicUsize = ::core::sync::atomic::AtomicUsize::new(usize::MAX);
+      static OFFSETS: [::core::sync::atomic::AtomicUsize; #count] = [UNSET; #count];
+      &OFFSETS
+    }
+
+    #[doc(hidden)]
+    fn __memory_layout_store_offsets(offsets: &[usize; #count]) {
+      for (slot, offset) in Self::__memory_layout_runtime_offsets().iter().zip(offsets.iter()) {
+        if *offset != usize::MAX {
+          slot.store(*offset, ::core::sync::atomic::Ordering::Relaxed);
+        }
+      }
+    }
+
+    /// Sets the offsets of the fields whose offsets are only known at runtime, such as ones found
+    /// by a signature scan. Every instance of the struct uses them, and they can be set again.
+    ///
+    /// Fails without changing any offset if a field wouldn't fit within the struct.
+    pub fn set_offsets(
+      #(#params),*
+    ) -> ::core::result::Result<(), #runtime::LayoutError> {
+      let mut offsets = [usize::MAX; #count];
+      #(#stores)*
+      Self::__memory_layout_store_offsets(&offsets);
+      ::core::result::Result::Ok(())
+    }
+
+    #scan
+
+    #(#accessors)*
+  })
+}
+
+/// `scan_offsets`, which resolves the `signature` of every field with a runtime offset in a module
+/// and sets the offsets.
+fn generate_scan_offsets(
+  struct_info: &StructInfo,
+  cfgs: &[Vec<&Attribute>],
+  stores: &[proc_macro2::TokenStream]
+) -> Option<proc_macro2::TokenStream> {
+  let runtime = runtime_crate();
+  let count = struct_info.runtime.len();
+  let resolves = struct_info
+    .runtime
+    .iter()
+    .zip(cfgs)
+    .map(|(field, cfg)| {
+      let ident = field.field.ident.as_ref().unwrap();
+      let signature = field.signature.as_ref()?;
+      let pattern = &signature.pattern;
+      let operand = signature
+        .operand
+        .as_ref()
+        .map(|operand| quote!(.operand(#operand)));
+      let rip_relative = signature.rip_relative.then(|| quote!(.rip_relative()));
+      Some(quote! {
+        #(#cfg)*
+        let #ident = #runtime::scan::Signature::new(#pattern)
+          #operand
+          #rip_relative
+          .resolve(module)?;
+      })
+    })
+    .collect::<Option<Vec<_>>>()?;
+
+  Some(quote! {
+    /// Finds the signature of every field with a runtime offset in `module`, the bytes of the
+    /// module as they're mapped in memory, and sets the offsets.
+    ///
+    /// Fails without changing any offset if a signature isn't found or a field wouldn't fit within
+    /// the struct.
+    pub fn scan_offsets(module: &[u8]) -> ::core::result::Result<(), #runtime::scan::ScanError> {
+      #(#resolves)*
+      let mut offsets = [usize::MAX; #count];
+      #(#stores)*
+      Self::__memory_layout_store_offsets(&offsets);
+      ::core::result::Result::Ok(())
+    }
+  })
+}
+
+fn setter_ident(ident: &syn::Ident) -> syn::Ident {
+  syn::Ident::new(&format!("set_{}", ident.unraw()), ident.span())
+}
+
+fn generate_setters(struct_info: &StructInfo) -> Vec<proc_macro2::TokenStream> {
+  struct_info
+    .fields
+    .iter()
+    .filter(|field| field.has_accessors() && field.access.writable())
+    .map(|field| {
+      let ident = field.field.ident.as_ref().unwrap();
+      let setter = setter_ident(field.accessor_ident());
+      let ty = &field.field.ty;
+      let vis = &field.field.vis;
+      let cfg = field.cfg_attr();
+      let (doc, write) = if field.volatile {
+        (
+          format!("Writes `{}` using a volatile write.", ident),
+          quote!(write_volatile)
+        )
+      } else {
+        (
+          format!("Writes `{}` without requiring it to be aligned.", ident),
+          quote!(write_unaligned)
+        )
+      };
+      let value = field.stored_value(quote!(value));
+      quote! {
+        #cfg
+        #[doc = #doc]
+        #[inline]
+        #vis fn #setter(&mut self, value: #ty)
+        where
+          for<'__memory_layout> #ty: ::core::marker::Copy
+        {
+          unsafe { ::core::ptr::addr_of_mut!(self.#ident).#write(#value) }
+        }
+      }
+    })
+    .collect::<Vec<_>>()
+}
+
+/// `Zeroable`, `AnyBitPattern` and `NoUninit` implementations, each applying when every field
+/// implements the trait, and the `zeroed()` constructor. Structs that may have uninitialized
+/// padding, see `StructInfo::may_be_uninit`, are never `NoUninit`.
+fn generate_zeroed(
+  struct_info: &StructInfo
+) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
+  let runtime = runtime_crate();
+  let zeroable = generate_marker_impl(struct_info, quote!(#runtime::Zeroable), quote!());
+  let any_bit_pattern =
+    generate_marker_impl(struct_info, quote!(#runtime::AnyBitPattern), quote!());
+  let no_uninit = (!struct_info.may_be_uninit())
+    .then(|| generate_marker_impl(struct_info, quote!(#runtime::NoUninit), quote!()));
+  let zeroable = quote! {
+    #zeroable
+    #any_bit_pattern
+    #no_uninit
+  };
+  let zeroed = match struct_info.fill_padding(quote!(&mut value)) {
+    Some(fill) => {
+      quote! {
+        /// Creates an instance with every field set to zero and padding set to the `pad_fill`
+        /// byte. Only available when every field is `Zeroable`.
+        pub fn zeroed() -> Self
+        where
+          for<'__memory_layout> Self: #runtime::Zeroable
+        {
+          let mut value: Self = unsafe { ::core::mem::zeroed() };
+          #fill
+          value
+        }
+      }
+    }
+    None => {
+      quote! {
+        /// Creates an instance with every byte, including padding, set to zero. Only available
+        /// when every field is `Zeroable`.
+        pub fn zeroed() -> Self
+        where
+          for<'__memory_layout> Self: #runtime::Zeroable
+        {
+          unsafe { ::core::mem::zeroed() }
+        }
+      }
+    }
+  };
+  (zeroable, zeroed)
+}
+
+/// Implements the unsafe marker trait `trait_path` for the struct, as long as every field
+/// implements it and the `extra` predicates hold.
+fn generate_marker_impl(
+  struct_info: &StructInfo,
+  trait_path: proc_macro2::TokenStream,
+  extra: proc_macro2::TokenStream
+) -> proc_macro2::TokenStream {
+  let name = &struct_info.derived.ident;
+  let types = struct_info
+    .fields
+    .iter()
+    .filter(|field| matches!(field.kind, FieldKind::Data))
+    .map(|field| &field.field.ty);
+  let (impl_generics, ty_generics, where_clause) = struct_info.derived.generics.split_for_impl();
+  let predicates = where_clause
+    .into_iter()
+    .flat_map(|clause| &clause.predicates);
+
+  quote! {
+    unsafe impl #impl_generics #trait_path for #name #ty_generics
+    where
+      #(#predicates,)*
+      #extra
+      #(for<'__memory_layout> #types: #trait_path),*
+    {
+    }
+  }
+}
+
+/// `bytemuck::Zeroable` and `bytemuck::Pod` implementations, with the `bytemuck` feature. Structs
+/// that may have uninitialized padding, see `StructInfo::may_be_uninit`, are never `Pod`.
+///
+/// Features are unified across the dependency graph, so the impls are only emitted for crates that
+/// depend on `bytemuck` themselves, keeping the feature additive.
+fn generate_bytemuck_impls(struct_info: &StructInfo) -> Option<proc_macro2::TokenStream> {
+  if !cfg!(feature = "bytemuck") {
+    return None;
+  }
+  let bytemuck = dependency_crate("bytemuck")?;
+
+  let zeroable = generate_marker_impl(struct_info, quote!(#bytemuck::Zeroable), quote!());
+  let pod = (!struct_info.may_be_uninit()).then(|| {
+    generate_marker_impl(
+      struct_info,
+      quote!(#bytemuck::Pod),
+      quote!(for<'__memory_layout> Self: ::core::marker::Copy + 'static,)
+    )
+  });
+  Some(quote! {
+    #zeroable
+    #pod
+  })
+}
+
+/// Declarations of the markers placed in front of the field at `index`, each followed by a comma.
+fn generate_marker_defs(struct_info: &StructInfo, index: usize) -> proc_macro2::TokenStream {
+  let markers = struct_info
+    .markers
+    .iter()
+    .filter(|marker| marker.before == index)
+    .map(|marker| {
+      let ident = marker.field.ident.as_ref().unwrap();
+      let ty = &marker.field.ty;
+      let vis = &marker.field.vis;
+      let attrs = marker
+        .field
+        .attrs
+        .iter()
+        .filter(|attr| !is_helper_attribute(attr));
+      quote! {
+        #(#attrs)*
+        #vis #ident: #ty,
+      }
+    });
+  quote!(#(#markers)*)
+}
+
+/// Checks that markers are zero-sized and that their offsets, if given, match the field they're
+/// placed in front of.
+fn generate_marker_checks(struct_info: &StructInfo) -> Vec<proc_macro2::TokenStream> {
+  let mut checks = Vec::new();
+  for marker in &struct_info.markers {
+    let ident = marker.field.ident.as_ref().unwrap();
+    let cfg = marker.cfg.as_ref().map(|cfg| quote!(#[cfg(#cfg)]));
+    if let Some(ty) = struct_info.const_type(&marker.field.ty) {
+      let message = format!(
+        "`{}` is a marker, but isn't zero-sized. Only zero-sized fields can share the offset of \
+         the next field.",
+        ident
+      );
+      checks.push(quote! {
+        #cfg
+        const _: () = assert!(::core::mem::size_of::<#ty>() == 0, #message);
+      });
+    }
+
+    let Some(offset) = &marker.offset else {
+      continue;
+    };
+    let (expected, message) = match struct_info.fields.get(marker.before) {
+      Some(next) => {
+        let start = match next.kind {
+          FieldKind::Data => next.offset.clone(),
+          // Gaps start where the previous field ends.
+          FieldKind::Gap => {
+            match &next.previous_type {
+              Some(ty) => next.previous_offset.after(ty),
+              None => next.previous_offset.clone()
+            }
+          }
+        };
+        let message = format!(
+          "Marker `{}` at offset {} has to share the offset of the next field `{}`.",
+          ident,
+          offset.describe(),
+          next.field.ident.as_ref().unwrap()
+        );
+        (start, message)
+      }
+      None => {
+        let (tail_offset, tail_type) = &struct_info.tail;
+        let end = match (&struct_info.args.size, tail_type) {
+          (Some(size), _) if !struct_info.args.verify => size.clone(),
+          (_, Some(ty)) => tail_offset.after(ty),
+          (_, None) => tail_offset.clone()
+        };
+        let message = format!(
+          "Marker `{}` at offset {} has to be at the end of the struct, as no field follows it.",
+          ident,
+          offset.describe()
+        );
+        (end, message)
+      }
+    };
+    let message = check_message(message);
+    checks.push(quote! {
+      #cfg
+      const _: () = assert!(#offset == #expected, #message);
+    });
+  }
+  checks
+}
+
+/// `Self { .. }` expression initializing every field with `value` and zeroing padding and gaps, or
+/// filling them with the `pad_fill` byte.
+fn generate_struct_literal(
+  struct_info: &StructInfo,
+  value: impl Fn(&FieldInfo) -> proc_macro2::TokenStream
+) -> proc_macro2::TokenStream {
+  let zero = quote!(unsafe { ::core::mem::zeroed() });
+  let mut initializers = struct_info
+    .fields
+    .iter()
+    .enumerate()
+    .map(|(i, field)| {
+      let ident = field.field.ident.as_ref().unwrap();
+      let cfg = field.cfg_attr();
+      let value = value(field);
+      match field.kind {
+        FieldKind::Data if struct_info.args.verify => quote!(#cfg #ident: #value,),
+        FieldKind::Data => {
+          let pad_ident = syn::Ident::new(&format!("__pad{}", i), ident.span());
+          let size_padding = struct_info
+            .size_padding(i, field)
+            .map(|(size_ident, _)| quote!(#cfg #size_ident: #zero,));
+          quote! {
+            #cfg
+            #pad_ident: #zero,
+            #cfg
+            #ident: #value,
+            #size_padding
+          }
+        }
+        FieldKind::Gap => quote!(#cfg #ident: #zero,)
+      }
+    })
+    .collect::<Vec<_>>();
+  initializers.extend(struct_info.markers.iter().map(|marker| {
+    let ident = marker.field.ident.as_ref().unwrap();
+    let cfg = marker.cfg.as_ref().map(|cfg| quote!(#[cfg(#cfg)]));
+    quote!(#cfg #ident: #zero,)
+  }));
+  if struct_info.args.size.is_some() && !struct_info.args.verify {
+    let pad_ident = syn::Ident::new(
+      &format!("__pad{}", struct_info.fields.len()),
+      Span::call_site()
+    );
+    initializers.push(quote!(#pad_ident: #zero,));
+  }
+
+  match struct_info.fill_padding(quote!(&mut value)) {
+    Some(fill) => {
+      quote! {
+        {
+          let mut value = Self {
+            #(#initializers)*
+          };
+          #fill
+          value
+        }
+      }
+    }
+    None => {
+      quote! {
+        Self {
+          #(#initializers)*
+        }
+      }
+    }
+  }
+}
+
+/// `Serialize` and `Deserialize` implementations going through a shadow struct without padding,
+/// which derives them. Deserializing zeroes the padding.
+fn generate_serde(struct_info: &StructInfo) -> proc_macro2::TokenStream {
+  let name = &struct_info.derived.ident;
+  let name_str = name.to_string();
+  // The dependency is checked while parsing the arguments, a renamed one is passed on to the
+  // derives.
+  let serde = dependency_crate("serde").unwrap_or_else(|| quote!(::serde));
+  let serde_str = serde.to_string().replace(' ', "");
+  let generics = &struct_info.derived.generics;
+  let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+  let turbofish = ty_generics.as_turbofish();
+  let fields = struct_info
+    .fields
+    .iter()
+    .filter(|field| matches!(field.kind, FieldKind::Data))
+    .collect::<Vec<_>>();
+  let types = fields
+    .iter()
+    .map(|field| &field.field.ty)
+    .collect::<Vec<_>>();
+  let predicates = where_clause
+    .into_iter()
+    .flat_map(|clause| &clause.predicates)
+    .collect::<Vec<_>>();
+
+  let shadow_fields = fields.iter().map(|field| {
+    let ident = field.field.ident.as_ref().unwrap();
+    let ty = &field.field.ty;
+    let cfg = field.cfg_attr();
+    let rename = field.base.then(|| quote!(#[serde(rename = "base")]));
+    quote!(#cfg #rename #ident: #ty)
+  });
+  let reads = fields.iter().map(|field| {
+    let ident = field.field.ident.as_ref().unwrap();
+    let cfg = field.cfg_attr();
+    let read = field.native_value(quote!(unsafe {
+      ::core::ptr::addr_of!(self.#ident).read_unaligned()
+    }));
+    quote! {
+      #cfg
+      #ident: #read,
+    }
+  });
+  let literal = generate_struct_literal(struct_info, |field| {
+    let ident = field.field.ident.as_ref().unwrap();
+    field.stored_value(quote!(shadow.#ident))
+  });
+
+  let mut de_generics = generics.clone();
+  de_generics.params.insert(
+    0,
+    syn::GenericParam::Lifetime(syn::LifetimeParam::new(syn::Lifetime::new(
+      "'de",
+      Span::call_site()
+    )))
+  );
+  let (de_impl_generics, ..) = de_generics.split_for_impl();
+
+  quote! {
+    const _: () = {
+      #[derive(#serde::Serialize, #serde::Deserialize)]
+      #[serde(crate = #serde_str, rename = #name_str)]
+      struct __MemoryLayoutShadow #generics #where_clause {
+        #(#shadow_fields),*
+      }
+
+      impl #impl_generics #serde::Serialize for #name #ty_generics
+      where
+        #(#predicates,)*
+        #(for<'__memory_layout> #types: #serde::Serialize + ::core::marker::Copy),*
+      {
+        fn serialize<__S: #serde::Serializer>(
+          &self,
+          serializer: __S
+        ) -> ::core::result::Result<__S::Ok, __S::Error> {
+          #serde::Serialize::serialize(
+            &__MemoryLayoutShadow #turbofish {
+              #(#reads)*
+            },
+            serializer
+          )
+        }
+      }
+
+      impl #de_impl_generics #serde::Deserialize<'de> for #name #ty_generics
+      where
+        #(#predicates,)*
+        #(for<'__memory_layout> #types: #serde::Deserialize<'de>),*
+      {
+        fn deserialize<__D: #serde::Deserializer<'de>>(
+          deserializer: __D
+        ) -> ::core::result::Result<Self, __D::Error> {
+          let shadow = <__MemoryLayoutShadow #ty_generics as #serde::Deserialize<'de>>::deserialize(
+            deserializer
+          )?;
+          ::core::result::Result::Ok(#literal)
+        }
+      }
+    };
+  }
+}
+
+/// `Default` implementation setting every field to its default and zeroing padding.
+fn generate_default(struct_info: &StructInfo) -> proc_macro2::TokenStream {
+  let name = &struct_info.derived.ident;
+  let literal = generate_struct_literal(struct_info, |field| {
+    field.stored_value(quote!(::core::default::Default::default()))
+  });
+  let types = struct_info
+    .fields
+    .iter()
+    .filter(|field| matches!(field.kind, FieldKind::Data))
+    .map(|field| &field.field.ty);
+  let (impl_generics, ty_generics, where_clause) = struct_info.derived.generics.split_for_impl();
+  let predicates = where_clause
+    .into_iter()
+    .flat_map(|clause| &clause.predicates);
+
+  quote! {
+    impl #impl_generics ::core::default::Default for #name #ty_generics
+    where
+      #(#predicates,)*
+      #(for<'__memory_layout> #types: ::core::default::Default),*
+    {
+      fn default() -> Self {
+        #literal
+      }
+    }
+  }
+}
+
+fn generate_clone_fields_from(struct_info: &StructInfo) -> proc_macro2::TokenStream {
+  let fields = struct_info
+    .fields
+    .iter()
+    .filter(|field| field.has_accessors() && field.access == Access::ReadWrite)
+    .collect::<Vec<_>>();
+  let types = fields.iter().map(|field| &field.field.ty);
+  let copies = fields.iter().map(|field| {
+    let getter = field.accessor_ident();
+    let setter = setter_ident(getter);
+    let cfg = field.cfg_attr();
+    if field.is_borrowed() && !field.volatile {
+      quote!(#cfg self.#setter(*other.#getter());)
+    } else {
+      quote!(#cfg self.#setter(other.#getter());)
+    }
+  });
+
+  quote! {
+    /// Copies every field from `other` using unaligned reads and writes, padding and fields with an
+    /// `access` mode are left untouched.
+    pub fn clone_fields_from(&mut self, other: &Self)
+    where
+      #(for<'__memory_layout> #types: ::core::marker::Copy),*
+    {
+      #(#copies)*
+    }
+  }
+}
+
+/// `Debug` implementation printing every readable field with its offset, padding is skipped.
+fn generate_debug(struct_info: &StructInfo) -> proc_macro2::TokenStream {
+  let name_str = struct_info.derived.ident.to_string();
+  let fields = struct_info
+    .fields
+    .iter()
+    .filter(|field| matches!(field.kind, FieldKind::Data) && field.access.readable())
+    .collect::<Vec<_>>();
+  let entries = fields.iter().map(|field| {
+    if field.base {
+      return quote! {
+        map.entry(
+          &::core::format_args!("base"),
+          &unsafe { ::core::ptr::addr_of!(self.__base).read_unaligned() }
+        );
+      };
+    }
+
+    let label = field.field.ident.as_ref().unwrap().unraw().to_string();
+    let getter = field.accessor_ident();
+    let offset = &field.offset;
+    let cfg = field.cfg_attr();
+    quote! {
+      #cfg
+      map.entry(
+        &::core::format_args!("{} @ 0x{:X}", #label, #offset),
+        &self.#getter()
+      );
+    }
+  });
+
+  generate_field_trait_impl(
+    struct_info,
+    &fields,
+    quote!(::core::fmt::Debug),
+    quote! {
+      fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        f.write_str(#name_str)?;
+        f.write_str(" ")?;
+        let mut map = f.debug_map();
+        #(#entries)*
+        map.finish()
+      }
+    }
+  )
+}
+
+/// Implements `trait_path` for the struct, as long as every field in `fields` is `Copy` and
+/// implements it as well.
+fn generate_field_trait_impl(
+  struct_info: &StructInfo,
+  fields: &[&FieldInfo],
+  trait_path: proc_macro2::TokenStream,
+  body: proc_macro2::TokenStream
+) -> proc_macro2::TokenStream {
+  let name = &struct_info.derived.ident;
+  let types = fields.iter().map(|field| &field.field.ty);
+  let (impl_generics, ty_generics, where_clause) = struct_info.derived.generics.split_for_impl();
+  let predicates = where_clause
+    .into_iter()
+    .flat_map(|clause| &clause.predicates);
+
+  // The higher-ranked bounds keep the implementation from failing to compile when a field doesn't
+  // implement the trait, it's simply not implemented for the struct then.
+  quote! {
+    impl #impl_generics #trait_path for #name #ty_generics
+    where
+      #(#predicates,)*
+      #(for<'__memory_layout> #types: ::core::marker::Copy + #trait_path),*
+    {
+      #body
+    }
+  }
+}
+
+/// `PartialEq` and `Eq` implementations comparing fields with unaligned reads, padding is ignored.
+fn generate_eq(struct_info: &StructInfo) -> proc_macro2::TokenStream {
+  let fields = struct_info
+    .fields
+    .iter()
+    .filter(|field| matches!(field.kind, FieldKind::Data))
+    .collect::<Vec<_>>();
+  let comparisons = fields.iter().map(|field| {
+    let ident = field.field.ident.as_ref().unwrap();
+    let cfg = field.cfg_attr();
+    quote! {
+      #cfg
+      if unsafe {
+        ::core::ptr::addr_of!(self.#ident).read_unaligned()
+          != ::core::ptr::addr_of!(other.#ident).read_unaligned()
+      } {
+        return false;
+      }
+    }
+  });
+
+  let partial_eq = generate_field_trait_impl(
+    struct_info,
+    &fields,
+    quote!(::core::cmp::PartialEq),
+    quote! {
+      fn eq(&self, other: &Self) -> bool {
+        #(#comparisons)*
+        true
+      }
+    }
+  );
+  let eq = generate_field_trait_impl(struct_info, &fields, quote!(::core::cmp::Eq), quote!());
+  quote! {
+    #partial_eq
+    #eq
+  }
+}
+
+/// `Hash` implementation hashing fields read with unaligned reads, padding is ignored.
+fn generate_hash(struct_info: &StructInfo) -> proc_macro2::TokenStream {
+  let fields = struct_info
+    .fields
+    .iter()
+    .filter(|field| matches!(field.kind, FieldKind::Data))
+    .collect::<Vec<_>>();
+  let hashes = fields.iter().map(|field| {
+    let ident = field.field.ident.as_ref().unwrap();
+    let cfg = field.cfg_attr();
+    quote! {
+      #cfg
+      ::core::hash::Hash::hash(
+        &unsafe { ::core::ptr::addr_of!(self.#ident).read_unaligned() },
+        state
+      );
+    }
+  });
+
+  generate_field_trait_impl(
+    struct_info,
+    &fields,
+    quote!(::core::hash::Hash),
+    quote! {
+      fn hash<H: ::core::hash::Hasher>(&self, state: &mut H) {
+        #(#hashes)*
+      }
+    }
+  )
+}
+
+/// Assertions that no bytes are left between a field and the one before it, for `deny_gaps`.
+fn generate_gap_checks(struct_info: &StructInfo) -> proc_macro2::TokenStream {
+  let checks = struct_info
+    .fields
+    .iter()
+    .filter(|field| matches!(field.kind, FieldKind::Data))
+    .map(|field| {
+      let ident = field.field.ident.as_ref().unwrap();
+      let cfg = field.cfg_attr();
+      let padding_size = field.padding_size();
+      let message = check_message(format!(
+        "Field `{}` at offset {} leaves a gap after the previous field, cover it with a `gap` \
+         field.",
+        ident.unraw(),
+        field.offset.describe()
+      ));
+      quote! {
+        #cfg
+        const _: () = assert!(#padding_size == 0, #message);
+      }
+    });
+  quote!(#(#checks)*)
+}
+
+/// Assertions that fields with a `field_size` fit in it.
+fn generate_field_size_checks(struct_info: &StructInfo) -> proc_macro2::TokenStream {
+  let checks = struct_info.fields.iter().filter_map(|field| {
+    let size = field.size?;
+    let ty = struct_info.const_type(&field.field.ty)?;
+    let cfg = field.cfg_attr();
+    let message = check_message(format!(
+      "Field `{}` has a field_size of {} bytes, but `size_of::<{}>()` is larger.",
+      field.field.ident.as_ref().unwrap().unraw(),
+      size,
+      quote!(#ty)
+    ));
+    Some(quote! {
+      #cfg
+      const _: () = assert!(::core::mem::size_of::<#ty>() <= #size, #message);
+    })
+  });
+  quote!(#(#checks)*)
+}
+
+/// Escapes braces in a message passed to `assert!`, which treats it as a format string. Messages
+/// can contain constant expressions or types with braces, such as `cfg` dependent offsets.
+fn check_message(message: String) -> String {
+  message.replace('{', "{{").replace('}', "}}")
+}
+
+fn constant_ident(prefix: &str, ident: &syn::Ident) -> syn::Ident {
+  syn::Ident::new(
+    &format!("{}_{}", prefix, ident.unraw().to_string().to_uppercase()),
+    ident.span()
+  )
+}
+
+/// Name of the field handle constant, the field name in upper case.
+fn handle_ident(ident: &syn::Ident) -> syn::Ident {
+  syn::Ident::new(&ident.unraw().to_string().to_uppercase(), ident.span())
+}
+
+fn generate_field_constants(struct_info: &StructInfo) -> Vec<proc_macro2::TokenStream> {
+  struct_info
+    .fields
+    .iter()
+    .filter(|field| field.has_accessors())
+    .map(|field| {
+      let ident = field.field.ident.as_ref().unwrap();
+      let offset_constant = constant_ident("OFFSET", ident);
+      let size_constant = constant_ident("SIZE", ident);
+      let end_constant = constant_ident("END", ident);
+      let offset = &field.offset;
+      let ty = &field.field.ty;
+      let offset_doc = format!("Offset of `{}` in bytes.", ident);
+      let size_doc = format!("Size of `{}` in bytes.", ident);
+      let end_doc = format!("Offset of the first byte after `{}`.", ident);
+      let cfg = field.cfg_attr();
+      quote! {
+        #cfg
+        #[doc = #offset_doc]
+        pub const #offset_constant: usize = #offset;
+        #cfg
+        #[doc = #size_doc]
+        pub const #size_constant: usize = ::core::mem::size_of::<#ty>();
+        #cfg
+        #[doc = #end_doc]
+        pub const #end_constant: usize = Self::#offset_constant + Self::#size_constant;
+      }
+    })
+    .collect::<Vec<_>>()
+}
+
+/// `Field` handles, which read and write a field like its getter and setter. Only fields without
+/// an access mode get one, and it has the visibility of the field's accessors.
+fn generate_field_handles(struct_info: &StructInfo) -> Vec<proc_macro2::TokenStream> {
+  // Offsets of generic structs can't be used as const generic arguments.
+  if !struct_info.derived.generics.params.is_empty() {
+    return Vec::new();
+  }
+
+  let runtime = runtime_crate();
+  struct_info
+    .fields
+    .iter()
+    .filter(|field| field.has_accessors() && field.access == Access::ReadWrite)
+    .map(|field| {
+      let ident = field.field.ident.as_ref().unwrap();
+      let handle_constant = handle_ident(ident);
+      let offset_constant = constant_ident("OFFSET", ident);
+      let ty = &field.field.ty;
+      let vis = &field.field.vis;
+      let cfg = field.cfg_attr();
+      let handle_doc = format!("Handle to `{}`, for projecting pointers to it.", ident);
+      quote! {
+        #cfg
+        #[doc = #handle_doc]
+        #vis const #handle_constant: #runtime::Field<Self, #ty, { Self::#offset_constant }> =
+          unsafe { #runtime::Field::new() };
+      }
+    })
+    .collect::<Vec<_>>()
+}
+
+/// Type as written in the source, without the spaces `quote` puts between tokens.
+fn type_name(ty: &Type) -> String {
+  let mut name = quote!(#ty).to_string();
+  for (from, to) in [
+    (" ;", ";"),
+    (" ,", ","),
+    (" <", "<"),
+    ("< ", "<"),
+    (" >", ">"),
+    (" :: ", "::"),
+    (":: ", "::"),
+    ("& ", "&"),
+    ("[ ", "["),
+    (" ]", "]"),
+    ("( ", "("),
+    (" )", ")")
+  ] {
+    name = name.replace(from, to);
+  }
+  name
+}
+
+/// The `FIELDS` table describing every field with its type and kind, ordered by offset.
+fn generate_fields_table(struct_info: &StructInfo) -> proc_macro2::TokenStream {
+  let runtime = runtime_crate();
+  let fields = struct_info.fields.iter().flat_map(|field| {
+    let cfg = field.cfg_attr();
+    let offset = &field.offset;
+    let ty = &field.field.ty;
+    let entry = |name: String, offset, size, type_name: String, kind| {
+      quote! {
+        #cfg
+        #runtime::FieldInfo {
+          name:      #name,
+          offset:    #offset,
+          size:      #size,
+          type_name: #type_name,
+          kind:      #runtime::RegionKind::#kind
+        }
+      }
+    };
+
+    let name = field.field.ident.as_ref().unwrap().unraw().to_string();
+    let size = quote!(::core::mem::size_of::<#ty>());
+    let mut entries = vec![match field.kind {
+      FieldKind::Data if field.base => {
+        entry(
+          "base".to_string(),
+          quote!(#offset),
+          size,
+          type_name(ty),
+          quote!(Base)
+        )
+      }
+      FieldKind::Data => entry(name, quote!(#offset), size, type_name(ty), quote!(Data)),
+      FieldKind::Gap => {
+        let size = field.padding_size();
+        entry(
+          name,
+          quote!(#offset - #size),
+          size,
+          "[u8]".to_string(),
+          quote!(Gap)
+        )
+      }
+    }];
+    entries.extend(
+      struct_info
+        .overlays
+        .iter()
+        .filter(|overlay| field.field.ident.as_ref() == Some(&overlay.target))
+        .map(|overlay| {
+          let ty = &overlay.field.ty;
+          entry(
+            overlay.field.ident.as_ref().unwrap().unraw().to_string(),
+            quote!(#offset),
+            quote!(::core::mem::size_of::<#ty>()),
+            type_name(ty),
+            quote!(Overlay)
+          )
+        })
+    );
+    entries
+  });
+
+  quote! {
+    /// Every field of the struct with its type, including gaps, overlapping fields and the base,
+    /// ordered by offset.
+    pub const FIELDS: &'static [#runtime::FieldInfo] = &[#(#fields),*];
+  }
+}
+
+fn generate_layout(struct_info: &StructInfo) -> proc_macro2::TokenStream {
+  let runtime = runtime_crate();
+  let layout_diagram = generate_layout_diagram();
+  let name = struct_info.derived.ident.to_string();
+  let fields = struct_info
+    .fields
+    .iter()
+    .filter(|field| matches!(field.kind, FieldKind::Data))
+    .flat_map(|field| {
+      let overlays = struct_info
+        .overlays
+        .iter()
+        .filter(|overlay| field.field.ident.as_ref() == Some(&overlay.target))
+        .map(|overlay| &overlay.field);
+      let offset = &field.offset;
+      let cfg = field.cfg_attr();
+      std::iter::once(&field.field)
+        .chain(overlays)
+        .map(|desc| {
+          let name = desc.ident.as_ref().unwrap().unraw().to_string();
+          let ty = &desc.ty;
+          quote! {
+            #cfg
+            #runtime::FieldDesc {
+              name:   #name,
+              offset: #offset,
+              size:   ::core::mem::size_of::<#ty>()
+            }
+          }
+        })
+        .collect::<Vec<_>>()
+    });
+
+  quote! {
+    #[doc(hidden)]
+    const __MEMORY_LAYOUT: #runtime::Layout = #runtime::Layout {
+      name:   #name,
+      size:   ::core::mem::size_of::<Self>(),
+      fields: &[#(#fields),*]
+    };
+
+    /// Describes the memory layout of this struct.
+    pub fn layout() -> &'static #runtime::Layout {
+      &Self::__MEMORY_LAYOUT
+    }
+
+    #layout_diagram
+  }
+}
+
+/// Implements `MemoryLayout` using the layout constant generated alongside `layout()`.
+fn generate_memory_layout_impl(
+  name: &syn::Ident,
+  generics: &syn::Generics,
+  size: Option<&Offset>
+) -> proc_macro2::TokenStream {
+  let runtime = runtime_crate();
+  let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+  let size = size.map(|size| quote!(const SIZE: usize = #size;));
+  quote! {
+    impl #impl_generics #runtime::MemoryLayout for #name #ty_generics #where_clause {
+      #size
+      const LAYOUT: #runtime::Layout = Self::__MEMORY_LAYOUT;
+    }
+  }
+}
+
+/// Implements `Reflect` using the `FIELDS` table.
+fn generate_reflect_impl(name: &syn::Ident, generics: &syn::Generics) -> proc_macro2::TokenStream {
+  let runtime = runtime_crate();
+  let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+  quote! {
+    impl #impl_generics #runtime::Reflect for #name #ty_generics #where_clause {
+      const FIELDS: &'static [#runtime::FieldInfo] = Self::FIELDS;
+    }
+  }
+}
+
+/// Allows for `field_offset`s to be defined in the struct.
+/// Fields must be defined in-order. A `field_offset` attribute includes an int literal, which
+/// indicates the offset the field should have. Fields without a `field_offset` are placed directly
+/// after the previous field, so only the first field of a contiguous run needs an offset.
+/// Both `#[field_offset(0x10)]` and `#[field_offset = 0x10]` are accepted.
+///
+/// The macro will also add `repr(C, packed)` to the struct it's applied to. If every field is
+/// naturally aligned at its offset, `#[repr(C)]` can be added to the struct to avoid packing, which
+/// is checked at compile time. Other `repr`s are rejected. Without a size the struct has to end
+/// after its last field, tail padding added by the compiler would be uninitialized. Generic
+/// `repr(C)` structs can't be checked, so they're never `NoUninit`.
+/// ```rust,compile_fail
+/// use memory_layout::memory_layout;
+///
+/// #[memory_layout]
+/// #[repr(C)]
+/// pub struct Example {
+///   #[field_offset(0x02)]
+///   a: u32
+/// }
+/// ```
+/// ```rust,compile_fail
+/// use memory_layout::memory_layout;
+///
+/// #[memory_layout]
+/// #[repr(C)]
+/// pub struct Example {
+///   #[field_offset(0x00)]
+///   a: u32,
+///   #[field_offset(0x04)]
+///   b: u8
+/// }
+/// ```
+///
+/// For every field a getter with the same name is generated, which reads the field without
+/// creating a reference to it. Getters can only be called for fields implementing `Copy`. Byte
+/// array fields (`[u8; N]`) are always aligned, their getters return a reference instead.
+///
+/// Instead of an absolute offset, `#[field_offset(end = 8)]` places a field 8 bytes before the
+/// end of the struct. This requires the struct size to be specified.
+///
+/// `#[field_offset(after(a))]` places a field directly after the preceding field `a`, and
+/// `#[field_offset(after(a) + 0x10)]` leaves a gap of 0x10 bytes after it.
+///
+/// Fields that need to be aligned, like SIMD vectors, can be annotated with
+/// `#[field_align(16)]`. The offset is checked to be a multiple of the alignment, and a
+/// `<field>_ref` getter is generated which borrows the field if it's aligned in memory.
+/// ```rust,compile_fail
+/// use memory_layout::memory_layout;
+///
+/// #[memory_layout]
+/// pub struct Example {
+///   #[field_offset(0x08)]
+///   #[field_align(16)]
+///   a: u128
+/// }
+/// ```
+///
+/// `#[field_size(0x30)]` makes a field occupy that many bytes regardless of `size_of` of its type,
+/// for opaque handles or types whose size is wrong for the target. Gaps and the offsets of
+/// following fields are computed from it, and the bytes the type doesn't cover are padding. The
+/// type can't be larger than the field size, which is checked at compile time.
+/// ```rust
+/// use memory_layout::memory_layout;
+///
+/// #[derive(Clone, Copy)]
+/// pub struct Handle(u64);
+///
+/// #[memory_layout(0x40)]
+/// pub struct CEntity {
+///   #[field_offset(0x08)]
+///   #[field_size(0x30)]
+///   pub handle: Handle,
+///   pub id:     u32
+/// }
+///
+/// assert_eq!(CEntity::OFFSET_ID, 0x38);
+/// ```
+/// ```rust,compile_fail
+/// use memory_layout::memory_layout;
+///
+/// #[memory_layout(0x40)]
+/// pub struct Example {
+///   #[field_offset(0x08)]
+///   #[field_size(0x04)]
+///   a: u64
+/// }
+/// ```
+///
+/// A struct without fields is only accepted when a size is specified, it becomes an opaque buffer
+/// of that many bytes.
+/// ```rust,compile_fail
+/// use memory_layout::memory_layout;
+///
+/// #[memory_layout]
+/// pub struct Empty {}
+/// ```
+///
+/// Passing an alignment, as in `#[memory_layout(size = 0x40, align = 0x10)]`, makes the struct
+/// `repr(C, align(n))` instead of packed, so `align_of` reflects the real alignment. As with
+/// `repr(C)` every field has to be naturally aligned at its offset, and the size has to be a
+/// multiple of the alignment. Enums and unions accept an alignment as well.
+///
+/// ```rust,compile_fail
+/// use memory_layout::memory_layout;
+///
+/// #[memory_layout(size = 0x18, align = 0x10)]
+/// pub struct Example {
+///   #[field_offset(0x00)]
+///   a: u64
+/// }
+/// ```
+///
+/// Instead of a size, `round_to = 0x10` takes the end of the last field rounded up to a multiple
+/// of the boundary, the way allocators size objects. The trailing padding is inserted like for an
+/// explicit size.
+/// ```rust
+/// use memory_layout::memory_layout;
+///
+/// #[memory_layout(round_to = 0x10)]
+/// pub struct CEntity {
+///   #[field_offset(0x08)]
+///   id:     u32,
+///   #[field_offset(0x14)]
+///   health: f32
+/// }
+///
+/// assert_eq!(core::mem::size_of::<CEntity>(), 0x20);
+/// ```
+///
+/// Passing `verify` keeps the natural `repr(C)` layout of the struct instead of inserting padding,
+/// and checks at compile time that every field lands on its annotated offset. Fields can then be
+/// borrowed normally. Gaps can't be used, and `as_bytes` isn't generated since the compiler's
+/// padding is uninitialized.
+///
+/// ```rust,compile_fail
+/// use memory_layout::memory_layout;
+///
+/// #[memory_layout(verify)]
+/// pub struct Example {
+///   #[field_offset(0x00)]
+///   a: u8,
+///   #[field_offset(0x02)]
+///   b: u32
+/// }
+/// ```
+///
+/// Compile time assertions check the size of the struct, either the desired size or the end of the
+/// last field, and with the `offset_of` or `memoffset` feature the offset of every field.
+///
+/// Fields that overlap their predecessor, or extend past the struct size, are rejected with an
+/// error naming both fields and the lowest valid offset.
+/// ```rust,compile_fail
+/// use memory_layout::memory_layout;
+///
+/// #[memory_layout]
+/// pub struct Example {
+///   #[field_offset(0x08)]
+///   a: [u32; 3],
+///   #[field_offset(0x10)]
+///   b: u32
+/// }
+/// ```
+///
+/// The end of the last field is checked against the desired size as well, including the size of
+/// its type, rather than only its offset.
+/// ```rust,compile_fail
+/// use memory_layout::memory_layout;
+///
+/// #[memory_layout(0x38)]
+/// pub struct Example {
+///   #[field_offset(0x30)]
+///   c: [u64; 2]
+/// }
+/// ```
+///
+/// Offsets can also be given as a path to a `usize` constant, such as
+/// `#[field_offset(offsets::FIELD_A)]`, or any constant expression, such as
+/// `#[field_offset(BASE + 0x8)]`. The struct size accepts expressions as well, as in
+/// `#[memory_layout(HEADER_SIZE * 2)]`. These are checked while evaluating the generated constants.
+///
+/// A field annotated with `#[computed(expr)]` takes up no space in the struct, only a getter
+/// returning `expr` is generated for it. The expression can use `self` to access other fields.
+///
+/// The last field can be an unsized array, like `data: [u8]` for a C `uint8_t data[]`. It isn't
+/// stored in the struct, so `size_of` and `SIZE` only cover the header. The array starts at its
+/// `field_offset`, or at the end of the struct without one. The unsafe getters `data(count)` and
+/// `data_mut(count)` borrow `count` elements from the memory following the struct,
+/// `OFFSET_DATA` holds its offset and `size_with_data(count)` the size of the struct with `count`
+/// elements.
+/// ```rust
+/// use memory_layout::memory_layout;
+///
+/// #[memory_layout(0x04)]
+/// pub struct Packet {
+///   #[field_offset(0x00)]
+///   len:  u16,
+///   #[field_offset(0x04)]
+///   data: [u8]
+/// }
+///
+/// let bytes = [3u8, 0, 0, 0, 0xAA, 0xBB, 0xCC];
+/// let packet = unsafe { &*(bytes.as_ptr() as *const Packet) };
+/// let data = unsafe { packet.data(packet.len() as usize) };
+/// assert_eq!(data, [0xAA, 0xBB, 0xCC]);
+/// assert_eq!(Packet::size_with_data(3), bytes.len());
+/// ```
+///
+/// Passing `strict` to the attribute, as in `#[memory_layout(0x40, strict)]`, rejects structs whose
+/// size extends past the end of the last field. Trailing reserved bytes have to be covered by a
+/// `gap` instead.
+/// ```rust,compile_fail
+/// use memory_layout::memory_layout;
+///
+/// #[memory_layout(0x40, strict)]
+/// pub struct Example {
+///   #[field_offset(0x00)]
+///   a: u64
+/// }
+/// ```
+///
+/// `deny_gaps` goes further for wire formats, where every byte has a meaning: besides trailing
+/// padding, bytes left between two fields are rejected too, catching members that were skipped by
+/// accident. Reserved bytes have to be covered by a `gap`.
+/// ```rust,compile_fail
+/// use memory_layout::memory_layout;
+///
+/// #[memory_layout(0x10, deny_gaps)]
+/// pub struct Header {
+///   #[field_offset(0x00)]
+///   magic:  u32,
+///   #[field_offset(0x08)]
+///   length: u64
+/// }
+/// ```
+///
+/// Passing `builder` generates a `<Name>Builder`, created through `<Name>::builder()`. Its
+/// `build()` function only becomes available once every field has been set, padding is zeroed.
+/// Fields that depend on a `cfg` aren't tracked and stay zeroed unless they're set, so they have to
+/// be `Zeroable`.
+/// ```compile_fail
+/// use memory_layout::memory_layout;
+///
+/// #[memory_layout(builder)]
+/// pub struct Handle {
+///   #[field_offset(0x00)]
+///   id:   u32,
+///   #[field_offset(0x08)]
+///   #[cfg(not(target_os = "none"))]
+///   name: &'static str
+/// }
+/// ```
+///
+/// Large gaps don't slow down compilation by themselves, a `[u8; N]` costs the same regardless of
+/// `N`. Initializing and copying the padding does cost time, passing `sparse` stores padding as
+/// `[MaybeUninit<u8>; N]` so constructors don't have to initialize it. The size of the struct
+/// stays the same, but `as_bytes` and `field_bytes` aren't generated since the padding may be
+/// uninitialized.
+///
+/// `pad = uninit` is another way to write `sparse`, for structs viewed over memory whose padding
+/// holds arbitrary or uninitialized bytes, such as memory mapped from another process. Viewing
+/// such memory as a struct with `[u8; N]` padding would claim those bytes are initialized.
+/// `pad = zeroed` spells out the default.
+/// ```rust
+/// use memory_layout::memory_layout;
+///
+/// #[memory_layout(0x10, pad = uninit)]
+/// pub struct CEntity {
+///   #[field_offset(0x08)]
+///   id: u32
+/// }
+///
+/// let memory = [0xCDu8; 0x10];
+/// let entity = CEntity::view(&memory).unwrap();
+/// assert_eq!(entity.id(), 0xCDCD_CDCD);
+/// ```
+///
+/// `pad_fill = 0xCC` fills padding and gaps with that byte instead of zero in `zeroed()`, the
+/// builder, `Default` and `to_bytes()`, so structs written into another process carry a
+/// recognizable pattern between their fields.
+/// ```rust
+/// use memory_layout::memory_layout;
+///
+/// #[memory_layout(0x08, pad_fill = 0xCC)]
+/// pub struct CEntity {
+///   #[field_offset(0x02)]
+///   pub id: u16
+/// }
+///
+/// let mut entity = CEntity::zeroed();
+/// entity.set_id(1);
+/// assert_eq!(entity.to_bytes(), [0xCC, 0xCC, 1, 0, 0xCC, 0xCC, 0xCC, 0xCC]);
+/// ```
+///
+/// Fields with a runtime offset may be stored in padding, which would be overwritten, so they
+/// can't be combined with `pad_fill`.
+/// ```rust,compile_fail
+/// use memory_layout::memory_layout;
+///
+/// #[memory_layout(0x08, pad_fill = 0xCC)]
+/// pub struct CEntity {
+///   #[field_offset(0x00)]
+///   pub id:     u16,
+///   #[field_offset(runtime)]
+///   pub health: u16
+/// }
+/// ```
+///
+/// `#[access(ro)]` only generates a getter for a field and `#[access(wo)]` only a setter, so a
+/// read-only field can't be written through a `&mut` accessor. Neither is copied by
+/// `clone_fields_from`. The field itself is private even if it's declared `pub`, the visibility
+/// applies to the accessors. No `Field` handle is generated for it either, as the handle would
+/// read and write the field regardless of its mode.
+/// ```rust,compile_fail
+/// mod device {
+///   use memory_layout::memory_layout;
+///
+///   #[memory_layout]
+///   pub struct Registers {
+///     #[field_offset(0x04)]
+///     #[access(ro)]
+///     pub status: u32
+///   }
+/// }
+///
+/// let mut registers: device::Registers = unsafe { core::mem::zeroed() };
+/// registers.status = 1;
+/// ```
+/// ```rust,compile_fail
+/// use memory_layout::memory_layout;
+///
+/// #[memory_layout]
+/// pub struct Registers {
+///   #[field_offset(0x04)]
+///   #[access(ro)]
+///   pub status: u32
+/// }
+///
+/// let mut registers = Registers::zeroed();
+/// Registers::STATUS.set(&mut registers, 7);
+/// ```
+///
+/// Fields annotated with `#[volatile]` are read and written with volatile accesses, so accesses to
+/// memory-mapped registers are never elided or reordered. Passing `volatile` to the attribute
+/// applies this to every field. Volatile accesses require aligned fields, so the struct has to be
+/// `repr(C)` or have an `align`.
+///
+/// `#[memory_layout(base = CEntity, size = 0x200)]` embeds `CEntity` at offset 0 and implements
+/// `Deref` and `DerefMut` to it, so the struct can be used like the class it derives from. Fields
+/// without a `field_offset` start after the base, and fields overlapping it are rejected. The
+/// struct has to be aligned to at least the alignment of its base, which generic structs check when
+/// `deref` is instantiated.
+/// ```rust,compile_fail
+/// use memory_layout::memory_layout;
+///
+/// #[memory_layout(0x10)]
+/// pub struct CEntity {
+///   #[field_offset(0x08)]
+///   id: u32
+/// }
+///
+/// #[memory_layout(base = CEntity, size = 0x20)]
+/// pub struct CPed {
+///   #[field_offset(0x08)]
+///   health: f32
+/// }
+/// ```
+///
+/// Offsets that differ between 32-bit and 64-bit builds of the target are given as
+/// `#[field_offset(x86 = 0x10, x64 = 0x18)]`, the size as
+/// `#[memory_layout(x86 = 0x40, x64 = 0x80)]`. The layout is then generated once per
+/// `target_pointer_width`, each checked like any other.
+/// ```rust
+/// use memory_layout::memory_layout;
+///
+/// #[memory_layout(x86 = 0x20, x64 = 0x30)]
+/// pub struct CEntity {
+///   vtable: *const (),
+///   #[field_offset(x86 = 0x10, x64 = 0x18)]
+///   health: f32
+/// }
+///
+/// #[cfg(target_pointer_width = "64")]
+/// assert_eq!((CEntity::OFFSET_HEALTH, std::mem::size_of::<CEntity>()), (0x18, 0x30));
+/// #[cfg(not(target_pointer_width = "64"))]
+/// assert_eq!((CEntity::OFFSET_HEALTH, std::mem::size_of::<CEntity>()), (0x10, 0x20));
+/// ```
+///
+/// Layouts that shift between versions of the target list the versions as
+/// `#[memory_layout(size(v1_58 = 0x200, v1_63 = 0x210), versions(v1_58, v1_63))]` and give offsets
+/// as `#[field_offset(v1_58 = 0x148, v1_63 = 0x150)]`. Each version is selected by the cargo
+/// feature of the same name, or by a predicate like `v1_63 = cfg(feature = "latest")`, and exactly
+/// one of them has to be enabled. Offsets that don't change stay plain.
+/// ```rust
+/// use memory_layout::memory_layout;
+///
+/// #[memory_layout(
+///   size(v1_58 = 0x200, v1_63 = 0x210),
+///   versions(v1_58 = cfg(not(feature = "v1_63")), v1_63 = cfg(feature = "v1_63"))
+/// )]
+/// pub struct CPed {
+///   #[field_offset(0x08)]
+///   id:     u32,
+///   #[field_offset(v1_58 = 0x148, v1_63 = 0x150)]
+///   health: f32,
+///   armor:  f32
+/// }
+///
+/// assert_eq!((CPed::OFFSET_HEALTH, CPed::OFFSET_ARMOR), (0x148, 0x14C));
+/// assert_eq!(std::mem::size_of::<CPed>(), 0x200);
+/// ```
+///
+/// ```rust,compile_fail
+/// use memory_layout::memory_layout;
+///
+/// #[memory_layout(0x200, versions(v1_58, v1_63))]
+/// pub struct CPed {
+///   // error: No value for version `v1_63`.
+///   #[field_offset(v1_58 = 0x148)]
+///   health: f32
+/// }
+/// ```
+///
+/// Fields whose offsets are only found at runtime, by a signature scan or from a config file, are
+/// declared as `#[field_offset(runtime)]`. They take up no space in the layout, and their getters
+/// and setters read the offset given to the generated `set_offsets`, panicking until it's set.
+/// As the offset may point anywhere in the struct, the getter requires the field type to be
+/// `AnyBitPattern` and the struct `NoUninit`, the setter the other way around.
+/// `<field>_offset()` returns the offset, if it's set. Giving every such field a
+/// `#[signature("8B 81 ?? ?? ?? ??")]` generates `scan_offsets`, which finds them in a module's
+/// code instead, see the `scan` module of memory-layout.
+/// ```rust
+/// use memory_layout::memory_layout;
+///
+/// #[memory_layout(0x200)]
+/// pub struct CPed {
+///   #[field_offset(0x08)]
+///   id:     u32,
+///   #[field_offset(runtime)]
+///   health: f32
+/// }
+///
+/// let mut ped = CPed::zeroed();
+/// assert_eq!(CPed::health_offset(), None);
+/// CPed::set_offsets(0x148).unwrap();
+/// ped.set_health(100.0);
+/// assert_eq!(ped.health(), 100.0);
+/// assert!(CPed::set_offsets(0x1FE).is_err());
+/// ```
+///
+/// Arrays whose elements are padded can be annotated with `#[stride(0x30)]`, which turns
+/// `[Item; 16]` into a `Strided<Item, 0x30, 16>` placing every element 0x30 bytes after the
+/// previous one. The getter borrows it, and a `_mut` accessor borrows it mutably. The same goes for
+/// `CStrArray<N>` fields, fixed buffers holding a NUL-terminated string.
+///
+/// Fields whose type is itself a `#[memory_layout]` struct can be annotated with `#[nested]`, which
+/// checks that the nested struct occupies the size it declares through `MemoryLayout::SIZE`.
+/// `#[nested(size = 0x10)]` also checks that size against the one the outer struct expects.
+/// ```rust,compile_fail
+/// use memory_layout::memory_layout;
+///
+/// #[memory_layout(0x18)]
+/// pub struct Vector {
+///   x: f64
+/// }
+///
+/// #[memory_layout(0x40)]
+/// pub struct Entity {
+///   #[field_offset(0x10)]
+///   #[nested(size = 0x10)]
+///   position: Vector
+/// }
+/// ```
+///
+/// Integer fields annotated with `#[endian(big)]` or `#[endian(little)]` are stored in that byte
+/// order, their getters and setters convert from and to the native order. Only integer fields can
+/// have an endian.
+/// ```rust,compile_fail
+/// use memory_layout::memory_layout;
 ///
-/// The macro will also add `repr(C, packed)` to the struct it's applied to.
+/// #[memory_layout]
+/// pub struct Header {
+///   #[endian(big)]
+///   magic: [u8; 4]
+/// }
+/// ```
+/// ```rust,compile_fail
+/// use memory_layout::memory_layout;
+///
+/// #[memory_layout]
+/// pub struct Registers {
+///   #[field_offset(0x04)]
+///   #[volatile]
+///   status: u32
+/// }
+/// ```
+///
+/// `<field>_ptr()` and `<field>_ptr_mut()` return raw pointers to a field, for hooking or interop,
+/// without creating an intermediate reference to the possibly unaligned field.
+///
+/// `#[derive(Debug)]` can't be used on packed structs, and would print the padding. Passing `debug`
+/// generates a `Debug` implementation instead, which reads fields through their getters and prints
+/// them with their offset, as in `Foo {a @ 0x10: 1}`.
+///
+/// Likewise `eq` generates `PartialEq` and `Eq`, and `hash` generates `Hash`. Both read fields
+/// without creating references and ignore padding, so instances only differing in their padding
+/// are equal. Bit fields are compared through the field holding them. Fields with a runtime offset
+/// may be stored in padding, so they can't be combined with `eq` or `hash`.
+/// ```rust,compile_fail
+/// use memory_layout::memory_layout;
+///
+/// #[memory_layout(0x08, eq)]
+/// pub struct CEntity {
+///   #[field_offset(0x00)]
+///   pub id:     u16,
+///   #[field_offset(runtime)]
+///   pub health: u16
+/// }
+/// ```
+///
+/// `zeroed()` creates an instance with every byte set to zero. It's available when the types of
+/// all fields implement `memory_layout::Zeroable`, which annotated structs do in turn. Passing
+/// `default` generates a `Default` implementation instead, which zeroes the padding and sets every
+/// field to its default.
+///
+/// With the `zerocopy` feature, passing `zerocopy` derives zerocopy's `FromBytes`, `IntoBytes`,
+/// `KnownLayout` and `Immutable`, and `Unaligned` for packed structs. As with the derives, every
+/// field has to implement these. The crate using the attribute has to depend on `zerocopy` with its
+/// `derive` feature, which is reported as an error on the `zerocopy` argument otherwise.
+///
+/// `view(bytes)` reinterprets a byte slice as a reference to the struct without copying, after
+/// checking its length and alignment. It's available when the types of all fields implement
+/// `memory_layout::AnyBitPattern`, such as integers, floats and arrays of them. `view_mut(bytes)`
+/// does the same for mutable slices, so fields can be edited in place, but like `as_bytes` it's
+/// not generated for `sparse` and `verify` structs. It also requires every field to be `NoUninit`,
+/// which rules out fields like `MaybeUninit<u32>` that are `AnyBitPattern` but could write
+/// uninitialized bytes into the slice.
+/// ```rust,compile_fail
+/// use core::mem::MaybeUninit;
+/// use memory_layout::memory_layout;
+///
+/// #[memory_layout(0x08)]
+/// pub struct Example {
+///   #[field_offset(0x04)]
+///   value: MaybeUninit<u32>
+/// }
+///
+/// fn edit(bytes: &mut [u8]) {
+///   let example = Example::view_mut(bytes).unwrap();
+///   example.set_value(MaybeUninit::uninit());
+/// }
+/// ```
+///
+/// `to_bytes()` and `from_bytes(bytes)` convert between the struct and an owned array of its size,
+/// `to_bytes` requires every field to be `NoUninit` and `from_bytes` every field to be
+/// `AnyBitPattern`. Generic structs don't get these, as their size can't be used as an array
+/// length.
+///
+/// With the `serde` feature, passing `serde` implements `Serialize` and `Deserialize` through a
+/// struct holding only the fields, which derives them. Padding isn't serialized and is zeroed when
+/// deserializing, so a round trip clears whatever the padding held. The crate using the attribute
+/// has to depend on `serde` with its `derive` feature, renamed dependencies are supported.
+///
+/// With the `bytemuck` feature, `bytemuck::Zeroable` and `bytemuck::Pod` are implemented when
+/// every field implements them. `sparse` and `verify` structs are never `Pod`, as their padding may
+/// be uninitialized. The impls are only emitted in crates depending on `bytemuck`, so enabling the
+/// feature doesn't break other crates using the attribute.
+///
+/// Setters named `set_<field>` write fields without creating a reference, and
+/// `clone_fields_from` copies all fields from another instance while leaving the padding untouched.
+///
+/// Every field also gets `OFFSET_<FIELD>`, `SIZE_<FIELD>` and `END_<FIELD>` constants holding its
+/// offset, size and the offset right after it, where `<FIELD>` is the field name in uppercase.
+/// Overlapping fields get them as well. Fields whose names only differ in case are rejected, as
+/// their constants would collide. Fields of non-generic structs without an `access` mode also get
+/// a `<FIELD>` constant holding a `memory_layout::Field` handle, which projects a `RemotePtr` to
+/// the field. The handle has the visibility of the field.
+///
+/// A `layout()` function returning a `memory_layout::Layout` describing the struct is generated as
+/// well. `as_bytes()` views the struct as raw bytes, and `field_bytes()` iterates over the name,
+/// offset and bytes of every field. `slice_between("b", "d")` returns the bytes from the start of
+/// `b` to the end of `d`. With `#[memory_layout(reflect)]`, a `FIELDS` constant also lists every
+/// field as a `memory_layout::FieldInfo`, with its type name and whether it's data, a gap, an
+/// overlapping field or the base.
+///
+/// Viewing the raw bytes requires every field to implement `memory_layout::NoUninit`, as bytes
+/// left uninitialized by a field like `Option<u16>` can't be read. The same goes for `to_bytes`,
+/// `diff_bytes` and `write_to`.
+/// ```rust,compile_fail
+/// use memory_layout::memory_layout;
+///
+/// #[memory_layout(0x08)]
+/// pub struct Example {
+///   #[field_offset(0x04)]
+///   pub a: Option<u16>
+/// }
+///
+/// fn bytes(example: &Example) -> &[u8] {
+///   example.as_bytes()
+/// }
+/// ```
+///
+/// Annotated structs, unions and enums implement `memory_layout::MemoryLayout`, which exposes their
+/// `SIZE`, `ALIGN` and `LAYOUT` as constants and looks up field offsets by name, for code that is
+/// generic over annotated types.
+///
+/// Fields with unwieldy names, such as `field_0x38`, can be given readable accessors with
+/// `#[accessor(name = "flags")]`. The getter becomes `flags()` and the setter `set_flags()`, while
+/// the stored field and its constants keep the original name. Accessor names have to be unique.
+///
+/// With the `alloc` feature, enabled by default, `diff_bytes(buffer)` lists every offset where the
+/// bytes of the struct differ from `buffer`, which helps comparing a struct against a memory dump.
+/// `layout_diagram()` renders the layout as a byte map with the fields spanning the bytes they
+/// cover, to review a struct transcribed from a tool like ReClass.NET.
+///
+/// `from_bytes_checked(bytes)` reads the struct from a buffer of exactly its size, returning a
+/// `memory_layout::LayoutError` if the length is off or any padding byte isn't zero. Like `view`,
+/// it requires every field to be `AnyBitPattern`.
+///
+/// The attribute can also be applied to a `union`, every member is placed at its `field_offset`
+/// and members may overlap. Members are stored in hidden packed wrappers, so they're accessed
+/// through the generated getters, which are `unsafe` like any union read, and setters.
+///
+/// Tuple structs keep their elements at their indices, so they're still accessed as `foo.0` and
+/// created with the generated `new(..)`. Every element needs a `field_offset`, but as padding
+/// between elements would shift the indices, each one has to start where the one before ends.
+/// Only trailing padding up to the size is inserted, as a last hidden element. Tuple structs
+/// accept a size, `align` and `strict`.
+/// ```rust
+/// use memory_layout::memory_layout;
+///
+/// #[memory_layout(0x10)]
+/// pub struct Handle(#[field_offset(0x00)] pub u32, #[field_offset(0x04)] pub u16);
+///
+/// let handle = Handle::new(7, 2);
+/// assert_eq!({ handle.0 }, 7);
+/// assert_eq!(Handle::OFFSET_1, 0x04);
+/// assert_eq!(core::mem::size_of::<Handle>(), 0x10);
+/// ```
+///
+/// Generic structs are supported as long as no padding depends on a type parameter, which Rust
+/// doesn't allow in array lengths. Lifetimes can be used anywhere, and so can type parameters
+/// behind pointers, references, `PhantomData` or `NonNull`, if they're `Sized`. A field whose size
+/// depends on a type parameter has to be the last one, in a struct without a size.
+/// ```rust
+/// use core::marker::PhantomData;
+/// use memory_layout::memory_layout;
+///
+/// #[memory_layout(0x18)]
+/// pub struct CList<T> {
+///   #[field_offset(0x00)]
+///   pub head:   *mut T,
+///   #[field_offset(0x08)]
+///   pub marker: PhantomData<T>,
+///   #[field_offset(0x10)]
+///   pub len:    u32
+/// }
+///
+/// #[memory_layout]
+/// pub struct CBox<T: Copy> {
+///   #[field_offset(0x08)]
+///   pub value: T
+/// }
+///
+/// assert_eq!(core::mem::size_of::<CList<u64>>(), 0x18);
+/// assert_eq!(core::mem::size_of::<CBox<u32>>(), 0x0C);
+/// ```
+///
+/// For the same reason the size and offsets can't be const parameters, as the padding would be
+/// computed from them. Objects whose reserved area varies by instantiation end in a byte array
+/// sized by the parameter instead, leaving out the size.
+/// ```rust
+/// use memory_layout::memory_layout;
+///
+/// #[memory_layout]
+/// pub struct CBuffer<const RESERVED: usize> {
+///   #[field_offset(0x00)]
+///   pub len:      u32,
+///   #[field_offset(0x08)]
+///   pub reserved: [u8; RESERVED]
+/// }
+///
+/// assert_eq!(core::mem::size_of::<CBuffer<0x38>>(), 0x40);
+/// ```
+/// ```rust,compile_fail
+/// use memory_layout::memory_layout;
+///
+/// #[memory_layout(SIZE)]
+/// pub struct CBuffer<const SIZE: usize> {
+///   #[field_offset(0x00)]
+///   pub len: u32
+/// }
+/// ```
+///
+/// Zero-sized fields like typestate markers or tags are annotated with `#[marker]`. They take up
+/// no space and are placed in front of the field declared after them, sharing its offset, or at
+/// the end of the struct if none follows. Their `field_offset` can be left out, and if given has to
+/// match that offset. Markers get no accessors and are left out of the layout, and the macro checks
+/// that they are zero-sized.
+/// ```rust
+/// use core::marker::PhantomData;
+/// use memory_layout::memory_layout;
+///
+/// pub struct Locked;
+///
+/// #[memory_layout(0x10)]
+/// pub struct Mutex<S> {
+///   #[field_offset(0x00)]
+///   pub owner: u32,
+///   #[marker]
+///   #[field_offset(0x08)]
+///   pub tag:   Locked,
+///   #[field_offset(0x08)]
+///   pub count: u32,
+///   #[marker]
+///   pub state: PhantomData<S>
+/// }
+///
+/// assert_eq!(Mutex::<()>::OFFSET_COUNT, 0x08);
+/// ```
+/// ```rust,compile_fail
+/// use memory_layout::memory_layout;
+///
+/// #[memory_layout(0x10)]
+/// pub struct Example {
+///   #[marker]
+///   #[field_offset(0x04)]
+///   pub tag:   u32,
+///   #[field_offset(0x04)]
+///   pub count: u32
+/// }
+/// ```
+///
+/// Bit fields are declared with `#[bit_offset(byte, bit, width)]` on a field with an unsigned
+/// integer type. Like computed fields they take up no space of their own, the getter and setter
+/// read and write `width` bits starting at `bit` of the integer of that type stored at `byte`,
+/// which has to lie within a regular field holding the flags. Padding and gaps are filled by
+/// `pad_fill` and skipped by `eq`, `hash`, `Debug` and serde, so bits stored there would be lost.
+/// Bit ranges that don't fit in the integer are rejected at compile time. The accessors are only
+/// available when the field is `AnyBitPattern + NoUninit`, or the whole struct if the field isn't
+/// known while expanding the macro.
+/// ```rust,compile_fail
+/// use memory_layout::memory_layout;
+///
+/// #[memory_layout(0x04)]
+/// pub struct Example {
+///   #[field_offset(0x00)]
+///   flag: bool,
+///   #[bit_offset(0x00, 1, 1)]
+///   high: u8
+/// }
+///
+/// Example::zeroed().set_high(1);
+/// ```
+/// ```rust,compile_fail
+/// use memory_layout::memory_layout;
+///
+/// #[memory_layout(0x10, pad_fill = 0xCC)]
+/// pub struct Example {
+///   #[field_offset(0x00)]
+///   flags: u8,
+///   #[bit_offset(0x08, 0, 4)]
+///   mode:  u32
+/// }
+/// ```
+/// ```rust,compile_fail
+/// use memory_layout::memory_layout;
+///
+/// #[memory_layout(0x04)]
+/// pub struct Example {
+///   #[field_offset(0x00)]
+///   flags: u8,
+///   #[bit_offset(0x00, 6, 4)]
+///   mode:  u8
+/// }
+/// ```
+///
+/// Signed integers are rejected, the getter couldn't tell a negative value from a large one.
+/// ```rust,compile_fail
+/// use memory_layout::memory_layout;
+///
+/// #[memory_layout(0x04)]
+/// pub struct Example {
+///   #[field_offset(0x00)]
+///   flags: i32,
+///   #[bit_offset(0x00, 0, 4)]
+///   mode:  i32
+/// }
+/// ```
+///
+/// A field annotated with `#[overlaps(flags)]` is another typed view of the bytes of the field
+/// `flags`, such as `flag_bytes: [u8; 4]` over `flags: u32`. It takes up no space of its own, its
+/// getter and setter copy from and to the bytes of `flags`. The view can't be larger than the
+/// field it overlaps, which is checked where the struct is defined, or by the accessors for generic
+/// structs. The getter is only available when any bytes form a valid value of the view and the
+/// struct has no uninitialized bytes, the setter when the view has no uninitialized bytes and any
+/// bytes are valid for `flags`.
+/// ```rust,compile_fail
+/// use memory_layout::memory_layout;
+///
+/// #[memory_layout(0x04)]
+/// pub struct Example {
+///   #[field_offset(0x00)]
+///   flags:  u8,
+///   #[overlaps(flags)]
+///   active: bool
+/// }
+///
+/// fn active(example: &Example) -> bool {
+///   example.active()
+/// }
+/// ```
+/// ```rust,compile_fail
+/// use memory_layout::memory_layout;
+///
+/// #[memory_layout]
+/// pub struct Example<T> {
+///   #[overlaps(value)]
+///   wide:  [u8; 64],
+///   #[field_offset(0x00)]
+///   value: T
+/// }
+///
+/// let wide = Example::<u16>::zeroed().wide();
+/// ```
+///
+/// Tagged unions can be described with an enum. The enum requires a size and a
+/// `#[discriminant(offset = 0x00, ty = u32)]` attribute, every variant needs an explicit
+/// discriminant and its fields a `field_offset`. The enum becomes a struct of raw bytes with a
+/// `discriminant()` getter, `is_<variant>()` checks, `new_<variant>(..)` constructors and
+/// `<variant>_<field>()` getters that return `None` while another variant is stored. Payload
+/// fields can't overlap the discriminant or another field of their variant, and their accessors
+/// require types that are `AnyBitPattern + NoUninit`, since variants share their bytes.
+/// ```rust,compile_fail
+/// use memory_layout::memory_layout;
+///
+/// #[memory_layout(0x08)]
+/// #[discriminant(offset = 0x00, ty = u8)]
+/// pub enum Example {
+///   A {
+///     #[field_offset(0x00)]
+///     clobber: u32
+///   } = 0
+/// }
+/// ```
+///
+/// Passing `sort` orders the fields by their offset instead of their declaration, so fields can be
+/// grouped by purpose. Every field then needs an integer literal `field_offset`, and gaps can't be
+/// used.
+///
+/// Large reserved regions can be named with a `gap` pseudo-field. A field annotated with
+/// `#[gap(0x100)]` must have the unit type `()` and no `field_offset`, it becomes a byte array
+/// covering everything from the end of the previous field up to the given absolute offset.
+///
+/// Fields can be made conditional with `#[cfg(...)]`. The field, its padding and everything
+/// generated for it is only included when the predicate holds, fields following it are placed
+/// relative to the end of whichever field is present. Without a declared size the struct ends after
+/// the last field that's present, so its size depends on the enabled features. Builders don't
+/// require conditional fields to be set, they stay zeroed.
 ///
 /// <p style="background:rgba(255,181,77,0.16);padding:0.75em;">
 /// <strong>Warning:</strong> The attribute has to be defined before any derive attributes.
@@ -130,7 +4868,7 @@ fn generate_field_offset_checks(_: &StructInfo) -> Vec<proc_macro2::TokenStream>
 ///
 /// # Example
 /// ```rust
-/// use ::memory_layout_codegen::memory_layout;
+/// use memory_layout::memory_layout;
 ///
 /// #[memory_layout(0x38)]
 /// pub struct Example {
@@ -141,42 +4879,76 @@ fn generate_field_offset_checks(_: &StructInfo) -> Vec<proc_macro2::TokenStream>
 ///   #[field_offset(0x18)]
 ///   c: f32
 /// }
-
 /// ```
 ///
-/// Will expand to:
+/// Will expand to the following struct, followed by the generated functions, constants and checks:
 /// ```rust
+/// #[repr(C, packed)]
 /// pub struct Example {
 ///   #[doc(hidden)]
 ///   __pad0: [u8; 0usize],
 ///   a:      i32,
 ///   #[doc(hidden)]
-///   __pad1: [u8; 16usize - ::core::mem::size_of::<i32>()],
+///   __pad1: [u8; 16usize.saturating_sub(::core::mem::size_of::<i32>())],
 ///   b:      u64,
 ///   #[doc(hidden)]
-///   __pad2: [u8; 8usize - ::core::mem::size_of::<u64>()],
+///   __pad2: [u8; 8usize.saturating_sub(::core::mem::size_of::<u64>())],
 ///   c:      f32,
-///   __pad3: [u8; 8usize - ::core::mem::size_of::<u64>()],
+///   #[doc(hidden)]
+///   __pad3: [u8; 32usize.saturating_sub(::core::mem::size_of::<f32>())]
 /// }
 /// ```
 #[proc_macro_attribute]
 pub fn memory_layout(attr: TokenStream, input: TokenStream) -> TokenStream {
-  let struct_info = parse_macro_input!(input as StructInfo);
+  let input = parse_macro_input!(input as DeriveInput);
+  let args = parse_macro_input!(attr as LayoutArgs);
 
-  let attr_value = parse_macro_input!(attr as Option<LitInt>);
+  expand_attribute(input, args)
+    .unwrap_or_else(|err| err.to_compile_error())
+    .into()
+}
 
-  let desired_size = if let Some(lit) = attr_value {
-    let Ok(r) = lit.base10_parse::<usize>() else {
-      return quote_spanned!(
-        lit.span() =>
-        compile_error!("Desired size must be a valid usize");
-      )
-      .into();
-    };
-    Some(r)
-  } else {
-    None
+/// Expands `#[memory_layout]`, once per version if the offsets depend on it.
+fn expand_attribute(input: DeriveInput, args: LayoutArgs) -> syn::Result<proc_macro2::TokenStream> {
+  if !args.versions.is_empty() || args.versioned_size.is_some() {
+    return versions::expand_per_version(input, args);
+  }
+  expand_for_target(input, args)
+}
+
+/// Expands the layout, once per pointer width if it depends on it.
+fn expand_for_target(
+  input: DeriveInput,
+  args: LayoutArgs
+) -> syn::Result<proc_macro2::TokenStream> {
+  if pointer_width::has_selectors(&input, &args) {
+    return pointer_width::expand_per_pointer_width(input, args);
+  }
+  Ok(expand(input, args))
+}
+
+/// Generates the struct, union or enum with every offset and size resolved.
+fn expand(input: DeriveInput, args: LayoutArgs) -> proc_macro2::TokenStream {
+  if let Data::Union(_) = &input.data {
+    return union::generate_union(&input, &args).unwrap_or_else(|err| err.to_compile_error());
+  }
+  if let Data::Enum(_) = &input.data {
+    return tagged::generate_tagged(&input, &args).unwrap_or_else(|err| err.to_compile_error());
+  }
+  if let Data::Struct(DataStruct {
+    fields: Fields::Unnamed(_),
+    ..
+  }) = &input.data
+  {
+    return tuple::generate_tuple(&input, &args).unwrap_or_else(|err| err.to_compile_error());
+  }
+
+  let struct_info = match StructInfo::new(input, args) {
+    Ok(struct_info) => struct_info,
+    Err(err) => return err.to_compile_error()
   };
+  // Taken after the fields are known, as `round_to` derives the size from them.
+  let desired_size = struct_info.args.size.clone();
 
   let mut fields = struct_info
     .fields
@@ -185,85 +4957,498 @@ pub fn memory_layout(attr: TokenStream, input: TokenStream) -> TokenStream {
     .map(|(i, f)| {
       let ident = f.field.ident.as_ref().unwrap();
       let typename = &f.field.ty;
-      let vis = &f.field.vis;
-      let relative_offset = f.relative_offset;
-      let previous_type = &f.previous_type;
+      // Fields with an access mode stay private, so they're only reached through the accessors the
+      // mode allows.
+      let vis = match f.access {
+        Access::ReadWrite => f.field.vis.clone(),
+        _ => syn::Visibility::Inherited
+      };
+      let padding_type = struct_info.padding_type(f.padding_size());
       let pad_ident = syn::Ident::new(&format!("__pad{}", i), ident.span());
       let attrs = f
         .field
         .attrs
         .iter()
-        .filter(|attr| !attr.path().is_ident("field_offset"));
-      match previous_type {
-        Some(ty) => {
+        .filter(|attr| !is_helper_attribute(attr));
+      let cfg = f.cfg_attr();
+      let markers = generate_marker_defs(&struct_info, i);
+      match f.kind {
+        FieldKind::Data if struct_info.args.verify => {
           quote! {
-            #[doc(hidden)]
-            #pad_ident: [u8; #relative_offset - ::core::mem::size_of::<#ty>()],
+            #markers
             #(#attrs)*
             #vis #ident: #typename
           }
         }
-        None => {
+        FieldKind::Data => {
+          let size_padding = struct_info
+            .size_padding(i, f)
+            .map(|(size_ident, size_type)| {
+              quote! {
+                ,
+                #cfg
+                #[doc(hidden)]
+                #size_ident: #size_type
+              }
+            });
           quote! {
+            #cfg
             #[doc(hidden)]
-            #pad_ident: [u8; #relative_offset],
+            #pad_ident: #padding_type,
+            #markers
             #(#attrs)*
             #vis #ident: #typename
+            #size_padding
+          }
+        }
+        FieldKind::Gap => {
+          quote! {
+            #markers
+            #(#attrs)*
+            #vis #ident: #padding_type
           }
         }
       }
     })
     .collect::<Vec<_>>();
 
-  if let Some(size) = desired_size {
+  let mut strict_check = None;
+  if let Some(size) = &desired_size {
     let pad_ident = syn::Ident::new(
       &format!("__pad{}", struct_info.fields.len()),
       Span::call_site()
     );
-    if let Some(last_field) = struct_info.fields.last() {
-      let last_offset = last_field.absolute_offset;
-      let prev_type = last_field.field.ty.clone();
-      let Some(required_padding) = size.checked_sub(last_offset) else {
-        return quote!(
-          compile_error!("Desired struct size is lower than the highest field offset.");
-        )
-        .into();
+    let (tail_offset, tail_type) = &struct_info.tail;
+    if matches!((tail_offset.known(), size.known()), (Some(tail), Some(size)) if tail > size) {
+      // Reported by the tail check with the field that ends past the size.
+      return match generate_tail_check(&struct_info) {
+        Err(err) => err.to_compile_error(),
+        Ok(_) => {
+          quote!(compile_error!("Desired struct size is lower than the highest field offset.");)
+        }
       };
+    };
 
+    let padding_size = tail_offset.padding_to(tail_type.as_ref(), size);
+    let padding_type = struct_info.padding_type(padding_size.clone());
+    if struct_info.args.strict || struct_info.args.deny_gaps {
+      let message = check_message(format!(
+        "Desired struct size {} is larger than the end of the last field, add an explicit \
+         reserved field.",
+        size.describe()
+      ));
+      strict_check = Some(quote! {
+        const _: () = assert!(#padding_size == 0, #message);
+      });
+    }
+
+    if !struct_info.args.verify {
       fields.push(quote! {
         #[doc(hidden)]
-        #pad_ident: [u8; #required_padding - ::core::mem::size_of::<#prev_type>()],
-      })
-    } else {
-      fields.push(quote! {
-        #[doc(hidden)]
-        #pad_ident: [u8; #size],
+        #pad_ident: #padding_type
       })
     }
   }
+  if struct_info
+    .markers
+    .iter()
+    .any(|marker| marker.before == struct_info.fields.len())
+  {
+    fields.push(generate_marker_defs(&struct_info, struct_info.fields.len()));
+  }
+  let marker_checks = generate_marker_checks(&struct_info);
 
-  let struct_ident = &struct_info.derived.ident;
-  let struct_size_check = desired_size.map(|size| {
-    quote! {
-      const _:() = assert!(::core::mem::size_of::<#struct_ident>() == #size);
+  let gap_checks = struct_info
+    .args
+    .deny_gaps
+    .then(|| generate_gap_checks(&struct_info));
+  let field_size_checks = generate_field_size_checks(&struct_info);
+
+  let struct_ty = struct_info.const_self_type();
+  let struct_size_check = match (&desired_size, &struct_info.tail, &struct_ty) {
+    (Some(size), _, Some(struct_ty)) => {
+      Some(quote! {
+        const _:() = assert!(::core::mem::size_of::<#struct_ty>() == #size);
+      })
     }
-  });
+    // Without a desired size a packed struct ends right after its last field.
+    (None, (tail_offset, Some(tail_type)), Some(struct_ty))
+      if struct_info.packed || struct_info.args.align.is_some() =>
+    {
+      let end = tail_offset.after(tail_type);
+      Some(quote! {
+        const _:() = assert!(::core::mem::size_of::<#struct_ty>() == #end);
+      })
+    }
+    // The compiler may append padding to a `repr(C)` struct, which would be uninitialized.
+    (None, (tail_offset, Some(tail_type)), Some(struct_ty)) if !struct_info.args.verify => {
+      let end = tail_offset.after(tail_type);
+      let message = format!(
+        "The compiler appends padding to `{}` after its last field, declare a size to make the \
+         padding explicit.",
+        struct_info.derived.ident
+      );
+      Some(quote! {
+        const _:() = assert!(::core::mem::size_of::<#struct_ty>() == #end, #message);
+      })
+    }
+    _ => None
+  };
 
+  let order_checks = generate_order_checks(&struct_info);
+  let overlap_checks = generate_overlap_checks(&struct_info);
+  let tail_check = match generate_tail_check(&struct_info) {
+    Ok(tail_check) => tail_check,
+    Err(err) => return err.to_compile_error()
+  };
+  let size_align_check = match (&desired_size, &struct_info.tail) {
+    (Some(size), _) => Some(size.clone()),
+    (None, (tail_offset, Some(tail_type))) if struct_ty.is_some() => {
+      match (tail_offset.known(), known_size(tail_type)) {
+        (Some(offset), Some(size)) => Some(Offset::Known(offset + size)),
+        _ => Some(tail_offset.after(tail_type))
+      }
+    }
+    _ => None
+  }
+  .map(|size| generate_size_align_check(&struct_info.derived.ident, &size, struct_info.args.align))
+  .transpose();
+  let size_align_check = match size_align_check {
+    Ok(check) => check.flatten(),
+    Err(err) => return err.to_compile_error()
+  };
   let field_offset_checks = generate_field_offset_checks(&struct_info);
+  let align_checks = generate_align_checks(&struct_info);
+  let natural_align_checks = generate_natural_align_checks(&struct_info);
+  let getters = generate_getters(&struct_info);
+  let aligned_getters = generate_aligned_getters(&struct_info);
+  let pointer_accessors = generate_pointer_accessors(&struct_info);
+  let computed_getters = generate_computed_getters(&struct_info);
+  let bit_accessors = generate_bit_accessors(&struct_info);
+  let bit_checks = generate_bit_checks(&struct_info);
+  let overlay_accessors = generate_overlay_accessors(&struct_info);
+  let flexible_accessors = generate_flexible_accessors(&struct_info);
+  let runtime_accessors = generate_runtime_accessors(&struct_info);
+  let remote_accessors = generate_remote_accessors(&struct_info);
+  let fields_table = struct_info
+    .args
+    .reflect
+    .then(|| generate_fields_table(&struct_info));
+  let overlay_checks = generate_overlay_checks(&struct_info);
+  let setters = generate_setters(&struct_info);
+  let clone_fields_from = generate_clone_fields_from(&struct_info);
+  let field_constants = generate_field_constants(&struct_info);
+  let field_handles = generate_field_handles(&struct_info);
+  let layout = generate_layout(&struct_info);
+  let byte_access =
+    (!struct_info.args.sparse && !struct_info.args.verify).then(generate_byte_access);
+  let checked_parse = generate_checked_parse(&struct_info);
+  let owned_bytes = generate_owned_bytes(&struct_info);
+  let memory_layout_impl = generate_memory_layout_impl(
+    &struct_info.derived.ident,
+    &struct_info.derived.generics,
+    struct_info.args.size.as_ref()
+  );
+  let reflect_impl = struct_info
+    .args
+    .reflect
+    .then(|| generate_reflect_impl(&struct_info.derived.ident, &struct_info.derived.generics));
+  let nested_checks = generate_nested_checks(&struct_info);
+  let base_deref = generate_base_deref(&struct_info);
+  let debug = struct_info.args.debug.then(|| generate_debug(&struct_info));
+  let (zeroable, zeroed) = generate_zeroed(&struct_info);
+  let bytemuck = generate_bytemuck_impls(&struct_info);
+  let default = struct_info
+    .args
+    .default
+    .then(|| generate_default(&struct_info));
+  let eq = struct_info.args.eq.then(|| generate_eq(&struct_info));
+  let serde = struct_info.args.serde.then(|| generate_serde(&struct_info));
+  let hash = struct_info.args.hash.then(|| generate_hash(&struct_info));
+  let builder = struct_info
+    .args
+    .builder
+    .then(|| builder::generate_builder(&struct_info));
 
-  let name = struct_info.derived.ident;
-  let vis = struct_info.derived.vis;
-  let attrs = struct_info.derived.attrs;
-  let generics = struct_info.derived.generics;
+  let name = &struct_info.derived.ident;
+  let vis = &struct_info.derived.vis;
+  let attrs = struct_info
+    .derived
+    .attrs
+    .iter()
+    .filter(|attr| !attr.path().is_ident("repr"));
+  let repr = if struct_info.packed {
+    quote!(#[repr(C, packed)])
+  } else {
+    repr_attribute(struct_info.args.align)
+  };
+  let zerocopy = struct_info.args.zerocopy.then(|| {
+    let unaligned = struct_info.packed.then(|| quote!(::zerocopy::Unaligned));
+    quote! {
+      #[derive(
+        ::zerocopy::FromBytes,
+        ::zerocopy::IntoBytes,
+        ::zerocopy::KnownLayout,
+        ::zerocopy::Immutable,
+        #unaligned
+      )]
+    }
+  });
+  let generics = &struct_info.derived.generics;
+  let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
   quote! {
+    #repr
+    #zerocopy
     #(#attrs)*
-    #vis struct #name #generics {
+    #vis struct #name #generics #where_clause {
       #(#fields),*
     }
 
+    impl #impl_generics #name #ty_generics #where_clause {
+      #(#field_constants)*
+
+      #(#field_handles)*
+
+      #(#getters)*
+
+      #(#aligned_getters)*
+
+      #(#pointer_accessors)*
+
+      #(#computed_getters)*
+
+      #(#bit_accessors)*
+
+      #(#overlay_accessors)*
+
+      #flexible_accessors
+
+      #runtime_accessors
+
+      #remote_accessors
+
+      #fields_table
+
+      #(#setters)*
+
+      #zeroed
+
+      #clone_fields_from
+
+      #layout
+
+      #byte_access
+
+      #checked_parse
+
+      #owned_bytes
+    }
+
+    #memory_layout_impl
+    #reflect_impl
+
+    #zeroable
+    #bytemuck
+    #default
+    #debug
+    #serde
+    #eq
+    #hash
+
+    #(#order_checks)*
+    #(#overlap_checks)*
+    #tail_check
+    #size_align_check
+    #(#align_checks)*
+    #(#natural_align_checks)*
+    #(#nested_checks)*
+    #base_deref
     #(#field_offset_checks)*
+    #(#bit_checks)*
+    #(#overlay_checks)*
     #struct_size_check
+    #strict_check
+    #(#marker_checks)*
+    #gap_checks
+    #field_size_checks
+
+    #builder
+  }
+}
+
+/// Describes the virtual function table of a class. Every field is a pointer sized entry, usually
+/// a function pointer, placed at the index given by `#[index(n)]`. Entries without an `index`
+/// follow the previous entry. An entry count can be passed to the attribute, as in
+/// `#[vtable_layout(16)]`, which pads the table to that many entries.
+///
+/// The table is `repr(C)`, unused entries are filled with hidden padding. `INDEX_<ENTRY>`
+/// constants hold the index of every entry, and `of(&object)` borrows the table an object points
+/// to with its first field. Entries that are function pointers taking a pointer or reference to
+/// the object as their first argument get a `call_<entry>` function, which looks up the entry in
+/// the object's vtable and calls it.
+/// ```rust
+/// use memory_layout::{memory_layout, vtable_layout};
+///
+/// #[vtable_layout]
+/// pub struct CEntityVTable {
+///   #[index(2)]
+///   pub health: unsafe extern "C" fn(*const CEntity) -> f32
+/// }
+///
+/// #[memory_layout(0x10)]
+/// pub struct CEntity {
+///   vtable: *const CEntityVTable,
+///   health: f32
+/// }
+///
+/// unsafe extern "C" fn health(entity: *const CEntity) -> f32 {
+///   (*entity).health()
+/// }
+///
+/// let table = [0, 0, health as usize];
+/// let mut entity = CEntity::zeroed();
+/// entity.set_vtable(table.as_ptr().cast());
+/// entity.set_health(100.0);
+///
+/// let vtable = unsafe { CEntityVTable::of(&entity) };
+/// assert_eq!(unsafe { (vtable.health)(&entity) }, 100.0);
+/// assert_eq!(unsafe { CEntityVTable::call_health(&entity) }, 100.0);
+/// assert_eq!(CEntityVTable::INDEX_HEALTH, 2);
+/// ```
+///
+/// Entries have to be declared in ascending order of their index.
+/// ```rust,compile_fail
+/// use memory_layout::vtable_layout;
+///
+/// #[vtable_layout]
+/// pub struct VTable {
+///   #[index(4)]
+///   update: fn(),
+///   #[index(2)]
+///   render: fn()
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn vtable_layout(attr: TokenStream, input: TokenStream) -> TokenStream {
+  let input = parse_macro_input!(input as DeriveInput);
+  let count = if attr.is_empty() {
+    None
+  } else {
+    Some(parse_macro_input!(attr as LitInt))
+  };
+
+  vtable::generate_vtable(&input, count)
+    .unwrap_or_else(|err| err.to_compile_error())
+    .into()
+}
+
+/// Declares the layout of a type that can't be annotated, like a type of another crate.
+///
+/// The declaration is a struct as for `#[memory_layout]`, whose attribute it can carry, followed
+/// by `for` and the described type. It expands to that struct with all its generated functions
+/// and `MemoryLayout` implementation, plus `from_target` and `from_target_mut` viewing a reference
+/// to the described type through it, and `from_target_ptr` and `from_target_ptr_mut` casting
+/// pointers to it. The layout can't be larger than the described type, which is checked at
+/// compile time unless the type is unsized, like a slice or a trait object.
+/// ```rust
+/// use memory_layout::declare_layout;
+///
+/// mod engine {
+///   #[repr(C)]
+///   pub struct CEntity {
+///     pub vtable: usize,
+///     pub id:     u32,
+///     pub health: f32
+///   }
+/// }
+///
+/// declare_layout! {
+///   #[memory_layout(0x10)]
+///   pub struct CEntityLayout for engine::CEntity {
+///     #[field_offset(0x08)]
+///     pub id:     u32,
+///     #[field_offset(0x0C)]
+///     pub health: f32
+///   }
+/// }
+///
+/// let mut entity = engine::CEntity { vtable: 0, id: 7, health: 100.0 };
+/// let layout = unsafe { CEntityLayout::from_target(&entity) };
+/// assert_eq!((layout.id(), layout.health()), (7, 100.0));
+///
+/// unsafe { CEntityLayout::from_target_mut(&mut entity) }.set_health(50.0);
+/// assert_eq!(entity.health, 50.0);
+/// ```
+/// ```rust,compile_fail
+/// use memory_layout::declare_layout;
+///
+/// declare_layout! {
+///   #[memory_layout(0x10)]
+///   pub struct Layout for u64 {
+///     #[field_offset(0x08)]
+///     pub id: u32
+///   }
+/// }
+/// ```
+#[proc_macro]
+pub fn declare_layout(input: TokenStream) -> TokenStream {
+  let input = parse_macro_input!(input as declare::DeclareLayout);
+  declare::generate_declared_layout(input)
+    .unwrap_or_else(|err| err.to_compile_error())
+    .into()
+}
+
+/// Offset of a field of a struct annotated with `#[memory_layout]`, like `core::mem::offset_of!`
+/// but usable on compilers predating it.
+///
+/// `field_offset!(Foo, b)` expands to the generated `Foo::OFFSET_B` constant, so it can be used in
+/// constant expressions.
+/// ```rust
+/// use memory_layout::{field_offset, memory_layout};
+///
+/// #[memory_layout(0x20)]
+/// pub struct CPed {
+///   #[field_offset(0x10)]
+///   health: f32
+/// }
+///
+/// const HEALTH: usize = field_offset!(CPed, health);
+/// assert_eq!(HEALTH, 0x10);
+/// ```
+///
+/// ```rust,compile_fail
+/// use memory_layout::{field_offset, memory_layout};
+///
+/// #[memory_layout(0x20)]
+/// pub struct CPed {
+///   #[field_offset(0x10)]
+///   health: f32
+/// }
+///
+/// // error: no associated item named `OFFSET_ARMOR` found for struct `CPed`
+/// let armor = field_offset!(CPed, armor);
+/// ```
+#[proc_macro]
+pub fn field_offset(input: TokenStream) -> TokenStream {
+  let args = parse_macro_input!(input as FieldOffsetArgs);
+  let ty = &args.ty;
+  let constant = constant_ident("OFFSET", &args.field);
+  quote!(<#ty>::#constant).into()
+}
+
+/// Arguments of `field_offset!`, a type and one of its fields.
+struct FieldOffsetArgs {
+  ty:    Type,
+  field: syn::Ident
+}
+
+impl Parse for FieldOffsetArgs {
+  fn parse(input: syn::parse::ParseStream) -> SynResult<Self> {
+    let ty = input.parse()?;
+    input.parse::<Token![,]>()?;
+    let field = input.call(syn::Ident::parse_any)?;
+    input.parse::<Option<Token![,]>>()?;
+    Ok(FieldOffsetArgs { ty, field })
   }
-  .into()
 }
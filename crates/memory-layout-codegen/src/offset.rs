@@ -0,0 +1,259 @@
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::{
+  parenthesized,
+  parse::{Parse, ParseStream},
+  Error as SynError, Expr, Ident, LitInt, Result as SynResult, Token, Type
+};
+
+mod kw {
+  syn::custom_keyword!(end);
+  syn::custom_keyword!(after);
+  syn::custom_keyword!(x86);
+  syn::custom_keyword!(x64);
+  syn::custom_keyword!(runtime);
+}
+
+/// An offset into the struct.
+#[derive(Clone)]
+pub enum Offset {
+  /// An offset that's known while expanding the macro.
+  Known(usize),
+  /// An offset that's only known once the generated constant expression is evaluated.
+  Expr(TokenStream)
+}
+
+impl Offset {
+  pub fn known(&self) -> Option<usize> {
+    match self {
+      Offset::Known(offset) => Some(*offset),
+      Offset::Expr(_) => None
+    }
+  }
+
+  /// The offset directly after a field of type `ty` placed at this offset.
+  pub fn after(&self, ty: &Type) -> Offset {
+    Offset::Expr(quote!(#self + ::core::mem::size_of::<#ty>()))
+  }
+
+  pub fn add(&self, value: usize) -> Offset {
+    match self {
+      Offset::Known(offset) => Offset::Known(offset + value),
+      Offset::Expr(_) if value == 0 => self.clone(),
+      Offset::Expr(_) => Offset::Expr(quote!(#self + #value))
+    }
+  }
+
+  /// Size of the padding needed to get from this offset, optionally followed by a field of type
+  /// `ty`, to `to`.
+  ///
+  /// Saturates at zero, overlapping fields are reported by separate checks with clearer messages
+  /// than an overflow in the padding size.
+  pub fn padding_to(&self, ty: Option<&Type>, to: &Offset) -> TokenStream {
+    let distance = match (self, to) {
+      (Offset::Known(from), Offset::Known(to)) => {
+        let distance = to - from;
+        quote!(#distance)
+      }
+      _ => quote!(#to.saturating_sub(#self))
+    };
+
+    match ty {
+      Some(ty) => quote!(#distance.saturating_sub(::core::mem::size_of::<#ty>())),
+      None => distance
+    }
+  }
+
+  /// Describes the offset for error messages.
+  pub fn describe(&self) -> String {
+    match self {
+      Offset::Known(offset) => format!("0x{:X}", offset),
+      Offset::Expr(expr) => format!("`{}`", expr)
+    }
+  }
+}
+
+impl ToTokens for Offset {
+  fn to_tokens(&self, tokens: &mut TokenStream) {
+    match self {
+      Offset::Known(offset) => offset.to_tokens(tokens),
+      Offset::Expr(expr) => tokens.extend(quote!((#expr)))
+    }
+  }
+}
+
+/// Argument of a `field_offset` attribute.
+pub enum FieldOffset {
+  /// `#[field_offset(0x10)]`, an offset from the start of the struct.
+  Absolute(usize),
+  /// `#[field_offset(end = 0x10)]`, an offset from the end of the struct.
+  FromEnd(usize),
+  /// `#[field_offset(after(a) + 0x10)]`, an offset relative to the end of a previous field.
+  After(Ident, usize),
+  /// `#[field_offset(offsets::FIELD_A)]` or `#[field_offset(BASE + 0x8)]`, a constant
+  /// expression.
+  Expr(TokenStream),
+  /// `#[field_offset(x86 = 0x10, x64 = 0x18)]`, an offset depending on the pointer width.
+  PointerWidth(PointerWidthValue),
+  /// `#[field_offset(v1_58 = 0x148, v1_63 = 0x150)]`, an offset per version of the target.
+  Versioned(VersionedValue),
+  /// `#[field_offset(runtime)]`, an offset that's only known at runtime.
+  Runtime
+}
+
+/// A value given as `x86 = .., x64 = ..`, selected by the target's pointer width.
+#[derive(Clone, Copy)]
+pub struct PointerWidthValue {
+  pub x86: usize,
+  pub x64: usize
+}
+
+impl PointerWidthValue {
+  pub fn select(self, x64: bool) -> usize {
+    if x64 {
+      self.x64
+    } else {
+      self.x86
+    }
+  }
+
+  /// Whether the input starts with a pointer width selector.
+  pub fn peek(input: ParseStream) -> bool {
+    (input.peek(kw::x86) || input.peek(kw::x64)) && input.peek2(Token![=])
+  }
+}
+
+impl Parse for PointerWidthValue {
+  fn parse(input: ParseStream) -> SynResult<Self> {
+    let span = input.span();
+    let (mut x86, mut x64) = (None, None);
+    while PointerWidthValue::peek(input) {
+      let lookahead = input.lookahead1();
+      let target = if lookahead.peek(kw::x86) {
+        input.parse::<kw::x86>()?;
+        &mut x86
+      } else {
+        input.parse::<kw::x64>()?;
+        &mut x64
+      };
+      input.parse::<Token![=]>()?;
+      let lit = input.parse::<LitInt>()?;
+      if target.replace(lit.base10_parse::<usize>()?).is_some() {
+        return Err(SynError::new(lit.span(), "Value is specified twice."));
+      }
+      // A comma is only part of the value if another selector follows it.
+      let fork = input.fork();
+      if fork.parse::<Token![,]>().is_ok() && PointerWidthValue::peek(&fork) {
+        input.parse::<Token![,]>()?;
+      }
+    }
+
+    match (x86, x64) {
+      (Some(x86), Some(x64)) => Ok(PointerWidthValue { x86, x64 }),
+      _ => {
+        Err(SynError::new(
+          span,
+          "Both an `x86` and an `x64` value are required."
+        ))
+      }
+    }
+  }
+}
+
+/// A value given as `v1_58 = .., v1_63 = ..`, selected by the enabled version.
+#[derive(Clone)]
+pub struct VersionedValue {
+  pub values: Vec<(Ident, usize)>
+}
+
+impl VersionedValue {
+  /// Whether the input starts with a version selector.
+  pub fn peek(input: ParseStream) -> bool {
+    input.peek(Ident) && input.peek2(Token![=])
+  }
+
+  /// The value for `version`.
+  pub fn get(&self, version: &Ident) -> Option<usize> {
+    self
+      .values
+      .iter()
+      .find(|(name, _)| name == version)
+      .map(|(_, value)| *value)
+  }
+}
+
+impl Parse for VersionedValue {
+  fn parse(input: ParseStream) -> SynResult<Self> {
+    let mut values = Vec::<(Ident, usize)>::new();
+    while VersionedValue::peek(input) {
+      let version = input.parse::<Ident>()?;
+      input.parse::<Token![=]>()?;
+      let value = input.parse::<LitInt>()?.base10_parse::<usize>()?;
+      if values.iter().any(|(name, _)| *name == version) {
+        return Err(SynError::new(version.span(), "Value is specified twice."));
+      }
+      values.push((version, value));
+      // A comma is only part of the value if another selector follows it.
+      let fork = input.fork();
+      if fork.parse::<Token![,]>().is_ok() && VersionedValue::peek(&fork) {
+        input.parse::<Token![,]>()?;
+      }
+    }
+
+    if values.is_empty() {
+      return Err(input.error("Expected a version selector."));
+    }
+    Ok(VersionedValue { values })
+  }
+}
+
+impl Parse for FieldOffset {
+  fn parse(input: ParseStream) -> SynResult<Self> {
+    if input.peek(kw::end) {
+      input.parse::<kw::end>()?;
+      input.parse::<Token![=]>()?;
+      Ok(FieldOffset::FromEnd(
+        input.parse::<LitInt>()?.base10_parse()?
+      ))
+    } else if PointerWidthValue::peek(input) {
+      Ok(FieldOffset::PointerWidth(input.parse()?))
+    } else if input.peek(kw::runtime) && {
+      let fork = input.fork();
+      fork.parse::<kw::runtime>()?;
+      fork.is_empty()
+    } {
+      input.parse::<kw::runtime>()?;
+      Ok(FieldOffset::Runtime)
+    } else if VersionedValue::peek(input) {
+      Ok(FieldOffset::Versioned(input.parse()?))
+    } else if input.peek(kw::after) {
+      input.parse::<kw::after>()?;
+      let content;
+      parenthesized!(content in input);
+      let ident = content.parse::<Ident>()?;
+      let extra = if input.peek(Token![+]) {
+        input.parse::<Token![+]>()?;
+        input.parse::<LitInt>()?.base10_parse()?
+      } else {
+        0
+      };
+      Ok(FieldOffset::After(ident, extra))
+    } else if input.peek(LitInt) && {
+      let fork = input.fork();
+      fork.parse::<LitInt>()?;
+      fork.is_empty() || fork.peek(Token![,])
+    } {
+      Ok(FieldOffset::Absolute(
+        input.parse::<LitInt>()?.base10_parse()?
+      ))
+    } else {
+      if input.is_empty() || input.peek(Token![,]) {
+        return Err(SynError::new(input.span(), "Expected an offset."));
+      }
+      // Parsed as a whole, so commas in generic arguments don't end the offset.
+      Ok(FieldOffset::Expr(
+        input.parse::<Expr>()?.into_token_stream()
+      ))
+    }
+  }
+}
@@ -0,0 +1,97 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Attribute, Data, DeriveInput, Result as SynResult};
+
+use crate::{
+  args::LayoutArgs,
+  expand,
+  offset::{FieldOffset, Offset, PointerWidthValue},
+  parse_attribute_value
+};
+
+/// Selector value of a `field_offset` attribute, if it has one.
+fn selector(attr: &Attribute) -> Option<PointerWidthValue> {
+  if !attr.path().is_ident("field_offset") {
+    return None;
+  }
+  match parse_attribute_value::<FieldOffset>(attr) {
+    Ok(FieldOffset::PointerWidth(value)) => Some(value),
+    _ => None
+  }
+}
+
+/// Whether the size or any field offset depends on the pointer width.
+pub fn has_selectors(input: &DeriveInput, args: &LayoutArgs) -> bool {
+  let fields = match &input.data {
+    Data::Struct(data) => data.fields.iter().collect::<Vec<_>>(),
+    Data::Union(data) => data.fields.named.iter().collect(),
+    Data::Enum(_) => vec![]
+  };
+  args.pointer_width_size.is_some()
+    || fields
+      .iter()
+      .flat_map(|field| &field.attrs)
+      .any(|attr| selector(attr).is_some())
+}
+
+/// Attributes of every field of a struct or union.
+pub fn field_attrs_mut(input: &mut DeriveInput) -> Vec<&mut Attribute> {
+  match &mut input.data {
+    Data::Struct(data) => {
+      data
+        .fields
+        .iter_mut()
+        .flat_map(|field| &mut field.attrs)
+        .collect()
+    }
+    Data::Union(data) => {
+      data
+        .fields
+        .named
+        .iter_mut()
+        .flat_map(|field| &mut field.attrs)
+        .collect()
+    }
+    Data::Enum(_) => vec![]
+  }
+}
+
+/// Replaces every selector with the value for one pointer width.
+fn resolve(input: &DeriveInput, args: &LayoutArgs, x64: bool) -> (DeriveInput, LayoutArgs) {
+  let mut input = input.clone();
+  let mut args = args.clone();
+  if let Some(size) = args.pointer_width_size.take() {
+    args.size = Some(Offset::Known(size.select(x64)));
+  }
+
+  for attr in field_attrs_mut(&mut input) {
+    if let Some(value) = selector(attr) {
+      let offset = value.select(x64);
+      *attr = syn::parse_quote!(#[field_offset(#offset)]);
+    }
+  }
+
+  (input, args)
+}
+
+/// Expands the layout once per pointer width, gating every generated item on
+/// `target_pointer_width`.
+pub fn expand_per_pointer_width(input: DeriveInput, args: LayoutArgs) -> SynResult<TokenStream> {
+  let widths = [
+    (quote!(target_pointer_width = "64"), true),
+    (quote!(not(target_pointer_width = "64")), false)
+  ];
+
+  let mut output = TokenStream::new();
+  for (cfg, x64) in widths {
+    let (input, args) = resolve(&input, &args, x64);
+    let file = syn::parse2::<syn::File>(expand(input, args))?;
+    for item in file.items {
+      output.extend(quote! {
+        #[cfg(#cfg)]
+        #item
+      });
+    }
+  }
+  Ok(output)
+}
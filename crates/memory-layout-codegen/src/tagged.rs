@@ -0,0 +1,405 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+  ext::IdentExt, Data, DataEnum, DeriveInput, Error as SynError, Fields, Result as SynResult, Type
+};
+
+use crate::{
+  args::{DiscriminantArgs, LayoutArgs},
+  check_message, constant_ident, generate_layout_diagram, generate_memory_layout_impl,
+  generate_size_align_check, is_helper_attribute, known_size,
+  offset::{FieldOffset, Offset},
+  repr_attribute, runtime_crate, setter_ident, StructInfo
+};
+
+/// A payload field of a variant.
+struct PayloadField<'a> {
+  field:  &'a syn::Field,
+  offset: Offset,
+  /// `<variant>_<field>`, the name of the generated getter.
+  getter: syn::Ident
+}
+
+struct VariantInfo<'a> {
+  variant: &'a syn::Variant,
+  /// Variant name in snake case.
+  snake:   String,
+  fields:  Vec<PayloadField<'a>>
+}
+
+fn snake_case(ident: &syn::Ident) -> String {
+  let mut result = String::new();
+  for (i, c) in ident.unraw().to_string().chars().enumerate() {
+    if c.is_uppercase() {
+      if i != 0 {
+        result.push('_');
+      }
+      result.extend(c.to_lowercase());
+    } else {
+      result.push(c);
+    }
+  }
+  result
+}
+
+fn get_discriminant(input: &DeriveInput) -> SynResult<(Offset, Type)> {
+  let attr = input
+    .attrs
+    .iter()
+    .find(|attr| attr.path().is_ident("discriminant"))
+    .ok_or_else(|| {
+      SynError::new_spanned(
+        &input.ident,
+        "Enums require a `#[discriminant(offset = .., ty = ..)]` attribute."
+      )
+    })?;
+  let args = attr.parse_args::<DiscriminantArgs>()?;
+  let offset = match args.offset {
+    FieldOffset::Absolute(offset) => Offset::Known(offset),
+    FieldOffset::Expr(expr) => Offset::Expr(expr),
+    _ => {
+      return Err(SynError::new_spanned(
+        attr,
+        "Discriminant offset must be an integer literal or a constant expression."
+      ))
+    }
+  };
+  Ok((offset, args.ty))
+}
+
+fn get_variants<'a>(data: &'a DataEnum, size: &Offset) -> SynResult<Vec<VariantInfo<'a>>> {
+  data
+    .variants
+    .iter()
+    .map(|variant| {
+      if variant.discriminant.is_none() {
+        return Err(SynError::new_spanned(
+          variant,
+          "Every variant requires an explicit discriminant."
+        ));
+      }
+      let named = match &variant.fields {
+        Fields::Named(fields) => fields.named.iter().collect::<Vec<_>>(),
+        Fields::Unit => vec![],
+        Fields::Unnamed(fields) => {
+          return Err(SynError::new_spanned(
+            fields,
+            "Variant fields must be named."
+          ))
+        }
+      };
+
+      let snake = snake_case(&variant.ident);
+      let fields = named
+        .into_iter()
+        .map(|field| {
+          let ident = field.ident.as_ref().unwrap();
+          let attr = field
+            .attrs
+            .iter()
+            .find(|attr| attr.path().is_ident("field_offset"))
+            .ok_or_else(|| {
+              SynError::new_spanned(ident, "Field requires a field_offset attribute.")
+            })?;
+          Ok(PayloadField {
+            field,
+            offset: StructInfo::get_field_offset_value(attr, Some(size), &[])?,
+            getter: format_ident!("{}_{}", snake, ident.unraw())
+          })
+        })
+        .collect::<SynResult<Vec<_>>>()?;
+
+      Ok(VariantInfo {
+        variant,
+        snake,
+        fields
+      })
+    })
+    .collect()
+}
+
+/// Rejects a payload field that shares bytes with `other`, the discriminant or another field of
+/// the same variant, at expansion when both ranges are known, otherwise with a const assert.
+fn generate_payload_overlap_check(
+  payload: &PayloadField,
+  variant: &syn::Ident,
+  (other_offset, other_ty, other_name): (&Offset, &Type, String)
+) -> SynResult<Option<TokenStream>> {
+  let ident = payload.field.ident.as_ref().unwrap();
+  let ty = &payload.field.ty;
+  let offset = &payload.offset;
+  let message = format!(
+    "`{}` of `{}` at {} overlaps {} at {}.",
+    ident,
+    variant,
+    offset.describe(),
+    other_name,
+    other_offset.describe()
+  );
+
+  let known = offset
+    .known()
+    .zip(known_size(ty))
+    .zip(other_offset.known().zip(known_size(other_ty)));
+  if let Some(((start, size), (other_start, other_size))) = known {
+    if start < other_start + other_size && other_start < start + size {
+      return Err(SynError::new_spanned(ident, message));
+    }
+    return Ok(None);
+  }
+
+  let message = check_message(message);
+  Ok(Some(quote! {
+    const _: () = assert!(
+      #offset + ::core::mem::size_of::<#ty>() <= #other_offset
+        || #other_offset + ::core::mem::size_of::<#other_ty>() <= #offset,
+      #message
+    );
+  }))
+}
+
+/// Generates a struct of `size` bytes holding a discriminant at a fixed offset, with accessors
+/// that only read a variant's payload while its discriminant is stored.
+pub fn generate_tagged(input: &DeriveInput, args: &LayoutArgs) -> SynResult<TokenStream> {
+  let Data::Enum(data) = &input.data else {
+    unreachable!()
+  };
+  let size = args.size.as_ref().ok_or_else(|| {
+    SynError::new_spanned(
+      &input.ident,
+      "Enums require a size, as in `#[memory_layout(0x20)]`."
+    )
+  })?;
+  if !input.generics.params.is_empty() {
+    return Err(SynError::new_spanned(
+      &input.generics,
+      "Generic enums aren't supported."
+    ));
+  }
+  if args.builder || args.sparse || args.strict || args.base.is_some() {
+    return Err(SynError::new_spanned(
+      &input.ident,
+      "Enums don't support builder, sparse, strict or base."
+    ));
+  }
+
+  let size_align_check = generate_size_align_check(&input.ident, size, args.align)?;
+  let repr = match args.align {
+    Some(_) => repr_attribute(args.align),
+    None => quote!(#[repr(C, packed)])
+  };
+  let (tag_offset, tag_ty) = get_discriminant(input)?;
+  let variants = get_variants(data, size)?;
+  let runtime = runtime_crate();
+  let layout_diagram = generate_layout_diagram();
+  let name = &input.ident;
+  let vis = &input.vis;
+  let attrs = input
+    .attrs
+    .iter()
+    .filter(|attr| !attr.path().is_ident("discriminant"));
+
+  let read = |offset: &Offset, ty: &Type| quote!(unsafe { (self.__bytes.as_ptr().add(#offset) as *const #ty).read_unaligned() });
+  let write = |target: TokenStream, offset: &Offset, ty: &Type, value: TokenStream| {
+    quote! {
+      unsafe { (#target.__bytes.as_mut_ptr().add(#offset) as *mut #ty).write_unaligned(#value) }
+    }
+  };
+  let read_tag = read(&tag_offset, &tag_ty);
+
+  let mut bounds_checks = vec![quote! {
+    const _: () = assert!(
+      #tag_offset + ::core::mem::size_of::<#tag_ty>() <= #size,
+      "The discriminant extends past the end of the enum."
+    );
+  }];
+  let mut items = Vec::new();
+  let mut layout_fields = vec![quote! {
+    #runtime::FieldDesc {
+      name:   "discriminant",
+      offset: #tag_offset,
+      size:   ::core::mem::size_of::<#tag_ty>()
+    }
+  }];
+
+  for variant in &variants {
+    let variant_ident = &variant.variant.ident;
+    let (_, discriminant) = variant.variant.discriminant.as_ref().unwrap();
+    for (index, payload) in variant.fields.iter().enumerate() {
+      let discriminant = (&tag_offset, &tag_ty, "the discriminant".to_string());
+      let siblings = variant.fields[..index].iter().map(|sibling| {
+        let sibling_ident = sibling.field.ident.as_ref().unwrap();
+        (
+          &sibling.offset,
+          &sibling.field.ty,
+          format!("`{}`", sibling_ident)
+        )
+      });
+      for other in std::iter::once(discriminant).chain(siblings) {
+        bounds_checks.extend(generate_payload_overlap_check(
+          payload,
+          variant_ident,
+          other
+        )?);
+      }
+    }
+
+    let is_variant = format_ident!("is_{}", variant.snake);
+    let constructor = format_ident!("new_{}", variant.snake);
+    let write_tag = write(quote!(result), &tag_offset, &tag_ty, quote!(#discriminant));
+    let params = variant.fields.iter().map(|payload| {
+      let ident = payload.field.ident.as_ref().unwrap();
+      let ty = &payload.field.ty;
+      quote!(#ident: #ty)
+    });
+    let payload_types = variant.fields.iter().map(|payload| &payload.field.ty);
+    let writes = variant.fields.iter().map(|payload| {
+      let ident = payload.field.ident.as_ref().unwrap();
+      write(
+        quote!(result),
+        &payload.offset,
+        &payload.field.ty,
+        quote!(#ident)
+      )
+    });
+    let is_doc = format!("Whether the discriminant is that of `{}`.", variant_ident);
+    let constructor_doc = format!(
+      "Creates a zeroed `{}` holding `{}` with the given payload.",
+      name, variant_ident
+    );
+    items.push(quote! {
+      #[doc = #is_doc]
+      #[inline]
+      pub fn #is_variant(&self) -> bool {
+        self.discriminant() == #discriminant
+      }
+
+      #[doc = #constructor_doc]
+      pub fn #constructor(#(#params),*) -> Self
+      where
+        #(for<'__memory_layout> #payload_types: #runtime::AnyBitPattern + #runtime::NoUninit),*
+      {
+        let mut result = Self { __bytes: [0; #size] };
+        #write_tag;
+        #(#writes;)*
+        result
+      }
+    });
+
+    for payload in &variant.fields {
+      let ident = payload.field.ident.as_ref().unwrap();
+      let getter = &payload.getter;
+      let setter = setter_ident(getter);
+      let ty = &payload.field.ty;
+      let offset = &payload.offset;
+      let vis = &payload.field.vis;
+      let attrs = payload
+        .field
+        .attrs
+        .iter()
+        .filter(|attr| !is_helper_attribute(attr));
+      let read_payload = read(offset, ty);
+      let write_payload = write(quote!(self), offset, ty, quote!(value));
+      let constant = constant_ident("OFFSET", getter);
+      let getter_doc = format!(
+        "Reads `{}` of `{}`, returns `None` if another variant is stored.",
+        ident, variant_ident
+      );
+      let setter_doc = format!(
+        "Writes `{}` of `{}`, returns `false` without writing if another variant is stored.",
+        ident, variant_ident
+      );
+      let constant_doc = format!("Offset of `{}` of `{}` in bytes.", ident, variant_ident);
+      let message = check_message(format!(
+        "`{}` of `{}` extends past the end of the enum.",
+        ident, variant_ident
+      ));
+      let layout_name = format!("{}::{}", variant_ident, ident.unraw());
+
+      bounds_checks.push(quote! {
+        const _: () = assert!(#offset + ::core::mem::size_of::<#ty>() <= #size, #message);
+      });
+      layout_fields.push(quote! {
+        #runtime::FieldDesc {
+          name:   #layout_name,
+          offset: #offset,
+          size:   ::core::mem::size_of::<#ty>()
+        }
+      });
+      items.push(quote! {
+        #[doc = #constant_doc]
+        pub const #constant: usize = #offset;
+
+        #(#attrs)*
+        #[doc = #getter_doc]
+        #[inline]
+        #vis fn #getter(&self) -> ::core::option::Option<#ty>
+        where
+          for<'__memory_layout> #ty: #runtime::AnyBitPattern + #runtime::NoUninit
+        {
+          if self.#is_variant() {
+            ::core::option::Option::Some(#read_payload)
+          } else {
+            ::core::option::Option::None
+          }
+        }
+
+        #[doc = #setter_doc]
+        #[inline]
+        #vis fn #setter(&mut self, value: #ty) -> bool
+        where
+          for<'__memory_layout> #ty: #runtime::AnyBitPattern + #runtime::NoUninit
+        {
+          if !self.#is_variant() {
+            return false;
+          }
+          #write_payload;
+          true
+        }
+      });
+    }
+  }
+
+  let layout_name = name.to_string();
+  let memory_layout_impl = generate_memory_layout_impl(name, &input.generics, Some(size));
+
+  Ok(quote! {
+    #repr
+    #(#attrs)*
+    #vis struct #name {
+      __bytes: [u8; #size]
+    }
+
+    #size_align_check
+    #(#bounds_checks)*
+
+    impl #name {
+      /// Offset of the discriminant in bytes.
+      pub const OFFSET_DISCRIMINANT: usize = #tag_offset;
+
+      /// Reads the stored discriminant.
+      #[inline]
+      pub fn discriminant(&self) -> #tag_ty {
+        #read_tag
+      }
+
+      #(#items)*
+
+      #[doc(hidden)]
+      const __MEMORY_LAYOUT: #runtime::Layout = #runtime::Layout {
+        name:   #layout_name,
+        size:   #size,
+        fields: &[#(#layout_fields),*]
+      };
+
+      /// Describes the memory layout of this enum, payloads of different variants may overlap.
+      pub fn layout() -> &'static #runtime::Layout {
+        &Self::__MEMORY_LAYOUT
+      }
+
+      #layout_diagram
+    }
+
+    #memory_layout_impl
+  })
+}
@@ -0,0 +1,267 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Data, DeriveInput, Error as SynError, Fields, Result as SynResult};
+
+use crate::{
+  args::LayoutArgs, check_message, generate_layout_diagram, generate_memory_layout_impl,
+  generate_size_align_check, is_helper_attribute, offset::Offset, repr_attribute, runtime_crate,
+  StructInfo
+};
+
+/// A tuple struct element placed at its `field_offset`.
+struct ElementInfo<'a> {
+  field:  &'a syn::Field,
+  offset: Offset
+}
+
+fn get_elements<'a>(
+  fields: impl Iterator<Item = &'a syn::Field>,
+  args: &LayoutArgs
+) -> SynResult<Vec<ElementInfo<'a>>> {
+  fields
+    .map(|field| {
+      let attr = field
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("field_offset"))
+        .ok_or_else(|| {
+          SynError::new_spanned(field, "Element requires a field_offset attribute.")
+        })?;
+      if let Some(attr) = field
+        .attrs
+        .iter()
+        .find(|attr| is_helper_attribute(attr) && !attr.path().is_ident("field_offset"))
+      {
+        return Err(SynError::new_spanned(
+          attr,
+          "Tuple struct elements only support field_offset."
+        ));
+      }
+
+      Ok(ElementInfo {
+        field,
+        offset: StructInfo::get_field_offset_value(attr, args.size.as_ref(), &[])?
+      })
+    })
+    .collect()
+}
+
+/// Generates a tuple struct whose elements keep their indices, so they're accessed as `foo.0`.
+/// Padding can only be appended after the last element without shifting the indices, so elements
+/// have to follow each other without gaps, which is checked at compile time.
+pub fn generate_tuple(input: &DeriveInput, args: &LayoutArgs) -> SynResult<TokenStream> {
+  let Data::Struct(data) = &input.data else {
+    unreachable!()
+  };
+  let Fields::Unnamed(fields) = &data.fields else {
+    unreachable!()
+  };
+  if !input.generics.params.is_empty() {
+    return Err(SynError::new_spanned(
+      &input.generics,
+      "Generic tuple structs aren't supported."
+    ));
+  }
+  if let Some(attr) = input.attrs.iter().find(|attr| attr.path().is_ident("repr")) {
+    return Err(SynError::new_spanned(
+      attr,
+      "A repr can't be combined with a memory_layout tuple struct."
+    ));
+  }
+  let unsupported = args.deny_gaps
+    || args.builder
+    || args.sparse
+    || args.pad_fill.is_some()
+    || args.sort
+    || args.verify
+    || args.volatile
+    || args.debug
+    || args.eq
+    || args.hash
+    || args.reflect
+    || args.default
+    || args.zerocopy
+    || args.serde
+    || args.base.is_some()
+    || args.round_to.is_some();
+  if unsupported {
+    return Err(SynError::new_spanned(
+      &input.ident,
+      "Tuple structs only support a size, align and strict."
+    ));
+  }
+
+  let elements = get_elements(fields.unnamed.iter(), args)?;
+  let runtime = runtime_crate();
+  let layout_diagram = generate_layout_diagram();
+  let name = &input.ident;
+  let vis = &input.vis;
+  let attrs = &input.attrs;
+  let repr = match args.align {
+    Some(_) => repr_attribute(args.align),
+    None => quote!(#[repr(C, packed)])
+  };
+  let size_align_check = match &args.size {
+    Some(size) => generate_size_align_check(name, size, args.align)?,
+    None => None
+  };
+
+  let mut checks = Vec::new();
+  let mut end = Offset::Known(0);
+  for (i, element) in elements.iter().enumerate() {
+    let offset = &element.offset;
+    let ty = &element.field.ty;
+    let message = match i {
+      0 => {
+        String::from(
+          "Element 0 has to be at offset 0, tuple structs can't pad before it. Use named fields \
+           instead."
+        )
+      }
+      _ => {
+        format!(
+          "Element {} has to start where element {} ends, tuple structs can't pad between \
+           elements. Use named fields instead.",
+          i,
+          i - 1
+        )
+      }
+    };
+    checks.push(quote! {
+      const _: () = assert!(#offset == #end, #message);
+    });
+    if args.align.is_some() {
+      let message = format!(
+        "Element {} isn't aligned at its offset, which is required with an align.",
+        i
+      );
+      checks.push(quote! {
+        const _: () = assert!(#offset % ::core::mem::align_of::<#ty>() == 0, #message);
+      });
+    }
+    end = offset.after(ty);
+  }
+
+  let padding = args.size.as_ref().map(|size| {
+    let padding_size = end.padding_to(None, size);
+    quote! {
+      #[doc(hidden)]
+      [u8; #padding_size]
+    }
+  });
+  if let (Some(size), true) = (&args.size, args.strict) {
+    let message = check_message(format!(
+      "Desired struct size {} is larger than the end of the last element.",
+      size.describe()
+    ));
+    checks.push(quote! {
+      const _: () = assert!(#end == #size, #message);
+    });
+  }
+  let size_check = args.size.as_ref().map(|size| {
+    quote! {
+      const _: () = assert!(
+        ::core::mem::size_of::<#name>() == #size,
+        "An element extends past the desired struct size."
+      );
+    }
+  });
+
+  let element_defs = elements.iter().map(|element| {
+    let field_vis = &element.field.vis;
+    let ty = &element.field.ty;
+    let attrs = element
+      .field
+      .attrs
+      .iter()
+      .filter(|attr| !is_helper_attribute(attr));
+    quote! {
+      #(#attrs)*
+      #field_vis #ty
+    }
+  });
+
+  let params = (0..elements.len())
+    .map(|i| format_ident!("element_{}", i))
+    .collect::<Vec<_>>();
+  let types = elements.iter().map(|element| &element.field.ty);
+  let zeroed_padding = padding
+    .as_ref()
+    .map(|_| quote!(unsafe { ::core::mem::zeroed() }));
+  let constructor = quote! {
+    /// Creates the struct from its elements, zeroing the trailing padding.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(#(#params: #types),*) -> Self {
+      Self(#(#params,)* #zeroed_padding)
+    }
+  };
+
+  let constants = elements.iter().enumerate().map(|(i, element)| {
+    let offset_constant = format_ident!("OFFSET_{}", i);
+    let size_constant = format_ident!("SIZE_{}", i);
+    let end_constant = format_ident!("END_{}", i);
+    let offset = &element.offset;
+    let ty = &element.field.ty;
+    let offset_doc = format!("Offset of element {} in bytes.", i);
+    let size_doc = format!("Size of element {} in bytes.", i);
+    let end_doc = format!("Offset of the first byte after element {}.", i);
+    quote! {
+      #[doc = #offset_doc]
+      pub const #offset_constant: usize = #offset;
+      #[doc = #size_doc]
+      pub const #size_constant: usize = ::core::mem::size_of::<#ty>();
+      #[doc = #end_doc]
+      pub const #end_constant: usize = Self::#offset_constant + Self::#size_constant;
+    }
+  });
+
+  let layout_name = name.to_string();
+  let memory_layout_impl = generate_memory_layout_impl(name, &input.generics, args.size.as_ref());
+  let layout_fields = elements.iter().enumerate().map(|(i, element)| {
+    let name = i.to_string();
+    let offset = &element.offset;
+    let ty = &element.field.ty;
+    quote! {
+      #runtime::FieldDesc {
+        name:   #name,
+        offset: #offset,
+        size:   ::core::mem::size_of::<#ty>()
+      }
+    }
+  });
+
+  Ok(quote! {
+    #repr
+    #(#attrs)*
+    #vis struct #name(
+      #(#element_defs,)*
+      #padding
+    );
+
+    #(#checks)*
+    #size_check
+    #size_align_check
+
+    impl #name {
+      #constructor
+
+      #(#constants)*
+
+      #[doc(hidden)]
+      const __MEMORY_LAYOUT: #runtime::Layout = #runtime::Layout {
+        name:   #layout_name,
+        size:   ::core::mem::size_of::<Self>(),
+        fields: &[#(#layout_fields),*]
+      };
+
+      /// Describes the memory layout of this struct.
+      pub fn layout() -> &'static #runtime::Layout {
+        &Self::__MEMORY_LAYOUT
+      }
+
+      #layout_diagram
+    }
+
+    #memory_layout_impl
+  })
+}
@@ -0,0 +1,238 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{ext::IdentExt, Data, DataUnion, DeriveInput, Error as SynError, Result as SynResult};
+
+use crate::{
+  args::LayoutArgs, constant_ident, generate_layout_diagram, generate_memory_layout_impl,
+  generate_size_align_check, is_helper_attribute, offset::Offset, repr_attribute, runtime_crate,
+  setter_ident, StructInfo
+};
+
+/// A union member placed at its `field_offset`.
+struct MemberInfo<'a> {
+  field:  &'a syn::Field,
+  offset: Offset,
+  /// Packed struct placing the member at its offset.
+  holder: syn::Ident
+}
+
+fn get_members<'a>(
+  input: &'a DeriveInput,
+  data: &'a DataUnion,
+  args: &LayoutArgs
+) -> SynResult<Vec<MemberInfo<'a>>> {
+  data
+    .fields
+    .named
+    .iter()
+    .map(|field| {
+      let ident = field.ident.as_ref().unwrap();
+      let attr = field
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("field_offset"))
+        .ok_or_else(|| SynError::new_spanned(ident, "Field requires a field_offset attribute."))?;
+      if let Some(attr) = field
+        .attrs
+        .iter()
+        .find(|attr| is_helper_attribute(attr) && !attr.path().is_ident("field_offset"))
+      {
+        return Err(SynError::new_spanned(
+          attr,
+          "Union members only support field_offset."
+        ));
+      }
+
+      Ok(MemberInfo {
+        field,
+        offset: StructInfo::get_field_offset_value(attr, args.size.as_ref(), &[])?,
+        holder: format_ident!("__{}_{}", input.ident, ident.unraw())
+      })
+    })
+    .collect()
+}
+
+/// Generates a union whose members are each wrapped in a packed struct that places them at
+/// their `field_offset`, so members can overlap at arbitrary offsets.
+pub fn generate_union(input: &DeriveInput, args: &LayoutArgs) -> SynResult<TokenStream> {
+  let Data::Union(data) = &input.data else {
+    unreachable!()
+  };
+  if !input.generics.params.is_empty() {
+    return Err(SynError::new_spanned(
+      &input.generics,
+      "Generic unions aren't supported."
+    ));
+  }
+  if let Some(attr) = input.attrs.iter().find(|attr| attr.path().is_ident("repr")) {
+    return Err(SynError::new_spanned(
+      attr,
+      "A repr can't be combined with a memory_layout union."
+    ));
+  }
+  if args.builder || args.sparse || args.strict || args.base.is_some() {
+    return Err(SynError::new_spanned(
+      &input.ident,
+      "Unions don't support builder, sparse, strict or base."
+    ));
+  }
+
+  let size_align_check = match &args.size {
+    Some(size) => generate_size_align_check(&input.ident, size, args.align)?,
+    None => None
+  };
+  let repr = match args.align {
+    Some(_) => repr_attribute(args.align),
+    None => quote!(#[repr(C, packed)])
+  };
+  let members = get_members(input, data, args)?;
+  let runtime = runtime_crate();
+  let layout_diagram = generate_layout_diagram();
+  let name = &input.ident;
+  let vis = &input.vis;
+  let attrs = &input.attrs;
+
+  let holders = members.iter().map(|member| {
+    let holder = &member.holder;
+    let offset = &member.offset;
+    let ty = &member.field.ty;
+    quote! {
+      #[doc(hidden)]
+      #[allow(non_camel_case_types)]
+      #[repr(C, packed)]
+      #[derive(Clone, Copy)]
+      #vis struct #holder {
+        __pad: [u8; #offset],
+        value: #ty
+      }
+    }
+  });
+
+  let fields = members.iter().map(|member| {
+    let ident = member.field.ident.as_ref().unwrap();
+    let holder = &member.holder;
+    let field_vis = &member.field.vis;
+    let attrs = member
+      .field
+      .attrs
+      .iter()
+      .filter(|attr| !is_helper_attribute(attr));
+    quote! {
+      #(#attrs)*
+      #field_vis #ident: #holder
+    }
+  });
+  let size_field = args.size.as_ref().map(|size| {
+    quote! {
+      #[doc(hidden)]
+      __size: [u8; #size],
+    }
+  });
+  let size_check = args.size.as_ref().map(|size| {
+    quote! {
+      const _: () = assert!(
+        ::core::mem::size_of::<#name>() == #size,
+        "A union member extends past the desired union size."
+      );
+    }
+  });
+
+  let accessors = members.iter().map(|member| {
+    let ident = member.field.ident.as_ref().unwrap();
+    let setter = setter_ident(ident);
+    let ty = &member.field.ty;
+    let field_vis = &member.field.vis;
+    let getter_doc = format!(
+      "Reads `{}` without requiring it to be aligned.\n\n# Safety\n\nThe bytes of `{}` must \
+       form a valid `{}`, as with reading any union field.",
+      ident,
+      ident,
+      quote!(#ty)
+    );
+    let setter_doc = format!("Writes `{}` without requiring it to be aligned.", ident);
+    quote! {
+      #[doc = #getter_doc]
+      #[inline]
+      #field_vis unsafe fn #ident(&self) -> #ty {
+        ::core::ptr::addr_of!(self.#ident.value).read_unaligned()
+      }
+
+      #[doc = #setter_doc]
+      #[inline]
+      #field_vis fn #setter(&mut self, value: #ty) {
+        unsafe { ::core::ptr::addr_of_mut!(self.#ident.value).write_unaligned(value) }
+      }
+    }
+  });
+
+  let constants = members.iter().map(|member| {
+    let ident = member.field.ident.as_ref().unwrap();
+    let offset_constant = constant_ident("OFFSET", ident);
+    let size_constant = constant_ident("SIZE", ident);
+    let end_constant = constant_ident("END", ident);
+    let offset = &member.offset;
+    let ty = &member.field.ty;
+    let offset_doc = format!("Offset of `{}` in bytes.", ident);
+    let size_doc = format!("Size of `{}` in bytes.", ident);
+    let end_doc = format!("Offset of the first byte after `{}`.", ident);
+    quote! {
+      #[doc = #offset_doc]
+      pub const #offset_constant: usize = #offset;
+      #[doc = #size_doc]
+      pub const #size_constant: usize = ::core::mem::size_of::<#ty>();
+      #[doc = #end_doc]
+      pub const #end_constant: usize = Self::#offset_constant + Self::#size_constant;
+    }
+  });
+
+  let layout_name = name.to_string();
+  let memory_layout_impl = generate_memory_layout_impl(name, &input.generics, args.size.as_ref());
+  let layout_fields = members.iter().map(|member| {
+    let name = member.field.ident.as_ref().unwrap().unraw().to_string();
+    let offset = &member.offset;
+    let ty = &member.field.ty;
+    quote! {
+      #runtime::FieldDesc {
+        name:   #name,
+        offset: #offset,
+        size:   ::core::mem::size_of::<#ty>()
+      }
+    }
+  });
+
+  Ok(quote! {
+    #(#holders)*
+
+    #repr
+    #(#attrs)*
+    #vis union #name {
+      #size_field
+      #(#fields),*
+    }
+
+    #size_check
+    #size_align_check
+
+    impl #name {
+      #(#accessors)*
+
+      #(#constants)*
+
+      #[doc(hidden)]
+      const __MEMORY_LAYOUT: #runtime::Layout = #runtime::Layout {
+        name:   #layout_name,
+        size:   ::core::mem::size_of::<Self>(),
+        fields: &[#(#layout_fields),*]
+      };
+
+      /// Describes the memory layout of this union, members may overlap.
+      pub fn layout() -> &'static #runtime::Layout {
+        &Self::__MEMORY_LAYOUT
+      }
+
+      #layout_diagram
+    }
+
+    #memory_layout_impl
+  })
+}
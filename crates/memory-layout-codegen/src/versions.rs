@@ -0,0 +1,125 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{DeriveInput, Error as SynError, Result as SynResult};
+
+use crate::{
+  args::{LayoutArgs, Version},
+  expand_for_target,
+  offset::{FieldOffset, Offset, VersionedValue},
+  parse_attribute_value,
+  pointer_width::field_attrs_mut
+};
+
+/// Checks that a selector has a value for every declared version and no others.
+fn check_selector(
+  value: &VersionedValue,
+  versions: &[Version],
+  spanned: &dyn quote::ToTokens
+) -> SynResult<()> {
+  if let Some((name, _)) = value
+    .values
+    .iter()
+    .find(|(name, _)| versions.iter().all(|version| version.name != *name))
+  {
+    return Err(SynError::new(
+      name.span(),
+      format!("Unknown version `{}`.", name)
+    ));
+  }
+  if let Some(version) = versions
+    .iter()
+    .find(|version| value.get(&version.name).is_none())
+  {
+    return Err(SynError::new_spanned(
+      spanned,
+      format!("No value for version `{}`.", version.name)
+    ));
+  }
+  Ok(())
+}
+
+/// Replaces every selector with the value for one version.
+fn resolve(
+  input: &DeriveInput,
+  args: &LayoutArgs,
+  version: &Version
+) -> SynResult<(DeriveInput, LayoutArgs)> {
+  let mut input = input.clone();
+  let mut args = args.clone();
+  let versions = std::mem::take(&mut args.versions);
+  if let Some(size) = args.versioned_size.take() {
+    check_selector(&size, &versions, &input.ident)?;
+    args.size = size.get(&version.name).map(Offset::Known);
+  }
+
+  for attr in field_attrs_mut(&mut input) {
+    if !attr.path().is_ident("field_offset") {
+      continue;
+    }
+    if let Ok(FieldOffset::Versioned(value)) = parse_attribute_value::<FieldOffset>(attr) {
+      check_selector(&value, &versions, attr)?;
+      let offset = value.get(&version.name);
+      *attr = syn::parse_quote!(#[field_offset(#offset)]);
+    }
+  }
+
+  Ok((input, args))
+}
+
+/// Errors for builds enabling no version or more than one.
+fn generate_version_checks(input: &DeriveInput, versions: &[Version]) -> TokenStream {
+  let name = &input.ident;
+  let cfgs = versions.iter().map(|version| &version.cfg);
+  let names = versions
+    .iter()
+    .map(|version| format!("`{}`", version.name))
+    .collect::<Vec<_>>()
+    .join(", ");
+  let missing = format!(
+    "`{}` requires one of the versions {} to be enabled.",
+    name, names
+  );
+
+  let conflicts = versions.iter().enumerate().flat_map(|(i, a)| {
+    versions.iter().skip(i + 1).map(move |b| {
+      let message = format!(
+        "The versions `{}` and `{}` of `{}` are enabled at the same time.",
+        a.name, b.name, name
+      );
+      let (a, b) = (&a.cfg, &b.cfg);
+      quote! {
+        #[cfg(all(#a, #b))]
+        ::core::compile_error!(#message);
+      }
+    })
+  });
+
+  quote! {
+    #[cfg(not(any(#(#cfgs),*)))]
+    ::core::compile_error!(#missing);
+    #(#conflicts)*
+  }
+}
+
+/// Expands the layout once per version, gating every generated item on the version's `cfg`.
+pub fn expand_per_version(input: DeriveInput, args: LayoutArgs) -> SynResult<TokenStream> {
+  if args.versions.is_empty() {
+    return Err(SynError::new_spanned(
+      &input.ident,
+      "Versioned sizes require a `versions(..)` argument."
+    ));
+  }
+  let mut output = generate_version_checks(&input, &args.versions);
+  for version in &args.versions {
+    let (input, args) = resolve(&input, &args, version)?;
+    let file = syn::parse2::<syn::File>(expand_for_target(input, args)?)?;
+    let cfg = &version.cfg;
+    for item in file.items {
+      output.extend(quote! {
+        #[cfg(#cfg)]
+        #item
+      });
+    }
+  }
+  Ok(output)
+}
@@ -0,0 +1,230 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{ext::IdentExt, Data, DeriveInput, Error as SynError, LitInt, Result as SynResult, Type};
+
+use crate::{check_message, constant_ident, parse_attribute_value};
+
+/// An entry of the vtable placed at its `index`.
+struct EntryInfo<'a> {
+  field: &'a syn::Field,
+  index: usize
+}
+
+fn get_entries(input: &DeriveInput) -> SynResult<Vec<EntryInfo<'_>>> {
+  let Data::Struct(data) = &input.data else {
+    return Err(SynError::new_spanned(
+      &input.ident,
+      "vtable_layout can only be applied to structs."
+    ));
+  };
+  let syn::Fields::Named(fields) = &data.fields else {
+    return Err(SynError::new_spanned(
+      &data.fields,
+      "vtable_layout requires named fields."
+    ));
+  };
+
+  let mut next = 0;
+  let mut result = Vec::new();
+  for field in &fields.named {
+    let index = match field
+      .attrs
+      .iter()
+      .find(|attr| attr.path().is_ident("index"))
+    {
+      Some(attr) => {
+        let lit = parse_attribute_value::<LitInt>(attr)?;
+        let index = lit.base10_parse::<usize>()?;
+        if index < next {
+          return Err(SynError::new_spanned(
+            lit,
+            format!(
+              "Index {} is lower than the next free index {}, entries have to be declared in \
+               ascending order.",
+              index, next
+            )
+          ));
+        }
+        index
+      }
+      None => next
+    };
+    next = index + 1;
+    result.push(EntryInfo { field, index });
+  }
+
+  Ok(result)
+}
+
+/// `call_<entry>` wrappers for function pointer entries taking the object as a pointer or reference
+/// in their first argument, which read the vtable from it and call the entry with every argument.
+fn generate_call_wrappers(entries: &[EntryInfo]) -> Vec<TokenStream> {
+  entries
+    .iter()
+    .filter_map(|entry| {
+      let Type::BareFn(function) = &entry.field.ty else {
+        return None;
+      };
+      let this = function.inputs.first()?;
+      if !matches!(this.ty, Type::Ptr(_) | Type::Reference(_)) || function.variadic.is_some() {
+        return None;
+      }
+
+      let ident = entry.field.ident.as_ref().unwrap();
+      let wrapper = format_ident!("call_{}", ident.unraw());
+      let vis = &entry.field.vis;
+      let names = (0..function.inputs.len())
+        .map(|i| format_ident!("arg{}", i))
+        .collect::<Vec<_>>();
+      let types = function.inputs.iter().map(|input| &input.ty);
+      let output = &function.output;
+      let this = &names[0];
+      let doc = format!(
+        "Calls the `{}` entry of the vtable `{}` points to.",
+        ident, this
+      );
+      Some(quote! {
+        #[doc = #doc]
+        ///
+        /// # Safety
+        ///
+        /// The first argument must point to an object starting with a valid pointer to a table
+        /// with this layout, and the entry must be safe to call with the arguments.
+        #[inline]
+        #vis unsafe fn #wrapper(#(#names: #types),*) #output {
+          ((*Self::pointer_of(#this as *const _ as *const ())).#ident)(#(#names),*)
+        }
+      })
+    })
+    .collect()
+}
+
+/// Generates a `repr(C)` table of pointer sized entries placed at their `index`, with functions
+/// reading the table pointer stored at the start of an object.
+pub fn generate_vtable(input: &DeriveInput, count: Option<LitInt>) -> SynResult<TokenStream> {
+  if !input.generics.params.is_empty() {
+    return Err(SynError::new_spanned(
+      &input.generics,
+      "Generic vtables aren't supported."
+    ));
+  }
+  let entries = get_entries(input)?;
+  let count = count
+    .map(|count| count.base10_parse::<usize>().map(|value| (count, value)))
+    .transpose()?;
+  let end = entries.last().map_or(0, |entry| entry.index + 1);
+  if let Some((lit, count)) = &count {
+    if *count < end {
+      return Err(SynError::new_spanned(
+        lit,
+        format!(
+          "The vtable has {} entries, but its last entry is at index {}.",
+          count,
+          end - 1
+        )
+      ));
+    }
+  }
+
+  let name = &input.ident;
+  let vis = &input.vis;
+  let attrs = &input.attrs;
+  let mut previous = 0;
+  let mut fields = entries
+    .iter()
+    .enumerate()
+    .map(|(i, entry)| {
+      let padding = entry.index - previous;
+      previous = entry.index + 1;
+      let pad_ident = syn::Ident::new(&format!("__pad{}", i), proc_macro2::Span::call_site());
+      let attrs = entry
+        .field
+        .attrs
+        .iter()
+        .filter(|attr| !attr.path().is_ident("index"));
+      let vis = &entry.field.vis;
+      let ident = &entry.field.ident;
+      let ty = &entry.field.ty;
+      quote! {
+        #[doc(hidden)]
+        #pad_ident: [usize; #padding],
+        #(#attrs)*
+        #vis #ident: #ty
+      }
+    })
+    .collect::<Vec<_>>();
+  if let Some((_, count)) = &count {
+    let padding = count - end;
+    let pad_ident = syn::Ident::new(
+      &format!("__pad{}", entries.len()),
+      proc_macro2::Span::call_site()
+    );
+    fields.push(quote! {
+      #[doc(hidden)]
+      #pad_ident: [usize; #padding]
+    });
+  }
+
+  let constants = entries.iter().map(|entry| {
+    let ident = entry.field.ident.as_ref().unwrap();
+    let constant = constant_ident("INDEX", ident);
+    let index = entry.index;
+    let doc = format!("Index of the `{}` entry.", ident);
+    quote! {
+      #[doc = #doc]
+      pub const #constant: usize = #index;
+    }
+  });
+  let call_wrappers = generate_call_wrappers(&entries);
+  let size_checks = entries.iter().map(|entry| {
+    let ty = &entry.field.ty;
+    let message = check_message(format!(
+      "Entry `{}` is not pointer sized.",
+      entry.field.ident.as_ref().unwrap()
+    ));
+    quote! {
+      const _: () = assert!(
+        ::core::mem::size_of::<#ty>() == ::core::mem::size_of::<usize>(),
+        #message
+      );
+    }
+  });
+
+  Ok(quote! {
+    #[repr(C)]
+    #(#attrs)*
+    #vis struct #name {
+      #(#fields),*
+    }
+
+    impl #name {
+      #(#constants)*
+
+      /// Reads the pointer to the vtable stored at offset 0 of `object`.
+      ///
+      /// # Safety
+      ///
+      /// `object` must be valid for reads of a pointer and start with a pointer to a table with
+      /// this layout.
+      #[inline]
+      pub unsafe fn pointer_of<T>(object: *const T) -> *const Self {
+        (object as *const *const Self).read_unaligned()
+      }
+
+      /// Borrows the vtable of `object`, whose first field is a pointer to it.
+      ///
+      /// # Safety
+      ///
+      /// `object` must start with a valid pointer to a table with this layout, which outlives the
+      /// object.
+      #[inline]
+      pub unsafe fn of<T>(object: &T) -> &Self {
+        &*Self::pointer_of(object)
+      }
+
+      #(#call_wrappers)*
+    }
+
+    #(#size_checks)*
+  })
+}
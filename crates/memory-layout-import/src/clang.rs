@@ -0,0 +1,200 @@
+//! Imports records from C and C++ headers through the layouts `clang -fdump-record-layouts`
+//! prints, so the offsets are exactly the ones the compiler produces for the target.
+//!
+//! Compiler flags such as `-target x86_64-pc-windows-msvc` or include paths can be passed to
+//! [`import_header`] to get the layouts of the platform the structs come from. Dumping every
+//! record of a header requires clang 15 or newer.
+
+use std::{path::Path, process::Command};
+
+use crate::{c_integer_size, rust_type, type_ident, ImportError, Record, RecordKind};
+
+/// Runs `clang` on `header` and imports the records named in `types`, or every record if it's
+/// empty. The program is taken from the `CLANG` environment variable, defaulting to `clang`.
+pub fn import_header(
+  header: &Path,
+  clang_args: &[String],
+  types: &[String]
+) -> Result<Vec<Record>, ImportError> {
+  let program = std::env::var("CLANG").unwrap_or_else(|_| String::from("clang"));
+  let output = Command::new(&program)
+    .args([
+      "-fsyntax-only",
+      "-Xclang",
+      "-fdump-record-layouts",
+      "-Xclang",
+      "-fdump-record-layouts-complete"
+    ])
+    .args(clang_args)
+    .arg(header)
+    .output()
+    .map_err(|err| {
+      ImportError::Tool {
+        program: program.clone(),
+        stderr:  err.to_string()
+      }
+    })?;
+  if !output.status.success() {
+    return Err(ImportError::Tool {
+      program,
+      stderr: String::from_utf8_lossy(&output.stderr).into_owned()
+    });
+  }
+
+  let records = parse_record_layouts(&String::from_utf8_lossy(&output.stdout))?;
+  Ok(
+    records
+      .into_iter()
+      .filter(|record| types.is_empty() || types.iter().any(|ty| type_ident(ty) == record.name))
+      .collect()
+  )
+}
+
+/// A line of a record layout dump, `offset | text` with `text` indented by its depth.
+struct Line<'a> {
+  number: usize,
+  /// Offset in bytes, and the range of bits for bit fields.
+  offset: Option<(usize, Option<(usize, usize)>)>,
+  depth:  usize,
+  text:   &'a str
+}
+
+fn parse_line(number: usize, line: &str) -> Result<Option<Line<'_>>, ImportError> {
+  let (offset, rest) = match line.split_once('|') {
+    Some(split) => split,
+    None => return Ok(None)
+  };
+  let error = |message: &str| {
+    ImportError::Parse {
+      line:    number,
+      message: message.to_string()
+    }
+  };
+  let offset = offset.trim();
+  let offset = if offset.is_empty() {
+    None
+  } else {
+    let number = |text: &str| text.parse::<usize>().map_err(|_| error("Invalid offset."));
+    Some(match offset.split_once(':') {
+      // Zero width bit fields, which can't be named.
+      Some((_, "-")) => return Ok(None),
+      Some((byte, bits)) => {
+        let (first, last) = bits
+          .split_once('-')
+          .ok_or_else(|| error("Invalid bit range."))?;
+        (number(byte)?, Some((number(first)?, number(last)?)))
+      }
+      None => (number(offset)?, None)
+    })
+  };
+  let text = rest.trim_start();
+  Ok(Some(Line {
+    number,
+    offset,
+    depth: (rest.len() - text.len()).saturating_sub(1) / 2,
+    text: text.trim_end()
+  }))
+}
+
+/// Parses the output of `clang -Xclang -fdump-record-layouts`. Records dumped more than once are
+/// only returned once. Anonymous records are named after their location, like
+/// `anonymous_at_sdk_h_12_3`.
+pub fn parse_record_layouts(dump: &str) -> Result<Vec<Record>, ImportError> {
+  let mut records: Vec<Record> = Vec::new();
+  let mut current: Option<Record> = None;
+
+  for (index, line) in dump.lines().enumerate() {
+    if line.starts_with("*** Dumping AST Record Layout") {
+      current = None;
+      continue;
+    }
+    let line = match parse_line(index + 1, line)? {
+      Some(line) => line,
+      None => continue
+    };
+
+    if let Some(sizes) = line.text.strip_prefix('[') {
+      if let Some(mut record) = current.take() {
+        record.size = sizes
+          .split([',', ']'])
+          .find_map(|entry| entry.trim().strip_prefix("sizeof="))
+          .and_then(|size| size.parse().ok())
+          .ok_or(ImportError::Parse {
+            line:    line.number,
+            message: String::from("Missing sizeof.")
+          })?;
+        if !records.iter().any(|other| other.name == record.name) {
+          records.push(record);
+        }
+      }
+      continue;
+    }
+
+    match (line.depth, &mut current) {
+      (0, _) => current = parse_record(line.text),
+      (1, Some(record)) => parse_member(record, &line),
+      _ => {}
+    }
+  }
+
+  Ok(records)
+}
+
+fn parse_record(text: &str) -> Option<Record> {
+  let (kind, name) = match text.split_once(' ')? {
+    ("struct" | "class", name) => (RecordKind::Struct, name),
+    ("union", name) => (RecordKind::Union, name),
+    _ => return None
+  };
+  Some(Record::new(name, kind, 0))
+}
+
+fn parse_member(record: &mut Record, line: &Line) {
+  let (offset, bits) = match line.offset {
+    Some(offset) => offset,
+    None => return
+  };
+  let text = line.text;
+
+  if text.ends_with("vtable pointer)") {
+    record.push("vtable", offset, String::from(crate::POINTER));
+    return;
+  }
+  if let Some(base) = text
+    .strip_suffix(" (primary base)")
+    .or_else(|| text.strip_suffix(" (base)"))
+    .or_else(|| text.strip_suffix(" (virtual base)"))
+  {
+    let ty = match rust_type(base) {
+      Some(ty) => ty,
+      None => return
+    };
+    if offset == 0 && record.base.is_none() && record.members.is_empty() {
+      record.base = Some(ty);
+    } else {
+      record.push(&format!("base_{}", ty), offset, ty);
+    }
+    return;
+  }
+  if text.ends_with("(empty)") {
+    return;
+  }
+
+  // Members of an anonymous type without a name of their own.
+  let (ty, name) = match text.rsplit_once(' ') {
+    _ if text.ends_with(')') => (text, "anonymous"),
+    Some(split) => split,
+    None => return
+  };
+  let ty = match rust_type(ty) {
+    Some(ty) => ty,
+    None => return
+  };
+  match bits {
+    Some((first, last)) => {
+      let unit = c_integer_size(&ty).unwrap_or(4);
+      record.push_bit_field(name, offset * 8 + first, last + 1 - first, ty, unit);
+    }
+    None => record.push(name, offset, ty)
+  }
+}
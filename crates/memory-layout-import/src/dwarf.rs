@@ -0,0 +1,1260 @@
+//! Imports records from the DWARF debug information of ELF binaries and object files.
+//!
+//! Anonymous structs declared through `typedef struct { .. } Name;` are named after the typedef,
+//! other anonymous records referenced by an imported one are imported alongside it and named after
+//! their offset in `.debug_info`. Enums are replaced by their underlying integer type. Bit fields
+//! are only described on little-endian targets, on big-endian ones the integer holding them is
+//! imported.
+
+use std::{borrow::Cow, collections::HashMap, path::Path};
+
+use crate::{ImportError, Record, RecordKind, POINTER};
+
+const DW_TAG_ARRAY_TYPE: u16 = 0x01;
+const DW_TAG_CLASS_TYPE: u16 = 0x02;
+const DW_TAG_ENUMERATION_TYPE: u16 = 0x04;
+const DW_TAG_MEMBER: u16 = 0x0D;
+const DW_TAG_POINTER_TYPE: u16 = 0x0F;
+const DW_TAG_REFERENCE_TYPE: u16 = 0x10;
+const DW_TAG_STRUCTURE_TYPE: u16 = 0x13;
+const DW_TAG_SUBROUTINE_TYPE: u16 = 0x15;
+const DW_TAG_TYPEDEF: u16 = 0x16;
+const DW_TAG_UNION_TYPE: u16 = 0x17;
+const DW_TAG_INHERITANCE: u16 = 0x1C;
+const DW_TAG_SUBRANGE_TYPE: u16 = 0x21;
+const DW_TAG_BASE_TYPE: u16 = 0x24;
+const DW_TAG_CONST_TYPE: u16 = 0x26;
+const DW_TAG_VOLATILE_TYPE: u16 = 0x35;
+const DW_TAG_RESTRICT_TYPE: u16 = 0x37;
+const DW_TAG_NAMESPACE: u16 = 0x39;
+const DW_TAG_RVALUE_REFERENCE_TYPE: u16 = 0x42;
+const DW_TAG_ATOMIC_TYPE: u16 = 0x47;
+
+const DW_AT_NAME: u16 = 0x03;
+const DW_AT_BYTE_SIZE: u16 = 0x0B;
+const DW_AT_BIT_OFFSET: u16 = 0x0C;
+const DW_AT_BIT_SIZE: u16 = 0x0D;
+const DW_AT_UPPER_BOUND: u16 = 0x2F;
+const DW_AT_COUNT: u16 = 0x37;
+const DW_AT_DATA_MEMBER_LOCATION: u16 = 0x38;
+const DW_AT_DECLARATION: u16 = 0x3C;
+const DW_AT_ENCODING: u16 = 0x3E;
+const DW_AT_TYPE: u16 = 0x49;
+const DW_AT_DATA_BIT_OFFSET: u16 = 0x6B;
+const DW_AT_STR_OFFSETS_BASE: u16 = 0x72;
+
+const DW_ATE_BOOLEAN: u64 = 0x02;
+const DW_ATE_FLOAT: u64 = 0x04;
+const DW_ATE_SIGNED: u64 = 0x05;
+const DW_ATE_SIGNED_CHAR: u64 = 0x06;
+const DW_ATE_UNSIGNED: u64 = 0x07;
+const DW_ATE_UNSIGNED_CHAR: u64 = 0x08;
+const DW_ATE_UTF: u64 = 0x10;
+
+const DW_OP_PLUS_UCONST: u8 = 0x23;
+
+/// Set in the flags of sections compressed with zlib or zstd.
+const SHF_COMPRESSED: u64 = 0x800;
+
+/// Reads the ELF file at `path` and imports the records named in `types`, or every record if it's
+/// empty.
+pub fn import_elf(path: &Path, types: &[String]) -> Result<Vec<Record>, ImportError> {
+  parse_elf(&std::fs::read(path)?, types)
+}
+
+/// Imports the records named in `types`, or every record if it's empty, from the bytes of an ELF
+/// file. Names are matched with and without namespaces.
+pub fn parse_elf(elf: &[u8], types: &[String]) -> Result<Vec<Record>, ImportError> {
+  let sections = Sections::parse(elf)?;
+  let info = sections.get(".debug_info")?.ok_or_else(|| {
+    ImportError::Malformed(String::from(
+      "No .debug_info section, the file has no debug information."
+    ))
+  })?;
+  let abbrev = sections.get(".debug_abbrev")?.unwrap_or_default();
+  let str = sections.get(".debug_str")?.unwrap_or_default();
+  let line_str = sections.get(".debug_line_str")?.unwrap_or_default();
+  let str_offsets = sections.get(".debug_str_offsets")?.unwrap_or_default();
+  let strings = Strings {
+    str:         &str,
+    line_str:    &line_str,
+    str_offsets: &str_offsets
+  };
+
+  let tree = Tree::parse(&info, &abbrev, &strings, sections.big_endian)?;
+  tree.records(types)
+}
+
+fn malformed(message: &str) -> ImportError {
+  ImportError::Malformed(format!("{} in DWARF.", message))
+}
+
+/// An endian-aware cursor.
+#[derive(Clone, Copy)]
+struct Reader<'a> {
+  data:       &'a [u8],
+  big_endian: bool
+}
+
+impl<'a> Reader<'a> {
+  fn new(data: &'a [u8], big_endian: bool) -> Self {
+    Reader { data, big_endian }
+  }
+
+  fn at(&self, offset: usize) -> Result<Self, ImportError> {
+    Ok(Reader {
+      data:       self
+        .data
+        .get(offset..)
+        .ok_or_else(|| malformed("Offset out of bounds"))?,
+      big_endian: self.big_endian
+    })
+  }
+
+  fn bytes(&mut self, count: usize) -> Result<&'a [u8], ImportError> {
+    if count > self.data.len() {
+      return Err(malformed("Unexpected end of data"));
+    }
+    let (bytes, rest) = self.data.split_at(count);
+    self.data = rest;
+    Ok(bytes)
+  }
+
+  /// An unsigned integer of `size` bytes.
+  fn uint(&mut self, size: usize) -> Result<u64, ImportError> {
+    let bytes = self.bytes(size)?;
+    let fold = |value: u64, byte: &u8| value << 8 | u64::from(*byte);
+    Ok(if self.big_endian {
+      bytes.iter().fold(0, fold)
+    } else {
+      bytes.iter().rev().fold(0, fold)
+    })
+  }
+
+  fn u8(&mut self) -> Result<u8, ImportError> {
+    Ok(self.bytes(1)?[0])
+  }
+
+  fn uleb(&mut self) -> Result<u64, ImportError> {
+    let mut value = 0u64;
+    let mut shift = 0;
+    loop {
+      let byte = self.u8()?;
+      if shift < 64 {
+        value |= u64::from(byte & 0x7F) << shift;
+      }
+      shift += 7;
+      if byte & 0x80 == 0 {
+        return Ok(value);
+      }
+    }
+  }
+
+  fn sleb(&mut self) -> Result<i64, ImportError> {
+    let mut value = 0i64;
+    let mut shift = 0;
+    loop {
+      let byte = self.u8()?;
+      if shift < 64 {
+        value |= i64::from(byte & 0x7F) << shift;
+      }
+      shift += 7;
+      if byte & 0x80 == 0 {
+        if shift < 64 && byte & 0x40 != 0 {
+          value |= -1 << shift;
+        }
+        return Ok(value);
+      }
+    }
+  }
+
+  fn cstr(&mut self) -> Result<&'a str, ImportError> {
+    let end = self
+      .data
+      .iter()
+      .position(|byte| *byte == 0)
+      .ok_or_else(|| malformed("Unterminated string"))?;
+    let string = std::str::from_utf8(&self.data[..end]).map_err(|_| malformed("Invalid string"))?;
+    self.data = &self.data[end + 1..];
+    Ok(string)
+  }
+}
+
+/// A section of an ELF file.
+struct Section<'a> {
+  name:  &'a str,
+  kind:  u64,
+  flags: u64,
+  /// Index of the associated section, the symbol table of relocations.
+  link:  usize,
+  data:  &'a [u8]
+}
+
+/// The sections of an ELF file.
+struct Sections<'a> {
+  big_endian: bool,
+  wide:       bool,
+  machine:    u64,
+  sections:   Vec<Section<'a>>
+}
+
+impl<'a> Sections<'a> {
+  fn parse(elf: &'a [u8]) -> Result<Self, ImportError> {
+    let not_elf = || ImportError::Malformed(String::from("Not an ELF file."));
+    if elf.get(..4) != Some(b"\x7FELF") {
+      return Err(not_elf());
+    }
+    let wide = match elf.get(4) {
+      Some(1) => false,
+      Some(2) => true,
+      _ => return Err(not_elf())
+    };
+    let big_endian = match elf.get(5) {
+      Some(1) => false,
+      Some(2) => true,
+      _ => return Err(not_elf())
+    };
+    let header = Reader::new(elf, big_endian);
+    let word = if wide { 8 } else { 4 };
+    let machine = header.at(0x12)?.uint(2)?;
+    let (table, entry_size, count, names) = if wide {
+      (
+        header.at(0x28)?.uint(8)?,
+        header.at(0x3A)?.uint(2)?,
+        header.at(0x3C)?.uint(2)?,
+        header.at(0x3E)?.uint(2)?
+      )
+    } else {
+      (
+        header.at(0x20)?.uint(4)?,
+        header.at(0x2E)?.uint(2)?,
+        header.at(0x30)?.uint(2)?,
+        header.at(0x32)?.uint(2)?
+      )
+    };
+
+    let section = |index: u64| -> Result<(u64, Section<'a>), ImportError> {
+      let mut entry = header.at((table + index * entry_size) as usize)?;
+      let name = entry.uint(4)?;
+      let kind = entry.uint(4)?;
+      let flags = entry.uint(word)?;
+      let _address = entry.uint(word)?;
+      let offset = entry.uint(word)? as usize;
+      let size = entry.uint(word)? as usize;
+      let link = entry.uint(4)? as usize;
+      // SHT_NOBITS sections like `.bss` take up no space in the file.
+      let data = if kind == 8 {
+        &[][..]
+      } else {
+        elf
+          .get(offset..offset.saturating_add(size))
+          .ok_or_else(|| malformed("Section out of bounds"))?
+      };
+      Ok((
+        name,
+        Section {
+          name: "",
+          kind,
+          flags,
+          link,
+          data
+        }
+      ))
+    };
+
+    let name_table = section(names)?.1.data;
+    let mut sections = Vec::new();
+    for index in 0..count {
+      let (name, mut section) = section(index)?;
+      section.name = Reader::new(name_table, big_endian)
+        .at(name as usize)?
+        .cstr()?;
+      sections.push(section);
+    }
+    Ok(Sections {
+      big_endian,
+      wide,
+      machine,
+      sections
+    })
+  }
+
+  /// Contents of the section `name`, with the relocations of object files applied.
+  fn get(&self, name: &str) -> Result<Option<Cow<'a, [u8]>>, ImportError> {
+    let section = match self.sections.iter().find(|section| section.name == name) {
+      Some(section) => section,
+      None => return Ok(None)
+    };
+    if section.flags & SHF_COMPRESSED != 0 {
+      return Err(ImportError::Malformed(format!(
+        "The {} section is compressed, decompress it with `objcopy --decompress-debug-sections`.",
+        name
+      )));
+    }
+
+    // Relocations with explicit addends, relocations without them already hold their addend.
+    let rela = format!(".rela{}", name);
+    let relocations = match self
+      .sections
+      .iter()
+      .find(|section| section.name == rela && section.kind == 4)
+    {
+      Some(relocations) => relocations,
+      None => return Ok(Some(Cow::Borrowed(section.data)))
+    };
+    let symbols = self
+      .sections
+      .get(relocations.link)
+      .ok_or_else(|| malformed("Missing symbol table"))?;
+
+    let mut data = section.data.to_vec();
+    let mut entries = Reader::new(relocations.data, self.big_endian);
+    while !entries.data.is_empty() {
+      let (offset, symbol, kind, addend) = if self.wide {
+        let offset = entries.uint(8)? as usize;
+        let info = entries.uint(8)?;
+        (
+          offset,
+          info >> 32,
+          info & 0xFFFF_FFFF,
+          entries.uint(8)? as i64
+        )
+      } else {
+        let offset = entries.uint(4)? as usize;
+        let info = entries.uint(4)?;
+        (
+          offset,
+          info >> 8,
+          info & 0xFF,
+          i64::from(entries.uint(4)? as u32 as i32)
+        )
+      };
+      let size = match relocation_size(self.machine, kind) {
+        Some(size) => size,
+        None => continue
+      };
+      let value = if self.wide {
+        Reader::new(symbols.data, self.big_endian)
+          .at(symbol as usize * 24 + 8)?
+          .uint(8)?
+      } else {
+        Reader::new(symbols.data, self.big_endian)
+          .at(symbol as usize * 16 + 4)?
+          .uint(4)?
+      };
+      let value = value.wrapping_add(addend as u64);
+      let target = data
+        .get_mut(offset..offset + size)
+        .ok_or_else(|| malformed("Relocation out of bounds"))?;
+      for (index, byte) in target.iter_mut().enumerate() {
+        let shift = if self.big_endian {
+          (size - 1 - index) * 8
+        } else {
+          index * 8
+        };
+        *byte = (value >> shift) as u8;
+      }
+    }
+    Ok(Some(Cow::Owned(data)))
+  }
+}
+
+/// Size of the absolute relocations debug information uses on common architectures.
+fn relocation_size(machine: u64, kind: u64) -> Option<usize> {
+  match (machine, kind) {
+    // x86-64: R_X86_64_64, R_X86_64_32 and R_X86_64_32S
+    (62, 1) => Some(8),
+    (62, 10 | 11) => Some(4),
+    // AArch64: R_AARCH64_ABS64 and R_AARCH64_ABS32
+    (183, 257) => Some(8),
+    (183, 258) => Some(4),
+    // PowerPC: R_PPC_ADDR32 and R_PPC64_ADDR64
+    (20 | 21, 1) => Some(4),
+    (21, 38) => Some(8),
+    // RISC-V: R_RISCV_32 and R_RISCV_64
+    (243, 1) => Some(4),
+    (243, 2) => Some(8),
+    _ => None
+  }
+}
+
+/// The string sections attributes refer to.
+struct Strings<'a> {
+  str:         &'a [u8],
+  line_str:    &'a [u8],
+  str_offsets: &'a [u8]
+}
+
+/// The value of an attribute.
+#[derive(Clone, Copy, Debug)]
+enum Value<'a> {
+  Uint(u64),
+  Sint(i64),
+  Str(&'a str),
+  /// Index into the string offsets of the unit.
+  StrIndex(u64),
+  /// Offset of a DIE in `.debug_info`.
+  Ref(usize),
+  Block(&'a [u8]),
+  Other
+}
+
+/// A debugging information entry.
+struct Die<'a> {
+  /// Offset in `.debug_info`.
+  offset:   usize,
+  tag:      u16,
+  /// Index of the unit.
+  unit:     usize,
+  parent:   Option<usize>,
+  children: Vec<usize>,
+  attrs:    Vec<(u16, Value<'a>)>
+}
+
+/// A unit of `.debug_info`.
+struct Unit {
+  offset_size:      usize,
+  str_offsets_base: Option<u64>
+}
+
+/// Abbreviation of a DIE, its tag, whether it has children and its attributes with their forms
+/// and implicit constants.
+struct Abbreviation {
+  tag:      u16,
+  children: bool,
+  attrs:    Vec<(u16, u16, i64)>
+}
+
+/// Every DIE of `.debug_info`.
+struct Tree<'a> {
+  dies:       Vec<Die<'a>>,
+  /// Index of the DIE at a `.debug_info` offset.
+  offsets:    HashMap<usize, usize>,
+  units:      Vec<Unit>,
+  strings:    &'a Strings<'a>,
+  big_endian: bool
+}
+
+impl<'a> Tree<'a> {
+  fn parse(
+    info: &'a [u8],
+    abbrev: &'a [u8],
+    strings: &'a Strings<'a>,
+    big_endian: bool
+  ) -> Result<Self, ImportError> {
+    let mut tree = Tree {
+      dies: Vec::new(),
+      offsets: HashMap::new(),
+      units: Vec::new(),
+      strings,
+      big_endian
+    };
+    let section = Reader::new(info, big_endian);
+    let mut offset = 0;
+    while offset < info.len() {
+      let mut header = section.at(offset)?;
+      let (length, offset_size) = match header.uint(4)? {
+        0xFFFF_FFFF => (header.uint(8)? as usize, 8),
+        length => (length as usize, 4)
+      };
+      // The length excludes itself, which takes 12 bytes in the 64-bit format.
+      let end = (offset + length + if offset_size == 8 { 12 } else { 4 }).min(info.len());
+      let version = header.uint(2)?;
+      let (kind, abbrev_offset, address_size) = if version >= 5 {
+        let kind = header.u8()?;
+        let address_size = header.u8()?;
+        (kind, header.uint(offset_size)?, address_size)
+      } else {
+        let abbrev_offset = header.uint(offset_size)?;
+        (1, abbrev_offset, header.u8()?)
+      };
+      // Only compile and partial units hold the types of the program.
+      if (2..=5).contains(&version) && matches!(kind, 1 | 3) {
+        let abbreviations =
+          parse_abbreviations(Reader::new(abbrev, big_endian).at(abbrev_offset as usize)?)?;
+        let context = Context {
+          unit_offset: offset,
+          offset_size,
+          address_size: address_size as usize,
+          version,
+          strings
+        };
+        let start = info.len() - header.data.len();
+        tree.parse_unit(section, start, end, &context, &abbreviations)?;
+      }
+      offset = end;
+    }
+    Ok(tree)
+  }
+
+  fn parse_unit(
+    &mut self,
+    section: Reader<'a>,
+    start: usize,
+    end: usize,
+    context: &Context<'a>,
+    abbreviations: &HashMap<u64, Abbreviation>
+  ) -> Result<(), ImportError> {
+    let unit = self.units.len();
+    self.units.push(Unit {
+      offset_size:      context.offset_size,
+      str_offsets_base: None
+    });
+
+    let mut data = section.at(start)?;
+    data.data = &data.data[..end.saturating_sub(start).min(data.data.len())];
+    let mut parents: Vec<usize> = Vec::new();
+    while !data.data.is_empty() {
+      let offset = end - data.data.len();
+      let code = data.uleb()?;
+      if code == 0 {
+        if parents.pop().is_none() {
+          break;
+        }
+        continue;
+      }
+      let abbreviation = abbreviations
+        .get(&code)
+        .ok_or_else(|| malformed("Unknown abbreviation"))?;
+      let mut attrs = Vec::with_capacity(abbreviation.attrs.len());
+      for (name, form, implicit) in &abbreviation.attrs {
+        attrs.push((*name, read_value(&mut data, *form, *implicit, context)?));
+      }
+
+      let index = self.dies.len();
+      let parent = parents.last().copied();
+      if let Some(parent) = parent {
+        self.dies[parent].children.push(index);
+      } else if let Some((_, Value::Uint(base))) = attrs
+        .iter()
+        .find(|(name, _)| *name == DW_AT_STR_OFFSETS_BASE)
+      {
+        self.units[unit].str_offsets_base = Some(*base);
+      }
+      self.dies.push(Die {
+        offset,
+        tag: abbreviation.tag,
+        unit,
+        parent,
+        children: Vec::new(),
+        attrs
+      });
+      self.offsets.insert(offset, index);
+      if abbreviation.children {
+        parents.push(index);
+      }
+    }
+    Ok(())
+  }
+
+  fn attr(&self, die: usize, name: u16) -> Option<Value<'a>> {
+    self.dies[die]
+      .attrs
+      .iter()
+      .find(|(attr, _)| *attr == name)
+      .map(|(_, value)| *value)
+  }
+
+  fn uint(&self, die: usize, name: u16) -> Option<u64> {
+    match self.attr(die, name)? {
+      Value::Uint(value) => Some(value),
+      Value::Sint(value) => u64::try_from(value).ok(),
+      _ => None
+    }
+  }
+
+  fn name(&self, die: usize) -> Option<&'a str> {
+    match self.attr(die, DW_AT_NAME)? {
+      Value::Str(name) => Some(name),
+      Value::StrIndex(index) => {
+        let unit = &self.units[self.dies[die].unit];
+        let base = unit
+          .str_offsets_base
+          .unwrap_or(if unit.offset_size == 8 { 16 } else { 8 });
+        let mut entry = Reader::new(self.strings.str_offsets, self.big_endian)
+          .at((base + index * unit.offset_size as u64) as usize)
+          .ok()?;
+        let offset = entry.uint(unit.offset_size).ok()? as usize;
+        Reader::new(self.strings.str, self.big_endian)
+          .at(offset)
+          .ok()?
+          .cstr()
+          .ok()
+      }
+      _ => None
+    }
+  }
+
+  fn target(&self, die: usize) -> Option<usize> {
+    match self.attr(die, DW_AT_TYPE)? {
+      Value::Ref(offset) => self.offsets.get(&offset).copied(),
+      _ => None
+    }
+  }
+
+  fn is_record(&self, die: usize) -> bool {
+    matches!(
+      self.dies[die].tag,
+      DW_TAG_STRUCTURE_TYPE | DW_TAG_CLASS_TYPE | DW_TAG_UNION_TYPE
+    )
+  }
+
+  /// Name of the record `die` including its namespaces and enclosing records.
+  fn qualified_name(&self, die: usize) -> Option<String> {
+    let mut name = String::from(self.name(die)?);
+    let mut parent = self.dies[die].parent;
+    while let Some(scope) = parent {
+      if self.dies[scope].tag == DW_TAG_NAMESPACE || self.is_record(scope) {
+        name = format!("{}::{}", self.name(scope).unwrap_or("(anonymous)"), name);
+      }
+      parent = self.dies[scope].parent;
+    }
+    Some(name)
+  }
+
+  fn records(&self, types: &[String]) -> Result<Vec<Record>, ImportError> {
+    // Names of anonymous records given by a typedef.
+    let mut typedefs = HashMap::new();
+    for (index, die) in self.dies.iter().enumerate() {
+      if die.tag == DW_TAG_TYPEDEF {
+        if let (Some(name), Some(target)) = (self.name(index), self.target(index)) {
+          if self.is_record(target) && self.name(target).is_none() {
+            typedefs.entry(target).or_insert(name);
+          }
+        }
+      }
+    }
+    let mut importer = Importer {
+      tree: self,
+      typedefs,
+      definitions: HashMap::new()
+    };
+    for index in 0..self.dies.len() {
+      if self.is_record(index) && self.attr(index, DW_AT_DECLARATION).is_none() {
+        if let Some(name) = self.qualified_name(index) {
+          importer.definitions.entry(name).or_insert(index);
+        }
+      }
+    }
+
+    let mut selected: Vec<usize> = Vec::new();
+    for index in 0..self.dies.len() {
+      if !self.is_record(index) || self.attr(index, DW_AT_DECLARATION).is_some() {
+        continue;
+      }
+      let name = match self.qualified_name(index) {
+        Some(name) => name,
+        None => {
+          match importer.typedefs.get(&index) {
+            Some(name) => String::from(*name),
+            None => continue
+          }
+        }
+      };
+      let ident = crate::type_ident(&name);
+      let wanted = types.is_empty()
+        || types
+          .iter()
+          .any(|ty| *ty == name || crate::type_ident(ty) == ident);
+      if wanted
+        && !selected
+          .iter()
+          .any(|other| importer.record_name(*other) == ident)
+      {
+        selected.push(index);
+      }
+    }
+
+    let mut records = Vec::new();
+    let mut index = 0;
+    while index < selected.len() {
+      let (record, anonymous) = importer.record(selected[index])?;
+      for die in anonymous {
+        if !selected.contains(&die) {
+          selected.push(die);
+        }
+      }
+      records.push(record);
+      index += 1;
+    }
+    Ok(records)
+  }
+}
+
+/// What's needed to decode the attributes of a unit.
+struct Context<'a> {
+  unit_offset:  usize,
+  offset_size:  usize,
+  address_size: usize,
+  version:      u64,
+  strings:      &'a Strings<'a>
+}
+
+fn parse_abbreviations(mut data: Reader) -> Result<HashMap<u64, Abbreviation>, ImportError> {
+  let mut abbreviations = HashMap::new();
+  loop {
+    let code = data.uleb()?;
+    if code == 0 {
+      return Ok(abbreviations);
+    }
+    let tag = data.uleb()? as u16;
+    let children = data.u8()? != 0;
+    let mut attrs = Vec::new();
+    loop {
+      let name = data.uleb()? as u16;
+      let form = data.uleb()? as u16;
+      if name == 0 && form == 0 {
+        break;
+      }
+      // DW_FORM_implicit_const stores its value in the abbreviation.
+      let implicit = if form == 0x21 { data.sleb()? } else { 0 };
+      attrs.push((name, form, implicit));
+    }
+    abbreviations.insert(
+      code,
+      Abbreviation {
+        tag,
+        children,
+        attrs
+      }
+    );
+  }
+}
+
+fn read_value<'a>(
+  data: &mut Reader<'a>,
+  form: u16,
+  implicit: i64,
+  context: &Context<'a>
+) -> Result<Value<'a>, ImportError> {
+  let unit_ref = |offset: u64| Value::Ref(context.unit_offset + offset as usize);
+  Ok(match form {
+    // addr
+    0x01 => {
+      data.uint(context.address_size)?;
+      Value::Other
+    }
+    // block2, block4, block, block1, exprloc
+    0x03 => {
+      let size = data.uint(2)? as usize;
+      Value::Block(data.bytes(size)?)
+    }
+    0x04 => {
+      let size = data.uint(4)? as usize;
+      Value::Block(data.bytes(size)?)
+    }
+    0x09 | 0x18 => {
+      let size = data.uleb()? as usize;
+      Value::Block(data.bytes(size)?)
+    }
+    0x0A => {
+      let size = data.u8()? as usize;
+      Value::Block(data.bytes(size)?)
+    }
+    // data2, data4, data8, data1, flag
+    0x05 => Value::Uint(data.uint(2)?),
+    0x06 => Value::Uint(data.uint(4)?),
+    0x07 => Value::Uint(data.uint(8)?),
+    0x0B | 0x0C => Value::Uint(u64::from(data.u8()?)),
+    // string
+    0x08 => Value::Str(data.cstr()?),
+    // sdata, udata
+    0x0D => Value::Sint(data.sleb()?),
+    0x0F => Value::Uint(data.uleb()?),
+    // strp, line_strp
+    0x0E | 0x1F => {
+      let section = if form == 0x0E {
+        context.strings.str
+      } else {
+        context.strings.line_str
+      };
+      let offset = data.uint(context.offset_size)? as usize;
+      Value::Str(Reader::new(section, data.big_endian).at(offset)?.cstr()?)
+    }
+    // ref_addr, which is address sized in DWARF 2
+    0x10 => {
+      let size = if context.version == 2 {
+        context.address_size
+      } else {
+        context.offset_size
+      };
+      Value::Ref(data.uint(size)? as usize)
+    }
+    // ref1, ref2, ref4, ref8, ref_udata
+    0x11 => unit_ref(u64::from(data.u8()?)),
+    0x12 => unit_ref(data.uint(2)?),
+    0x13 => unit_ref(data.uint(4)?),
+    0x14 => unit_ref(data.uint(8)?),
+    0x15 => unit_ref(data.uleb()?),
+    // indirect
+    0x16 => {
+      let form = data.uleb()? as u16;
+      let implicit = if form == 0x21 { data.sleb()? } else { 0 };
+      return read_value(data, form, implicit, context);
+    }
+    // sec_offset, strp_sup, ref_sup4
+    0x17 | 0x1D => {
+      data.uint(context.offset_size)?;
+      Value::Other
+    }
+    0x1C => {
+      data.uint(4)?;
+      Value::Other
+    }
+    // flag_present
+    0x19 => Value::Uint(1),
+    // strx, addrx, loclistx, rnglistx
+    0x1A => Value::StrIndex(data.uleb()?),
+    0x1B | 0x22 | 0x23 => {
+      data.uleb()?;
+      Value::Other
+    }
+    // data16
+    0x1E => {
+      data.bytes(16)?;
+      Value::Other
+    }
+    // ref_sig8, ref_sup8
+    0x20 | 0x24 => {
+      data.uint(8)?;
+      Value::Other
+    }
+    // implicit_const
+    0x21 => Value::Sint(implicit),
+    // strx1 to strx4
+    0x25..=0x28 => Value::StrIndex(data.uint(usize::from(form - 0x24))?),
+    // addrx1 to addrx4
+    0x29..=0x2C => {
+      data.uint(usize::from(form - 0x28))?;
+      Value::Other
+    }
+    _ => return Err(malformed("Unsupported attribute form"))
+  })
+}
+
+/// Translates record DIEs to [`Record`]s.
+struct Importer<'t, 'a> {
+  tree:        &'t Tree<'a>,
+  /// Names of anonymous records given by a typedef.
+  typedefs:    HashMap<usize, &'a str>,
+  /// Defined records by qualified name, resolving declarations.
+  definitions: HashMap<String, usize>
+}
+
+impl Importer<'_, '_> {
+  fn record_name(&self, die: usize) -> String {
+    match self.tree.name(die) {
+      Some(name) => crate::type_ident(name),
+      None => {
+        match self.typedefs.get(&die) {
+          Some(name) => crate::type_ident(name),
+          None => format!("anonymous_{:x}", self.tree.dies[die].offset)
+        }
+      }
+    }
+  }
+
+  /// The definition of a record that may only be declared at `die`.
+  fn definition(&self, die: usize) -> usize {
+    if self.tree.attr(die, DW_AT_DECLARATION).is_none() {
+      return die;
+    }
+    self
+      .tree
+      .qualified_name(die)
+      .and_then(|name| self.definitions.get(&name).copied())
+      .unwrap_or(die)
+  }
+
+  /// Rust type and size of the type `die`, or `None` for `void`. Anonymous records it contains by
+  /// value are added to `anonymous`.
+  fn rust_type(
+    &self,
+    die: Option<usize>,
+    anonymous: &mut Vec<usize>
+  ) -> Result<Option<(String, usize)>, ImportError> {
+    let die = match die {
+      Some(die) => die,
+      None => return Ok(None)
+    };
+    let tree = self.tree;
+    let size = tree.uint(die, DW_AT_BYTE_SIZE).unwrap_or(0) as usize;
+    Ok(Some(match tree.dies[die].tag {
+      DW_TAG_BASE_TYPE => {
+        let encoding = tree.uint(die, DW_AT_ENCODING).unwrap_or(0);
+        let ty = match (encoding, size) {
+          (DW_ATE_BOOLEAN, 1) => String::from("bool"),
+          (DW_ATE_FLOAT, 4) => String::from("f32"),
+          (DW_ATE_FLOAT, 8) => String::from("f64"),
+          (DW_ATE_SIGNED_CHAR | DW_ATE_UNSIGNED_CHAR, 1) if tree.name(die) == Some("char") => {
+            String::from("core::ffi::c_char")
+          }
+          (DW_ATE_SIGNED | DW_ATE_SIGNED_CHAR, 1 | 2 | 4 | 8 | 16) => format!("i{}", size * 8),
+          (
+            DW_ATE_UNSIGNED | DW_ATE_UNSIGNED_CHAR | DW_ATE_UTF | DW_ATE_BOOLEAN,
+            1 | 2 | 4 | 8 | 16
+          ) => {
+            format!("u{}", size * 8)
+          }
+          _ => format!("[u8; {}]", size)
+        };
+        (ty, size)
+      }
+      DW_TAG_POINTER_TYPE | DW_TAG_REFERENCE_TYPE | DW_TAG_RVALUE_REFERENCE_TYPE => {
+        let target = tree.target(die);
+        let constant = target.is_some_and(|target| tree.dies[target].tag == DW_TAG_CONST_TYPE);
+        let pointee = match target {
+          Some(target) if tree.dies[target].tag == DW_TAG_SUBROUTINE_TYPE => None,
+          _ => self.rust_type(target, &mut Vec::new())?
+        };
+        let pointee = pointee.map_or(String::from("core::ffi::c_void"), |(pointee, _)| pointee);
+        let mutability = if constant { "const" } else { "mut" };
+        (format!("*{} {}", mutability, pointee), size)
+      }
+      DW_TAG_CONST_TYPE | DW_TAG_VOLATILE_TYPE | DW_TAG_RESTRICT_TYPE | DW_TAG_ATOMIC_TYPE
+      | DW_TAG_TYPEDEF => return self.rust_type(tree.target(die), anonymous),
+      DW_TAG_ENUMERATION_TYPE => {
+        match self.rust_type(tree.target(die), anonymous)? {
+          Some(underlying) => underlying,
+          None => (format!("u{}", size * 8), size)
+        }
+      }
+      DW_TAG_ARRAY_TYPE => {
+        let (mut ty, element_size) = self
+          .rust_type(tree.target(die), anonymous)?
+          .ok_or_else(|| malformed("Array of void"))?;
+        let mut size = element_size;
+        let subranges: Vec<usize> = tree.dies[die]
+          .children
+          .iter()
+          .copied()
+          .filter(|child| tree.dies[*child].tag == DW_TAG_SUBRANGE_TYPE)
+          .collect();
+        for subrange in subranges.into_iter().rev() {
+          let count = tree.uint(subrange, DW_AT_COUNT).or_else(|| {
+            tree
+              .uint(subrange, DW_AT_UPPER_BOUND)
+              .map(|bound| bound + 1)
+          });
+          let count = match count {
+            Some(count) => count as usize,
+            // Flexible array members take up no space.
+            None => return Ok(None)
+          };
+          ty = format!("[{}; {}]", ty, count);
+          size *= count;
+        }
+        (ty, size)
+      }
+      DW_TAG_SUBROUTINE_TYPE => return Ok(None),
+      _ if tree.is_record(die) => {
+        let definition = self.definition(die);
+        if tree.name(definition).is_none() && !self.typedefs.contains_key(&definition) {
+          anonymous.push(definition);
+        }
+        (
+          self.record_name(definition),
+          tree.uint(definition, DW_AT_BYTE_SIZE).unwrap_or(0) as usize
+        )
+      }
+      _ => return Err(malformed("Unsupported type"))
+    }))
+  }
+
+  /// Imports the record `die`, returning it with the anonymous records it contains.
+  fn record(&self, die: usize) -> Result<(Record, Vec<usize>), ImportError> {
+    let tree = self.tree;
+    let kind = if tree.dies[die].tag == DW_TAG_UNION_TYPE {
+      RecordKind::Union
+    } else {
+      RecordKind::Struct
+    };
+    let size = tree.uint(die, DW_AT_BYTE_SIZE).unwrap_or(0) as usize;
+    let mut record = Record::new(&self.record_name(die), kind, size);
+    let mut anonymous = Vec::new();
+
+    for child in tree.dies[die].children.iter().copied() {
+      let tag = tree.dies[child].tag;
+      if tag != DW_TAG_MEMBER && tag != DW_TAG_INHERITANCE {
+        continue;
+      }
+      let offset = match tree.attr(child, DW_AT_DATA_MEMBER_LOCATION) {
+        Some(Value::Uint(offset)) => Some(offset as usize),
+        Some(Value::Sint(offset)) => Some(offset as usize),
+        Some(Value::Block(expression)) => {
+          let mut expression = Reader::new(expression, tree.big_endian);
+          match expression.u8() {
+            Ok(DW_OP_PLUS_UCONST) => Some(expression.uleb()? as usize),
+            // Virtual bases are located at runtime.
+            _ => continue
+          }
+        }
+        _ => None
+      };
+
+      let (ty, member_size) = match self.rust_type(tree.target(child), &mut anonymous)? {
+        Some(ty) => ty,
+        None => continue
+      };
+      if tag == DW_TAG_INHERITANCE {
+        let offset = offset.unwrap_or(0);
+        if member_size == 0 {
+          // Empty bases take up no space.
+        } else if offset == 0 && record.base.is_none() && record.members.is_empty() {
+          record.base = Some(ty);
+        } else {
+          record.push(&format!("base_{}", ty), offset, ty);
+        }
+        continue;
+      }
+
+      let name = tree.name(child).unwrap_or("anonymous");
+      if name.starts_with("_vptr") {
+        record.push("vtable", offset.unwrap_or(0), String::from(POINTER));
+        continue;
+      }
+      let bit_size = match tree.uint(child, DW_AT_BIT_SIZE) {
+        Some(bit_size) => bit_size as usize,
+        None => {
+          record.push(name, offset.unwrap_or(0), ty);
+          continue;
+        }
+      };
+
+      let storage = tree
+        .uint(child, DW_AT_BYTE_SIZE)
+        .map_or(member_size, |size| size as usize);
+      let bit_offset = match tree.uint(child, DW_AT_DATA_BIT_OFFSET) {
+        Some(bit_offset) => bit_offset as usize,
+        // DWARF 2 and 3 count from the most significant bit of the storage unit.
+        None => {
+          let from_msb = tree.uint(child, DW_AT_BIT_OFFSET).unwrap_or(0) as usize;
+          offset.unwrap_or(0) * 8 + (storage * 8).saturating_sub(from_msb + bit_size)
+        }
+      };
+      if tree.big_endian {
+        let unit = storage.max(1);
+        let backing = bit_offset / 8 / unit * unit;
+        if !record.members.iter().any(|member| member.offset == backing) {
+          record.push(
+            &format!("bitfield_{:x}", backing),
+            backing,
+            format!("u{}", unit * 8)
+          );
+        }
+      } else {
+        record.push_bit_field(name, bit_offset, bit_size, ty, storage);
+      }
+    }
+
+    record.members.sort_by_key(|member| member.offset);
+    Ok((record, anonymous))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::render;
+
+  /// A little-endian ELF64 file holding `sections` followed by the section name table.
+  fn elf(sections: &[(&str, &[u8])]) -> Vec<u8> {
+    let mut names = vec![0u8];
+    for (name, _) in sections {
+      names.extend(name.bytes().chain([0]));
+    }
+    let names_offset = names.len();
+    names.extend(b".shstrtab\0");
+
+    let mut data = vec![0u8; 0x40];
+    let mut headers = vec![0u8; 0x40];
+    let mut name = 1;
+    for (section_name, contents) in sections.iter().chain([&(".shstrtab", &names[..])]) {
+      let kind: u32 = if *section_name == ".shstrtab" { 3 } else { 1 };
+      headers.extend((name as u32).to_le_bytes());
+      headers.extend(kind.to_le_bytes());
+      headers.extend([0; 16]);
+      headers.extend((data.len() as u64).to_le_bytes());
+      headers.extend((contents.len() as u64).to_le_bytes());
+      headers.extend([0; 24]);
+      name += section_name.len() + 1;
+      data.extend_from_slice(contents);
+    }
+    assert_eq!(name, names_offset + ".shstrtab".len() + 1);
+
+    let table = data.len() as u64;
+    data[..8].copy_from_slice(b"\x7FELF\x02\x01\x01\x00");
+    data[0x12..0x14].copy_from_slice(&62u16.to_le_bytes());
+    data[0x28..0x30].copy_from_slice(&table.to_le_bytes());
+    data[0x3A..0x3C].copy_from_slice(&0x40u16.to_le_bytes());
+    data[0x3C..0x3E].copy_from_slice(&(sections.len() as u16 + 2).to_le_bytes());
+    data[0x3E..0x40].copy_from_slice(&(sections.len() as u16 + 1).to_le_bytes());
+    data.extend(headers);
+    data
+  }
+
+  /// Code, tag, whether it has children and the attributes with their forms of an abbreviation.
+  type Abbrev<'a> = (u8, u16, bool, &'a [(u16, u8)]);
+
+  #[test]
+  fn test_parse_elf() {
+    let abbreviations: &[Abbrev] = &[
+      (1, 0x11, true, &[]),
+      (
+        2,
+        DW_TAG_BASE_TYPE,
+        false,
+        &[
+          (DW_AT_NAME, 0x08),
+          (DW_AT_ENCODING, 0x0B),
+          (DW_AT_BYTE_SIZE, 0x0B)
+        ]
+      ),
+      (
+        3,
+        DW_TAG_STRUCTURE_TYPE,
+        true,
+        &[(DW_AT_NAME, 0x08), (DW_AT_BYTE_SIZE, 0x0B)]
+      ),
+      (
+        4,
+        DW_TAG_MEMBER,
+        false,
+        &[
+          (DW_AT_NAME, 0x08),
+          (DW_AT_TYPE, 0x13),
+          (DW_AT_DATA_MEMBER_LOCATION, 0x0B)
+        ]
+      ),
+      (
+        5,
+        DW_TAG_POINTER_TYPE,
+        false,
+        &[(DW_AT_TYPE, 0x13), (DW_AT_BYTE_SIZE, 0x0B)]
+      ),
+      (6, DW_TAG_STRUCTURE_TYPE, true, &[(DW_AT_BYTE_SIZE, 0x0B)]),
+      (
+        7,
+        DW_TAG_MEMBER,
+        false,
+        &[
+          (DW_AT_NAME, 0x08),
+          (DW_AT_TYPE, 0x13),
+          (DW_AT_BIT_SIZE, 0x0B),
+          (DW_AT_DATA_BIT_OFFSET, 0x0B)
+        ]
+      ),
+      (
+        8,
+        DW_TAG_TYPEDEF,
+        false,
+        &[(DW_AT_NAME, 0x08), (DW_AT_TYPE, 0x13)]
+      ),
+      (
+        9,
+        DW_TAG_STRUCTURE_TYPE,
+        false,
+        &[(DW_AT_NAME, 0x08), (DW_AT_DECLARATION, 0x19)]
+      ),
+      (10, DW_TAG_ARRAY_TYPE, true, &[(DW_AT_TYPE, 0x13)]),
+      (
+        11,
+        DW_TAG_SUBRANGE_TYPE,
+        false,
+        &[(DW_AT_UPPER_BOUND, 0x0B)]
+      )
+    ];
+    let mut abbrev = Vec::new();
+    for (code, tag, children, attrs) in abbreviations {
+      abbrev.extend([*code, *tag as u8, *children as u8]);
+      for (name, form) in attrs.iter() {
+        abbrev.extend([*name as u8, *form]);
+      }
+      abbrev.extend([0, 0]);
+    }
+    abbrev.push(0);
+
+    // Entries are appended after the 11 byte unit header, references are relative to the unit.
+    let mut info = vec![0u8; 11];
+    let mut die = |code: u8, attrs: &[&[u8]]| -> u32 {
+      let offset = info.len() as u32;
+      info.push(code);
+      for attr in attrs {
+        info.extend_from_slice(attr);
+      }
+      offset
+    };
+    let string = |name: &str| -> Vec<u8> { name.bytes().chain([0]).collect() };
+    let reference = |offset: u32| offset.to_le_bytes();
+
+    die(1, &[]);
+    let float = die(2, &[&string("float"), &[DW_ATE_FLOAT as u8], &[4]]);
+    let uint = die(
+      2,
+      &[&string("unsigned int"), &[DW_ATE_UNSIGNED as u8], &[4]]
+    );
+    die(9, &[&string("Vec2")]);
+    let vec2 = die(3, &[&string("Vec2"), &[8]]);
+    die(4, &[&string("x"), &reference(float), &[0]]);
+    die(4, &[&string("y"), &reference(float), &[4]]);
+    die(0, &[]);
+    let pointer = die(5, &[&reference(vec2), &[8]]);
+    let array = die(10, &[&reference(uint)]);
+    die(11, &[&[2]]);
+    die(0, &[]);
+    let player = die(6, &[&[0x20]]);
+    die(4, &[&string("pos"), &reference(vec2), &[0]]);
+    die(4, &[&string("target"), &reference(pointer), &[8]]);
+    die(7, &[&string("alive"), &reference(uint), &[1], &[0x80]]);
+    die(7, &[&string("lives"), &reference(uint), &[3], &[0x81]]);
+    die(4, &[&string("ammo"), &reference(array), &[0x14]]);
+    die(0, &[]);
+    die(8, &[&string("Player"), &reference(player)]);
+    die(0, &[]);
+    let length = info.len() as u32 - 4;
+    info[..4].copy_from_slice(&length.to_le_bytes());
+    info[4..6].copy_from_slice(&4u16.to_le_bytes());
+    info[10] = 8;
+
+    let elf = elf(&[(".debug_info", &info), (".debug_abbrev", &abbrev)]);
+    assert_eq!(
+      render(&parse_elf(&elf, &[]).unwrap()),
+      "\
+use memory_layout::memory_layout;
+
+#[memory_layout(0x8)]
+pub struct Vec2 {
+  #[field_offset(0x00)]
+  pub x: f32,
+
+  #[field_offset(0x04)]
+  pub y: f32
+}
+
+#[memory_layout(0x20)]
+pub struct Player {
+  #[field_offset(0x00)]
+  pub pos: Vec2,
+
+  #[field_offset(0x08)]
+  pub target: *mut Vec2,
+
+  #[field_offset(0x10)]
+  pub bitfield_10: u32,
+  #[bit_offset(0x10, 0, 1)]
+  pub alive: u32,
+  #[bit_offset(0x10, 1, 3)]
+  pub lives: u32,
+
+  #[field_offset(0x14)]
+  pub ammo: [u32; 3]
+}
+"
+    );
+
+    let players = parse_elf(&elf, &[String::from("Player")]).unwrap();
+    assert_eq!(players.len(), 1);
+    assert_eq!(players[0].name, "Player");
+    assert!(parse_elf(b"MZ", &[]).is_err());
+  }
+}
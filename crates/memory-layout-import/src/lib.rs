@@ -0,0 +1,564 @@
+//! Generates structs annotated with `#[memory_layout]` from existing definitions, so the offsets
+//! the C compiler or the debug information of a binary specify don't need to be transcribed by
+//! hand.
+//!
+//! Every importer produces [`Record`]s, which [`render`] turns into Rust source:
+//! * [`clang`] reads C and C++ headers through the record layouts dumped by `clang`.
+//! * [`pdb`] reads the type information of PDB files.
+//! * [`dwarf`] reads the DWARF debug information of ELF binaries and object files.
+//! * [`reclass`] reads ReClass.NET projects.
+//!
+//! The `memory-layout-import` binary wraps the importers:
+//! ```text
+//! memory-layout-import clang sdk.h --type CPed --type CEntity -- -I include > src/sdk.rs
+//! ```
+//!
+//! C types are translated to their Rust equivalents, other records are referred to by name, so
+//! types that weren't imported, like enums, have to be defined next to the generated code.
+
+pub mod clang;
+pub mod dwarf;
+pub mod pdb;
+pub mod reclass;
+mod zip;
+
+use std::{error::Error, fmt, io};
+
+/// Error returned when definitions can't be imported.
+#[derive(Debug)]
+pub enum ImportError {
+  /// Reading the input or running an external tool failed.
+  Io(io::Error),
+  /// An external tool exited unsuccessfully.
+  Tool {
+    /// The program that was run.
+    program: String,
+    /// What the program printed to stderr.
+    stderr:  String
+  },
+  /// The input isn't in the expected format.
+  Parse {
+    /// Line of the input, starting at 1.
+    line:    usize,
+    /// What's wrong with it.
+    message: String
+  },
+  /// A binary input isn't in the expected format.
+  Malformed(String)
+}
+
+impl fmt::Display for ImportError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      ImportError::Io(err) => fmt::Display::fmt(err, f),
+      ImportError::Tool { program, stderr } => {
+        write!(f, "`{}` failed: {}", program, stderr.trim_end())
+      }
+      ImportError::Parse { line, message } => write!(f, "line {}: {}", line, message),
+      ImportError::Malformed(message) => f.write_str(message)
+    }
+  }
+}
+
+impl Error for ImportError {
+  fn source(&self) -> Option<&(dyn Error + 'static)> {
+    match self {
+      ImportError::Io(err) => Some(err),
+      _ => None
+    }
+  }
+}
+
+impl From<io::Error> for ImportError {
+  fn from(err: io::Error) -> Self {
+    ImportError::Io(err)
+  }
+}
+
+/// Whether a [`Record`] is a struct or a union.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RecordKind {
+  /// A struct or class.
+  Struct,
+  /// A union, whose members all start at offset 0.
+  Union
+}
+
+/// An imported struct or union.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Record {
+  /// Name of the record as a Rust identifier.
+  pub name:    String,
+  /// Whether the record is a struct or a union.
+  pub kind:    RecordKind,
+  /// Size of the record, in bytes.
+  pub size:    usize,
+  /// The record embedded at offset 0 as the base, generated as `base = ..`.
+  pub base:    Option<String>,
+  /// Members of the record, ordered by offset.
+  pub members: Vec<Member>
+}
+
+/// A member of a [`Record`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Member {
+  /// Name of the member as written in the source.
+  pub name:   String,
+  /// Offset of the member, or of the integer holding a bit field, in bytes.
+  pub offset: usize,
+  /// Rust type of the member.
+  pub ty:     String,
+  /// The bits of the integer at `offset` the member occupies, generated as `#[bit_offset]`.
+  pub bits:   Option<Bits>
+}
+
+/// Position of a bit field within its backing integer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Bits {
+  /// Index of the lowest bit.
+  pub bit:   usize,
+  /// Number of bits.
+  pub width: usize
+}
+
+impl Record {
+  /// Creates a record without members.
+  pub fn new(name: &str, kind: RecordKind, size: usize) -> Self {
+    Record {
+      name: type_ident(name),
+      kind,
+      size,
+      base: None,
+      members: Vec::new()
+    }
+  }
+
+  /// Adds a member of the Rust type `ty`.
+  pub fn push(&mut self, name: &str, offset: usize, ty: String) {
+    self.members.push(Member {
+      name: name.to_string(),
+      offset,
+      ty,
+      bits: None
+    });
+  }
+
+  /// Adds a bit field of the Rust type `ty`, which is `unit` bytes in size, starting at bit
+  /// `bit_offset` of the record. The bytes holding it are reserved by a backing integer unless a
+  /// previous member already covers them. Bit fields of types other than integers, like `bool` or
+  /// enums, are accessed as unsigned integers.
+  pub fn push_bit_field(
+    &mut self,
+    name: &str,
+    bit_offset: usize,
+    width: usize,
+    ty: String,
+    unit: usize
+  ) {
+    let unit = unit.max(1);
+    let ty = if type_size(&ty) == 0 {
+      format!("u{}", unit * 8)
+    } else {
+      ty
+    };
+    let offset = bit_offset / 8 / unit * unit;
+    let covered = self
+      .members
+      .iter()
+      .filter(|member| member.bits.is_none())
+      .any(|member| {
+        member.offset <= offset && offset + unit <= member.offset + type_size(&member.ty)
+      });
+    if !covered {
+      self.push(
+        &format!("bitfield_{:x}", offset),
+        offset,
+        format!("u{}", unit * 8)
+      );
+    }
+    self.members.push(Member {
+      name: name.to_string(),
+      offset,
+      ty,
+      bits: Some(Bits {
+        bit: bit_offset - offset * 8,
+        width
+      })
+    });
+  }
+}
+
+/// Size of the integer types generated for backing integers, 0 for anything else.
+fn type_size(ty: &str) -> usize {
+  match ty {
+    "u8" | "i8" => 1,
+    "u16" | "i16" => 2,
+    "u32" | "i32" => 4,
+    "u64" | "i64" => 8,
+    _ => 0
+  }
+}
+
+/// Renders `records` as Rust source, one `#[memory_layout]` item per record.
+pub fn render(records: &[Record]) -> String {
+  let mut out = String::from("use memory_layout::memory_layout;\n");
+  for record in records {
+    out.push('\n');
+    render_record(&mut out, record);
+  }
+  out
+}
+
+fn render_record(out: &mut String, record: &Record) {
+  match &record.base {
+    Some(base) => {
+      out.push_str(&format!(
+        "#[memory_layout(base = {}, size = 0x{:X})]\n",
+        base, record.size
+      ))
+    }
+    None => out.push_str(&format!("#[memory_layout(0x{:X})]\n", record.size))
+  }
+  let keyword = match record.kind {
+    RecordKind::Struct => "struct",
+    RecordKind::Union => "union"
+  };
+  out.push_str(&format!("pub {} {} {{", keyword, record.name));
+
+  let mut names = Vec::new();
+  for (index, member) in record.members.iter().enumerate() {
+    let mut name = field_ident(&member.name);
+    let base = name.clone();
+    let mut suffix = 1;
+    while names.contains(&name) {
+      suffix += 1;
+      name = format!("{}_{}", base, suffix);
+    }
+    if index > 0 {
+      out.push(',');
+    }
+    if index > 0 && member.bits.is_none() {
+      out.push('\n');
+    }
+    out.push('\n');
+    match member.bits {
+      Some(bits) => {
+        out.push_str(&format!(
+          "  #[bit_offset(0x{:02X}, {}, {})]\n",
+          member.offset, bits.bit, bits.width
+        ))
+      }
+      None => out.push_str(&format!("  #[field_offset(0x{:02X})]\n", member.offset))
+    }
+    out.push_str(&format!("  pub {}: {}", name, member.ty));
+    names.push(name);
+  }
+  out.push_str("\n}\n");
+}
+
+const KEYWORDS: &[&str] = &[
+  "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn",
+  "else", "enum", "extern", "false", "final", "fn", "for", "if", "impl", "in", "let", "loop",
+  "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return", "static",
+  "struct", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use", "virtual",
+  "where", "while", "yield"
+];
+
+/// `name` as a field identifier, replacing characters that can't be part of one.
+fn field_ident(name: &str) -> String {
+  let ident = sanitize(name);
+  if KEYWORDS.contains(&ident.as_str()) {
+    format!("r#{}", ident)
+  } else if matches!(ident.as_str(), "self" | "Self" | "super" | "crate") {
+    format!("{}_", ident)
+  } else {
+    ident
+  }
+}
+
+/// `name` as a type identifier, dropping namespaces and replacing characters that can't be part
+/// of one, so `ns::Vector<float>` becomes `Vector_float`.
+pub fn type_ident(name: &str) -> String {
+  let name = name.trim();
+  let end = name.find('<').unwrap_or(name.len());
+  let start = name[..end].rfind("::").map_or(0, |split| split + 2);
+  let ident = sanitize(&name[start..]);
+  if KEYWORDS.contains(&ident.as_str()) || ident == "Self" {
+    format!("{}_", ident)
+  } else {
+    ident
+  }
+}
+
+fn sanitize(name: &str) -> String {
+  let mut ident = String::new();
+  for c in name.chars() {
+    if c.is_ascii_alphanumeric() || c == '_' {
+      ident.push(c);
+    } else if !ident.is_empty() && !ident.ends_with('_') {
+      ident.push('_');
+    }
+  }
+  let ident = ident.trim_end_matches('_');
+  match ident.chars().next() {
+    None => String::from("_"),
+    Some(first) if first.is_ascii_digit() => format!("_{}", ident),
+    Some(_) => ident.to_string()
+  }
+}
+
+const POINTER: &str = "*const core::ffi::c_void";
+
+/// Translates a C or C++ type name like `const char *` or `unsigned int[4]` to Rust. Records are
+/// referred to by [`type_ident`], arrays without a length yield `None`.
+pub fn rust_type(c_type: &str) -> Option<String> {
+  let mut ty = c_type.trim();
+  for qualifier in [
+    " const",
+    " volatile",
+    " __ptr64",
+    " __restrict",
+    " restrict"
+  ] {
+    while let Some(rest) = ty.strip_suffix(qualifier) {
+      ty = rest.trim_end();
+    }
+  }
+
+  if ty.contains("(*") || ty.contains("(__") || ty.contains("(&") {
+    return Some(String::from(POINTER));
+  }
+  if let Some(open) = ty.find('[') {
+    let close = open + ty[open..].find(']')?;
+    let count = ty[open + 1..close].trim();
+    if count.is_empty() {
+      return None;
+    }
+    let element = format!("{}{}", &ty[..open], &ty[close + 1..]);
+    return Some(format!("[{}; {}]", rust_type(&element)?, count));
+  }
+  if let Some(pointee) = ty.strip_suffix('*').or_else(|| ty.strip_suffix('&')) {
+    let pointee = pointee.trim();
+    let mutability = if pointee.starts_with("const ") || pointee.ends_with(" const") {
+      "const"
+    } else {
+      "mut"
+    };
+    let pointee = match rust_type(pointee) {
+      Some(pointee) if pointee != "()" => pointee,
+      _ => String::from("core::ffi::c_void")
+    };
+    return Some(format!("*{} {}", mutability, pointee));
+  }
+
+  for prefix in [
+    "const ",
+    "volatile ",
+    "struct ",
+    "class ",
+    "union ",
+    "enum "
+  ] {
+    while let Some(rest) = ty.strip_prefix(prefix) {
+      ty = rest.trim_start();
+    }
+  }
+  let segment = ty.strip_prefix("std::").unwrap_or(ty);
+  let primitive = match segment {
+    "void" => "()",
+    "bool" | "_Bool" => "bool",
+    "char" => "core::ffi::c_char",
+    "signed char" | "int8_t" | "__int8" => "i8",
+    "unsigned char" | "uint8_t" | "unsigned __int8" | "char8_t" => "u8",
+    "short" | "short int" | "signed short" | "signed short int" | "int16_t" | "__int16" => "i16",
+    "unsigned short" | "unsigned short int" | "uint16_t" | "unsigned __int16" | "char16_t" => "u16",
+    "int" | "signed" | "signed int" | "int32_t" | "__int32" => "i32",
+    "unsigned" | "unsigned int" | "uint32_t" | "unsigned __int32" | "char32_t" => "u32",
+    "long" | "long int" | "signed long" | "signed long int" => "core::ffi::c_long",
+    "unsigned long" | "unsigned long int" => "core::ffi::c_ulong",
+    "long long" | "long long int" | "signed long long" | "int64_t" | "__int64" => "i64",
+    "unsigned long long" | "unsigned long long int" | "uint64_t" | "unsigned __int64" => "u64",
+    "float" => "f32",
+    "double" => "f64",
+    "size_t" | "uintptr_t" => "usize",
+    "ssize_t" | "intptr_t" | "ptrdiff_t" => "isize",
+    _ => return Some(type_ident(ty))
+  };
+  Some(String::from(primitive))
+}
+
+/// Size of the C integer type of a bit field, if it's known regardless of the target.
+pub(crate) fn c_integer_size(rust_type: &str) -> Option<usize> {
+  match rust_type {
+    "bool" | "i8" | "u8" | "core::ffi::c_char" => Some(1),
+    "i16" | "u16" => Some(2),
+    "i32" | "u32" => Some(4),
+    "i64" | "u64" => Some(8),
+    _ => None
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{clang, render, rust_type, type_ident, Record, RecordKind};
+
+  #[test]
+  fn test_rust_type() {
+    for (c, rust) in [
+      ("unsigned int", "u32"),
+      ("const char *", "*const core::ffi::c_char"),
+      ("struct CEntity *", "*mut CEntity"),
+      ("void *", "*mut core::ffi::c_void"),
+      ("int[2][3]", "[[i32; 3]; 2]"),
+      ("float[4]", "[f32; 4]"),
+      ("void (*)(int)", "*const core::ffi::c_void"),
+      ("std::uint64_t", "u64"),
+      ("class ns::Vector<float>", "Vector_float"),
+      ("unsigned __int64", "u64")
+    ] {
+      assert_eq!(rust_type(c).as_deref(), Some(rust), "{}", c);
+    }
+    assert_eq!(rust_type("int[]"), None);
+    assert_eq!(
+      type_ident("(anonymous struct at a.h:3:1)"),
+      "anonymous_struct_at_a_h_3_1"
+    );
+  }
+
+  #[test]
+  fn test_render() {
+    let mut entity = Record::new("CEntity", RecordKind::Struct, 0x10);
+    entity.push("type", 0x08, String::from("u32"));
+    entity.push_bit_field("visible", 0x60, 1, String::from("u32"), 4);
+    entity.push_bit_field("mode", 0x61, 3, String::from("u32"), 4);
+
+    let mut ped = Record::new("CPed", RecordKind::Struct, 0x20);
+    ped.base = Some(String::from("CEntity"));
+    ped.push("health", 0x10, String::from("f32"));
+    ped.push("health", 0x14, String::from("f32"));
+
+    assert_eq!(
+      render(&[entity, ped]),
+      "\
+use memory_layout::memory_layout;
+
+#[memory_layout(0x10)]
+pub struct CEntity {
+  #[field_offset(0x08)]
+  pub r#type: u32,
+
+  #[field_offset(0x0C)]
+  pub bitfield_c: u32,
+  #[bit_offset(0x0C, 0, 1)]
+  pub visible: u32,
+  #[bit_offset(0x0C, 1, 3)]
+  pub mode: u32
+}
+
+#[memory_layout(base = CEntity, size = 0x20)]
+pub struct CPed {
+  #[field_offset(0x10)]
+  pub health: f32,
+
+  #[field_offset(0x14)]
+  pub health_2: f32
+}
+"
+    );
+  }
+
+  #[test]
+  fn test_clang_record_layouts() {
+    let dump = "
+*** Dumping AST Record Layout
+         0 | class CEntity
+         0 |   (CEntity vtable pointer)
+         8 |   unsigned int id
+        12 |   _Bool active
+       13:0-2 |   unsigned char flags
+       13:3-7 |   unsigned char mode
+        13:- |   int
+           | [sizeof=16, dsize=14, align=8,
+           |  nvsize=14, nvalign=8]
+
+*** Dumping AST Record Layout
+         0 | struct Vector3
+         0 |   float x
+         4 |   float y
+         8 |   float z
+           | [sizeof=12, dsize=12, align=4,
+           |  nvsize=12, nvalign=4]
+
+*** Dumping AST Record Layout
+         0 | class CPed
+         0 |   class CEntity (primary base)
+         0 |     (CEntity vtable pointer)
+         8 |     unsigned int id
+        16 |   struct Vector3 position
+        16 |     float x
+        20 |     float y
+        24 |     float z
+        32 |   class CEntity * target
+        40 |   char[16] name
+        56 |   union (anonymous at sdk.h:20:3)
+        56 |     int i
+        56 |     float f
+        60 |   int[] items
+           | [sizeof=64, dsize=60, align=8,
+           |  nvsize=60, nvalign=8]
+";
+    let records = clang::parse_record_layouts(dump).unwrap();
+    assert_eq!(
+      render(&records),
+      "\
+use memory_layout::memory_layout;
+
+#[memory_layout(0x10)]
+pub struct CEntity {
+  #[field_offset(0x00)]
+  pub vtable: *const core::ffi::c_void,
+
+  #[field_offset(0x08)]
+  pub id: u32,
+
+  #[field_offset(0x0C)]
+  pub active: bool,
+
+  #[field_offset(0x0D)]
+  pub bitfield_d: u8,
+  #[bit_offset(0x0D, 0, 3)]
+  pub flags: u8,
+  #[bit_offset(0x0D, 3, 5)]
+  pub mode: u8
+}
+
+#[memory_layout(0xC)]
+pub struct Vector3 {
+  #[field_offset(0x00)]
+  pub x: f32,
+
+  #[field_offset(0x04)]
+  pub y: f32,
+
+  #[field_offset(0x08)]
+  pub z: f32
+}
+
+#[memory_layout(base = CEntity, size = 0x40)]
+pub struct CPed {
+  #[field_offset(0x10)]
+  pub position: Vector3,
+
+  #[field_offset(0x20)]
+  pub target: *mut CEntity,
+
+  #[field_offset(0x28)]
+  pub name: [core::ffi::c_char; 16],
+
+  #[field_offset(0x38)]
+  pub anonymous: anonymous_at_sdk_h_20_3
+}
+"
+    );
+  }
+}
@@ -0,0 +1,65 @@
+use std::{path::Path, process::ExitCode};
+
+use memory_layout_import::{clang, dwarf, pdb, reclass, render, ImportError, Record};
+
+const USAGE: &str = "\
+usage: memory-layout-import <importer> <input> [--type <name>]... [-- <tool arguments>...]
+
+Prints `#[memory_layout]` structs for the records of <input>, or only the ones selected with
+--type.
+
+importers:
+  clang    a C or C++ header, the tool arguments are passed to clang
+  pdb      a PDB file
+  dwarf    an ELF binary or object file with DWARF debug information
+  reclass  a ReClass.NET project, .rcnet or its Data.xml";
+
+fn main() -> ExitCode {
+  let args: Vec<String> = std::env::args().skip(1).collect();
+  let (args, tool_args) = match args.iter().position(|arg| arg == "--") {
+    Some(split) => (&args[..split], &args[split + 1..]),
+    None => (&args[..], &[][..])
+  };
+
+  let mut positional = Vec::new();
+  let mut types = Vec::new();
+  let mut iter = args.iter();
+  while let Some(arg) = iter.next() {
+    match arg.as_str() {
+      "--type" | "-t" => {
+        match iter.next() {
+          Some(ty) => types.push(ty.clone()),
+          None => return usage()
+        }
+      }
+      "--help" | "-h" => {
+        println!("{}", USAGE);
+        return ExitCode::SUCCESS;
+      }
+      _ => positional.push(arg.as_str())
+    }
+  }
+
+  let records: Result<Vec<Record>, ImportError> = match positional[..] {
+    ["clang", input] => clang::import_header(Path::new(input), tool_args, &types),
+    ["pdb", input] => pdb::import_pdb(Path::new(input), &types),
+    ["dwarf", input] => dwarf::import_elf(Path::new(input), &types),
+    ["reclass", input] => reclass::import_reclass(Path::new(input), &types),
+    _ => return usage()
+  };
+  match records {
+    Ok(records) => {
+      print!("{}", render(&records));
+      ExitCode::SUCCESS
+    }
+    Err(err) => {
+      eprintln!("error: {}", err);
+      ExitCode::FAILURE
+    }
+  }
+}
+
+fn usage() -> ExitCode {
+  eprintln!("{}", USAGE);
+  ExitCode::from(2)
+}
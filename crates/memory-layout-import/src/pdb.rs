@@ -0,0 +1,727 @@
+//! Imports records from the type information of PDB files, which MSVC writes next to Windows
+//! binaries.
+//!
+//! Members of anonymous unions, which PDBs list as members of the enclosing struct, are imported
+//! until one overlaps a previous member. Enums are replaced by their underlying integer type,
+//! virtual base classes are left out.
+
+use std::{collections::HashMap, path::Path};
+
+use crate::{ImportError, Record, RecordKind, POINTER};
+
+const MSF_MAGIC: &[u8; 32] = b"Microsoft C/C++ MSF 7.00\r\n\x1aDS\0\0\0";
+/// Index of the stream holding the type records.
+const TPI_STREAM: usize = 2;
+/// First type index referring to a type record, lower ones are built-in types.
+const FIRST_TYPE_INDEX: u32 = 0x1000;
+
+const LF_MODIFIER: u16 = 0x1001;
+const LF_POINTER: u16 = 0x1002;
+const LF_PROCEDURE: u16 = 0x1008;
+const LF_MFUNCTION: u16 = 0x1009;
+const LF_FIELDLIST: u16 = 0x1203;
+const LF_BITFIELD: u16 = 0x1205;
+const LF_BCLASS: u16 = 0x1400;
+const LF_VBCLASS: u16 = 0x1401;
+const LF_IVBCLASS: u16 = 0x1402;
+const LF_INDEX: u16 = 0x1404;
+const LF_VFUNCTAB: u16 = 0x1409;
+const LF_FRIENDCLS: u16 = 0x140B;
+const LF_VFUNCOFF: u16 = 0x140C;
+const LF_ARRAY: u16 = 0x1503;
+const LF_CLASS: u16 = 0x1504;
+const LF_STRUCTURE: u16 = 0x1505;
+const LF_UNION: u16 = 0x1506;
+const LF_ENUM: u16 = 0x1507;
+const LF_FRIENDFCN: u16 = 0x150C;
+const LF_MEMBER: u16 = 0x150D;
+const LF_STMEMBER: u16 = 0x150E;
+const LF_METHOD: u16 = 0x150F;
+const LF_NESTTYPE: u16 = 0x1510;
+const LF_ONEMETHOD: u16 = 0x1511;
+const LF_INTERFACE: u16 = 0x1519;
+
+/// Set in the properties of records that are only declared.
+const FORWARD_REFERENCE: u16 = 0x80;
+
+/// Reads the PDB at `path` and imports the records named in `types`, or every record if it's
+/// empty.
+pub fn import_pdb(path: &Path, types: &[String]) -> Result<Vec<Record>, ImportError> {
+  parse_pdb(&std::fs::read(path)?, types)
+}
+
+/// Imports the records named in `types`, or every record if it's empty, from the bytes of a PDB.
+/// Names are matched with and without namespaces.
+pub fn parse_pdb(pdb: &[u8], types: &[String]) -> Result<Vec<Record>, ImportError> {
+  let tpi = read_stream(pdb, TPI_STREAM)?;
+  let table = TypeTable::parse(&tpi)?;
+
+  let mut records: Vec<Record> = Vec::new();
+  for position in 0..table.leaves.len() {
+    let index = FIRST_TYPE_INDEX + position as u32;
+    let udt = match table.udt(index) {
+      Some(udt) if udt.properties & FORWARD_REFERENCE == 0 => udt,
+      _ => continue
+    };
+    let name = table.udt_name(index, &udt);
+    let selected = types.is_empty()
+      || types
+        .iter()
+        .any(|ty| *ty == udt.name || crate::type_ident(ty) == name);
+    if !selected || records.iter().any(|record| record.name == name) {
+      continue;
+    }
+    records.push(table.record(&name, &udt)?);
+  }
+
+  Ok(records)
+}
+
+fn malformed(message: &str) -> ImportError {
+  ImportError::Malformed(format!("{} in PDB.", message))
+}
+
+/// Reads stream `index` from the MSF container of a PDB.
+fn read_stream(pdb: &[u8], index: usize) -> Result<Vec<u8>, ImportError> {
+  if pdb.get(..MSF_MAGIC.len()) != Some(&MSF_MAGIC[..]) {
+    return Err(ImportError::Malformed(String::from(
+      "Not a PDB, the MSF 7.00 signature is missing."
+    )));
+  }
+  let mut header = Reader::new(&pdb[MSF_MAGIC.len()..]);
+  let block_size = header.u32()? as usize;
+  let _free_block_map = header.u32()?;
+  let _block_count = header.u32()?;
+  let directory_size = header.u32()? as usize;
+  let _reserved = header.u32()?;
+  let directory_map = header.u32()? as usize;
+  if block_size == 0 {
+    return Err(malformed("Invalid block size"));
+  }
+
+  let block = |index: usize| {
+    pdb
+      .get(index * block_size..(index + 1) * block_size)
+      .ok_or_else(|| malformed("Block out of bounds"))
+  };
+  let read_blocks = |blocks: &mut Reader, size: usize| -> Result<Vec<u8>, ImportError> {
+    let mut data = Vec::with_capacity(size);
+    while data.len() < size {
+      let block = block(blocks.u32()? as usize)?;
+      let take = block_size.min(size - data.len());
+      data.extend_from_slice(&block[..take]);
+    }
+    Ok(data)
+  };
+
+  let directory = read_blocks(&mut Reader::new(block(directory_map)?), directory_size)?;
+  let mut directory = Reader::new(&directory);
+  let stream_count = directory.u32()? as usize;
+  let sizes = (0..stream_count)
+    .map(|_| {
+      directory
+        .u32()
+        .map(|size| if size == u32::MAX { 0 } else { size as usize })
+    })
+    .collect::<Result<Vec<_>, _>>()?;
+  let size = *sizes
+    .get(index)
+    .ok_or_else(|| malformed("Missing type stream"))?;
+  for size in &sizes[..index] {
+    directory.skip(size.div_ceil(block_size) * 4)?;
+  }
+  read_blocks(&mut directory, size)
+}
+
+/// A little-endian cursor over the bytes of a record.
+struct Reader<'a> {
+  data: &'a [u8]
+}
+
+impl<'a> Reader<'a> {
+  fn new(data: &'a [u8]) -> Self {
+    Reader { data }
+  }
+
+  fn bytes(&mut self, count: usize) -> Result<&'a [u8], ImportError> {
+    if count > self.data.len() {
+      return Err(malformed("Unexpected end of data"));
+    }
+    let (bytes, rest) = self.data.split_at(count);
+    self.data = rest;
+    Ok(bytes)
+  }
+
+  fn skip(&mut self, count: usize) -> Result<(), ImportError> {
+    self.bytes(count).map(|_| ())
+  }
+
+  fn u8(&mut self) -> Result<u8, ImportError> {
+    Ok(self.bytes(1)?[0])
+  }
+
+  fn u16(&mut self) -> Result<u16, ImportError> {
+    let bytes = self.bytes(2)?;
+    Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+  }
+
+  fn u32(&mut self) -> Result<u32, ImportError> {
+    let bytes = self.bytes(4)?;
+    Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+  }
+
+  /// A numeric leaf, a value below 0x8000 or a kind followed by the value.
+  fn numeric(&mut self) -> Result<u64, ImportError> {
+    let value = self.u16()?;
+    Ok(match value {
+      0..=0x7FFF => u64::from(value),
+      0x8000 => self.u8()? as i8 as u64,
+      0x8001 => self.u16()? as i16 as u64,
+      0x8002 => u64::from(self.u16()?),
+      0x8003 => self.u32()? as i32 as u64,
+      0x8004 => u64::from(self.u32()?),
+      0x8009 | 0x800A => {
+        let bytes = self.bytes(8)?;
+        u64::from_le_bytes(bytes.try_into().unwrap())
+      }
+      _ => return Err(malformed("Unsupported numeric leaf"))
+    })
+  }
+
+  fn name(&mut self) -> Result<&'a str, ImportError> {
+    let end = self
+      .data
+      .iter()
+      .position(|byte| *byte == 0)
+      .ok_or_else(|| malformed("Unterminated name"))?;
+    let name = std::str::from_utf8(&self.data[..end]).map_err(|_| malformed("Invalid name"))?;
+    self.data = &self.data[end + 1..];
+    Ok(name)
+  }
+
+  /// Skips the `LF_PAD` bytes aligning the entries of a field list.
+  fn skip_padding(&mut self) {
+    while let Some(byte) = self.data.first() {
+      if *byte < 0xF0 {
+        break;
+      }
+      self.data = &self.data[1..];
+    }
+  }
+}
+
+/// A struct, class or union record.
+struct Udt<'a> {
+  kind:       RecordKind,
+  properties: u16,
+  fields:     u32,
+  size:       u64,
+  name:       &'a str
+}
+
+/// The type records of the TPI stream, indexed from [`FIRST_TYPE_INDEX`].
+struct TypeTable<'a> {
+  /// Kind and data of every record.
+  leaves:      Vec<(u16, &'a [u8])>,
+  /// Indices of defined structs, classes and unions by name, resolving forward references.
+  definitions: HashMap<&'a str, u32>
+}
+
+impl<'a> TypeTable<'a> {
+  fn parse(tpi: &'a [u8]) -> Result<Self, ImportError> {
+    let mut header = Reader::new(tpi);
+    let _version = header.u32()?;
+    let header_size = header.u32()? as usize;
+    let first = header.u32()?;
+    let _end = header.u32()?;
+    let record_bytes = header.u32()? as usize;
+    if first != FIRST_TYPE_INDEX {
+      return Err(malformed("Unexpected first type index"));
+    }
+
+    let mut records = Reader::new(
+      tpi
+        .get(header_size..header_size + record_bytes)
+        .ok_or_else(|| malformed("Type records out of bounds"))?
+    );
+    let mut table = TypeTable {
+      leaves:      Vec::new(),
+      definitions: HashMap::new()
+    };
+    while !records.data.is_empty() {
+      let length = records.u16()? as usize;
+      let mut record = Reader::new(records.bytes(length)?);
+      let kind = record.u16()?;
+      table.leaves.push((kind, record.data));
+    }
+    for position in 0..table.leaves.len() {
+      let index = FIRST_TYPE_INDEX + position as u32;
+      if let Some(udt) = table.udt(index) {
+        if udt.properties & FORWARD_REFERENCE == 0 && !is_anonymous(udt.name) {
+          table.definitions.entry(udt.name).or_insert(index);
+        }
+      }
+    }
+    Ok(table)
+  }
+
+  fn leaf(&self, index: u32) -> Option<(u16, Reader<'a>)> {
+    let position = index.checked_sub(FIRST_TYPE_INDEX)? as usize;
+    self
+      .leaves
+      .get(position)
+      .map(|(kind, data)| (*kind, Reader::new(data)))
+  }
+
+  fn udt(&self, index: u32) -> Option<Udt<'a>> {
+    let (kind, mut data) = self.leaf(index)?;
+    let mut parse = |kind| -> Result<Udt<'a>, ImportError> {
+      let _count = data.u16()?;
+      let properties = data.u16()?;
+      let fields = data.u32()?;
+      if kind == RecordKind::Struct {
+        let _derived = data.u32()?;
+        let _vshape = data.u32()?;
+      }
+      let size = data.numeric()?;
+      let name = data.name()?;
+      Ok(Udt {
+        kind,
+        properties,
+        fields,
+        size,
+        name
+      })
+    };
+    match kind {
+      LF_CLASS | LF_STRUCTURE | LF_INTERFACE => parse(RecordKind::Struct).ok(),
+      LF_UNION => parse(RecordKind::Union).ok(),
+      _ => None
+    }
+  }
+
+  /// Name of the record as a Rust identifier, anonymous ones are named after their type index.
+  fn udt_name(&self, index: u32, udt: &Udt) -> String {
+    if is_anonymous(udt.name) {
+      format!("anonymous_{:x}", index)
+    } else {
+      crate::type_ident(udt.name)
+    }
+  }
+
+  /// Whether the type `index` is qualified with `const`.
+  fn is_const(&self, index: u32) -> bool {
+    match self.leaf(index) {
+      Some((LF_MODIFIER, mut data)) => {
+        data.skip(4).is_ok() && data.u16().is_ok_and(|modifiers| modifiers & 1 != 0)
+      }
+      _ => false
+    }
+  }
+
+  /// Rust type and size of the type `index`.
+  fn rust_type(&self, index: u32) -> Result<(String, usize), ImportError> {
+    if index < FIRST_TYPE_INDEX {
+      return simple_type(index).ok_or_else(|| malformed("Unsupported built-in type"));
+    }
+    let (kind, mut data) = self
+      .leaf(index)
+      .ok_or_else(|| malformed("Type index out of bounds"))?;
+    match kind {
+      LF_MODIFIER => self.rust_type(data.u32()?),
+      LF_POINTER => {
+        let referent = data.u32()?;
+        let attributes = data.u32()?;
+        let size = ((attributes >> 13) & 0x3F) as usize;
+        let pointee = match self.rust_type(referent) {
+          Ok((pointee, _)) if pointee != "()" => pointee,
+          _ => String::from("core::ffi::c_void")
+        };
+        let mutability = if self.is_const(referent) {
+          "const"
+        } else {
+          "mut"
+        };
+        Ok((format!("*{} {}", mutability, pointee), size))
+      }
+      LF_PROCEDURE | LF_MFUNCTION => Ok((String::from("()"), 0)),
+      LF_ARRAY => {
+        let element = data.u32()?;
+        let _index_type = data.u32()?;
+        let size = data.numeric()? as usize;
+        let (element, element_size) = self.rust_type(element)?;
+        if element_size == 0 {
+          return Err(malformed("Array of unsized elements"));
+        }
+        Ok((format!("[{}; {}]", element, size / element_size), size))
+      }
+      LF_ENUM => {
+        let _count = data.u16()?;
+        let _properties = data.u16()?;
+        self.rust_type(data.u32()?)
+      }
+      LF_BITFIELD => self.rust_type(data.u32()?),
+      _ => {
+        let udt = self
+          .udt(index)
+          .ok_or_else(|| malformed("Unsupported type record"))?;
+        let definition = self
+          .definitions
+          .get(udt.name)
+          .copied()
+          .filter(|_| !is_anonymous(udt.name))
+          .unwrap_or(index);
+        let udt = self.udt(definition).unwrap_or(udt);
+        Ok((self.udt_name(definition, &udt), udt.size as usize))
+      }
+    }
+  }
+
+  /// Imports the struct or union `udt` as `name`.
+  fn record(&self, name: &str, udt: &Udt) -> Result<Record, ImportError> {
+    let mut record = Record::new(name, udt.kind, udt.size as usize);
+    let union = udt.kind == RecordKind::Union;
+    // End of the members imported so far, members of structs starting before it overlap them.
+    let mut end = 0;
+    // Bytes of the backing integer of the current run of bit fields.
+    let mut run = 0..0;
+    let mut fields = Some(udt.fields);
+    while let Some(list) = fields.take() {
+      let mut data = match self.leaf(list) {
+        Some((LF_FIELDLIST, data)) => data,
+        _ => return Err(malformed("Expected a field list"))
+      };
+
+      while !data.data.is_empty() {
+        match data.u16()? {
+          LF_MEMBER => {
+            let _attributes = data.u16()?;
+            let ty = data.u32()?;
+            let offset = data.numeric()? as usize;
+            let member = data.name()?;
+            match self.leaf(ty) {
+              Some((LF_BITFIELD, mut bits)) => {
+                let (ty, unit) = self.rust_type(bits.u32()?)?;
+                let width = bits.u8()? as usize;
+                let bit_offset = offset * 8 + bits.u8()? as usize;
+                let unit = unit.max(1);
+                let backing = bit_offset / 8 / unit * unit;
+                if union || run.contains(&backing) || backing >= end {
+                  record.push_bit_field(member, bit_offset, width, ty, unit);
+                  if !run.contains(&backing) {
+                    run = backing..backing + unit;
+                  }
+                  end = end.max(run.end);
+                }
+              }
+              _ => {
+                let (ty, size) = self.rust_type(ty)?;
+                if union || offset >= end {
+                  record.push(member, offset, ty);
+                  end = offset + size;
+                }
+              }
+            }
+          }
+          LF_BCLASS => {
+            let _attributes = data.u16()?;
+            let (ty, size) = self.rust_type(data.u32()?)?;
+            let offset = data.numeric()? as usize;
+            // Empty bases take up no space.
+            if size > 0 {
+              if offset == 0 && record.base.is_none() && record.members.is_empty() {
+                record.base = Some(ty);
+              } else {
+                record.push(&format!("base_{}", ty), offset, ty);
+              }
+              end = end.max(offset + size);
+            }
+          }
+          LF_VBCLASS | LF_IVBCLASS => {
+            data.skip(10)?;
+            data.numeric()?;
+            data.numeric()?;
+          }
+          LF_VFUNCTAB => {
+            let _padding = data.u16()?;
+            let (_, size) = self.rust_type(data.u32()?)?;
+            if record.base.is_none() && record.members.is_empty() {
+              record.push("vtable", 0, String::from(POINTER));
+              end = end.max(size);
+            }
+          }
+          LF_INDEX => {
+            let _padding = data.u16()?;
+            fields = Some(data.u32()?);
+          }
+          LF_ONEMETHOD => {
+            let attributes = data.u16()?;
+            data.skip(4)?;
+            // Introducing virtual methods store their vtable offset.
+            if matches!((attributes >> 2) & 7, 4 | 6) {
+              data.skip(4)?;
+            }
+            data.name()?;
+          }
+          LF_METHOD | LF_NESTTYPE | LF_STMEMBER | LF_FRIENDFCN => {
+            data.skip(6)?;
+            data.name()?;
+          }
+          LF_FRIENDCLS => data.skip(6)?,
+          LF_VFUNCOFF => data.skip(10)?,
+          _ => return Err(malformed("Unsupported field list entry"))
+        }
+        data.skip_padding();
+      }
+    }
+
+    record.members.sort_by_key(|member| member.offset);
+    Ok(record)
+  }
+}
+
+fn is_anonymous(name: &str) -> bool {
+  name.starts_with('<') || name.contains("::<")
+}
+
+/// Rust type and size of a built-in type index, whose low byte is the type and bits 8 to 11 the
+/// pointer mode.
+fn simple_type(index: u32) -> Option<(String, usize)> {
+  let pointer_size = match (index >> 8) & 0xF {
+    0 => 0,
+    4 | 5 => 4,
+    6 => 8,
+    _ => return None
+  };
+  let (ty, size) = match index & 0xFF {
+    0x03 => ("()", 0),
+    0x10 | 0x68 => ("i8", 1),
+    0x20 | 0x69 | 0x7C => ("u8", 1),
+    0x70 => ("core::ffi::c_char", 1),
+    0x11 | 0x72 => ("i16", 2),
+    0x21 | 0x73 | 0x71 | 0x7A => ("u16", 2),
+    0x12 | 0x74 => ("i32", 4),
+    0x22 | 0x75 | 0x7B => ("u32", 4),
+    0x13 | 0x76 => ("i64", 8),
+    0x23 | 0x77 => ("u64", 8),
+    0x14 | 0x78 => ("i128", 16),
+    0x24 | 0x79 => ("u128", 16),
+    0x40 => ("f32", 4),
+    0x41 => ("f64", 8),
+    0x30 => ("bool", 1),
+    0x31 => ("u16", 2),
+    0x32 => ("u32", 4),
+    0x33 => ("u64", 8),
+    _ => return None
+  };
+  if pointer_size == 0 {
+    Some((String::from(ty), size))
+  } else if ty == "()" {
+    Some((String::from("*mut core::ffi::c_void"), pointer_size))
+  } else {
+    Some((format!("*mut {}", ty), pointer_size))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::render;
+
+  fn leaf(kind: u16, body: &[u8]) -> Vec<u8> {
+    let mut data = kind.to_le_bytes().to_vec();
+    data.extend_from_slice(body);
+    while !(data.len() + 2).is_multiple_of(4) {
+      data.push(0xF0 | (4 - (data.len() + 2) % 4) as u8);
+    }
+    let mut record = (data.len() as u16).to_le_bytes().to_vec();
+    record.extend(data);
+    record
+  }
+
+  /// Field list entry, padded to 4 bytes.
+  fn entry(kind: u16, body: &[u8]) -> Vec<u8> {
+    let mut data = kind.to_le_bytes().to_vec();
+    data.extend_from_slice(body);
+    while !data.len().is_multiple_of(4) {
+      data.push(0xF0 | (4 - data.len() % 4) as u8);
+    }
+    data
+  }
+
+  fn member(ty: u32, offset: u16, name: &str) -> Vec<u8> {
+    let mut body = 3u16.to_le_bytes().to_vec();
+    body.extend(ty.to_le_bytes());
+    body.extend(offset.to_le_bytes());
+    body.extend(name.bytes().chain([0]));
+    entry(LF_MEMBER, &body)
+  }
+
+  fn udt(kind: u16, properties: u16, fields: u32, size: u16, name: &str) -> Vec<u8> {
+    let mut body = 0u16.to_le_bytes().to_vec();
+    body.extend(properties.to_le_bytes());
+    body.extend(fields.to_le_bytes());
+    if kind != LF_UNION {
+      body.extend([0; 8]);
+    }
+    body.extend(size.to_le_bytes());
+    body.extend(name.bytes().chain([0]));
+    leaf(kind, &body)
+  }
+
+  fn msf(streams: &[Vec<u8>]) -> Vec<u8> {
+    const BLOCK_SIZE: usize = 0x200;
+    let blocks = |size: usize| size.div_ceil(BLOCK_SIZE);
+    let mut next = 4;
+    let mut directory = (streams.len() as u32).to_le_bytes().to_vec();
+    for stream in streams {
+      directory.extend((stream.len() as u32).to_le_bytes());
+    }
+    for stream in streams {
+      for _ in 0..blocks(stream.len()) {
+        directory.extend((next as u32).to_le_bytes());
+        next += 1;
+      }
+    }
+    assert!(directory.len() <= BLOCK_SIZE);
+
+    let mut file = MSF_MAGIC.to_vec();
+    for value in [BLOCK_SIZE, 1, next, directory.len(), 0, 2] {
+      file.extend((value as u32).to_le_bytes());
+    }
+    file.resize(2 * BLOCK_SIZE, 0);
+    file.extend(3u32.to_le_bytes());
+    file.resize(3 * BLOCK_SIZE, 0);
+    file.extend(directory);
+    for stream in streams {
+      file.resize(file.len().div_ceil(BLOCK_SIZE) * BLOCK_SIZE, 0);
+      file.extend(stream);
+    }
+    file.resize(next * BLOCK_SIZE, 0);
+    file
+  }
+
+  fn pdb(records: &[Vec<u8>]) -> Vec<u8> {
+    let records = records.concat();
+    let mut tpi = Vec::new();
+    for value in [
+      20040203,
+      56,
+      FIRST_TYPE_INDEX,
+      FIRST_TYPE_INDEX + 16,
+      records.len() as u32
+    ] {
+      tpi.extend(value.to_le_bytes());
+    }
+    tpi.resize(56, 0);
+    tpi.extend(records);
+    msf(&[Vec::new(), Vec::new(), tpi])
+  }
+
+  #[test]
+  fn test_parse_pdb() {
+    let mut vfunctab = 0u16.to_le_bytes().to_vec();
+    vfunctab.extend(0x0603u32.to_le_bytes());
+    let entity_fields = [entry(LF_VFUNCTAB, &vfunctab), member(0x0075, 0x08, "id")].concat();
+
+    let mut base = 3u16.to_le_bytes().to_vec();
+    base.extend(0x1001u32.to_le_bytes());
+    base.extend(0u16.to_le_bytes());
+    let mut method = 3u16.to_le_bytes().to_vec();
+    method.extend(0x0003u32.to_le_bytes());
+    method.extend(b"Update\0");
+    let ped_fields = [
+      entry(LF_BCLASS, &base),
+      member(0x1003, 0x10, "position"),
+      member(0x1004, 0x20, "target"),
+      member(0x1005, 0x28, "flags"),
+      member(0x1006, 0x28, "mode"),
+      member(0x1007, 0x2C, "state"),
+      member(0x0074, 0x30, "i"),
+      member(0x0040, 0x30, "f"),
+      entry(LF_ONEMETHOD, &method)
+    ]
+    .concat();
+
+    let pointer = [0x1001u32.to_le_bytes(), (0x0Cu32 | 8 << 13).to_le_bytes()].concat();
+    let array = [
+      &0x0040u32.to_le_bytes()[..],
+      &0x0023u32.to_le_bytes(),
+      &[12, 0, 0]
+    ]
+    .concat();
+    let bits =
+      |position: u8, width: u8| [&0x0075u32.to_le_bytes()[..], &[width, position]].concat();
+    let state = [
+      &[0, 0, 0, 0][..],
+      &0x0020u32.to_le_bytes(),
+      &[0; 4],
+      b"State\0"
+    ]
+    .concat();
+
+    let pdb = pdb(&[
+      leaf(LF_FIELDLIST, &entity_fields),
+      udt(LF_CLASS, FORWARD_REFERENCE, 0, 0, "CEntity"),
+      udt(LF_CLASS, 0, 0x1000, 0x10, "CEntity"),
+      leaf(LF_ARRAY, &array),
+      leaf(LF_POINTER, &pointer),
+      leaf(LF_BITFIELD, &bits(0, 3)),
+      leaf(LF_BITFIELD, &bits(3, 5)),
+      leaf(LF_ENUM, &state),
+      leaf(LF_FIELDLIST, &ped_fields),
+      udt(LF_STRUCTURE, 0, 0x1008, 0x38, "game::CPed")
+    ]);
+
+    assert_eq!(
+      render(&parse_pdb(&pdb, &[]).unwrap()),
+      "\
+use memory_layout::memory_layout;
+
+#[memory_layout(0x10)]
+pub struct CEntity {
+  #[field_offset(0x00)]
+  pub vtable: *const core::ffi::c_void,
+
+  #[field_offset(0x08)]
+  pub id: u32
+}
+
+#[memory_layout(base = CEntity, size = 0x38)]
+pub struct CPed {
+  #[field_offset(0x10)]
+  pub position: [f32; 3],
+
+  #[field_offset(0x20)]
+  pub target: *mut CEntity,
+
+  #[field_offset(0x28)]
+  pub bitfield_28: u32,
+  #[bit_offset(0x28, 0, 3)]
+  pub flags: u32,
+  #[bit_offset(0x28, 3, 5)]
+  pub mode: u32,
+
+  #[field_offset(0x2C)]
+  pub state: u8,
+
+  #[field_offset(0x30)]
+  pub i: i32
+}
+"
+    );
+
+    let records = parse_pdb(&pdb, &[String::from("CPed")]).unwrap();
+    assert_eq!(
+      records
+        .iter()
+        .map(|record| record.name.as_str())
+        .collect::<Vec<_>>(),
+      ["CPed"]
+    );
+    assert!(matches!(
+      parse_pdb(b"not a pdb", &[]),
+      Err(ImportError::Malformed(_))
+    ));
+  }
+}
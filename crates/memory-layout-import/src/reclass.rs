@@ -0,0 +1,568 @@
+//! Imports the classes of ReClass.NET projects, either `.rcnet` files or the `Data.xml` inside
+//! them.
+//!
+//! Nodes are laid out one after another like ReClass.NET displays them. Hex nodes become padding
+//! unless they were renamed, enums are replaced by integers of their size, and unions are
+//! imported as separate records named after the class and the node. Pointers have the size of the
+//! project's platform.
+
+use std::{cell::RefCell, collections::HashMap, path::Path};
+
+use crate::{ImportError, Record, RecordKind, POINTER};
+
+/// Reads the ReClass.NET project at `path` and imports the classes named in `types`, or every
+/// class if it's empty.
+pub fn import_reclass(path: &Path, types: &[String]) -> Result<Vec<Record>, ImportError> {
+  let data = std::fs::read(path)?;
+  let xml = if crate::zip::is_zip(&data) {
+    crate::zip::read_file(&data, "Data.xml")?
+  } else {
+    data
+  };
+  let xml = String::from_utf8(xml)
+    .map_err(|_| ImportError::Malformed(String::from("The project isn't valid UTF-8.")))?;
+  parse_reclass(&xml, types)
+}
+
+/// Imports the classes named in `types`, or every class if it's empty, from the `Data.xml` of a
+/// ReClass.NET project.
+pub fn parse_reclass(xml: &str, types: &[String]) -> Result<Vec<Record>, ImportError> {
+  let root = parse_xml(xml)?;
+  if root.name != "reclass" {
+    return Err(ImportError::Malformed(String::from(
+      "Not a ReClass.NET project."
+    )));
+  }
+  let pointer = if root.attr("type") == Some("x86") {
+    4
+  } else {
+    8
+  };
+  let classes: Vec<&Element> = root
+    .children("classes")
+    .flat_map(|classes| classes.children("class"))
+    .collect();
+  let enums = root
+    .children("enums")
+    .flat_map(|enums| enums.children("enum"))
+    .filter_map(|element| Some((element.attr("name")?, enum_size(element.attr("size")?)?)))
+    .collect();
+  let project = Project {
+    pointer,
+    classes: classes
+      .iter()
+      .filter_map(|class| Some((class.attr("uuid")?, *class)))
+      .collect(),
+    enums,
+    sizes: RefCell::new(HashMap::new())
+  };
+
+  let mut records = Vec::new();
+  for class in classes {
+    let name = class.attr("name").unwrap_or_default();
+    let ident = crate::type_ident(name);
+    if types.is_empty() || types.iter().any(|ty| crate::type_ident(ty) == ident) {
+      project.class(class, &mut records)?;
+    }
+  }
+  Ok(records)
+}
+
+/// Size of an enum, stored as a number or as the name of the `UnderlyingTypeSize` variant.
+fn enum_size(size: &str) -> Option<usize> {
+  match size {
+    "OneByte" => Some(1),
+    "TwoBytes" => Some(2),
+    "FourBytes" => Some(4),
+    "EightBytes" => Some(8),
+    _ => size.parse().ok()
+  }
+}
+
+/// Whether `name` is a name ReClass.NET generates for new nodes, like `N0000001A`.
+fn is_default_name(name: &str) -> bool {
+  name.len() == 9 && name.starts_with('N') && name[1..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
+fn invalid_node(node: &Element, message: &str) -> ImportError {
+  ImportError::Malformed(format!(
+    "{} node {}: {}",
+    node.attr("type").unwrap_or_default(),
+    node.attr("name").unwrap_or_default(),
+    message
+  ))
+}
+
+struct Project<'a> {
+  /// Size of pointers on the platform of the project.
+  pointer: usize,
+  /// Classes by uuid.
+  classes: HashMap<&'a str, &'a Element>,
+  /// Sizes of enums by name.
+  enums:   HashMap<&'a str, usize>,
+  /// Sizes of classes by uuid, `None` while being computed.
+  sizes:   RefCell<HashMap<&'a str, Option<usize>>>
+}
+
+impl<'a> Project<'a> {
+  fn class(&self, class: &'a Element, records: &mut Vec<Record>) -> Result<(), ImportError> {
+    let name = class.attr("name").unwrap_or_default();
+    let mut members = Vec::new();
+    let mut offset = 0;
+    for node in class.children("node") {
+      let union = format!("{}_{}", name, node.attr("name").unwrap_or_default());
+      let (ty, size) = self.node(node, Some((&union, &mut *records)))?;
+      if let Some(ty) = ty {
+        let name = match node.attr("name") {
+          Some(name) if !name.is_empty() => name.to_string(),
+          _ => format!("field_{:x}", offset)
+        };
+        members.push((name, offset, ty));
+      }
+      offset += size;
+    }
+
+    let mut record = Record::new(name, RecordKind::Struct, offset);
+    for (name, offset, ty) in members {
+      record.push(&name, offset, ty);
+    }
+    records.push(record);
+    Ok(())
+  }
+
+  fn class_name(&self, uuid: &str, node: &Element) -> Result<String, ImportError> {
+    self
+      .classes
+      .get(uuid)
+      .map(|class| crate::type_ident(class.attr("name").unwrap_or_default()))
+      .ok_or_else(|| invalid_node(node, "unknown class."))
+  }
+
+  fn class_size(&self, uuid: &'a str, node: &Element) -> Result<(String, usize), ImportError> {
+    let name = self.class_name(uuid, node)?;
+    let class = self.classes[uuid];
+    if let Some(size) = self.sizes.borrow().get(uuid) {
+      return size
+        .map(|size| (name.clone(), size))
+        .ok_or_else(|| invalid_node(node, "the class contains itself."));
+    }
+
+    self.sizes.borrow_mut().insert(uuid, None);
+    let mut size = 0;
+    for child in class.children("node") {
+      size += self.node(child, None)?.1;
+    }
+    self.sizes.borrow_mut().insert(uuid, Some(size));
+    Ok((name, size))
+  }
+
+  /// Rust type and size of `node`, with no type for padding. Unions are named and added to the
+  /// records given, if any.
+  fn node(
+    &self,
+    node: &'a Element,
+    union: Option<(&str, &mut Vec<Record>)>
+  ) -> Result<(Option<String>, usize), ImportError> {
+    let kind = node.attr("type").unwrap_or_default();
+    let number = |name: &str| -> Result<usize, ImportError> {
+      node
+        .attr(name)
+        .and_then(|value| value.parse().ok())
+        .ok_or_else(|| invalid_node(node, &format!("missing {}.", name)))
+    };
+    let reference = || {
+      node
+        .attr("reference")
+        .ok_or_else(|| invalid_node(node, "missing reference."))
+    };
+    let primitive = |ty: &str, size: usize| Ok((Some(ty.to_string()), size));
+    let floats = |rows: usize, columns: usize| {
+      let row = format!("[f32; {}]", columns);
+      let ty = if rows == 1 {
+        row
+      } else {
+        format!("[{}; {}]", row, rows)
+      };
+      Ok((Some(ty), rows * columns * 4))
+    };
+    let pointer = |ty: String| Ok((Some(ty), self.pointer));
+
+    match kind {
+      "Hex8Node" | "Hex16Node" | "Hex32Node" | "Hex64Node" => {
+        let bits: usize = kind[3..kind.len() - 4].parse().unwrap_or(8);
+        // Hex nodes named by the user instead of ReClass.NET are integers.
+        let named = node
+          .attr("name")
+          .is_some_and(|name| !name.is_empty() && !is_default_name(name));
+        let ty = named.then(|| format!("u{}", bits));
+        Ok((ty, bits / 8))
+      }
+      "BoolNode" => primitive("bool", 1),
+      "Int8Node" => primitive("i8", 1),
+      "Int16Node" => primitive("i16", 2),
+      "Int32Node" => primitive("i32", 4),
+      "Int64Node" => primitive("i64", 8),
+      "UInt8Node" => primitive("u8", 1),
+      "UInt16Node" => primitive("u16", 2),
+      "UInt32Node" => primitive("u32", 4),
+      "UInt64Node" => primitive("u64", 8),
+      "NIntNode" => primitive(&format!("i{}", self.pointer * 8), self.pointer),
+      "NUIntNode" => primitive(&format!("u{}", self.pointer * 8), self.pointer),
+      "FloatNode" => primitive("f32", 4),
+      "DoubleNode" => primitive("f64", 8),
+      "Vector2Node" => floats(1, 2),
+      "Vector3Node" => floats(1, 3),
+      "Vector4Node" => floats(1, 4),
+      "Matrix3x3Node" => floats(3, 3),
+      "Matrix3x4Node" => floats(3, 4),
+      "Matrix4x4Node" => floats(4, 4),
+      "Utf8TextNode" | "Utf16TextNode" | "Utf32TextNode" => {
+        let unit = match kind {
+          "Utf8TextNode" => 1,
+          "Utf16TextNode" => 2,
+          _ => 4
+        };
+        let length = number("length")?;
+        Ok((Some(format!("[u{}; {}]", unit * 8, length)), unit * length))
+      }
+      "Utf8TextPtrNode" => pointer(String::from("*const u8")),
+      "Utf16TextPtrNode" => pointer(String::from("*const u16")),
+      "Utf32TextPtrNode" => pointer(String::from("*const u32")),
+      "FunctionPtrNode" | "VirtualMethodTableNode" => pointer(String::from(POINTER)),
+      "BitFieldNode" => {
+        let bits = number("bits")?;
+        primitive(&format!("u{}", bits), bits / 8)
+      }
+      "EnumNode" => {
+        let size = self
+          .enums
+          .get(reference()?)
+          .copied()
+          .ok_or_else(|| invalid_node(node, "unknown enum."))?;
+        primitive(&format!("u{}", size * 8), size)
+      }
+      "ClassInstanceNode" => {
+        let (name, size) = self.class_size(reference()?, node)?;
+        Ok((Some(name), size))
+      }
+      "ClassPtrNode" => pointer(format!("*mut {}", self.class_name(reference()?, node)?)),
+      "PointerNode" => {
+        let target = match node.children("node").next() {
+          Some(inner) if inner.attr("type") == Some("ClassInstanceNode") => {
+            let inner_reference = inner
+              .attr("reference")
+              .ok_or_else(|| invalid_node(inner, "missing reference."))?;
+            Some(self.class_name(inner_reference, inner)?)
+          }
+          Some(inner) => self.node(inner, None)?.0,
+          None => None
+        };
+        pointer(target.map_or(String::from(POINTER), |target| format!("*mut {}", target)))
+      }
+      "ArrayNode" | "ClassInstanceArrayNode" | "ClassPtrArrayNode" => {
+        let count = number("count")?;
+        let (element, size) = match (kind, node.children("node").next()) {
+          ("ClassInstanceArrayNode", _) => self.class_size(reference()?, node)?,
+          ("ClassPtrArrayNode", _) => {
+            let target = self.class_name(reference()?, node)?;
+            (format!("*mut {}", target), self.pointer)
+          }
+          (_, Some(inner)) => {
+            let (element, size) = self.node(inner, None)?;
+            (element.unwrap_or_else(|| format!("[u8; {}]", size)), size)
+          }
+          (_, None) => return Err(invalid_node(node, "missing element node."))
+        };
+        Ok((Some(format!("[{}; {}]", element, count)), size * count))
+      }
+      "UnionNode" => {
+        let mut members = Vec::new();
+        let mut size = 0;
+        for (index, inner) in node.children("node").enumerate() {
+          let (ty, inner_size) = self.node(inner, None)?;
+          let name = match inner.attr("name") {
+            Some(name) if !name.is_empty() => name.to_string(),
+            _ => format!("variant_{}", index)
+          };
+          members.push((name, ty.unwrap_or_else(|| format!("[u8; {}]", inner_size))));
+          size = size.max(inner_size);
+        }
+        let (name, records) = match union {
+          Some(union) => union,
+          None => return Ok((Some(format!("[u8; {}]", size)), size))
+        };
+        let mut record = Record::new(name, RecordKind::Union, size);
+        for (name, ty) in members {
+          record.push(&name, 0, ty);
+        }
+        let ty = record.name.clone();
+        records.push(record);
+        Ok((Some(ty), size))
+      }
+      _ => Err(invalid_node(node, "unsupported node type."))
+    }
+  }
+}
+
+/// An element of an XML document.
+#[derive(Debug)]
+struct Element {
+  name:     String,
+  attrs:    Vec<(String, String)>,
+  elements: Vec<Element>
+}
+
+impl Element {
+  fn attr(&self, name: &str) -> Option<&str> {
+    self
+      .attrs
+      .iter()
+      .find(|(attr, _)| attr == name)
+      .map(|(_, value)| value.as_str())
+  }
+
+  fn children<'e>(&'e self, name: &'e str) -> impl Iterator<Item = &'e Element> + 'e {
+    self
+      .elements
+      .iter()
+      .filter(move |element| element.name == name)
+  }
+}
+
+/// Parses the elements and attributes of an XML document, skipping text, comments and
+/// declarations.
+fn parse_xml(xml: &str) -> Result<Element, ImportError> {
+  let line = |rest: &str| xml[..xml.len() - rest.len()].lines().count().max(1);
+  let error = |rest: &str, message: &str| {
+    ImportError::Parse {
+      line:    line(rest),
+      message: message.to_string()
+    }
+  };
+
+  let mut stack: Vec<Element> = vec![Element {
+    name:     String::new(),
+    attrs:    Vec::new(),
+    elements: Vec::new()
+  }];
+  let mut rest = xml;
+  while let Some(start) = rest.find('<') {
+    rest = &rest[start..];
+    if let Some(comment) = rest.strip_prefix("<!--") {
+      let end = comment
+        .find("-->")
+        .ok_or_else(|| error(rest, "Unterminated comment."))?;
+      rest = &comment[end + 3..];
+      continue;
+    }
+    if rest.starts_with("<?") || rest.starts_with("<!") {
+      let end = rest
+        .find('>')
+        .ok_or_else(|| error(rest, "Unterminated declaration."))?;
+      rest = &rest[end + 1..];
+      continue;
+    }
+    if let Some(closing) = rest.strip_prefix("</") {
+      let end = closing
+        .find('>')
+        .ok_or_else(|| error(rest, "Unterminated tag."))?;
+      let element = stack.pop().filter(|_| !stack.is_empty());
+      match element {
+        Some(element) if element.name == closing[..end].trim() => {
+          stack
+            .last_mut()
+            .expect("the document is on the stack")
+            .elements
+            .push(element);
+        }
+        _ => return Err(error(rest, "Mismatched closing tag."))
+      }
+      rest = &closing[end + 1..];
+      continue;
+    }
+
+    let tag = &rest[1..];
+    let name_end = tag
+      .find(|c: char| c.is_whitespace() || c == '/' || c == '>')
+      .ok_or_else(|| error(rest, "Unterminated tag."))?;
+    let mut element = Element {
+      name:     tag[..name_end].to_string(),
+      attrs:    Vec::new(),
+      elements: Vec::new()
+    };
+    let mut tag = tag[name_end..].trim_start();
+    loop {
+      if let Some(after) = tag.strip_prefix("/>") {
+        stack
+          .last_mut()
+          .expect("the document is on the stack")
+          .elements
+          .push(element);
+        rest = after;
+        break;
+      }
+      if let Some(after) = tag.strip_prefix('>') {
+        stack.push(element);
+        rest = after;
+        break;
+      }
+      let (name, value) = tag
+        .split_once('=')
+        .ok_or_else(|| error(tag, "Invalid attribute."))?;
+      let value = value.trim_start();
+      let quote = value
+        .chars()
+        .next()
+        .filter(|quote| *quote == '"' || *quote == '\'')
+        .ok_or_else(|| error(value, "Unquoted attribute."))?;
+      let end = value[1..]
+        .find(quote)
+        .ok_or_else(|| error(value, "Unterminated attribute."))?;
+      element
+        .attrs
+        .push((name.trim().to_string(), unescape(&value[1..end + 1])));
+      tag = value[end + 2..].trim_start();
+    }
+  }
+
+  let document = stack.pop().filter(|_| stack.is_empty());
+  document
+    .and_then(|mut document| document.elements.pop())
+    .ok_or_else(|| error(rest, "Unclosed or missing root element."))
+}
+
+fn unescape(text: &str) -> String {
+  let mut out = String::with_capacity(text.len());
+  let mut rest = text;
+  while let Some(start) = rest.find('&') {
+    out.push_str(&rest[..start]);
+    rest = &rest[start..];
+    let end = match rest.find(';') {
+      Some(end) => end,
+      None => break
+    };
+    let entity = &rest[1..end];
+    let c = match entity {
+      "amp" => Some('&'),
+      "lt" => Some('<'),
+      "gt" => Some('>'),
+      "quot" => Some('"'),
+      "apos" => Some('\''),
+      _ => {
+        entity
+          .strip_prefix("#x")
+          .map(|hex| u32::from_str_radix(hex, 16))
+          .or_else(|| entity.strip_prefix('#').map(str::parse))
+          .and_then(Result::ok)
+          .and_then(char::from_u32)
+      }
+    };
+    match c {
+      Some(c) => {
+        out.push(c);
+        rest = &rest[end + 1..];
+      }
+      None => {
+        out.push('&');
+        rest = &rest[1..];
+      }
+    }
+  }
+  out.push_str(rest);
+  out
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::render;
+
+  #[test]
+  fn test_parse_reclass() {
+    let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+<reclass version="65537" type="x86">
+  <!--ReClass.NET by KN4CK3R-->
+  <custom_data />
+  <enums>
+    <enum name="Team" use_flags="false" size="TwoBytes">
+      <item name="Red" value="0" />
+    </enum>
+  </enums>
+  <classes>
+    <class uuid="AAAA" name="Vec&amp;Angle" comment="" address="0">
+      <node type="Vector3Node" name="pos" comment="" hidden="false" />
+      <node type="Hex32Node" name="N0000000C" comment="" hidden="false" />
+    </class>
+    <class uuid="BBBB" name="CPed" comment="" address="0">
+      <node type="VirtualMethodTableNode" name="vtable" comment="" hidden="false" />
+      <node type="ClassInstanceNode" name="transform" comment="" hidden="false" reference="AAAA" />
+      <node type="EnumNode" name="team" comment="" hidden="false" reference="Team" />
+      <node type="Hex16Node" name="flags" comment="" hidden="false" />
+      <node type="PointerNode" name="target" comment="" hidden="false">
+        <node type="ClassInstanceNode" name="" comment="" hidden="false" reference="BBBB" />
+      </node>
+      <node type="Utf8TextNode" name="name" comment="" hidden="false" length="6" />
+      <node type="UnionNode" name="value" comment="" hidden="false">
+        <node type="Int32Node" name="i" comment="" hidden="false" />
+        <node type="DoubleNode" name="d" comment="" hidden="false" />
+      </node>
+      <node type="ArrayNode" name="ammo" comment="" hidden="false" count="2">
+        <node type="NUIntNode" name="" comment="" hidden="false" />
+      </node>
+    </class>
+  </classes>
+</reclass>
+"#;
+    assert_eq!(
+      render(&parse_reclass(xml, &[String::from("CPed")]).unwrap()),
+      "\
+use memory_layout::memory_layout;
+
+#[memory_layout(0x8)]
+pub union CPed_value {
+  #[field_offset(0x00)]
+  pub i: i32,
+
+  #[field_offset(0x00)]
+  pub d: f64
+}
+
+#[memory_layout(0x32)]
+pub struct CPed {
+  #[field_offset(0x00)]
+  pub vtable: *const core::ffi::c_void,
+
+  #[field_offset(0x04)]
+  pub transform: Vec_Angle,
+
+  #[field_offset(0x14)]
+  pub team: u16,
+
+  #[field_offset(0x16)]
+  pub flags: u16,
+
+  #[field_offset(0x18)]
+  pub target: *mut CPed,
+
+  #[field_offset(0x1C)]
+  pub name: [u8; 6],
+
+  #[field_offset(0x22)]
+  pub value: CPed_value,
+
+  #[field_offset(0x2A)]
+  pub ammo: [u32; 2]
+}
+"
+    );
+
+    let records = parse_reclass(xml, &[]).unwrap();
+    assert_eq!(records[0].name, "Vec_Angle");
+    assert_eq!(records[0].size, 0x10);
+    assert_eq!(records[0].members.len(), 1);
+
+    let unsupported = xml.replace("Vector3Node", "FunctionNode");
+    assert!(parse_reclass(&unsupported, &[]).is_err());
+    assert!(parse_reclass("<classes></class>", &[]).is_err());
+  }
+}
@@ -0,0 +1,311 @@
+//! Reads files from zip archives, stored or compressed with deflate.
+
+use crate::ImportError;
+
+fn malformed(message: &str) -> ImportError {
+  ImportError::Malformed(format!("{} in zip archive.", message))
+}
+
+fn u16_at(data: &[u8], offset: usize) -> Result<usize, ImportError> {
+  data
+    .get(offset..offset + 2)
+    .map(|bytes| usize::from(u16::from_le_bytes([bytes[0], bytes[1]])))
+    .ok_or_else(|| malformed("Unexpected end of data"))
+}
+
+fn u32_at(data: &[u8], offset: usize) -> Result<usize, ImportError> {
+  data
+    .get(offset..offset + 4)
+    .map(|bytes| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize)
+    .ok_or_else(|| malformed("Unexpected end of data"))
+}
+
+/// Whether `data` starts like a zip archive.
+pub(crate) fn is_zip(data: &[u8]) -> bool {
+  data.starts_with(b"PK\x03\x04")
+}
+
+/// The contents of the file `name` in the zip archive `archive`.
+pub(crate) fn read_file(archive: &[u8], name: &str) -> Result<Vec<u8>, ImportError> {
+  // The end of central directory record is followed by a comment of up to 64 KiB.
+  let end = (0..archive.len().saturating_sub(21))
+    .rev()
+    .find(|offset| archive[*offset..].starts_with(b"PK\x05\x06"))
+    .ok_or_else(|| malformed("Missing central directory"))?;
+  let count = u16_at(archive, end + 10)?;
+  let mut entry = u32_at(archive, end + 16)?;
+
+  for _ in 0..count {
+    if u32_at(archive, entry)? != 0x0201_4B50 {
+      return Err(malformed("Invalid central directory"));
+    }
+    let method = u16_at(archive, entry + 10)?;
+    let compressed = u32_at(archive, entry + 20)?;
+    let size = u32_at(archive, entry + 24)?;
+    let name_length = u16_at(archive, entry + 28)?;
+    let skip = name_length + u16_at(archive, entry + 30)? + u16_at(archive, entry + 32)?;
+    let local = u32_at(archive, entry + 42)?;
+    let entry_name = archive
+      .get(entry + 46..entry + 46 + name_length)
+      .ok_or_else(|| malformed("Unexpected end of data"))?;
+    entry += 46 + skip;
+    if entry_name != name.as_bytes() {
+      continue;
+    }
+
+    let start = local + 30 + u16_at(archive, local + 26)? + u16_at(archive, local + 28)?;
+    let data = archive
+      .get(start..start + compressed)
+      .ok_or_else(|| malformed("Unexpected end of data"))?;
+    let contents = match method {
+      0 => data.to_vec(),
+      8 => inflate(data)?,
+      _ => return Err(malformed("Unsupported compression method"))
+    };
+    if contents.len() != size {
+      return Err(malformed("Wrong file size"));
+    }
+    return Ok(contents);
+  }
+  Err(ImportError::Malformed(format!(
+    "The zip archive has no file named {}.",
+    name
+  )))
+}
+
+const LENGTH_BASE: [u16; 29] = [
+  3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+  163, 195, 227, 258
+];
+const LENGTH_EXTRA: [u8; 29] = [
+  0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0
+];
+const DISTANCE_BASE: [u16; 30] = [
+  1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537, 2049,
+  3073, 4097, 6145, 8193, 12289, 16385, 24577
+];
+const DISTANCE_EXTRA: [u8; 30] = [
+  0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13, 13
+];
+/// Order the lengths of the code length code are stored in.
+const CODE_LENGTH_ORDER: [usize; 19] = [
+  16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15
+];
+
+/// A canonical Huffman code, the number of codes of each length and the symbols ordered by code.
+struct Huffman {
+  counts:  [u16; 16],
+  symbols: Vec<u16>
+}
+
+impl Huffman {
+  fn new(lengths: &[u8]) -> Self {
+    let mut counts = [0u16; 16];
+    for length in lengths {
+      counts[usize::from(*length)] += 1;
+    }
+    counts[0] = 0;
+    let mut offsets = [0usize; 16];
+    for length in 1..15 {
+      offsets[length + 1] = offsets[length] + usize::from(counts[length]);
+    }
+    let mut symbols = vec![0; lengths.len()];
+    for (symbol, length) in lengths.iter().enumerate() {
+      if *length != 0 {
+        symbols[offsets[usize::from(*length)]] = symbol as u16;
+        offsets[usize::from(*length)] += 1;
+      }
+    }
+    Huffman { counts, symbols }
+  }
+}
+
+/// Decompresses raw deflate data.
+struct Inflater<'a> {
+  input:  &'a [u8],
+  /// Position in `input`, in bits.
+  bit:    usize,
+  output: Vec<u8>
+}
+
+fn inflate(input: &[u8]) -> Result<Vec<u8>, ImportError> {
+  let mut inflater = Inflater {
+    input,
+    bit: 0,
+    output: Vec::new()
+  };
+  loop {
+    let last = inflater.bits(1)? == 1;
+    match inflater.bits(2)? {
+      0 => inflater.stored()?,
+      1 => inflater.fixed()?,
+      2 => inflater.dynamic()?,
+      _ => return Err(malformed("Invalid deflate block"))
+    }
+    if last {
+      return Ok(inflater.output);
+    }
+  }
+}
+
+impl Inflater<'_> {
+  fn bits(&mut self, count: usize) -> Result<usize, ImportError> {
+    let mut value = 0;
+    for index in 0..count {
+      let byte = self
+        .input
+        .get(self.bit / 8)
+        .ok_or_else(|| malformed("Unexpected end of deflate data"))?;
+      value |= usize::from(byte >> (self.bit % 8) & 1) << index;
+      self.bit += 1;
+    }
+    Ok(value)
+  }
+
+  fn stored(&mut self) -> Result<(), ImportError> {
+    let start = self.bit.div_ceil(8);
+    let length = u16_at(self.input, start)?;
+    let data = self
+      .input
+      .get(start + 4..start + 4 + length)
+      .ok_or_else(|| malformed("Unexpected end of deflate data"))?;
+    self.output.extend_from_slice(data);
+    self.bit = (start + 4 + length) * 8;
+    Ok(())
+  }
+
+  fn decode(&mut self, huffman: &Huffman) -> Result<usize, ImportError> {
+    let mut code = 0;
+    let mut first = 0;
+    let mut index = 0;
+    for length in 1..16 {
+      code |= self.bits(1)?;
+      let count = usize::from(huffman.counts[length]);
+      if code < first + count {
+        return Ok(usize::from(huffman.symbols[index + code - first]));
+      }
+      index += count;
+      first = (first + count) << 1;
+      code <<= 1;
+    }
+    Err(malformed("Invalid Huffman code"))
+  }
+
+  fn fixed(&mut self) -> Result<(), ImportError> {
+    let mut lengths = [0u8; 288];
+    lengths[..144].fill(8);
+    lengths[144..256].fill(9);
+    lengths[256..280].fill(7);
+    lengths[280..].fill(8);
+    self.codes(&Huffman::new(&lengths), &Huffman::new(&[5; 30]))
+  }
+
+  fn dynamic(&mut self) -> Result<(), ImportError> {
+    let literals = self.bits(5)? + 257;
+    let distances = self.bits(5)? + 1;
+    let code_lengths = self.bits(4)? + 4;
+    let mut lengths = [0u8; 19];
+    for index in CODE_LENGTH_ORDER.iter().take(code_lengths) {
+      lengths[*index] = self.bits(3)? as u8;
+    }
+    let code_length_code = Huffman::new(&lengths);
+
+    let mut lengths = vec![0u8; literals + distances];
+    let mut index = 0;
+    while index < lengths.len() {
+      let symbol = self.decode(&code_length_code)?;
+      let (length, repeat) = match symbol {
+        0..=15 => (symbol as u8, 1),
+        16 => {
+          let previous = *index
+            .checked_sub(1)
+            .and_then(|previous| lengths.get(previous))
+            .ok_or_else(|| malformed("Invalid code lengths"))?;
+          (previous, 3 + self.bits(2)?)
+        }
+        17 => (0, 3 + self.bits(3)?),
+        _ => (0, 11 + self.bits(7)?)
+      };
+      let end = index + repeat;
+      lengths
+        .get_mut(index..end)
+        .ok_or_else(|| malformed("Invalid code lengths"))?
+        .fill(length);
+      index = end;
+    }
+    self.codes(
+      &Huffman::new(&lengths[..literals]),
+      &Huffman::new(&lengths[literals..])
+    )
+  }
+
+  fn codes(&mut self, literals: &Huffman, distances: &Huffman) -> Result<(), ImportError> {
+    loop {
+      let symbol = self.decode(literals)?;
+      match symbol {
+        0..=255 => self.output.push(symbol as u8),
+        256 => return Ok(()),
+        _ => {
+          let symbol = symbol - 257;
+          let length = *LENGTH_BASE
+            .get(symbol)
+            .ok_or_else(|| malformed("Invalid length"))? as usize
+            + self.bits(usize::from(LENGTH_EXTRA[symbol]))?;
+          let symbol = self.decode(distances)?;
+          let distance = *DISTANCE_BASE
+            .get(symbol)
+            .ok_or_else(|| malformed("Invalid distance"))? as usize
+            + self.bits(usize::from(DISTANCE_EXTRA[symbol]))?;
+          let start = self
+            .output
+            .len()
+            .checked_sub(distance)
+            .ok_or_else(|| malformed("Invalid distance"))?;
+          for index in start..start + length {
+            let byte = self.output[index];
+            self.output.push(byte);
+          }
+        }
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_inflate() {
+    // `zlib.compress(text, wbits=-15)` of a text with repetitions, using a dynamic Huffman code.
+    let text: String = (0..40)
+      .map(|index| {
+        format!(
+          "<node type=\"Int{}Node\" name=\"field_{:x}\" />\n",
+          index % 4 * 8 + 8,
+          index * 7
+        )
+      })
+      .collect();
+    let compressed = [
+      0x8D, 0xD4, 0xB1, 0x0A, 0xC2, 0x30, 0x14, 0x46, 0xE1, 0xDD, 0xA7, 0x08, 0x7D, 0x01, 0x7B,
+      0x93, 0x34, 0x49, 0xA1, 0xBA, 0xBB, 0xF8, 0x0A, 0x92, 0x36, 0x09, 0x08, 0x5A, 0x1D, 0xBA,
+      0xF8, 0xF6, 0x16, 0x9C, 0x24, 0x19, 0xCE, 0x78, 0xE1, 0x1F, 0xCE, 0xF0, 0x71, 0xA7, 0xF5,
+      0x95, 0xB2, 0xDA, 0x3E, 0xEF, 0x7C, 0xEA, 0x2E, 0xEB, 0x16, 0xAE, 0xFB, 0xD9, 0xA9, 0x35,
+      0x3E, 0xF7, 0xBB, 0xDC, 0xF3, 0x23, 0xDD, 0xFA, 0x4E, 0x1D, 0xCF, 0x87, 0xE9, 0x7F, 0x27,
+      0xAE, 0x1E, 0xFA, 0xD6, 0x50, 0xDB, 0x7A, 0x98, 0x5B, 0x43, 0xA3, 0xEB, 0xA1, 0x0C, 0xAD,
+      0x65, 0xA3, 0x51, 0x16, 0x1A, 0xA9, 0x0D, 0xAD, 0xD4, 0x91, 0x66, 0x1A, 0x81, 0x99, 0x26,
+      0xD0, 0x4C, 0x53, 0x68, 0xA6, 0x75, 0x34, 0xD3, 0x26, 0x98, 0x39, 0x58, 0x9A, 0x39, 0xCC,
+      0x34, 0xD3, 0x69, 0x9A, 0xE9, 0x46, 0x98, 0xE9, 0xB9, 0x4C, 0x4C, 0xD3, 0x63, 0x9B, 0x81,
+      0xDA, 0x0C, 0xD8, 0xE6, 0x88, 0x6D, 0x8E, 0xD8, 0x66, 0xA4, 0x36, 0x23, 0xB6, 0x19, 0xB1,
+      0xCD, 0x19, 0xDB, 0x9C, 0xA9, 0xCD, 0x05, 0xDB, 0x5C, 0xB0, 0xCD, 0x84, 0x6D, 0x26, 0x6A,
+      0x33, 0x63, 0x9B, 0x99, 0xBF, 0x4D, 0x6C, 0xB3, 0x50, 0x9B, 0x05, 0xDB, 0x94, 0x1E, 0xE3,
+      0x94, 0x1E, 0xEB, 0x14, 0xF9, 0xF1, 0xFC, 0x02
+    ];
+    assert_eq!(inflate(&compressed).unwrap(), text.as_bytes());
+
+    // A stored block followed by a block with the fixed Huffman code.
+    let compressed = [0x00, 0x02, 0x00, 0xFD, 0xFF, b'a', b'b', 0x4B, 0x02, 0x00];
+    assert_eq!(inflate(&compressed).unwrap(), b"abb");
+  }
+}
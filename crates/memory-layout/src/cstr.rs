@@ -0,0 +1,163 @@
+use core::{ascii, fmt, fmt::Write, hash, str};
+
+use crate::{AnyBitPattern, NoUninit, Zeroable};
+
+/// Error returned when a string doesn't fit into a [`CStrArray`] together with its terminator.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CapacityError {
+  /// Length of the string, in bytes.
+  pub len:      usize,
+  /// Longest string the array can hold, in bytes.
+  pub capacity: usize
+}
+
+impl fmt::Display for CapacityError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "string of {} bytes exceeds the capacity of {} bytes",
+      self.len, self.capacity
+    )
+  }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CapacityError {}
+
+/// NUL-terminated string stored in a fixed buffer of `N` bytes, like a `char name[N]` field.
+///
+/// Everything after the first NUL is ignored when reading or comparing the string. A buffer without
+/// a NUL is read in full.
+/// ```rust
+/// use memory_layout::{memory_layout, CStrArray};
+///
+/// #[memory_layout(0x20)]
+/// pub struct Player {
+///   #[field_offset(0x10)]
+///   name: CStrArray<16>
+/// }
+///
+/// let mut player = Player::zeroed();
+/// player.name_mut().set("Niko").unwrap();
+/// assert_eq!(player.name().as_str(), Ok("Niko"));
+/// assert!(player.name_mut().set("Niko Bellic's cousin").is_err());
+/// ```
+#[derive(Clone, Copy)]
+#[repr(transparent)]
+pub struct CStrArray<const N: usize>([u8; N]);
+
+impl<const N: usize> CStrArray<N> {
+  /// Creates an empty string.
+  pub const fn new() -> Self {
+    Self([0; N])
+  }
+
+  /// Longest string the array can hold, one byte is needed for the terminator.
+  pub const fn capacity(&self) -> usize {
+    N.saturating_sub(1)
+  }
+
+  /// Bytes of the string up to the first NUL.
+  pub fn as_bytes(&self) -> &[u8] {
+    let len = self.0.iter().position(|&byte| byte == 0).unwrap_or(N);
+    &self.0[..len]
+  }
+
+  /// Length of the string in bytes.
+  pub fn len(&self) -> usize {
+    self.as_bytes().len()
+  }
+
+  /// Whether the string is empty.
+  pub fn is_empty(&self) -> bool {
+    self.as_bytes().is_empty()
+  }
+
+  /// The string, if it's valid UTF-8.
+  pub fn as_str(&self) -> Result<&str, str::Utf8Error> {
+    str::from_utf8(self.as_bytes())
+  }
+
+  /// The whole buffer, including the bytes after the terminator.
+  pub const fn raw(&self) -> &[u8; N] {
+    &self.0
+  }
+
+  /// Replaces the string, zeroing the rest of the buffer.
+  pub fn set(&mut self, value: &str) -> Result<(), CapacityError> {
+    self.set_bytes(value.as_bytes())
+  }
+
+  /// Replaces the string with raw bytes, zeroing the rest of the buffer. A NUL in `value` ends the
+  /// string early.
+  pub fn set_bytes(&mut self, value: &[u8]) -> Result<(), CapacityError> {
+    if value.len() > self.capacity() {
+      return Err(CapacityError {
+        len:      value.len(),
+        capacity: self.capacity()
+      });
+    }
+
+    self.0[..value.len()].copy_from_slice(value);
+    self.0[value.len()..].fill(0);
+    Ok(())
+  }
+}
+
+impl<const N: usize> Default for CStrArray<N> {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<const N: usize> TryFrom<&str> for CStrArray<N> {
+  type Error = CapacityError;
+
+  fn try_from(value: &str) -> Result<Self, Self::Error> {
+    let mut result = Self::new();
+    result.set(value)?;
+    Ok(result)
+  }
+}
+
+impl<const N: usize> PartialEq for CStrArray<N> {
+  fn eq(&self, other: &Self) -> bool {
+    self.as_bytes() == other.as_bytes()
+  }
+}
+
+impl<const N: usize> Eq for CStrArray<N> {}
+
+impl<const N: usize> PartialEq<str> for CStrArray<N> {
+  fn eq(&self, other: &str) -> bool {
+    self.as_bytes() == other.as_bytes()
+  }
+}
+
+impl<const N: usize> PartialEq<&str> for CStrArray<N> {
+  fn eq(&self, other: &&str) -> bool {
+    self.as_bytes() == other.as_bytes()
+  }
+}
+
+impl<const N: usize> hash::Hash for CStrArray<N> {
+  fn hash<H: hash::Hasher>(&self, state: &mut H) {
+    self.as_bytes().hash(state);
+  }
+}
+
+impl<const N: usize> fmt::Debug for CStrArray<N> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_char('"')?;
+    for &byte in self.as_bytes() {
+      for escaped in ascii::escape_default(byte) {
+        f.write_char(escaped as char)?;
+      }
+    }
+    f.write_char('"')
+  }
+}
+
+unsafe impl<const N: usize> Zeroable for CStrArray<N> {}
+unsafe impl<const N: usize> AnyBitPattern for CStrArray<N> {}
+unsafe impl<const N: usize> NoUninit for CStrArray<N> {}
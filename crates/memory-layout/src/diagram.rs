@@ -0,0 +1,130 @@
+use alloc::{string::String, vec::Vec};
+use core::fmt::Write;
+
+use crate::Layout;
+
+/// Bytes per row of a diagram.
+const ROW: usize = 16;
+
+/// A range of a diagram row, covered by a field or padding.
+struct Segment<'a> {
+  start: usize,
+  end:   usize,
+  label: &'a str
+}
+
+impl Layout {
+  /// Renders a byte map of the struct, 16 bytes per row with the offset of each row down the
+  /// side. Fields span the bytes they cover with their name, padding is marked as `pad` and
+  /// ranges continued from the row before start with `..`.
+  ///
+  /// Rows fully covered by the same field or padding as the row before are collapsed into a `*`
+  /// line. Fields overlapping another one are listed below the map. Generated as
+  /// `layout_diagram()` for every annotated type.
+  /// ```
+  /// use memory_layout::memory_layout;
+  ///
+  /// #[memory_layout(0x10)]
+  /// pub struct CEntity {
+  ///   #[field_offset(0x04)]
+  ///   pub id:     u32,
+  ///   #[field_offset(0x08)]
+  ///   pub health: f32
+  /// }
+  ///
+  /// assert_eq!(
+  ///   CEntity::layout_diagram(),
+  ///   "\
+  /// CEntity (0x10 bytes)
+  ///         00 01 02 03 04 05 06 07 08 09 0A 0B 0C 0D 0E 0F
+  /// 0x0000 |pad        |id         |health     |pad        |
+  /// "
+  /// );
+  /// ```
+  pub fn diagram(&self) -> String {
+    let mut segments = Vec::new();
+    let mut overlaps = Vec::new();
+    let mut cursor = 0;
+    for field in self.fields {
+      if field.offset < cursor {
+        overlaps.push(field);
+        continue;
+      }
+      if field.offset > cursor {
+        segments.push(Segment {
+          start: cursor,
+          end:   field.offset,
+          label: "pad"
+        });
+      }
+      if field.size > 0 {
+        segments.push(Segment {
+          start: field.offset,
+          end:   field.end(),
+          label: field.name
+        });
+      }
+      cursor = field.end();
+    }
+    if self.size > cursor {
+      segments.push(Segment {
+        start: cursor,
+        end:   self.size,
+        label: "pad"
+      });
+    }
+
+    let mut out = String::new();
+    let _ = writeln!(out, "{} (0x{:X} bytes)", self.name, self.size);
+    out.push_str("       ");
+    for column in 0..ROW.min(self.size) {
+      let _ = write!(out, " {:02X}", column);
+    }
+    out.push('\n');
+
+    let mut collapsed = false;
+    for row in (0..self.size).step_by(ROW) {
+      let end = (row + ROW).min(self.size);
+      let repeated = segments
+        .iter()
+        .any(|segment| segment.start + ROW <= row && segment.end >= end);
+      if repeated {
+        if !collapsed {
+          out.push_str("*\n");
+          collapsed = true;
+        }
+        continue;
+      }
+      collapsed = false;
+
+      let _ = write!(out, "0x{:04X} |", row);
+      for segment in segments
+        .iter()
+        .filter(|segment| segment.start < end && segment.end > row)
+      {
+        let start = segment.start.max(row);
+        let width = (segment.end.min(end) - start) * 3 - 1;
+        // Fields continued from the row before are marked with a leading `..`.
+        let continued = if start == segment.start { "" } else { ".." };
+        let label: String = continued
+          .chars()
+          .chain(segment.label.chars())
+          .take(width)
+          .collect();
+        let _ = write!(out, "{:width$}|", label, width = width);
+      }
+      out.push('\n');
+    }
+
+    for field in overlaps {
+      let _ = writeln!(
+        out,
+        "0x{:04X}..0x{:04X} {} overlaps another field",
+        field.offset,
+        field.end(),
+        field.name
+      );
+    }
+    out
+  }
+}
@@ -0,0 +1,189 @@
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::{FieldDesc, Layout, MemoryLayout};
+
+/// Differences between the bytes of a struct and a buffer, returned by the generated
+/// `diff_bytes` function.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BytesDiff {
+  /// Offset, byte of the struct and byte of the buffer, for every byte that differs.
+  pub differences:     Vec<(usize, u8, u8)>,
+  /// Length of the struct and the buffer when they differ, bytes past the shorter of the two
+  /// aren't compared.
+  pub length_mismatch: Option<(usize, usize)>
+}
+
+impl BytesDiff {
+  /// Compares `ours` against `theirs` up to the length of the shorter one.
+  pub fn between(ours: &[u8], theirs: &[u8]) -> Self {
+    let differences = ours
+      .iter()
+      .zip(theirs)
+      .enumerate()
+      .filter(|(_, (a, b))| a != b)
+      .map(|(offset, (a, b))| (offset, *a, *b))
+      .collect();
+    let length_mismatch = (ours.len() != theirs.len()).then(|| (ours.len(), theirs.len()));
+
+    BytesDiff {
+      differences,
+      length_mismatch
+    }
+  }
+
+  /// Whether the bytes are identical and have the same length.
+  pub fn is_empty(&self) -> bool {
+    self.differences.is_empty() && self.length_mismatch.is_none()
+  }
+}
+
+/// A difference between a field of two layouts, matched by name.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FieldChange {
+  /// The field is at a different offset.
+  Moved {
+    /// Name of the field.
+    name: &'static str,
+    /// Offset in the old layout.
+    from: usize,
+    /// Offset in the new layout.
+    to:   usize
+  },
+  /// The field has a different size.
+  Resized {
+    /// Name of the field.
+    name: &'static str,
+    /// Size in the old layout.
+    from: usize,
+    /// Size in the new layout.
+    to:   usize
+  },
+  /// The field only exists in the new layout.
+  Added(FieldDesc),
+  /// The field only exists in the old layout.
+  Removed(FieldDesc)
+}
+
+/// Differences between two layouts, returned by [`diff`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LayoutDiff {
+  /// Size of the old and new struct when they differ.
+  pub size:    Option<(usize, usize)>,
+  /// Changed fields, removed and changed ones in the order of the old layout followed by added
+  /// ones in the order of the new layout. A field that moved and changed its size is listed twice.
+  pub changes: Vec<FieldChange>
+}
+
+impl LayoutDiff {
+  /// Compares the fields of `old` and `new` by name.
+  pub fn between(old: &Layout, new: &Layout) -> Self {
+    let mut changes = Vec::new();
+    for field in old.fields {
+      let other = match new.field(field.name) {
+        Some(other) => other,
+        None => {
+          changes.push(FieldChange::Removed(*field));
+          continue;
+        }
+      };
+      if field.offset != other.offset {
+        changes.push(FieldChange::Moved {
+          name: field.name,
+          from: field.offset,
+          to:   other.offset
+        });
+      }
+      if field.size != other.size {
+        changes.push(FieldChange::Resized {
+          name: field.name,
+          from: field.size,
+          to:   other.size
+        });
+      }
+    }
+    changes.extend(
+      new
+        .fields
+        .iter()
+        .filter(|field| old.field(field.name).is_none())
+        .map(|field| FieldChange::Added(*field))
+    );
+
+    LayoutDiff {
+      size: (old.size != new.size).then(|| (old.size, new.size)),
+      changes
+    }
+  }
+
+  /// Whether the layouts have the same size and fields.
+  pub fn is_empty(&self) -> bool {
+    self.size.is_none() && self.changes.is_empty()
+  }
+}
+
+impl fmt::Display for LayoutDiff {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    if let Some((from, to)) = self.size {
+      writeln!(f, "size: 0x{:X} -> 0x{:X}", from, to)?;
+    }
+    for change in &self.changes {
+      match change {
+        FieldChange::Moved { name, from, to } => {
+          writeln!(f, "moved {}: 0x{:X} -> 0x{:X}", name, from, to)?
+        }
+        FieldChange::Resized { name, from, to } => {
+          writeln!(f, "resized {}: 0x{:X} -> 0x{:X} bytes", name, from, to)?
+        }
+        FieldChange::Added(field) => {
+          writeln!(
+            f,
+            "added {}: 0x{:X} (0x{:X} bytes)",
+            field.name, field.offset, field.size
+          )?
+        }
+        FieldChange::Removed(field) => {
+          writeln!(
+            f,
+            "removed {}: 0x{:X} (0x{:X} bytes)",
+            field.name, field.offset, field.size
+          )?
+        }
+      }
+    }
+
+    Ok(())
+  }
+}
+
+/// Compares the layouts of two annotated types, such as the same struct for two builds of a
+/// program, matching fields by name.
+/// ```rust
+/// use memory_layout::{diff, memory_layout, FieldChange};
+///
+/// #[memory_layout(0x40)]
+/// pub struct CPedOld {
+///   #[field_offset(0x10)]
+///   health: f32
+/// }
+///
+/// #[memory_layout(0x48)]
+/// pub struct CPedNew {
+///   #[field_offset(0x18)]
+///   health: f32
+/// }
+///
+/// let diff = diff::<CPedOld, CPedNew>();
+/// assert_eq!(diff.size, Some((0x40, 0x48)));
+/// assert_eq!(
+///   diff.changes,
+///   [FieldChange::Moved {
+///     name: "health",
+///     from: 0x10,
+///     to:   0x18
+///   }]
+/// );
+/// ```
+pub fn diff<A: MemoryLayout, B: MemoryLayout>() -> LayoutDiff {
+  LayoutDiff::between(&A::LAYOUT, &B::LAYOUT)
+}
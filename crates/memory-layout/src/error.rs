@@ -0,0 +1,62 @@
+use core::fmt;
+
+/// Error returned when raw bytes don't form a valid instance of a struct.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LayoutError {
+  /// The buffer doesn't have the size of the struct.
+  InvalidLength {
+    /// Size of the struct, in bytes.
+    expected: usize,
+    /// Size of the buffer, in bytes.
+    actual:   usize
+  },
+  /// A padding byte isn't zero.
+  NonZeroPadding {
+    /// Offset of the first nonzero padding byte.
+    offset: usize
+  },
+  /// The buffer isn't aligned to the alignment of the struct.
+  Misaligned {
+    /// Alignment of the struct, in bytes.
+    align: usize
+  },
+  /// A runtime offset places a field past the end of the struct.
+  FieldOutOfBounds {
+    /// Name of the field.
+    field:  &'static str,
+    /// Offset given for the field.
+    offset: usize,
+    /// Size of the field, in bytes.
+    size:   usize
+  }
+}
+
+impl fmt::Display for LayoutError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      LayoutError::InvalidLength { expected, actual } => {
+        write!(f, "expected 0x{:X} bytes, got 0x{:X}", expected, actual)
+      }
+      LayoutError::NonZeroPadding { offset } => {
+        write!(f, "nonzero padding byte at offset 0x{:X}", offset)
+      }
+      LayoutError::Misaligned { align } => {
+        write!(f, "buffer isn't aligned to {} bytes", align)
+      }
+      LayoutError::FieldOutOfBounds {
+        field,
+        offset,
+        size
+      } => {
+        write!(
+          f,
+          "`{}` of 0x{:X} bytes at offset 0x{:X} exceeds the struct",
+          field, size, offset
+        )
+      }
+    }
+  }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for LayoutError {}
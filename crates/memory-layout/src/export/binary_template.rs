@@ -0,0 +1,177 @@
+use alloc::{format, string::String};
+use core::fmt::Write;
+
+use super::{Endian, Exporter, Primitive, Region, StructDef, Type};
+use crate::Reflect;
+
+impl Exporter {
+  /// Renders the structs and `T` as an 010 Editor binary template, which reads a `T` at the cursor
+  /// so memory dumps and files can be inspected with the layouts of the Rust code.
+  ///
+  /// Every struct is a `typedef struct` with explicit padding members. Pointers are displayed as
+  /// hex addresses with the Rust type as comment, since 010 Editor can't follow them.
+  /// ```
+  /// use memory_layout::{export::Exporter, memory_layout};
+  ///
+  /// #[memory_layout(0x10, reflect)]
+  /// pub struct CEntity {
+  ///   #[field_offset(0x08)]
+  ///   pub id: u32
+  /// }
+  ///
+  /// assert_eq!(
+  ///   Exporter::new().binary_template::<CEntity>(),
+  ///   "\
+  /// //------------------------------------------------
+  /// //--- 010 Editor Binary Template
+  /// //   Purpose: Structs exported by memory_layout.
+  /// //------------------------------------------------
+  /// LittleEndian();
+  ///
+  /// typedef struct {
+  ///   /* 0x0000 */ uchar _pad_0000[0x8];
+  ///   /* 0x0008 */ uint32 id;
+  ///   /* 0x000C */ uchar _pad_000C[0x4];
+  /// } CEntity;
+  ///
+  /// CEntity root;
+  /// "
+  /// );
+  /// ```
+  pub fn binary_template<T: Reflect>(&self) -> String {
+    let mut exporter = self.clone();
+    exporter.add::<T>();
+
+    let mut out = String::from(
+      "//------------------------------------------------\n\
+       //--- 010 Editor Binary Template\n\
+       //   Purpose: Structs exported by memory_layout.\n\
+       //------------------------------------------------\n"
+    );
+    let _ = writeln!(out, "{}();", endian_function(NATIVE));
+    for def in exporter.ordered() {
+      out.push('\n');
+      exporter.write_template_struct(&mut out, def);
+    }
+    let _ = writeln!(out, "\n{} root;", T::LAYOUT.name);
+    out
+  }
+
+  fn write_template_struct(&self, out: &mut String, def: &StructDef) {
+    out.push_str("typedef struct {\n");
+    for region in def.regions() {
+      let _ = writeln!(
+        out,
+        "  /* 0x{:04X} */ {}",
+        region.offset,
+        self.template_member(&region)
+      );
+    }
+    for overlay in def.overlays() {
+      let _ = writeln!(
+        out,
+        "  /* 0x{:04X} {}: {} (0x{:X} bytes), overlaps another field */",
+        overlay.offset, overlay.name, overlay.type_name, overlay.size
+      );
+    }
+    let _ = writeln!(out, "}} {};", def.name);
+  }
+
+  /// The declaration of the member covering `region`.
+  fn template_member(&self, region: &Region) -> String {
+    let field = match region.field {
+      Some(field) => field,
+      None => {
+        let name = match region.gap {
+          Some(gap) => String::from(gap),
+          None => format!("_pad_{:04X}", region.offset)
+        };
+        return format!("uchar {}[0x{:X}];", name, region.size);
+      }
+    };
+
+    let ty = self.resolve(field.type_name, field.size);
+    let attributes = match ty {
+      Type::Pointer(_) | Type::Opaque => {
+        format!(
+          " <format=hex, comment=\"{}\">",
+          template_escape(field.type_name)
+        )
+      }
+      _ => String::new()
+    };
+    let declaration = match ty {
+      Type::Opaque => format!("uchar {}[0x{:X}]{};", field.name, field.size, attributes),
+      _ => format!("{}{};", template_declaration(&ty, field.name), attributes)
+    };
+
+    // Fields with an explicit byte order switch to it while they are read.
+    let mut element = &ty;
+    while let Type::Array(inner, _) = element {
+      element = inner;
+    }
+    match element {
+      Type::Primitive(_, Some(endian)) if *endian != NATIVE => {
+        format!(
+          "{}(); {} {}();",
+          endian_function(*endian),
+          declaration,
+          endian_function(NATIVE)
+        )
+      }
+      _ => declaration
+    }
+  }
+}
+
+const NATIVE: Endian = if cfg!(target_endian = "big") {
+  Endian::Big
+} else {
+  Endian::Little
+};
+
+fn endian_function(endian: Endian) -> &'static str {
+  match endian {
+    Endian::Little => "LittleEndian",
+    Endian::Big => "BigEndian"
+  }
+}
+
+fn template_escape(text: &str) -> String {
+  text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Declares `name` with the 010 Editor equivalent of `ty`.
+fn template_declaration(ty: &Type, name: &str) -> String {
+  match ty {
+    Type::Primitive(primitive, _) => {
+      let primitive = match primitive {
+        Primitive::Bool | Primitive::U8 => "uchar",
+        Primitive::I8 => "char",
+        Primitive::U16 => "uint16",
+        Primitive::I16 => "int16",
+        Primitive::Char | Primitive::U32 => "uint32",
+        Primitive::I32 => "int32",
+        Primitive::U64 => "uint64",
+        Primitive::I64 => "int64",
+        Primitive::Usize if primitive.size() == 8 => "uint64",
+        Primitive::Usize => "uint32",
+        Primitive::Isize if primitive.size() == 8 => "int64",
+        Primitive::Isize => "int32",
+        Primitive::F32 => "float",
+        Primitive::F64 => "double"
+      };
+      format!("{} {}", primitive, name)
+    }
+    Type::Pointer(_) => {
+      let address = Type::Primitive(Primitive::Usize, None);
+      template_declaration(&address, name)
+    }
+    Type::Char => format!("char {}", name),
+    Type::Array(element, count) => {
+      template_declaration(element, &format!("{}[0x{:X}]", name, count))
+    }
+    Type::Struct(target) => format!("{} {}", target, name),
+    Type::Opaque => format!("uchar {}", name)
+  }
+}
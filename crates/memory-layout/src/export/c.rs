@@ -0,0 +1,230 @@
+use alloc::{format, string::String};
+use core::fmt::Write;
+
+use super::{Exporter, Primitive, Region, StructDef, Type};
+
+impl Exporter {
+  /// Renders the structs as a C11 header.
+  ///
+  /// Every struct is a packed `typedef struct` with explicit padding members, followed by
+  /// `_Static_assert`s checking its size and field offsets. Since the structs are packed, their
+  /// alignment is 1.
+  /// ```
+  /// use memory_layout::{export::Exporter, memory_layout};
+  ///
+  /// #[memory_layout(0x10, reflect)]
+  /// pub struct CEntity {
+  ///   #[field_offset(0x08)]
+  ///   pub id: u32
+  /// }
+  ///
+  /// assert_eq!(
+  ///   Exporter::new().add::<CEntity>().c_header(),
+  ///   "\
+  /// #include <stdbool.h>
+  /// #include <stddef.h>
+  /// #include <stdint.h>
+  ///
+  /// #pragma pack(push, 1)
+  ///
+  /// typedef struct CEntity CEntity;
+  ///
+  /// struct CEntity {
+  ///   /* 0x0000 */ uint8_t _pad_0000[0x8];
+  ///   /* 0x0008 */ uint32_t id;
+  ///   /* 0x000C */ uint8_t _pad_000C[0x4];
+  /// };
+  /// _Static_assert(sizeof(CEntity) == 0x10, \"size of CEntity\");
+  /// _Static_assert(offsetof(CEntity, id) == 0x8, \"offset of CEntity.id\");
+  ///
+  /// #pragma pack(pop)
+  /// "
+  /// );
+  /// ```
+  pub fn c_header(&self) -> String {
+    self.c_family(Dialect::C)
+  }
+
+  /// Renders the structs as a C++ header.
+  ///
+  /// Every struct is a packed class with public members and explicit `std::uint8_t` padding,
+  /// followed by `static_assert`s checking its size and field offsets. Since the structs are
+  /// packed, their alignment is 1.
+  /// ```
+  /// use memory_layout::{export::Exporter, memory_layout};
+  ///
+  /// #[memory_layout(0x10, reflect)]
+  /// pub struct CEntity {
+  ///   #[field_offset(0x08)]
+  ///   pub id: u32
+  /// }
+  ///
+  /// assert_eq!(
+  ///   Exporter::new().add::<CEntity>().cpp_header(),
+  ///   "\
+  /// #pragma once
+  ///
+  /// #include <cstddef>
+  /// #include <cstdint>
+  ///
+  /// #pragma pack(push, 1)
+  ///
+  /// class CEntity;
+  ///
+  /// class CEntity {
+  /// public:
+  ///   /* 0x0000 */ std::uint8_t pad_0000[0x8];
+  ///   /* 0x0008 */ std::uint32_t id;
+  ///   /* 0x000C */ std::uint8_t pad_000C[0x4];
+  /// };
+  /// static_assert(sizeof(CEntity) == 0x10, \"size of CEntity\");
+  /// static_assert(offsetof(CEntity, id) == 0x8, \"offset of CEntity.id\");
+  ///
+  /// #pragma pack(pop)
+  /// "
+  /// );
+  /// ```
+  pub fn cpp_header(&self) -> String {
+    self.c_family(Dialect::Cpp)
+  }
+
+  fn c_family(&self, dialect: Dialect) -> String {
+    let mut out = String::new();
+    out.push_str(match dialect {
+      Dialect::C => "#include <stdbool.h>\n#include <stddef.h>\n#include <stdint.h>\n\n",
+      Dialect::Cpp => "#pragma once\n\n#include <cstddef>\n#include <cstdint>\n\n"
+    });
+    out.push_str("#pragma pack(push, 1)\n\n");
+
+    let structs = self.ordered();
+    for def in &structs {
+      let _ = match dialect {
+        Dialect::C => writeln!(out, "typedef struct {0} {0};", def.name),
+        Dialect::Cpp => writeln!(out, "class {};", def.name)
+      };
+    }
+    for def in &structs {
+      out.push('\n');
+      self.write_c_struct(&mut out, def, dialect);
+    }
+
+    out.push_str("\n#pragma pack(pop)\n");
+    out
+  }
+
+  fn write_c_struct(&self, out: &mut String, def: &StructDef, dialect: Dialect) {
+    let _ = match dialect {
+      Dialect::C => writeln!(out, "struct {} {{", def.name),
+      Dialect::Cpp => writeln!(out, "class {} {{\npublic:", def.name)
+    };
+    for region in def.regions() {
+      let _ = writeln!(
+        out,
+        "  /* 0x{:04X} */ {};",
+        region.offset,
+        self.c_member(&region, dialect)
+      );
+    }
+    for overlay in def.overlays() {
+      let _ = writeln!(
+        out,
+        "  /* 0x{:04X} {}: {} (0x{:X} bytes), overlaps another field */",
+        overlay.offset, overlay.name, overlay.type_name, overlay.size
+      );
+    }
+    out.push_str("};\n");
+
+    let static_assert = match dialect {
+      Dialect::C => "_Static_assert",
+      Dialect::Cpp => "static_assert"
+    };
+    let _ = writeln!(
+      out,
+      "{0}(sizeof({1}) == 0x{2:X}, \"size of {1}\");",
+      static_assert, def.name, def.size
+    );
+    for field in def.regions().iter().filter_map(|region| region.field) {
+      let _ = writeln!(
+        out,
+        "{0}(offsetof({1}, {2}) == 0x{3:X}, \"offset of {1}.{2}\");",
+        static_assert, def.name, field.name, field.offset
+      );
+    }
+  }
+
+  /// The declaration of the member covering `region`.
+  fn c_member(&self, region: &Region, dialect: Dialect) -> String {
+    let byte = dialect.primitive(Primitive::U8);
+    let field = match region.field {
+      Some(field) => field,
+      None => {
+        let name = match (region.gap, dialect) {
+          (Some(gap), _) => String::from(gap),
+          (None, Dialect::C) => format!("_pad_{:04X}", region.offset),
+          (None, Dialect::Cpp) => format!("pad_{:04X}", region.offset)
+        };
+        return format!("{} {}[0x{:X}]", byte, name, region.size);
+      }
+    };
+
+    match self.resolve(field.type_name, field.size) {
+      Type::Opaque => {
+        format!(
+          "{} {}[0x{:X}] /* {} */",
+          byte, field.name, field.size, field.type_name
+        )
+      }
+      ty @ Type::Primitive(_, Some(_)) => {
+        format!(
+          "{} /* {} */",
+          dialect.declaration(&ty, field.name),
+          field.type_name
+        )
+      }
+      ty => dialect.declaration(&ty, field.name)
+    }
+  }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Dialect {
+  C,
+  Cpp
+}
+
+impl Dialect {
+  /// Declares `name` with the equivalent of `ty`.
+  fn declaration(self, ty: &Type, name: &str) -> String {
+    match ty {
+      Type::Primitive(primitive, _) => format!("{} {}", self.primitive(*primitive), name),
+      Type::Pointer(Some(target)) => format!("{} *{}", target, name),
+      Type::Pointer(None) => format!("void *{}", name),
+      Type::Char => format!("char {}", name),
+      Type::Array(element, count) => self.declaration(element, &format!("{}[0x{:X}]", name, count)),
+      Type::Struct(target) => format!("{} {}", target, name),
+      Type::Opaque => format!("{} {}", self.primitive(Primitive::U8), name)
+    }
+  }
+
+  fn primitive(self, primitive: Primitive) -> &'static str {
+    let name = match primitive {
+      Primitive::Bool => return "bool",
+      Primitive::F32 => return "float",
+      Primitive::F64 => return "double",
+      Primitive::Char | Primitive::U32 => "std::uint32_t",
+      Primitive::U8 => "std::uint8_t",
+      Primitive::U16 => "std::uint16_t",
+      Primitive::U64 => "std::uint64_t",
+      Primitive::Usize => "std::uintptr_t",
+      Primitive::I8 => "std::int8_t",
+      Primitive::I16 => "std::int16_t",
+      Primitive::I32 => "std::int32_t",
+      Primitive::I64 => "std::int64_t",
+      Primitive::Isize => "std::intptr_t"
+    };
+    match self {
+      Dialect::C => &name["std::".len()..],
+      Dialect::Cpp => name
+    }
+  }
+}
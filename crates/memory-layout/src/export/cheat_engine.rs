@@ -0,0 +1,160 @@
+use alloc::{format, string::String};
+use core::fmt::Write;
+
+use super::{xml_escape, Endian, Exporter, Primitive, StructDef, Type};
+use crate::RegionKind;
+
+impl Exporter {
+  /// Renders the structs as Cheat Engine structure definitions, which the "Dissect data/structures"
+  /// window imports through File > Import.
+  ///
+  /// Cheat Engine elements can't contain structs, so structs embedded by value are flattened into
+  /// elements named like `pos.x`, the base without a prefix, and arrays into one element per item.
+  /// Byte arrays and types Cheat Engine has no equivalent for are exported as arrays of bytes.
+  /// Pointers to exported structs can be expanded to them. Overlapping fields are kept.
+  /// ```
+  /// use memory_layout::{export::Exporter, memory_layout};
+  ///
+  /// #[memory_layout(0x10, reflect)]
+  /// pub struct CEntity {
+  ///   #[field_offset(0x08)]
+  ///   pub health: f32
+  /// }
+  ///
+  /// let xml = Exporter::new().add::<CEntity>().cheat_engine_xml();
+  /// assert!(xml.contains(r#"<Element Offset="8" Vartype="Float" Bytesize="4""#));
+  /// ```
+  pub fn cheat_engine_xml(&self) -> String {
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<Structures>\n");
+    for def in self.ordered() {
+      let _ = writeln!(
+        out,
+        "  <Structure Name=\"{}\" AutoFill=\"0\" AutoCreate=\"1\" DefaultHex=\"0\" \
+         AutoDestroy=\"0\" DoNotSaveLocal=\"0\" RLECompression=\"1\" AutoCreateStructsize=\"{}\">",
+        xml_escape(def.name),
+        def.size
+      );
+      out.push_str("    <Elements>\n");
+      self.write_cheat_engine_fields(&mut out, def, "", 0);
+      out.push_str("    </Elements>\n  </Structure>\n");
+    }
+    out.push_str("</Structures>\n");
+    out
+  }
+
+  fn write_cheat_engine_fields(
+    &self,
+    out: &mut String,
+    def: &StructDef,
+    prefix: &str,
+    base: usize
+  ) {
+    for field in def.fields {
+      let ty = self.resolve(field.type_name, field.size);
+      let name = match (field.kind, &ty) {
+        (RegionKind::Gap, _) => continue,
+        (RegionKind::Base, Type::Struct(_)) => String::from(prefix.trim_end_matches('.')),
+        _ => format!("{}{}", prefix, field.name)
+      };
+      self.write_cheat_engine_element(out, &ty, &name, base + field.offset, field.size);
+    }
+  }
+
+  /// Writes the elements covering `ty`, named `name` or after it.
+  fn write_cheat_engine_element(
+    &self,
+    out: &mut String,
+    ty: &Type,
+    name: &str,
+    offset: usize,
+    size: usize
+  ) {
+    let (vartype, display, extra) = match ty {
+      Type::Struct(target) => {
+        if let Some(def) = self.find(target) {
+          let prefix = if name.is_empty() {
+            String::new()
+          } else {
+            format!("{}.", name)
+          };
+          self.write_cheat_engine_fields(out, def, &prefix, offset);
+        }
+        return;
+      }
+      Type::Array(element, count)
+        if !matches!(
+          **element,
+          Type::Char | Type::Primitive(Primitive::U8 | Primitive::I8, _)
+        ) =>
+      {
+        let element_size = size / count;
+        for index in 0..*count {
+          self.write_cheat_engine_element(
+            out,
+            element,
+            &format!("{}[{}]", name, index),
+            offset + index * element_size,
+            element_size
+          );
+        }
+        return;
+      }
+      Type::Array(element, _) if **element == Type::Char => {
+        ("String", "unsigned integer", String::new())
+      }
+      Type::Primitive(primitive, endian) => {
+        let signed = matches!(
+          primitive,
+          Primitive::I8 | Primitive::I16 | Primitive::I32 | Primitive::I64 | Primitive::Isize
+        );
+        let display = if signed {
+          "signed integer"
+        } else {
+          "unsigned integer"
+        };
+        match (primitive, primitive.size(), endian) {
+          (Primitive::F32, ..) => ("Float", display, String::new()),
+          (Primitive::F64, ..) => ("Double", display, String::new()),
+          (_, 2, Some(Endian::Big)) => {
+            (
+              "Custom",
+              display,
+              String::from(" CustomType=\"2 Byte Big Endian\"")
+            )
+          }
+          (_, 4, Some(Endian::Big)) => {
+            (
+              "Custom",
+              display,
+              String::from(" CustomType=\"4 Byte Big Endian\"")
+            )
+          }
+          (_, _, Some(Endian::Big)) => ("Array of byte", "hexadecimal", String::new()),
+          (_, 1, _) => ("Byte", display, String::new()),
+          (_, 2, _) => ("2 Bytes", display, String::new()),
+          (_, 4, _) => ("4 Bytes", display, String::new()),
+          _ => ("8 Bytes", display, String::new())
+        }
+      }
+      Type::Pointer(target) => {
+        let child = target.map_or(String::new(), |target| {
+          format!(" ChildStruct=\"{}\"", xml_escape(target))
+        });
+        ("Pointer", "hexadecimal", child)
+      }
+      Type::Char => ("String", "unsigned integer", String::new()),
+      Type::Array(..) | Type::Opaque => ("Array of byte", "hexadecimal", String::new())
+    };
+    let _ = writeln!(
+      out,
+      "      <Element Offset=\"{0}\" Vartype=\"{1}\" Bytesize=\"{2}\" OffsetHex=\"{0:08X}\" \
+       Description=\"{3}\" DisplayMethod=\"{4}\"{5}/>",
+      offset,
+      vartype,
+      size,
+      xml_escape(name),
+      display,
+      extra
+    );
+  }
+}
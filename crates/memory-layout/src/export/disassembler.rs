@@ -0,0 +1,244 @@
+use alloc::{format, string::String};
+use core::fmt::Write;
+
+use super::{Exporter, Primitive, StructDef, Type};
+
+impl Exporter {
+  /// Renders the structs as a Python script for Ghidra's Script Manager, which defines them in the
+  /// data types of the current program under the `/memory_layout` category.
+  ///
+  /// Structs with the same name in the category are replaced. Since Ghidra structures can't hold
+  /// overlapping components, fields overlapping another one are listed as comments.
+  /// ```
+  /// use memory_layout::{export::Exporter, memory_layout};
+  ///
+  /// #[memory_layout(0x10, reflect)]
+  /// pub struct CEntity {
+  ///   #[field_offset(0x08)]
+  ///   pub health: f32
+  /// }
+  ///
+  /// let script = Exporter::new().add::<CEntity>().ghidra_script();
+  /// assert!(script.contains(
+  ///   r#"types["CEntity"].replaceAtOffset(0x8, FloatDataType.dataType, 0x4, "health", None)"#
+  /// ));
+  /// ```
+  pub fn ghidra_script(&self) -> String {
+    let structs = self.ordered();
+    let mut out = String::from(
+      "# Defines the structs exported by memory_layout in the current program.\n\
+       # @category memory_layout\n\
+       from ghidra.program.model.data import *\n\n\
+       dtm = currentProgram.getDataTypeManager()\n\
+       category = CategoryPath(\"/memory_layout\")\n\
+       types = {}\n"
+    );
+    out.push_str("for name, size in [");
+    for (index, def) in structs.iter().enumerate() {
+      let separator = if index == 0 { "" } else { ", " };
+      let _ = write!(out, "{}(\"{}\", 0x{:X})", separator, def.name, def.size);
+    }
+    out.push_str(
+      "]:\n    types[name] = dtm.addDataType(\n        StructureDataType(category, name, size), \
+       DataTypeConflictHandler.REPLACE_HANDLER\n    )\n"
+    );
+
+    for def in &structs {
+      out.push('\n');
+      for region in def.regions() {
+        let field = match region.field {
+          Some(field) => field,
+          None => continue
+        };
+        let ty = self.resolve(field.type_name, field.size);
+        let comment = match ty {
+          Type::Primitive(_, Some(_)) | Type::Opaque => {
+            format!("\"{}\"", python_escape(field.type_name))
+          }
+          _ => String::from("None")
+        };
+        let _ = writeln!(
+          out,
+          "types[\"{}\"].replaceAtOffset(0x{:X}, {}, 0x{:X}, \"{}\", {})",
+          def.name,
+          field.offset,
+          ghidra_type(&ty, field.size),
+          field.size,
+          field.name,
+          comment
+        );
+      }
+      write_overlay_comments(&mut out, def);
+    }
+    out
+  }
+
+  /// Renders the structs as an IDAPython script, run through File > Script file, which defines
+  /// them in the database and checks their sizes.
+  ///
+  /// Structs with the same name are replaced. Since IDA structs can't hold overlapping members,
+  /// fields overlapping another one are listed as comments.
+  /// ```
+  /// use memory_layout::{export::Exporter, memory_layout};
+  ///
+  /// #[memory_layout(0x10, reflect)]
+  /// pub struct CEntity {
+  ///   #[field_offset(0x08)]
+  ///   pub health: f32
+  /// }
+  ///
+  /// let script = Exporter::new().add::<CEntity>().ida_script();
+  /// assert!(script.contains(r#"(0x8, "health", 0x4, "float"),"#));
+  /// ```
+  pub fn ida_script(&self) -> String {
+    let mut out = String::from(
+      "# Defines the structs exported by memory_layout in the database.\n\
+       import idc\n\n\
+       # Name, size and members as offset, name, size and type.\n\
+       structs = [\n"
+    );
+    let structs = self.ordered();
+    for def in &structs {
+      let _ = writeln!(out, "    (\"{}\", 0x{:X}, [", def.name, def.size);
+      let regions = def.regions();
+      for (index, region) in regions.iter().enumerate() {
+        let (name, ty) = match region.field {
+          Some(field) => {
+            (
+              String::from(field.name),
+              self.resolve(field.type_name, field.size)
+            )
+          }
+          // Trailing padding keeps the size of the struct.
+          None if index == regions.len() - 1 => {
+            (format!("_pad_{:04X}", region.offset), Type::Opaque)
+          }
+          None => continue
+        };
+        let _ = writeln!(
+          out,
+          "        (0x{:X}, \"{}\", 0x{:X}, \"{}\"),",
+          region.offset,
+          name,
+          region.size,
+          ida_type(&ty, region.size)
+        );
+      }
+      let mut overlays = String::new();
+      write_overlay_comments(&mut overlays, def);
+      for line in overlays.lines() {
+        let _ = writeln!(out, "        {}", line);
+      }
+      out.push_str("    ]),\n");
+    }
+    out.push_str(
+      "]\n\n\
+       for name, size, members in structs:\n\
+      \x20   sid = idc.get_struc_id(name)\n\
+      \x20   if sid != idc.BADADDR:\n\
+      \x20       idc.del_struc(sid)\n\
+      \x20   idc.add_struc(-1, name, 0)\n\n\
+       for name, size, members in structs:\n\
+      \x20   sid = idc.get_struc_id(name)\n\
+      \x20   for offset, member, length, declaration in members:\n\
+      \x20       idc.add_struc_member(sid, member, offset, idc.FF_BYTE | idc.FF_DATA, -1, length)\n\
+      \x20       idc.SetType(idc.get_member_id(sid, offset), declaration)\n\
+      \x20   assert idc.get_struc_size(sid) == size, \"size of \" + name\n"
+    );
+    out
+  }
+}
+
+fn write_overlay_comments(out: &mut String, def: &StructDef) {
+  for overlay in def.overlays() {
+    let _ = writeln!(
+      out,
+      "# 0x{:04X} {}: {} (0x{:X} bytes), overlaps another field",
+      overlay.offset, overlay.name, overlay.type_name, overlay.size
+    );
+  }
+}
+
+fn python_escape(text: &str) -> String {
+  text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// A Python expression creating the Ghidra data type of `ty`.
+fn ghidra_type(ty: &Type, size: usize) -> String {
+  let name = match ty {
+    Type::Primitive(primitive, _) => {
+      match primitive {
+        Primitive::Bool => "BooleanDataType",
+        Primitive::U8 => "ByteDataType",
+        Primitive::I8 => "SignedByteDataType",
+        Primitive::U16 => "WordDataType",
+        Primitive::I16 => "SignedWordDataType",
+        Primitive::Char | Primitive::U32 => "DWordDataType",
+        Primitive::I32 => "SignedDWordDataType",
+        Primitive::U64 => "QWordDataType",
+        Primitive::I64 => "SignedQWordDataType",
+        Primitive::Usize if size == 8 => "QWordDataType",
+        Primitive::Usize => "DWordDataType",
+        Primitive::Isize if size == 8 => "SignedQWordDataType",
+        Primitive::Isize => "SignedDWordDataType",
+        Primitive::F32 => "FloatDataType",
+        Primitive::F64 => "DoubleDataType"
+      }
+    }
+    Type::Pointer(Some(target)) => {
+      return format!("PointerDataType(types[\"{}\"], {})", target, size)
+    }
+    Type::Pointer(None) => return format!("PointerDataType(VoidDataType.dataType, {})", size),
+    Type::Char => "CharDataType",
+    Type::Array(element, count) => {
+      let element_size = size / count;
+      return format!(
+        "ArrayDataType({}, {}, {})",
+        ghidra_type(element, element_size),
+        count,
+        element_size
+      );
+    }
+    Type::Struct(name) => return format!("types[\"{}\"]", name),
+    Type::Opaque => return format!("ArrayDataType(ByteDataType.dataType, {}, 1)", size)
+  };
+  format!("{}.dataType", name)
+}
+
+/// The IDA type of `ty` as abstract declarator, like `CEntity *` or `float[2][3]`.
+fn ida_type(ty: &Type, size: usize) -> String {
+  let name = match ty {
+    Type::Primitive(primitive, _) => {
+      match primitive {
+        Primitive::Bool => "bool",
+        Primitive::U8 => "unsigned __int8",
+        Primitive::I8 => "__int8",
+        Primitive::U16 => "unsigned __int16",
+        Primitive::I16 => "__int16",
+        Primitive::Char | Primitive::U32 => "unsigned __int32",
+        Primitive::I32 => "__int32",
+        Primitive::U64 => "unsigned __int64",
+        Primitive::I64 => "__int64",
+        Primitive::Usize if size == 8 => "unsigned __int64",
+        Primitive::Usize => "unsigned __int32",
+        Primitive::Isize if size == 8 => "__int64",
+        Primitive::Isize => "__int32",
+        Primitive::F32 => "float",
+        Primitive::F64 => "double"
+      }
+    }
+    Type::Pointer(Some(target)) => return format!("{} *", target),
+    Type::Pointer(None) => "void *",
+    Type::Char => "char",
+    Type::Array(element, count) => {
+      // The dimensions of nested arrays are listed outermost first.
+      let mut element = ida_type(element, size / count);
+      let at = element.find('[').unwrap_or(element.len());
+      element.insert_str(at, &format!("[{}]", count));
+      return element;
+    }
+    Type::Struct(name) => name,
+    Type::Opaque => return format!("unsigned __int8[{}]", size)
+  };
+  String::from(name)
+}
@@ -0,0 +1,101 @@
+use alloc::string::String;
+use core::fmt::Write;
+
+use super::Exporter;
+use crate::RegionKind;
+
+impl Exporter {
+  /// Renders the layouts of the structs as a JSON document, for tools that read them without
+  /// depending on one of the other formats.
+  ///
+  /// The document holds a `structs` array in the order of [`Exporter`], each struct with its
+  /// `name`, `size`, `align` and `fields`. Every field has a `name`, `offset` and `size` in bytes,
+  /// its `type` as written in the struct and its `kind`: `data`, `gap`, `overlay` or `base`.
+  /// ```
+  /// use memory_layout::{export::Exporter, memory_layout};
+  ///
+  /// #[memory_layout(0x10, reflect)]
+  /// pub struct CEntity {
+  ///   #[field_offset(0x08)]
+  ///   pub id: u32
+  /// }
+  ///
+  /// assert_eq!(
+  ///   Exporter::new().add::<CEntity>().json(),
+  ///   r#"{
+  ///   "structs": [
+  ///     {
+  ///       "name": "CEntity",
+  ///       "size": 16,
+  ///       "align": 1,
+  ///       "fields": [
+  ///         { "name": "id", "offset": 8, "size": 4, "type": "u32", "kind": "data" }
+  ///       ]
+  ///     }
+  ///   ]
+  /// }
+  /// "#
+  /// );
+  /// ```
+  pub fn json(&self) -> String {
+    let mut out = String::from("{\n  \"structs\": [");
+    for (index, def) in self.ordered().iter().enumerate() {
+      out.push_str(if index == 0 { "\n" } else { ",\n" });
+      let _ = write!(
+        out,
+        "    {{\n      \"name\": {},\n      \"size\": {},\n      \"align\": {},\n      \"fields\": [",
+        json_string(def.name),
+        def.size,
+        def.align
+      );
+      for (index, field) in def.fields.iter().enumerate() {
+        let kind = match field.kind {
+          RegionKind::Data => "data",
+          RegionKind::Gap => "gap",
+          RegionKind::Overlay => "overlay",
+          RegionKind::Base => "base"
+        };
+        let _ = write!(
+          out,
+          "{}        {{ \"name\": {}, \"offset\": {}, \"size\": {}, \"type\": {}, \"kind\": \"{}\" }}",
+          if index == 0 { "\n" } else { ",\n" },
+          json_string(field.name),
+          field.offset,
+          field.size,
+          json_string(field.type_name),
+          kind
+        );
+      }
+      out.push_str(if def.fields.is_empty() {
+        "]\n    }"
+      } else {
+        "\n      ]\n    }"
+      });
+    }
+    out.push_str(if self.structs.is_empty() {
+      "]\n}\n"
+    } else {
+      "\n  ]\n}\n"
+    });
+    out
+  }
+}
+
+/// `text` as JSON string literal.
+fn json_string(text: &str) -> String {
+  let mut out = String::with_capacity(text.len() + 2);
+  out.push('"');
+  for c in text.chars() {
+    match c {
+      '"' => out.push_str("\\\""),
+      '\\' => out.push_str("\\\\"),
+      '\n' => out.push_str("\\n"),
+      c if (c as u32) < 0x20 => {
+        let _ = write!(out, "\\u{:04x}", c as u32);
+      }
+      c => out.push(c)
+    }
+  }
+  out.push('"');
+  out
+}
@@ -0,0 +1,211 @@
+use alloc::{format, string::String, vec, vec::Vec};
+use core::fmt::Write;
+
+use super::{Endian, Exporter, Primitive, StructDef, Type};
+use crate::Reflect;
+
+impl Exporter {
+  /// Renders the structs as a Kaitai Struct schema with `T` as top-level type, so the layouts can
+  /// be used by Kaitai based viewers and compiled to parsers for other languages.
+  ///
+  /// Type and field names are converted to the lower snake case Kaitai requires. Padding is read
+  /// as explicit byte fields, fields overlapping another one become instances at their position,
+  /// and fields of the endian wrappers in [`crate::types`] keep their byte order.
+  /// ```
+  /// use memory_layout::{export::Exporter, memory_layout};
+  ///
+  /// #[memory_layout(0x10, reflect)]
+  /// pub struct CEntity {
+  ///   #[field_offset(0x08)]
+  ///   pub id: u32
+  /// }
+  ///
+  /// assert_eq!(
+  ///   Exporter::new().kaitai_schema::<CEntity>(),
+  ///   "\
+  /// meta:
+  ///   id: c_entity
+  ///   endian: le
+  /// seq:
+  ///   - id: pad_0000
+  ///     size: 0x8
+  ///   - id: id
+  ///     type: u4
+  ///   - id: pad_000c
+  ///     size: 0x4
+  /// "
+  /// );
+  /// ```
+  pub fn kaitai_schema<T: Reflect>(&self) -> String {
+    let mut exporter = self.clone();
+    exporter.add::<T>();
+    let structs = exporter.ordered();
+
+    let mut out = String::from("meta:\n");
+    let _ = writeln!(out, "  id: {}", kaitai_id(T::LAYOUT.name));
+    out.push_str(if cfg!(target_endian = "big") {
+      "  endian: be\n"
+    } else {
+      "  endian: le\n"
+    });
+    if let Some(root) = structs.iter().find(|def| def.name == T::LAYOUT.name) {
+      exporter.write_kaitai_body(&mut out, root, 0);
+    }
+
+    let mut types = structs
+      .iter()
+      .filter(|def| def.name != T::LAYOUT.name)
+      .peekable();
+    if types.peek().is_some() {
+      out.push_str("types:\n");
+    }
+    for def in types {
+      let _ = writeln!(out, "  {}:", kaitai_id(def.name));
+      exporter.write_kaitai_body(&mut out, def, 4);
+    }
+    out
+  }
+
+  /// Writes the `seq` and `instances` of `def`, indented by `indent`.
+  fn write_kaitai_body(&self, out: &mut String, def: &StructDef, indent: usize) {
+    let _ = writeln!(out, "{:indent$}seq:", "", indent = indent);
+    for region in def.regions() {
+      let (id, attributes) = match region.field {
+        Some(field) => {
+          (
+            kaitai_id(field.name),
+            self.kaitai_attributes(field.type_name, field.size)
+          )
+        }
+        None => {
+          let id = match region.gap {
+            Some(gap) => kaitai_id(gap),
+            None => format!("pad_{:04x}", region.offset)
+          };
+          (id, vec![("size", format!("0x{:X}", region.size))])
+        }
+      };
+      let _ = writeln!(out, "{:indent$}  - id: {}", "", id, indent = indent);
+      for (key, value) in attributes {
+        let _ = writeln!(out, "{:indent$}    {}: {}", "", key, value, indent = indent);
+      }
+    }
+
+    let mut overlays = def.overlays().peekable();
+    if overlays.peek().is_some() {
+      let _ = writeln!(out, "{:indent$}instances:", "", indent = indent);
+    }
+    for overlay in overlays {
+      let _ = writeln!(
+        out,
+        "{:indent$}  {}:\n{:indent$}    pos: 0x{:X}",
+        "",
+        kaitai_id(overlay.name),
+        "",
+        overlay.offset,
+        indent = indent
+      );
+      for (key, value) in self.kaitai_attributes(overlay.type_name, overlay.size) {
+        let _ = writeln!(out, "{:indent$}    {}: {}", "", key, value, indent = indent);
+      }
+    }
+  }
+
+  /// The attributes reading a field of the Rust type `type_name`.
+  fn kaitai_attributes(&self, type_name: &str, size: usize) -> Vec<(&'static str, String)> {
+    let ty = self.resolve(type_name, size);
+    let mut attributes = kaitai_type(&ty, size);
+    if matches!(ty, Type::Pointer(_) | Type::Opaque) {
+      attributes.push(("doc", yaml_string(type_name)));
+    }
+    attributes
+  }
+}
+
+/// The attributes reading `ty`, `size` bytes long.
+fn kaitai_type(ty: &Type, size: usize) -> Vec<(&'static str, String)> {
+  match ty {
+    Type::Primitive(primitive, endian) => {
+      let name = match primitive {
+        Primitive::Bool | Primitive::U8 => "u1",
+        Primitive::I8 => "s1",
+        Primitive::U16 => "u2",
+        Primitive::I16 => "s2",
+        Primitive::Char | Primitive::U32 => "u4",
+        Primitive::I32 => "s4",
+        Primitive::U64 => "u8",
+        Primitive::I64 => "s8",
+        Primitive::Usize if size == 8 => "u8",
+        Primitive::Usize => "u4",
+        Primitive::Isize if size == 8 => "s8",
+        Primitive::Isize => "s4",
+        Primitive::F32 => "f4",
+        Primitive::F64 => "f8"
+      };
+      let suffix = match endian {
+        // Single bytes have no byte order.
+        _ if size == 1 => "",
+        Some(Endian::Little) => "le",
+        Some(Endian::Big) => "be",
+        None => ""
+      };
+      vec![("type", format!("{}{}", name, suffix))]
+    }
+    Type::Pointer(_) => kaitai_type(&Type::Primitive(Primitive::Usize, None), size),
+    Type::Char => vec![("type", String::from("u1"))],
+    Type::Array(element, count) if **element == Type::Char => {
+      vec![
+        ("type", String::from("strz")),
+        ("size", format!("0x{:X}", count)),
+        ("encoding", String::from("UTF-8")),
+      ]
+    }
+    // Byte arrays are read as bytes.
+    Type::Array(element, _) if **element == Type::Primitive(Primitive::U8, None) => {
+      vec![("size", format!("0x{:X}", size))]
+    }
+    Type::Array(element, count) => {
+      let mut attributes = kaitai_type(element, size / count);
+      attributes.push(("repeat", String::from("expr")));
+      attributes.push(("repeat-expr", format!("{}", count)));
+      attributes
+    }
+    // Structs read from a substream of their size, so positions of their instances are relative.
+    Type::Struct(name) => {
+      vec![("type", kaitai_id(name)), ("size", format!("0x{:X}", size))]
+    }
+    Type::Opaque => vec![("size", format!("0x{:X}", size))]
+  }
+}
+
+/// Converts `name` to a Kaitai identifier, like `CEntity` to `c_entity`.
+fn kaitai_id(name: &str) -> String {
+  let chars: Vec<char> = name.chars().collect();
+  let mut id = String::with_capacity(name.len() + 4);
+  for (index, c) in chars.iter().copied().enumerate() {
+    let previous = index.checked_sub(1).map(|index| chars[index]);
+    let next = chars.get(index + 1).copied();
+    // Words start at an uppercase letter after a lowercase one, or before one like in `CEntity`.
+    let word = c.is_ascii_uppercase()
+      && (previous.map_or(false, |c| c.is_ascii_lowercase() || c.is_ascii_digit())
+        || previous.map_or(false, |c| c.is_ascii_uppercase())
+          && next.map_or(false, |c| c.is_ascii_lowercase()));
+    if word && !id.is_empty() && !id.ends_with('_') {
+      id.push('_');
+    }
+    if c.is_ascii_alphanumeric() {
+      id.push(c.to_ascii_lowercase());
+    } else if !id.is_empty() && !id.ends_with('_') {
+      id.push('_');
+    }
+  }
+  let id = id.trim_end_matches('_');
+  match id.chars().next() {
+    Some(c) if c.is_ascii_lowercase() => String::from(id),
+    _ => format!("f_{}", id)
+  }
+}
+
+fn yaml_string(text: &str) -> String {
+  format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
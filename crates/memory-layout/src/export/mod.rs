@@ -0,0 +1,394 @@
+//! Exports the layouts of structs annotated with `#[memory_layout(reflect)]` to other languages
+//! and tools, so they can share the definitions of the Rust code.
+//!
+//! The structs are collected in an [`Exporter`], which renders them in one of the supported
+//! formats. Running it from a test or a small binary keeps the generated files in sync with the
+//! Rust definitions:
+//! ```
+//! use memory_layout::{export::Exporter, memory_layout};
+//!
+//! #[memory_layout(0x20, reflect)]
+//! pub struct CPed {
+//!   #[field_offset(0x08)]
+//!   pub id:     u32,
+//!   #[field_offset(0x10)]
+//!   pub health: f32
+//! }
+//!
+//! let header = Exporter::new().add::<CPed>().c_header();
+//! assert!(header.contains("/* 0x0010 */ float health;"));
+//! ```
+//!
+//! Field types are mapped from their names in the struct. Primitives, pointers, arrays of those
+//! and other exported structs are translated, any other type is exported as bytes of the same size.
+
+mod binary_template;
+mod c;
+mod cheat_engine;
+mod disassembler;
+mod json;
+mod kaitai;
+mod reclass;
+
+use alloc::{boxed::Box, string::String, vec::Vec};
+use core::mem::size_of;
+
+use crate::{FieldInfo, Reflect, RegionKind};
+
+/// Collects annotated structs to export.
+///
+/// Structs embedding another exported struct by value are emitted after it, regardless of the
+/// order they were added in.
+#[derive(Clone, Debug, Default)]
+pub struct Exporter {
+  structs: Vec<StructDef>
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct StructDef {
+  name:   &'static str,
+  size:   usize,
+  align:  usize,
+  fields: &'static [FieldInfo]
+}
+
+impl Exporter {
+  /// Creates an exporter without any structs.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Adds the struct `T`. Adding a struct twice has no effect.
+  pub fn add<T: Reflect>(&mut self) -> &mut Self {
+    let def = StructDef {
+      name:   T::LAYOUT.name,
+      size:   T::LAYOUT.size,
+      align:  T::ALIGN,
+      fields: T::FIELDS
+    };
+    if !self.structs.contains(&def) {
+      self.structs.push(def);
+    }
+    self
+  }
+
+  fn find(&self, name: &str) -> Option<&StructDef> {
+    self.structs.iter().find(|def| def.name == name)
+  }
+
+  /// The structs ordered so every struct comes after the structs it contains by value.
+  fn ordered(&self) -> Vec<&StructDef> {
+    fn visit<'a>(exporter: &'a Exporter, def: &'a StructDef, ordered: &mut Vec<&'a StructDef>) {
+      if ordered.iter().any(|other| other.name == def.name) {
+        return;
+      }
+      ordered.push(def);
+      let index = ordered.len() - 1;
+      for field in def.fields {
+        let dependency = exporter
+          .resolve(field.type_name, field.size)
+          .value_dependency()
+          .and_then(|name| exporter.find(name));
+        if let Some(dependency) = dependency {
+          if !ordered.iter().any(|other| other.name == dependency.name) {
+            let at = ordered.len();
+            visit(exporter, dependency, ordered);
+            let dependencies: Vec<_> = ordered.drain(at..).collect();
+            ordered.splice(index..index, dependencies);
+          }
+        }
+      }
+    }
+
+    let mut ordered = Vec::new();
+    for def in &self.structs {
+      visit(self, def, &mut ordered);
+    }
+    ordered
+  }
+
+  /// Maps the Rust type of a field of `size` bytes to a [`Type`].
+  fn resolve(&self, type_name: &str, size: usize) -> Type {
+    let name = type_name.trim();
+    if let Some(inner) = name
+      .strip_prefix('[')
+      .and_then(|name| name.strip_suffix(']'))
+    {
+      let element = match inner.rfind(';') {
+        Some(split) => &inner[..split],
+        None => return Type::Opaque
+      };
+      return match self.element_size(element) {
+        Some(element_size) if element_size != 0 && size % element_size == 0 => {
+          Type::Array(
+            Box::new(self.resolve(element, element_size)),
+            size / element_size
+          )
+        }
+        _ => Type::Opaque
+      };
+    }
+
+    if size == size_of::<usize>() {
+      if let Some(target) = pointer_target(name) {
+        return Type::Pointer(self.find(last_segment(target)).map(|def| def.name));
+      }
+    }
+
+    let segment = last_segment(name);
+    if let Some(capacity) = segment
+      .strip_prefix("CStrArray<")
+      .and_then(|rest| rest.strip_suffix('>'))
+    {
+      return match capacity.trim().parse() {
+        Ok(capacity) if capacity == size => Type::Array(Box::new(Type::Char), capacity),
+        _ => Type::Opaque
+      };
+    }
+    if let Some(def) = self.find(segment) {
+      if def.size == size {
+        return Type::Struct(def.name);
+      }
+    }
+    match Primitive::parse(segment) {
+      Some((primitive, endian)) if primitive.size() == size => Type::Primitive(primitive, endian),
+      _ => Type::Opaque
+    }
+  }
+
+  /// Size of an array element type, if it can be known without the field size.
+  fn element_size(&self, type_name: &str) -> Option<usize> {
+    let name = type_name.trim();
+    if name.starts_with('[') {
+      return None;
+    }
+    if pointer_target(name).is_some() {
+      return Some(size_of::<usize>());
+    }
+    let segment = last_segment(name);
+    self
+      .find(segment)
+      .map(|def| def.size)
+      .or_else(|| Primitive::parse(segment).map(|(primitive, _)| primitive.size()))
+  }
+}
+
+/// An exported field type.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Type {
+  /// A primitive, with the byte order of the endian wrappers in [`crate::types`].
+  Primitive(Primitive, Option<Endian>),
+  /// A pointer to an exported struct, or to something unknown.
+  Pointer(Option<&'static str>),
+  /// A byte of a nul-terminated string.
+  Char,
+  /// An array of a number of elements.
+  Array(Box<Type>, usize),
+  /// An exported struct.
+  Struct(&'static str),
+  /// Any other type, exported as bytes.
+  Opaque
+}
+
+impl Type {
+  /// The exported struct this type contains by value.
+  fn value_dependency(&self) -> Option<&'static str> {
+    match self {
+      Type::Struct(name) => Some(name),
+      Type::Array(element, _) => element.value_dependency(),
+      _ => None
+    }
+  }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Primitive {
+  Bool,
+  Char,
+  U8,
+  U16,
+  U32,
+  U64,
+  Usize,
+  I8,
+  I16,
+  I32,
+  I64,
+  Isize,
+  F32,
+  F64
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Endian {
+  Little,
+  Big
+}
+
+impl Primitive {
+  fn parse(name: &str) -> Option<(Self, Option<Endian>)> {
+    let primitive = match name {
+      "bool" => Primitive::Bool,
+      "char" => Primitive::Char,
+      "u8" => Primitive::U8,
+      "u16" | "U16Le" | "U16Be" => Primitive::U16,
+      "u32" | "U32Le" | "U32Be" => Primitive::U32,
+      "u64" | "U64Le" | "U64Be" => Primitive::U64,
+      "usize" => Primitive::Usize,
+      "i8" => Primitive::I8,
+      "i16" | "I16Le" | "I16Be" => Primitive::I16,
+      "i32" | "I32Le" | "I32Be" => Primitive::I32,
+      "i64" | "I64Le" | "I64Be" => Primitive::I64,
+      "isize" => Primitive::Isize,
+      "f32" => Primitive::F32,
+      "f64" => Primitive::F64,
+      _ => return None
+    };
+    let endian = if name.ends_with("Le") {
+      Some(Endian::Little)
+    } else if name.ends_with("Be") {
+      Some(Endian::Big)
+    } else {
+      None
+    };
+    Some((primitive, endian))
+  }
+
+  fn size(self) -> usize {
+    match self {
+      Primitive::Bool | Primitive::U8 | Primitive::I8 => 1,
+      Primitive::U16 | Primitive::I16 => 2,
+      Primitive::Char | Primitive::U32 | Primitive::I32 | Primitive::F32 => 4,
+      Primitive::U64 | Primitive::I64 | Primitive::F64 => 8,
+      Primitive::Usize | Primitive::Isize => size_of::<usize>()
+    }
+  }
+}
+
+/// The pointee of a pointer-like type: raw pointers, references, `NonNull`, `RemotePtr`, function
+/// pointers and `Option`s of those.
+fn pointer_target(name: &str) -> Option<&str> {
+  let name = name.trim();
+  if let Some(inner) = generic_argument(name, "Option") {
+    return pointer_target(inner);
+  }
+  if let Some(inner) =
+    generic_argument(name, "NonNull").or_else(|| generic_argument(name, "RemotePtr"))
+  {
+    return Some(inner);
+  }
+  if name.starts_with("fn") || name.starts_with("unsafe ") || name.starts_with("extern ") {
+    return Some("");
+  }
+  let target = if let Some(rest) = name.strip_prefix('*') {
+    let rest = rest.trim_start();
+    rest
+      .strip_prefix("const")
+      .or_else(|| rest.strip_prefix("mut"))?
+  } else {
+    let rest = name.strip_prefix('&')?;
+    let rest = match rest.strip_prefix('\'') {
+      Some(lifetime) => lifetime.split_once(' ')?.1,
+      None => rest
+    }
+    .trim_start();
+    rest.strip_prefix("mut ").unwrap_or(rest)
+  };
+  Some(target.trim())
+}
+
+/// The argument of `Wrapper<T>` for a type named by any path ending in `wrapper`.
+fn generic_argument<'a>(name: &'a str, wrapper: &str) -> Option<&'a str> {
+  let inner = name.strip_suffix('>')?;
+  let open = inner.find('<')?;
+  (last_segment(&inner[..open]) == wrapper).then(|| &inner[open + 1..])
+}
+
+/// The last segment of a path like `crate::types::U32Be`.
+fn last_segment(name: &str) -> &str {
+  let name = name.trim();
+  let end = name.find('<').unwrap_or(name.len());
+  match name[..end].rfind("::") {
+    Some(split) => &name[split + 2..],
+    None => name
+  }
+}
+
+/// Escapes `text` for XML attributes and text.
+fn xml_escape(text: &str) -> String {
+  let mut out = String::with_capacity(text.len());
+  for c in text.chars() {
+    match c {
+      '&' => out.push_str("&amp;"),
+      '<' => out.push_str("&lt;"),
+      '>' => out.push_str("&gt;"),
+      '"' => out.push_str("&quot;"),
+      _ => out.push(c)
+    }
+  }
+  out
+}
+
+/// A range of bytes of an exported struct.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Region<'a> {
+  offset: usize,
+  size:   usize,
+  /// The field covering the range, `None` for padding and gaps.
+  field:  Option<&'a FieldInfo>,
+  /// Name of the `#[gap]` pseudo-field reserving the range.
+  gap:    Option<&'static str>
+}
+
+impl StructDef {
+  /// The fields and padding covering the struct without overlapping, in order. Overlapping fields
+  /// are left out and listed by [`StructDef::overlays`].
+  fn regions(&self) -> Vec<Region<'_>> {
+    let mut regions = Vec::new();
+    let mut cursor = 0;
+    for field in self.fields {
+      if field.kind == RegionKind::Overlay || field.offset < cursor || field.end() > self.size {
+        continue;
+      }
+      if field.offset > cursor {
+        regions.push(Region {
+          offset: cursor,
+          size:   field.offset - cursor,
+          field:  None,
+          gap:    None
+        });
+      }
+      let gap = field.kind == RegionKind::Gap;
+      if field.size > 0 {
+        regions.push(Region {
+          offset: field.offset,
+          size:   field.size,
+          field:  (!gap).then(|| field),
+          gap:    gap.then(|| field.name)
+        });
+      }
+      cursor = field.end();
+    }
+    if self.size > cursor {
+      regions.push(Region {
+        offset: cursor,
+        size:   self.size - cursor,
+        field:  None,
+        gap:    None
+      });
+    }
+    regions
+  }
+
+  /// The fields left out of [`StructDef::regions`] because they overlap another field.
+  fn overlays(&self) -> impl Iterator<Item = &FieldInfo> + '_ {
+    let regions = self.regions();
+    self.fields.iter().filter(move |field| {
+      !regions.iter().any(|region| {
+        region
+          .field
+          .map_or(false, |other| core::ptr::eq(other, *field))
+      }) && field.kind != RegionKind::Gap
+    })
+  }
+}
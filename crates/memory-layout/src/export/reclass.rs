@@ -0,0 +1,276 @@
+use alloc::{format, string::String, vec::Vec};
+use core::{fmt::Write, mem::size_of};
+
+use super::{xml_escape, Exporter, Primitive, Region, StructDef, Type};
+
+impl Exporter {
+  /// Renders the structs as the `Data.xml` of a ReClass.NET project, with a class per struct.
+  ///
+  /// Padding becomes hex nodes, exported structs are referenced through class instance and pointer
+  /// nodes, and types ReClass.NET can't display are exported as byte arrays with the Rust type as
+  /// comment. [`Exporter::reclass_project`] packs it into a `.rcnet` file ReClass.NET can open.
+  /// ```
+  /// use memory_layout::{export::Exporter, memory_layout};
+  ///
+  /// #[memory_layout(0x10, reflect)]
+  /// pub struct CEntity {
+  ///   #[field_offset(0x08)]
+  ///   pub health: f32
+  /// }
+  ///
+  /// let xml = Exporter::new().add::<CEntity>().reclass_xml();
+  /// assert!(xml.contains(r#"<node type="FloatNode" name="health" comment="" hidden="false" />"#));
+  /// ```
+  pub fn reclass_xml(&self) -> String {
+    let platform = if size_of::<usize>() == 8 {
+      "x64"
+    } else {
+      "x86"
+    };
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    let _ = writeln!(out, "<reclass version=\"65537\" type=\"{}\">", platform);
+    out.push_str("  <custom_data />\n  <enums />\n  <classes>\n");
+    for def in self.ordered() {
+      self.write_reclass_class(&mut out, def);
+    }
+    out.push_str("  </classes>\n</reclass>\n");
+    out
+  }
+
+  /// Renders the structs as a ReClass.NET project file, a zip archive holding
+  /// [`Exporter::reclass_xml`] as `Data.xml`.
+  pub fn reclass_project(&self) -> Vec<u8> {
+    zip_stored("Data.xml", self.reclass_xml().as_bytes())
+  }
+
+  fn write_reclass_class(&self, out: &mut String, def: &StructDef) {
+    let _ = writeln!(
+      out,
+      "    <class uuid=\"{}\" name=\"{}\" comment=\"\" address=\"0\">",
+      uuid(def.name),
+      xml_escape(def.name)
+    );
+    for region in def.regions() {
+      self.write_reclass_region(out, &region);
+    }
+    for overlay in def.overlays() {
+      let _ = writeln!(
+        out,
+        "      <!-- 0x{:04X} {}: {} (0x{:X} bytes), overlaps another field -->",
+        overlay.offset,
+        xml_escape(overlay.name),
+        xml_escape(overlay.type_name),
+        overlay.size
+      );
+    }
+    out.push_str("    </class>\n");
+  }
+
+  fn write_reclass_region(&self, out: &mut String, region: &Region) {
+    let field = match region.field {
+      Some(field) => field,
+      None => {
+        // Padding is split into the largest aligned hex nodes.
+        let mut offset = region.offset;
+        let end = region.offset + region.size;
+        while offset < end {
+          let size = [8, 4, 2, 1]
+            .iter()
+            .copied()
+            .find(|size| offset % size == 0 && offset + size <= end)
+            .unwrap_or(1);
+          let name = if offset == region.offset {
+            region.gap.unwrap_or("")
+          } else {
+            ""
+          };
+          write_node(out, 6, &hex_node(size), name, "", "");
+          out.push_str(" />\n");
+          offset += size;
+        }
+        return;
+      }
+    };
+
+    match self.resolve(field.type_name, field.size) {
+      Type::Primitive(primitive, Some(_)) => {
+        write_node(
+          out,
+          6,
+          &hex_node(primitive.size()),
+          field.name,
+          field.type_name,
+          ""
+        );
+        out.push_str(" />\n");
+      }
+      Type::Opaque => {
+        write_node(
+          out,
+          6,
+          "ArrayNode",
+          field.name,
+          field.type_name,
+          &format!(" count=\"{}\"", field.size)
+        );
+        out.push_str(">\n");
+        write_node(out, 8, "UInt8Node", "", "", "");
+        out.push_str(" />\n      </node>\n");
+      }
+      ty => {
+        write_reclass_node(out, 6, &ty, field.name);
+        out.push('\n');
+      }
+    }
+  }
+}
+
+/// Writes the node for `ty`, without a trailing newline.
+fn write_reclass_node(out: &mut String, indent: usize, ty: &Type, name: &str) {
+  let (kind, extra, inner) = match ty {
+    Type::Primitive(primitive, _) => {
+      let kind = match primitive {
+        Primitive::Bool => "BoolNode",
+        Primitive::Char | Primitive::U32 => "UInt32Node",
+        Primitive::U8 => "UInt8Node",
+        Primitive::U16 => "UInt16Node",
+        Primitive::U64 => "UInt64Node",
+        Primitive::Usize => "NUIntNode",
+        Primitive::I8 => "Int8Node",
+        Primitive::I16 => "Int16Node",
+        Primitive::I32 => "Int32Node",
+        Primitive::I64 => "Int64Node",
+        Primitive::Isize => "NIntNode",
+        Primitive::F32 => "FloatNode",
+        Primitive::F64 => "DoubleNode"
+      };
+      (kind, String::new(), None)
+    }
+    Type::Pointer(Some(target)) => ("PointerNode", String::new(), Some(Type::Struct(target))),
+    Type::Pointer(None) => ("PointerNode", String::new(), None),
+    Type::Char => ("Utf8TextNode", String::from(" length=\"1\""), None),
+    Type::Array(element, count) if **element == Type::Char => {
+      ("Utf8TextNode", format!(" length=\"{}\"", count), None)
+    }
+    Type::Array(element, count) => {
+      (
+        "ArrayNode",
+        format!(" count=\"{}\"", count),
+        Some((**element).clone())
+      )
+    }
+    Type::Struct(target) => {
+      (
+        "ClassInstanceNode",
+        format!(" reference=\"{}\"", uuid(target)),
+        None
+      )
+    }
+    Type::Opaque => ("UInt8Node", String::new(), None)
+  };
+  write_node(out, indent, kind, name, "", &extra);
+  match inner {
+    Some(inner) => {
+      out.push_str(">\n");
+      write_reclass_node(out, indent + 2, &inner, "");
+      let _ = write!(out, "\n{:indent$}</node>", "", indent = indent);
+    }
+    None => out.push_str(" />")
+  }
+}
+
+/// Writes the opening tag of a node without closing it.
+fn write_node(out: &mut String, indent: usize, kind: &str, name: &str, comment: &str, extra: &str) {
+  let _ = write!(
+    out,
+    "{:indent$}<node type=\"{}\" name=\"{}\" comment=\"{}\" hidden=\"false\"{}",
+    "",
+    kind,
+    xml_escape(name),
+    xml_escape(comment),
+    extra,
+    indent = indent
+  );
+}
+
+fn hex_node(size: usize) -> String {
+  format!("Hex{}Node", size * 8)
+}
+
+/// A stable ReClass.NET class id for the struct `name`: a base64 encoded GUID derived from the
+/// name.
+fn uuid(name: &str) -> String {
+  let hash = |seed: u64| {
+    name.bytes().fold(seed, |hash, byte| {
+      (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01B3)
+    })
+  };
+  let mut bytes = [0; 16];
+  bytes[..8].copy_from_slice(&hash(0xCBF2_9CE4_8422_2325).to_le_bytes());
+  bytes[8..].copy_from_slice(&hash(0x8422_2325_CBF2_9CE4).to_le_bytes());
+
+  const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+  let mut out = String::new();
+  for chunk in bytes.chunks(3) {
+    let value = chunk.iter().enumerate().fold(0u32, |value, (index, byte)| {
+      value | u32::from(*byte) << (16 - 8 * index)
+    });
+    for index in 0..4 {
+      if index <= chunk.len() {
+        out.push(ALPHABET[(value >> (18 - 6 * index) & 0x3F) as usize] as char);
+      } else {
+        out.push('=');
+      }
+    }
+  }
+  out
+}
+
+/// A zip archive holding `data` uncompressed as the file `name`.
+fn zip_stored(name: &str, data: &[u8]) -> Vec<u8> {
+  let crc = !data.iter().fold(!0u32, |crc, byte| {
+    (0..8).fold(crc ^ u32::from(*byte), |crc, _| {
+      if crc & 1 != 0 {
+        crc >> 1 ^ 0xEDB8_8320
+      } else {
+        crc >> 1
+      }
+    })
+  });
+  // Version 2.0, no flags, stored, 1980-01-01 00:00.
+  let common = [
+    &20u16.to_le_bytes()[..],
+    &0u16.to_le_bytes(),
+    &0u16.to_le_bytes(),
+    &0u16.to_le_bytes(),
+    &0x21u16.to_le_bytes(),
+    &crc.to_le_bytes(),
+    &(data.len() as u32).to_le_bytes(),
+    &(data.len() as u32).to_le_bytes(),
+    &(name.len() as u16).to_le_bytes(),
+    &0u16.to_le_bytes()
+  ]
+  .concat();
+
+  let mut zip = Vec::new();
+  zip.extend_from_slice(&0x0403_4B50u32.to_le_bytes());
+  zip.extend_from_slice(&common);
+  zip.extend_from_slice(name.as_bytes());
+  zip.extend_from_slice(data);
+
+  let directory = zip.len();
+  zip.extend_from_slice(&0x0201_4B50u32.to_le_bytes());
+  zip.extend_from_slice(&20u16.to_le_bytes());
+  zip.extend_from_slice(&common);
+  // No comment, disk 0, no attributes and the local header at offset 0.
+  zip.extend_from_slice(&[0; 14]);
+  zip.extend_from_slice(name.as_bytes());
+  let directory_size = zip.len() - directory;
+
+  zip.extend_from_slice(&0x0605_4B50u32.to_le_bytes());
+  zip.extend_from_slice(&[0, 0, 0, 0, 1, 0, 1, 0]);
+  zip.extend_from_slice(&(directory_size as u32).to_le_bytes());
+  zip.extend_from_slice(&(directory as u32).to_le_bytes());
+  zip.extend_from_slice(&0u16.to_le_bytes());
+  zip
+}
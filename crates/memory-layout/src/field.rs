@@ -0,0 +1,163 @@
+use core::{fmt, marker::PhantomData, mem, mem::MaybeUninit, ptr, slice};
+
+use crate::{remote::RemoteProcess, AnyBitPattern, NoUninit};
+
+/// Handle to a field of type `T` at `OFFSET` in the struct `S`.
+///
+/// Structs annotated with `#[memory_layout]` have one for every field without an `access` mode,
+/// named after the field in upper case. The handles are zero-sized, the type and offset are part of
+/// their type.
+/// ```rust
+/// use memory_layout::{memory_layout, Field};
+///
+/// #[memory_layout(0x20)]
+/// pub struct CPed {
+///   #[field_offset(0x10)]
+///   health: f32
+/// }
+///
+/// let health: Field<CPed, f32, 0x10> = CPed::HEALTH;
+/// assert_eq!(health.offset(), 0x10);
+/// ```
+///
+/// Generic code can access fields of any struct through them:
+/// ```rust
+/// use memory_layout::{memory_layout, remote::RemoteProcess, Field};
+///
+/// fn read_field<P: RemoteProcess, S, T: memory_layout::AnyBitPattern, const O: usize>(
+///   process: &P,
+///   base: usize,
+///   field: Field<S, T, O>
+/// ) -> Result<T, P::Error> {
+///   field.read(process, base)
+/// }
+/// ```
+///
+/// Handles access the bytes as they're stored, so the byte order of `#[endian(..)]` fields isn't
+/// converted.
+///
+/// A handle has the visibility of its field, so it can't read or write a private field from
+/// outside the module:
+/// ```rust,compile_fail
+/// mod entity {
+///   use memory_layout::memory_layout;
+///
+///   #[memory_layout(0x20)]
+///   pub struct CPed {
+///     #[field_offset(0x10)]
+///     health: f32
+///   }
+/// }
+///
+/// let health = entity::CPed::HEALTH;
+/// ```
+///
+/// Handles are invariant in `S` and `T`. Otherwise a handle to a `&'static str` field could be
+/// turned into one to a `&'a str` field, and [`set`](Field::set) would store a short-lived
+/// reference in it:
+/// ```rust,compile_fail
+/// use memory_layout::Field;
+///
+/// struct Names {
+///   name: &'static str
+/// }
+///
+/// fn shorten<'a>(field: Field<Names, &'static str, 0>) -> Field<Names, &'a str, 0> {
+///   field
+/// }
+/// ```
+pub struct Field<S, T, const OFFSET: usize> {
+  marker: PhantomData<(Invariant<S>, Invariant<T>)>
+}
+
+type Invariant<T> = fn(T) -> T;
+
+impl<S, T, const OFFSET: usize> Field<S, T, OFFSET> {
+  /// Creates a handle.
+  ///
+  /// # Safety
+  ///
+  /// `S` must contain a field of type `T` at `OFFSET`.
+  pub const unsafe fn new() -> Self {
+    Self {
+      marker: PhantomData
+    }
+  }
+
+  /// Offset of the field in bytes.
+  pub const fn offset(self) -> usize {
+    OFFSET
+  }
+
+  /// Size of the field in bytes.
+  pub const fn size(self) -> usize {
+    mem::size_of::<T>()
+  }
+
+  /// Reads the field of `value` without requiring it to be aligned.
+  pub fn get(self, value: &S) -> T
+  where
+    T: Copy
+  {
+    unsafe { ptr::read_unaligned((value as *const S as *const u8).add(OFFSET) as *const T) }
+  }
+
+  /// Writes the field of `value` without requiring it to be aligned.
+  pub fn set(self, value: &mut S, field: T)
+  where
+    T: Copy
+  {
+    unsafe { ptr::write_unaligned((value as *mut S as *mut u8).add(OFFSET) as *mut T, field) }
+  }
+
+  /// Reads the field from the raw bytes of a struct, `None` if they end before the field does.
+  pub fn from_bytes(self, bytes: &[u8]) -> Option<T>
+  where
+    T: AnyBitPattern
+  {
+    let bytes = bytes.get(OFFSET..OFFSET.checked_add(mem::size_of::<T>())?)?;
+    Some(unsafe { ptr::read_unaligned(bytes.as_ptr() as *const T) })
+  }
+
+  /// Reads the field of the struct at `base` in `process`.
+  pub fn read<P: RemoteProcess + ?Sized>(self, process: &P, base: usize) -> Result<T, P::Error>
+  where
+    T: AnyBitPattern
+  {
+    let mut field = MaybeUninit::<T>::zeroed();
+    process.read_bytes(base.wrapping_add(OFFSET), unsafe {
+      slice::from_raw_parts_mut(field.as_mut_ptr() as *mut u8, mem::size_of::<T>())
+    })?;
+    Ok(unsafe { field.assume_init() })
+  }
+
+  /// Writes the field of the struct at `base` in `process`. `T` must not contain uninitialized
+  /// bytes, such as padding.
+  pub fn write<P: RemoteProcess + ?Sized>(
+    self,
+    process: &P,
+    base: usize,
+    field: &T
+  ) -> Result<(), P::Error>
+  where
+    T: NoUninit
+  {
+    process.write_bytes(base.wrapping_add(OFFSET), unsafe {
+      slice::from_raw_parts(field as *const T as *const u8, mem::size_of::<T>())
+    })
+  }
+}
+
+impl<S, T, const OFFSET: usize> Clone for Field<S, T, OFFSET> {
+  fn clone(&self) -> Self {
+    *self
+  }
+}
+
+impl<S, T, const OFFSET: usize> Copy for Field<S, T, OFFSET> {}
+
+impl<S, T, const OFFSET: usize> fmt::Debug for Field<S, T, OFFSET> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "Field(0x{:X})", OFFSET)
+  }
+}
@@ -0,0 +1,109 @@
+use core::{fmt, mem::size_of, slice};
+
+use crate::{Layout, MemoryLayout};
+
+/// Bytes per line of a hexdump.
+const LINE: usize = 16;
+
+/// Dumps the bytes of `value` with every field annotated, see [`Hexdump`].
+///
+/// The fields of `T` must not contain uninitialized bytes of their own, like for the generated
+/// `as_bytes`.
+/// ```
+/// use memory_layout::memory_layout;
+///
+/// #[memory_layout(0x10)]
+/// pub struct CEntity {
+///   #[field_offset(0x08)]
+///   pub id: u32
+/// }
+///
+/// let entity = CEntity::from_bytes_checked(&[0, 0, 0, 0, 0, 0, 0, 0, 0x2A, 0, 0, 0, 0, 0, 0, 0])
+///   .unwrap();
+/// assert_eq!(
+///   memory_layout::hexdump(&entity).to_string(),
+///   "\
+/// CEntity (0x10 bytes)
+/// 0x0000  00 00 00 00 00 00 00 00                          <padding>
+/// 0x0008  2A 00 00 00                                      id
+/// 0x000C  00 00 00 00                                      <padding>
+/// "
+/// );
+/// ```
+pub fn hexdump<T: MemoryLayout>(value: &T) -> Hexdump<'_> {
+  let bytes = unsafe { slice::from_raw_parts(value as *const T as *const u8, size_of::<T>()) };
+  Hexdump::new(&T::LAYOUT, bytes)
+}
+
+/// Displays raw bytes of a struct line by line, each field and padding range starting on a new
+/// line annotated with its name. Fields longer than a line continue on the following lines.
+///
+/// Created by [`hexdump`] for an instance, or by [`Hexdump::new`] for a buffer such as a memory
+/// dump. Bytes past the end of a shorter buffer are left out.
+#[derive(Clone, Copy, Debug)]
+pub struct Hexdump<'a> {
+  layout: &'a Layout,
+  bytes:  &'a [u8]
+}
+
+impl<'a> Hexdump<'a> {
+  /// Dumps `bytes` annotated with the fields of `layout`.
+  pub fn new(layout: &'a Layout, bytes: &'a [u8]) -> Self {
+    Self { layout, bytes }
+  }
+
+  fn write_range(
+    &self,
+    f: &mut fmt::Formatter<'_>,
+    start: usize,
+    end: usize,
+    label: &str,
+    overlaps: bool
+  ) -> fmt::Result {
+    let end = end.min(self.bytes.len());
+    let mut offset = start;
+    while offset < end {
+      let line = &self.bytes[offset..end.min(offset + LINE)];
+      write!(f, "0x{:04X} ", offset)?;
+      for byte in line {
+        write!(f, " {:02X}", byte)?;
+      }
+      // Only the first line of a range is labeled.
+      if offset == start {
+        let padding = (LINE - line.len()) * 3 + 2;
+        write!(f, "{:width$}{}", "", label, width = padding)?;
+        if overlaps {
+          f.write_str(" (overlaps)")?;
+        }
+      }
+      writeln!(f)?;
+      offset += line.len();
+    }
+    Ok(())
+  }
+}
+
+impl fmt::Display for Hexdump<'_> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    writeln!(f, "{} (0x{:X} bytes)", self.layout.name, self.layout.size)?;
+
+    let mut cursor = 0usize;
+    for field in self.layout.fields {
+      if field.offset > cursor {
+        self.write_range(f, cursor, field.offset, "<padding>", false)?;
+      }
+      self.write_range(
+        f,
+        field.offset,
+        field.end(),
+        field.name,
+        field.offset < cursor
+      )?;
+      cursor = field.end().max(cursor);
+    }
+    if self.layout.size > cursor {
+      self.write_range(f, cursor, self.layout.size, "<padding>", false)?;
+    }
+    Ok(())
+  }
+}
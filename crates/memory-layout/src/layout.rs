@@ -0,0 +1,198 @@
+use core::{fmt, iter, mem, ops::Range};
+
+/// Describes a single field of a struct annotated with `#[memory_layout]`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct FieldDesc {
+  /// Name of the field.
+  pub name:   &'static str,
+  /// Offset of the field from the start of the struct, in bytes.
+  pub offset: usize,
+  /// Size of the field, in bytes.
+  pub size:   usize
+}
+
+impl FieldDesc {
+  /// Offset of the first byte after the field.
+  pub const fn end(&self) -> usize {
+    self.offset + self.size
+  }
+}
+
+/// What a region described by a [`FieldInfo`] holds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RegionKind {
+  /// A regular field.
+  Data,
+  /// Bytes reserved by a `#[gap]` pseudo-field.
+  Gap,
+  /// A field viewing the bytes of another field through `#[overlaps]`.
+  Overlay,
+  /// The `base` the struct embeds at offset 0.
+  Base
+}
+
+/// Describes a field of a struct annotated with `#[memory_layout(reflect)]`, including its type.
+///
+/// Listed by the generated `FIELDS` table.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct FieldInfo {
+  /// Name of the field.
+  pub name:      &'static str,
+  /// Offset of the field from the start of the struct, in bytes.
+  pub offset:    usize,
+  /// Size of the field, in bytes.
+  pub size:      usize,
+  /// Type of the field as written in the struct.
+  pub type_name: &'static str,
+  /// What the field holds.
+  pub kind:      RegionKind
+}
+
+impl FieldInfo {
+  /// Offset of the first byte after the field.
+  pub const fn end(&self) -> usize {
+    self.offset + self.size
+  }
+}
+
+/// Describes the layout of a struct annotated with `#[memory_layout]`.
+///
+/// Returned by the generated `layout()` function, the `Display` implementation prints a memory map
+/// of the struct including the padding between fields.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Layout {
+  /// Name of the struct.
+  pub name:   &'static str,
+  /// Total size of the struct, in bytes.
+  pub size:   usize,
+  /// Fields of the struct, ordered by offset.
+  pub fields: &'static [FieldDesc]
+}
+
+impl Layout {
+  /// Finds a field by name.
+  pub fn field(&self, name: &str) -> Option<&FieldDesc> {
+    self.fields.iter().find(|field| field.name == name)
+  }
+
+  /// Byte ranges that aren't covered by any field, including gaps. Overlapping fields are taken
+  /// into account.
+  pub fn padding(&self) -> impl Iterator<Item = Range<usize>> + '_ {
+    let starts = iter::once(0).chain(self.fields.iter().scan(0, |covered, field| {
+      *covered = field.end().max(*covered);
+      Some(*covered)
+    }));
+    let ends = self
+      .fields
+      .iter()
+      .map(|field| field.offset)
+      .chain(iter::once(self.size));
+    starts
+      .zip(ends)
+      .filter(|(start, end)| end > start)
+      .map(|(start, end)| start..end)
+  }
+}
+
+/// Implemented by every type annotated with `#[memory_layout]`, so generic code can work with
+/// their layouts.
+///
+/// # Example
+/// ```rust
+/// use memory_layout::{memory_layout, MemoryLayout};
+///
+/// #[memory_layout(0x20)]
+/// pub struct Foo {
+///   #[field_offset(0x10)]
+///   a: u32
+/// }
+///
+/// fn describe<T: MemoryLayout>() -> (usize, Option<usize>) {
+///   (T::SIZE, T::offset_of("a"))
+/// }
+///
+/// assert_eq!(describe::<Foo>(), (0x20, Some(0x10)));
+/// ```
+pub trait MemoryLayout: Sized {
+  /// Size of the type in bytes.
+  const SIZE: usize = mem::size_of::<Self>();
+  /// Alignment of the type in bytes.
+  const ALIGN: usize = mem::align_of::<Self>();
+  /// Layout of the type, the same value `layout()` returns.
+  const LAYOUT: Layout;
+
+  /// Offset of the field named `name`, if there is one.
+  fn offset_of(name: &str) -> Option<usize> {
+    Self::LAYOUT.field(name).map(|field| field.offset)
+  }
+}
+
+/// Implemented by types annotated with `#[memory_layout(reflect)]`, exposing their `FIELDS` table
+/// to generic code such as the exporters.
+pub trait Reflect: MemoryLayout {
+  /// Every field of the type with its type, the same table as the generated `FIELDS` constant.
+  const FIELDS: &'static [FieldInfo];
+}
+
+impl fmt::Display for Layout {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    writeln!(f, "{} (0x{:X} bytes)", self.name, self.size)?;
+
+    let mut cursor = 0usize;
+    for field in self.fields {
+      if field.offset > cursor {
+        writeln!(f, "  0x{:04X}..0x{:04X}  <padding>", cursor, field.offset)?;
+      }
+      writeln!(
+        f,
+        "  0x{:04X}..0x{:04X}  {} (0x{:X} bytes)",
+        field.offset,
+        field.end(),
+        field.name,
+        field.size
+      )?;
+      cursor = field.end().max(cursor);
+    }
+
+    if self.size > cursor {
+      writeln!(f, "  0x{:04X}..0x{:04X}  <padding>", cursor, self.size)?;
+    }
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{FieldDesc, Layout};
+
+  #[test]
+  fn test_display() {
+    let layout = Layout {
+      name:   "Foo",
+      size:   0x10,
+      fields: &[
+        FieldDesc {
+          name:   "a",
+          offset: 0x00,
+          size:   4
+        },
+        FieldDesc {
+          name:   "b",
+          offset: 0x08,
+          size:   4
+        }
+      ]
+    };
+
+    assert_eq!(
+      layout.to_string(),
+      "Foo (0x10 bytes)\n  0x0000..0x0004  a (0x4 bytes)\n  0x0004..0x0008  <padding>\n  0x0008..0x000C  b \
+       (0x4 bytes)\n  0x000C..0x0010  <padding>\n"
+    );
+    assert_eq!(
+      layout.padding().collect::<Vec<_>>(),
+      [0x04..0x08, 0x0C..0x10]
+    );
+  }
+}
@@ -1,10 +1,86 @@
-pub use memory_layout_codegen::memory_layout;
+#![cfg_attr(not(any(test, feature = "std")), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+extern crate self as memory_layout;
+
+mod cstr;
+#[cfg(feature = "alloc")]
+mod diagram;
+#[cfg(feature = "alloc")]
+mod diff;
+mod error;
+#[cfg(feature = "alloc")]
+pub mod export;
+mod field;
+mod hexdump;
+mod layout;
+mod macros;
+mod marker;
+pub mod remote;
+pub mod scan;
+mod strided;
+pub mod types;
+mod vtable;
+
+pub use cstr::{CStrArray, CapacityError};
+#[cfg(feature = "alloc")]
+pub use diff::{diff, BytesDiff, FieldChange, LayoutDiff};
+pub use error::LayoutError;
+pub use field::Field;
+pub use hexdump::{hexdump, Hexdump};
+pub use layout::{FieldDesc, FieldInfo, Layout, MemoryLayout, Reflect, RegionKind};
+pub use marker::{AnyBitPattern, NoUninit, Zeroable};
+pub use memory_layout_codegen::{declare_layout, field_offset, memory_layout, vtable_layout};
+pub use strided::Strided;
+pub use vtable::virtual_function;
+
+#[doc(hidden)]
+pub mod __private {
+  #[cfg(feature = "alloc")]
+  pub use alloc::string::String;
+
+  #[cfg(feature = "memoffset")]
+  pub use memoffset_crate::offset_of;
+}
 
 #[cfg(test)]
 mod tests {
   use core::mem::size_of;
 
-  use crate::memory_layout;
+  use crate::{
+    memory_layout, reexport_layout, remote::RemoteProcess, FieldDesc, Layout, LayoutError,
+    MemoryLayout
+  };
+
+  /// Process whose memory is a local buffer, failing with the address of out of bounds accesses.
+  struct FakeProcess<const N: usize>(core::cell::RefCell<[u8; N]>);
+
+  impl<const N: usize> FakeProcess<N> {
+    fn new() -> Self {
+      FakeProcess(core::cell::RefCell::new([0; N]))
+    }
+  }
+
+  impl<const N: usize> RemoteProcess for FakeProcess<N> {
+    type Error = usize;
+
+    fn read_bytes(&self, address: usize, buffer: &mut [u8]) -> Result<(), usize> {
+      let memory = self.0.borrow();
+      let bytes = memory.get(address..address + buffer.len()).ok_or(address)?;
+      buffer.copy_from_slice(bytes);
+      Ok(())
+    }
+
+    fn write_bytes(&self, address: usize, bytes: &[u8]) -> Result<(), usize> {
+      let mut memory = self.0.borrow_mut();
+      let target = memory
+        .get_mut(address..address + bytes.len())
+        .ok_or(address)?;
+      target.copy_from_slice(bytes);
+      Ok(())
+    }
+  }
 
   #[test]
   fn test_size() {
@@ -22,4 +98,3087 @@ mod tests {
 
     assert_eq!(size_of::<Foo>(), 0x38, "`Foo` should be 0x38 bytes in size")
   }
+
+  #[test]
+  fn test_gap() {
+    #[memory_layout(0x110)]
+    pub struct Foo {
+      #[field_offset(0x00)]
+      pub a: i32,
+
+      #[gap(0x104)]
+      pub reserved: (),
+
+      #[field_offset(0x104)]
+      pub b: i32
+    }
+
+    let _: fn(Foo) -> [u8; 0x100] = |foo| foo.reserved;
+
+    assert_eq!(
+      size_of::<Foo>(),
+      0x110,
+      "`Foo` should be 0x110 bytes in size"
+    )
+  }
+
+  #[test]
+  fn test_generic_default() {
+    #[memory_layout]
+    pub struct Foo<T = u32> {
+      #[field_offset(0x00)]
+      pub a: u8,
+
+      #[field_offset(0x08)]
+      pub b: T
+    }
+
+    let foo: Foo = Foo {
+      __pad0: [0; 0],
+      a:      1,
+      __pad1: [0; 7],
+      b:      0xDEAD_BEEF
+    };
+
+    assert_eq!(foo.a(), 1);
+    assert_eq!(foo.b(), 0xDEAD_BEEF);
+    assert_eq!(size_of::<Foo>(), 0x0C, "`Foo` should be 0x0C bytes in size")
+  }
+
+  #[test]
+  fn test_layout() {
+    #[memory_layout(0x20)]
+    pub struct Foo {
+      #[field_offset(0x04)]
+      pub a: u16,
+
+      #[field_offset(0x10)]
+      pub b: u64
+    }
+
+    let layout = Foo::layout();
+    assert_eq!(layout.name, "Foo");
+    assert_eq!(layout.size, 0x20);
+    assert_eq!(
+      layout.fields,
+      &[
+        FieldDesc {
+          name:   "a",
+          offset: 0x04,
+          size:   2
+        },
+        FieldDesc {
+          name:   "b",
+          offset: 0x10,
+          size:   8
+        }
+      ]
+    );
+    assert_eq!(layout.field("b").map(FieldDesc::end), Some(0x18));
+  }
+
+  #[test]
+  fn test_offset_from_end() {
+    #[memory_layout(0x40)]
+    pub struct Foo {
+      #[field_offset(0x00)]
+      pub magic: u32,
+
+      #[field_offset(end = 8)]
+      pub checksum: u64
+    }
+
+    assert_eq!(size_of::<Foo>(), 0x40, "`Foo` should be 0x40 bytes in size");
+    assert_eq!(
+      Foo::layout().field("checksum").map(|field| field.offset),
+      Some(0x38)
+    );
+  }
+
+  #[test]
+  fn test_getters() {
+    #[memory_layout]
+    pub struct Foo {
+      #[field_offset(0x01)]
+      pub a: u32,
+
+      #[field_offset(0x07)]
+      pub b: f64
+    }
+
+    let foo = Foo {
+      __pad0: [0; 1],
+      a:      0x12345678,
+      __pad1: [0; 2],
+      b:      1.0
+    };
+    assert_eq!(foo.a(), 0x12345678);
+    assert_eq!(foo.b(), 1.0);
+  }
+
+  #[test]
+  fn test_setters() {
+    #[memory_layout(0x11)]
+    pub struct Foo {
+      #[field_offset(0x03)]
+      pub a: u16,
+
+      #[field_offset(0x09)]
+      pub b: u64
+    }
+
+    let mut foo = Foo::from_bytes_checked(&[0; 0x11]).unwrap();
+    foo.set_a(0xBEEF);
+    foo.set_b(u64::MAX);
+    assert_eq!((foo.a(), foo.b()), (0xBEEF, u64::MAX));
+    assert_eq!(&foo.as_bytes()[0x09..0x11], &[0xFF; 8]);
+    assert_eq!(foo.as_bytes()[0x00..0x03], [0; 3]);
+  }
+
+  #[test]
+  fn test_pointer_accessors() {
+    #[memory_layout(0x10)]
+    pub struct Foo {
+      #[field_offset(0x05)]
+      pub a: u32
+    }
+
+    let mut foo = Foo::from_bytes_checked(&[0; 0x10]).unwrap();
+    let base = &foo as *const Foo as usize;
+    assert_eq!(foo.a_ptr() as usize - base, 0x05);
+    unsafe { foo.a_ptr_mut().write_unaligned(7) };
+    assert_eq!(foo.a(), 7);
+  }
+
+  #[test]
+  fn test_volatile() {
+    #[memory_layout(0x10, volatile)]
+    #[repr(C)]
+    pub struct Registers {
+      #[field_offset(0x04)]
+      pub status: u32,
+
+      #[field_offset(0x08)]
+      pub data: [u8; 4]
+    }
+
+    #[memory_layout(size = 0x10, align = 0x10)]
+    pub struct Mixed {
+      #[field_offset(0x00)]
+      pub plain: u32,
+
+      #[field_offset(0x08)]
+      #[volatile]
+      pub control: u64
+    }
+
+    let mut registers = Registers::from_bytes_checked(&[0; 0x10]).unwrap();
+    registers.set_status(0x8000_0001);
+    registers.set_data([1, 2, 3, 4]);
+    assert_eq!(registers.status(), 0x8000_0001);
+    assert_eq!(registers.data(), [1, 2, 3, 4]);
+
+    let mut mixed = Mixed::from_bytes_checked(&[0; 0x10]).unwrap();
+    mixed.set_control(3);
+    assert_eq!((mixed.plain(), mixed.control()), (0, 3));
+  }
+
+  #[test]
+  fn test_access() {
+    #[memory_layout(0x0C)]
+    pub struct Registers {
+      #[field_offset(0x00)]
+      #[access(ro)]
+      pub status: u32,
+
+      #[field_offset(0x04)]
+      #[access(wo)]
+      pub command: u32,
+
+      #[field_offset(0x08)]
+      #[access(rw)]
+      pub data: u32
+    }
+
+    let mut registers = Registers::from_bytes_checked(&[0; 0x0C]).unwrap();
+    registers.set_command(1);
+    registers.set_data(2);
+    assert_eq!((registers.status(), registers.data()), (0, 2));
+    assert_eq!(unsafe { registers.command_ptr().read_unaligned() }, 1);
+
+    let mut other = Registers::from_bytes_checked(&[0xFF; 0x0C]).unwrap();
+    other.clone_fields_from(&registers);
+    assert_eq!((other.status(), other.data()), (u32::MAX, 2));
+  }
+
+  #[test]
+  fn test_debug() {
+    #[memory_layout(0x20, debug)]
+    pub struct Foo {
+      #[field_offset(0x01)]
+      pub a: u32,
+
+      #[field_offset(0x10)]
+      pub b: [u8; 2],
+
+      #[field_offset(0x18)]
+      #[access(wo)]
+      pub c: u8
+    }
+
+    #[memory_layout(debug)]
+    pub struct Wrapper<T>
+    where
+      T: Copy
+    {
+      #[field_offset(0x02)]
+      pub value: T
+    }
+
+    let mut foo = Foo::from_bytes_checked(&[0; 0x20]).unwrap();
+    foo.set_a(7);
+    foo.set_b([1, 2]);
+    let wrapper = Wrapper {
+      __pad0: [0; 2],
+      value:  3u16
+    };
+    assert_eq!(format!("{:?}", wrapper), "Wrapper {value @ 0x2: 3}");
+    assert_eq!(format!("{:?}", foo), "Foo {a @ 0x1: 7, b @ 0x10: [1, 2]}");
+  }
+
+  #[test]
+  fn test_eq_hash() {
+    use std::{
+      collections::hash_map::DefaultHasher,
+      hash::{Hash, Hasher}
+    };
+
+    #[memory_layout(0x10, eq, hash)]
+    pub struct Foo {
+      #[field_offset(0x01)]
+      pub a: u32,
+
+      #[field_offset(0x08)]
+      pub b: [u8; 2]
+    }
+
+    #[memory_layout(eq)]
+    pub struct Float {
+      #[field_offset(0x01)]
+      pub a: f32
+    }
+
+    fn hash(foo: &Foo) -> u64 {
+      let mut hasher = DefaultHasher::new();
+      foo.hash(&mut hasher);
+      hasher.finish()
+    }
+
+    let mut bytes = [0xAA; 0x10];
+    bytes[0x01..0x05].copy_from_slice(&7u32.to_ne_bytes());
+    bytes[0x08..0x0A].copy_from_slice(&[1, 2]);
+    let ours = unsafe { core::ptr::read_unaligned(bytes.as_ptr() as *const Foo) };
+    let mut theirs = Foo::from_bytes_checked(&[0; 0x10]).unwrap();
+    theirs.set_a(7);
+    theirs.set_b([1, 2]);
+
+    assert!(ours == theirs);
+    assert_eq!(hash(&ours), hash(&theirs));
+    theirs.set_b([1, 3]);
+    assert!(ours != theirs);
+
+    let float = Float {
+      __pad0: [0; 1],
+      a:      f32::NAN
+    };
+    assert!(float != float);
+  }
+
+  #[test]
+  fn test_zeroed_default() {
+    #[memory_layout(0x20, default)]
+    pub struct Foo {
+      #[field_offset(0x04)]
+      pub a: u32,
+
+      #[gap(0x10)]
+      reserved: (),
+
+      #[field_offset(0x10)]
+      pub b: Bar
+    }
+
+    #[memory_layout(0x08)]
+    #[derive(Clone, Copy)]
+    pub struct Bar {
+      #[field_offset(0x02)]
+      pub c: Wrapper
+    }
+
+    #[derive(Clone, Copy, Default)]
+    pub struct Wrapper(u16);
+
+    unsafe impl crate::Zeroable for Wrapper {}
+    unsafe impl crate::AnyBitPattern for Wrapper {}
+    unsafe impl crate::NoUninit for Wrapper {}
+
+    impl Default for Bar {
+      fn default() -> Self {
+        let mut bar = Bar::from_bytes_checked(&[0; 0x08]).unwrap();
+        bar.set_c(Wrapper(5));
+        bar
+      }
+    }
+
+    #[memory_layout]
+    pub struct Zeroable {
+      #[field_offset(0x01)]
+      pub a: [f32; 2]
+    }
+
+    let foo = Foo::default();
+    assert_eq!((foo.a(), foo.b().c().0), (0, 5));
+    assert!(foo.as_bytes()[0x08..0x10].iter().all(|byte| *byte == 0));
+    assert!(Zeroable::zeroed().as_bytes().iter().all(|byte| *byte == 0));
+  }
+
+  #[test]
+  fn test_byte_array_getter() {
+    #[memory_layout]
+    pub struct Foo {
+      #[field_offset(0x00)]
+      pub a: u8,
+
+      #[field_offset(0x01)]
+      pub blob: [u8; 64]
+    }
+
+    let mut blob = [0u8; 64];
+    blob[63] = 0xFF;
+    let foo = Foo {
+      __pad0: [0; 0],
+      a: 0,
+      __pad1: [0; 0],
+      blob
+    };
+
+    let borrowed: &[u8; 64] = foo.blob();
+    assert_eq!(borrowed, &blob);
+    assert_eq!(
+      borrowed.as_ptr() as usize - (&foo as *const Foo as usize),
+      1
+    );
+  }
+
+  #[test]
+  fn test_clone_fields_from() {
+    #[memory_layout(0x10)]
+    pub struct Foo {
+      #[field_offset(0x01)]
+      pub a: u32,
+
+      #[field_offset(0x08)]
+      pub b: [u8; 4]
+    }
+
+    let source = Foo {
+      __pad0: [0xAA; 1],
+      a:      0x1234_5678,
+      __pad1: [0xAA; 3],
+      b:      [1, 2, 3, 4],
+      __pad2: [0xAA; 4]
+    };
+    let mut target = Foo {
+      __pad0: [0; 1],
+      a:      0,
+      __pad1: [0; 3],
+      b:      [0; 4],
+      __pad2: [0; 4]
+    };
+
+    target.clone_fields_from(&source);
+
+    assert_eq!(target.a(), 0x1234_5678);
+    assert_eq!(target.b(), &[1, 2, 3, 4]);
+    assert_eq!(target.__pad0, [0; 1], "padding should be left untouched");
+    assert_eq!(target.__pad1, [0; 3], "padding should be left untouched");
+    assert_eq!(target.__pad2, [0; 4], "padding should be left untouched");
+
+    target.set_a(7);
+    assert_eq!(target.a(), 7);
+  }
+
+  #[test]
+  fn test_offset_after() {
+    #[memory_layout]
+    pub struct Small {
+      #[field_offset(0x00)]
+      pub a: u32,
+
+      #[field_offset(after(a))]
+      pub b: u8,
+
+      #[field_offset(after(b) + 0x10)]
+      pub c: u8
+    }
+
+    #[memory_layout]
+    pub struct Large {
+      #[field_offset(0x00)]
+      pub a: u64,
+
+      #[field_offset(after(a))]
+      pub b: u8,
+
+      #[field_offset(after(b) + 0x10)]
+      pub c: u8
+    }
+
+    let offset = |layout: &Layout, name| layout.field(name).map(|field| field.offset);
+    assert_eq!(offset(Small::layout(), "b"), Some(0x04));
+    assert_eq!(offset(Small::layout(), "c"), Some(0x15));
+    assert_eq!(offset(Large::layout(), "b"), Some(0x08));
+    assert_eq!(offset(Large::layout(), "c"), Some(0x19));
+    assert_eq!(
+      size_of::<Large>(),
+      0x1A,
+      "`Large` should be 0x1A bytes in size"
+    );
+  }
+
+  #[test]
+  fn test_strict() {
+    #[memory_layout(0x10, strict)]
+    pub struct Exact {
+      #[field_offset(0x08)]
+      pub a: u64
+    }
+
+    #[memory_layout(0x10, strict)]
+    pub struct Reserved {
+      #[field_offset(0x00)]
+      pub a: u64,
+
+      #[gap(0x10)]
+      pub reserved: ()
+    }
+
+    assert_eq!(
+      size_of::<Exact>(),
+      0x10,
+      "`Exact` should be 0x10 bytes in size"
+    );
+    assert_eq!(
+      size_of::<Reserved>(),
+      0x10,
+      "`Reserved` should be 0x10 bytes in size"
+    );
+  }
+
+  #[test]
+  fn test_field_align() {
+    #[memory_layout(0x30)]
+    pub struct Foo {
+      #[field_offset(0x00)]
+      pub a: u8,
+
+      #[field_offset(0x20)]
+      #[field_align(16)]
+      pub vec: [f32; 4]
+    }
+
+    #[repr(align(16))]
+    struct Aligned(Foo);
+
+    let aligned = Aligned(Foo {
+      __pad0: [0; 0],
+      a:      0,
+      __pad1: [0; 0x1F],
+      vec:    [1.0, 2.0, 3.0, 4.0],
+      __pad2: [0; 0]
+    });
+
+    assert_eq!(aligned.0.vec_ref(), Some(&[1.0, 2.0, 3.0, 4.0]));
+  }
+
+  #[test]
+  fn test_field_bytes() {
+    #[memory_layout(0x10)]
+    pub struct Foo {
+      #[field_offset(0x02)]
+      pub a: u16,
+
+      #[field_offset(0x08)]
+      pub b: u32
+    }
+
+    let foo = Foo {
+      __pad0: [0; 2],
+      a:      0x1122,
+      __pad1: [0; 4],
+      b:      0x3344_5566,
+      __pad2: [0; 4]
+    };
+
+    let mut reconstructed = [0u8; 0x10];
+    for (name, offset, bytes) in foo.field_bytes() {
+      assert_eq!(Some(bytes.len()), Foo::layout().field(name).map(|f| f.size));
+      reconstructed[offset..offset + bytes.len()].copy_from_slice(bytes);
+    }
+
+    assert_eq!(&reconstructed[..], foo.as_bytes());
+    assert_eq!(
+      foo.field_bytes().map(|(name, ..)| name).collect::<Vec<_>>(),
+      ["a", "b"]
+    );
+  }
+
+  #[test]
+  fn test_opaque() {
+    #[memory_layout(0x20)]
+    pub struct Opaque {}
+
+    #[memory_layout(0x20)]
+    pub struct Unit;
+
+    assert_eq!(
+      size_of::<Opaque>(),
+      0x20,
+      "`Opaque` should be 0x20 bytes in size"
+    );
+    assert_eq!(
+      size_of::<Unit>(),
+      0x20,
+      "`Unit` should be 0x20 bytes in size"
+    );
+    assert!(Opaque::layout().fields.is_empty());
+  }
+
+  mod offsets {
+    pub const B: usize = 0x10;
+    pub const C: usize = 0x1C;
+    pub const SIZE: usize = 0x20;
+  }
+
+  #[test]
+  fn test_offset_path() {
+    #[memory_layout(0x20)]
+    pub struct Foo {
+      #[field_offset(0x00)]
+      pub a: u32,
+
+      #[field_offset(offsets::B)]
+      pub b: u32,
+
+      #[field_offset(self::offsets::C)]
+      pub c: u32
+    }
+
+    assert_eq!(Foo::layout().field("b").map(|f| f.offset), Some(offsets::B));
+    assert_eq!(size_of::<Foo>(), 0x20, "`Foo` should be 0x20 bytes in size");
+  }
+
+  #[test]
+  fn test_crate_path_offset() {
+    #[memory_layout(crate::tests::offsets::SIZE)]
+    pub struct Foo {
+      #[field_offset(::core::mem::size_of::<u64>())]
+      pub a: u32,
+
+      #[field_offset(crate::tests::offsets::B)]
+      pub b: u32
+    }
+
+    assert_eq!((Foo::OFFSET_A, Foo::OFFSET_B), (0x08, offsets::B));
+    assert_eq!(size_of::<Foo>(), offsets::SIZE);
+  }
+
+  #[test]
+  fn test_builder() {
+    #[memory_layout(0x10, builder)]
+    pub struct Foo {
+      #[field_offset(0x01)]
+      pub a: u32,
+
+      #[field_offset(0x08)]
+      pub b: u16
+    }
+
+    let foo = Foo::builder().set_b(0xBEEF).set_a(0x1234_5678).build();
+
+    assert_eq!(foo.a(), 0x1234_5678);
+    assert_eq!(foo.b(), 0xBEEF);
+    assert!(
+      foo.as_bytes()[..1]
+        .iter()
+        .chain(&foo.as_bytes()[5..8])
+        .chain(&foo.as_bytes()[10..])
+        .all(|byte| *byte == 0),
+      "padding should be zeroed"
+    );
+
+    #[memory_layout(builder)]
+    pub struct Generic<T = u16> {
+      #[field_offset(0x00)]
+      pub a: u8,
+
+      #[field_offset(0x04)]
+      pub b: T
+    }
+
+    let generic: Generic<u64> = Generic::builder().set_a(1).set_b(2).build();
+    assert_eq!((generic.a(), generic.b()), (1, 2));
+  }
+
+  #[test]
+  fn test_computed() {
+    #[memory_layout]
+    pub struct Foo {
+      #[field_offset(0x00)]
+      pub lo: u8,
+
+      #[field_offset(0x04)]
+      pub hi: u8,
+
+      /// `lo` and `hi` combined.
+      #[computed(u16::from_le_bytes([self.lo(), self.hi()]))]
+      pub word: u16
+    }
+
+    let foo = Foo {
+      __pad0: [0; 0],
+      lo:     0x34,
+      __pad1: [0; 3],
+      hi:     0x12
+    };
+
+    assert_eq!(foo.word(), 0x1234);
+    assert_eq!(size_of::<Foo>(), 0x05, "`Foo` should be 0x05 bytes in size");
+  }
+
+  #[test]
+  fn test_unsized_array() {
+    #[memory_layout(0x08)]
+    pub struct Entry {
+      #[field_offset(0x00)]
+      pub id:    u32,
+      #[field_offset(0x04)]
+      pub value: u32
+    }
+
+    #[memory_layout(0x10)]
+    pub struct Table {
+      #[field_offset(0x08)]
+      pub count:   u32,
+      /// Entries following the header.
+      #[field_offset(0x10)]
+      pub entries: [Entry]
+    }
+
+    #[memory_layout]
+    pub struct Packet {
+      #[field_offset(0x00)]
+      pub len:  u16,
+      pub data: [u8]
+    }
+
+    assert_eq!(size_of::<Table>(), 0x10);
+    assert_eq!(Table::OFFSET_ENTRIES, 0x10);
+    assert_eq!(Table::size_with_entries(3), 0x28);
+    assert_eq!(Table::size_with_entries(0), 0x10);
+    assert_eq!(Packet::OFFSET_DATA, 0x02);
+    assert_eq!(Packet::size_with_data(4), 0x06);
+
+    let mut bytes = [0u8; 0x28];
+    bytes[0x08] = 3;
+    for index in 0..3 {
+      bytes[0x10 + index * 8] = index as u8;
+      bytes[0x14 + index * 8] = 10 * index as u8;
+    }
+    let table = unsafe { &mut *(bytes.as_mut_ptr() as *mut Table) };
+    let count = table.count() as usize;
+    let entries = unsafe { table.entries(count) };
+    assert_eq!(entries.len(), 3);
+    assert_eq!((entries[2].id(), entries[2].value()), (2, 20));
+
+    let entries = unsafe { table.entries_mut(count) };
+    entries[1].set_value(7);
+    assert_eq!(bytes[0x1C], 7);
+  }
+
+  #[test]
+  fn test_reexport_layout() {
+    #[memory_layout]
+    pub struct Foo {
+      #[field_offset(0x08)]
+      pub a: u32,
+
+      #[field_offset(0x10)]
+      pub r#type: u8
+    }
+
+    reexport_layout!(pub Bar = Foo);
+
+    assert_eq!(Bar::OFFSET_A, Foo::OFFSET_A);
+    assert_eq!(Bar::OFFSET_A, 0x08);
+    assert_eq!(Bar::OFFSET_TYPE, 0x10);
+    assert_eq!(Bar::layout(), Foo::layout());
+  }
+
+  #[test]
+  fn test_repr_c() {
+    #[memory_layout(0x20)]
+    #[repr(C)]
+    pub struct Foo {
+      #[field_offset(0x04)]
+      pub a: u32,
+
+      #[field_offset(0x10)]
+      pub b: u64
+    }
+
+    let foo = Foo {
+      __pad0: [0; 4],
+      a:      1,
+      __pad1: [0; 8],
+      b:      2,
+      __pad2: [0; 8]
+    };
+
+    assert_eq!((&foo.a, &foo.b), (&1, &2), "fields should be referenceable");
+    assert_eq!(size_of::<Foo>(), 0x20, "`Foo` should be 0x20 bytes in size");
+    assert_eq!(
+      core::mem::align_of::<Foo>(),
+      8,
+      "`Foo` should not be packed"
+    );
+  }
+
+  #[test]
+  fn test_sparse() {
+    #[memory_layout(0x4_0010, sparse, builder)]
+    pub struct Foo {
+      #[field_offset(0x00)]
+      pub a: u32,
+
+      #[gap(0x2_0000)]
+      pub reserved: (),
+
+      #[field_offset(0x4_0000)]
+      pub b: u32
+    }
+
+    let foo = Foo::builder().set_a(1).set_b(2).build();
+
+    assert_eq!((foo.a(), foo.b()), (1, 2));
+    assert_eq!(
+      size_of::<Foo>(),
+      0x4_0010,
+      "`Foo` should be 0x4_0010 bytes in size"
+    );
+  }
+
+  #[test]
+  fn test_pad_uninit() {
+    #[memory_layout(0x10, pad = uninit)]
+    pub struct Foo {
+      #[field_offset(0x04)]
+      pub a: u32,
+
+      #[field_offset(0x0C)]
+      pub b: u16
+    }
+
+    let memory = [0xABu8; 0x10];
+    let foo = Foo::view(&memory).unwrap();
+
+    assert_eq!((foo.a(), foo.b()), (0xABAB_ABAB, 0xABAB));
+    assert_eq!(size_of::<Foo>(), 0x10, "`Foo` should be 0x10 bytes in size");
+  }
+
+  #[test]
+  fn test_pad_fill() {
+    #[memory_layout(0x10, pad_fill = 0xAA, builder)]
+    pub struct Foo {
+      #[field_offset(0x02)]
+      pub a: u16,
+
+      #[gap(0x08)]
+      pub reserved: (),
+
+      #[field_offset(0x08)]
+      pub b: u32
+    }
+
+    let filled = [
+      0xAA, 0xAA, 1, 0, 0xAA, 0xAA, 0xAA, 0xAA, 2, 0, 0, 0, 0xAA, 0xAA, 0xAA, 0xAA
+    ];
+    let mut foo = Foo::zeroed();
+    foo.set_a(1);
+    foo.set_b(2);
+    assert_eq!(foo.as_bytes(), &filled);
+
+    let foo = Foo::builder().set_a(1).set_b(2).build();
+    assert_eq!(foo.as_bytes(), &filled);
+
+    let foo = Foo::from_bytes([0; 0x10]);
+    assert_eq!((foo.a(), foo.b()), (0, 0));
+    let mut zeroed = [0; 0x10];
+    zeroed[..0x02].fill(0xAA);
+    zeroed[0x04..0x08].fill(0xAA);
+    zeroed[0x0C..].fill(0xAA);
+    assert_eq!(foo.to_bytes(), zeroed);
+
+    #[memory_layout(0x10, pad_fill = 0xCC)]
+    pub struct Flags {
+      #[field_offset(0x00)]
+      pub a: u8,
+
+      #[field_offset(0x08)]
+      pub flags: u32,
+
+      #[bit_offset(0x08, 0, 4)]
+      pub mode: u32
+    }
+
+    let mut flags = Flags::zeroed();
+    assert_eq!((flags.flags(), flags.mode()), (0, 0));
+    flags.set_mode(5);
+    let bytes = flags.to_bytes();
+    assert_eq!((bytes[0x01], bytes[0x08]), (0xCC, 5));
+    assert_eq!(Flags::from_bytes(bytes).mode(), 5);
+  }
+
+  #[test]
+  fn test_deny_gaps() {
+    #[memory_layout(0x10, deny_gaps)]
+    pub struct Header {
+      #[field_offset(0x00)]
+      pub magic: u32,
+
+      #[gap(0x08)]
+      pub reserved: (),
+
+      #[field_offset(0x08)]
+      pub length: u64
+    }
+
+    assert_eq!(Header::OFFSET_LENGTH, 0x08);
+    assert_eq!(
+      size_of::<Header>(),
+      0x10,
+      "`Header` should be 0x10 bytes in size"
+    );
+  }
+
+  #[test]
+  fn test_round_to() {
+    #[memory_layout(round_to = 0x10)]
+    pub struct Foo {
+      #[field_offset(0x04)]
+      pub a: u32,
+
+      #[field_offset(0x10)]
+      pub b: u8
+    }
+
+    #[memory_layout(round_to = 0x08)]
+    pub struct Bar {
+      #[field_offset(0x00)]
+      pub a: u64
+    }
+
+    assert_eq!(size_of::<Foo>(), 0x20, "`Foo` should be 0x20 bytes in size");
+    assert_eq!(size_of::<Bar>(), 0x08, "`Bar` should be 0x08 bytes in size");
+  }
+
+  #[test]
+  fn test_slice_between() {
+    #[memory_layout(0x10)]
+    pub struct Foo {
+      #[field_offset(0x00)]
+      pub a: u8,
+
+      #[field_offset(0x02)]
+      pub b: u16,
+
+      #[field_offset(0x04)]
+      pub c: u32,
+
+      #[field_offset(0x0A)]
+      pub d: u16
+    }
+
+    let foo = Foo {
+      __pad0: [0; 0],
+      a:      0xAA,
+      __pad1: [0; 1],
+      b:      0xBBBB,
+      __pad2: [0; 0],
+      c:      0xCCCC_CCCC,
+      __pad3: [0; 2],
+      d:      0xDDDD,
+      __pad4: [0; 4]
+    };
+
+    assert_eq!(
+      foo.slice_between("b", "d"),
+      Some(&foo.as_bytes()[0x02..0x0C])
+    );
+    assert_eq!(
+      foo.slice_between("c", "c"),
+      Some(&0xCCCC_CCCCu32.to_ne_bytes()[..])
+    );
+    assert_eq!(foo.slice_between("d", "b"), None);
+    assert_eq!(foo.slice_between("c", "b"), None);
+    assert_eq!(foo.slice_between("a", "e"), None);
+  }
+
+  #[test]
+  fn test_name_value_offset() {
+    #[memory_layout(0x10)]
+    pub struct List {
+      #[field_offset(0x04)]
+      pub a: u32,
+
+      #[field_offset(0x08)]
+      pub b: u32
+    }
+
+    #[memory_layout(0x10)]
+    pub struct NameValue {
+      #[field_offset = 0x04]
+      pub a: u32,
+
+      #[field_offset = 0x08]
+      pub b: u32
+    }
+
+    assert_eq!(List::layout().fields, NameValue::layout().fields);
+    assert_eq!(size_of::<List>(), size_of::<NameValue>());
+  }
+
+  #[test]
+  fn test_field_constants() {
+    #[memory_layout(0x20)]
+    pub struct Foo {
+      #[field_offset(0x04)]
+      pub a: u16,
+
+      #[field_offset(0x10)]
+      pub b: [u32; 3]
+    }
+
+    assert_eq!((Foo::OFFSET_A, Foo::SIZE_A), (0x04, 2));
+    assert_eq!((Foo::OFFSET_B, Foo::SIZE_B), (0x10, 12));
+    assert_eq!((Foo::END_A, Foo::END_B), (0x06, 0x1C));
+  }
+
+  #[test]
+  fn test_cfg_field() {
+    #[memory_layout(builder)]
+    pub struct Enabled {
+      #[field_offset(0x00)]
+      pub a: u32,
+
+      #[field_offset(0x20)]
+      #[cfg(test)]
+      pub extra: u32
+    }
+
+    #[memory_layout(builder)]
+    pub struct Disabled {
+      #[field_offset(0x00)]
+      pub a: u32,
+
+      #[field_offset(0x20)]
+      #[cfg(not(test))]
+      pub extra: u32
+    }
+
+    #[memory_layout]
+    pub struct Middle {
+      #[field_offset(0x00)]
+      pub a: u32,
+
+      #[field_offset(0x10)]
+      #[cfg(not(test))]
+      pub extra: u64,
+
+      #[field_offset(0x18)]
+      pub b: u32
+    }
+
+    #[memory_layout(0x20)]
+    pub struct Adjacent {
+      pub a:     u32,
+      #[cfg(not(test))]
+      pub extra: u64,
+      pub b:     u32,
+      #[cfg(test)]
+      pub c:     u16,
+      pub d:     u8
+    }
+
+    assert_eq!(size_of::<Enabled>(), 0x24);
+    assert_eq!(size_of::<Disabled>(), 0x04);
+    assert_eq!(size_of::<Middle>(), 0x1C);
+    assert_eq!(size_of::<Adjacent>(), 0x20);
+    assert_eq!((Adjacent::OFFSET_B, Adjacent::OFFSET_C), (0x04, 0x08));
+    assert_eq!(Adjacent::OFFSET_D, 0x0A);
+    assert_eq!(Enabled::OFFSET_EXTRA, 0x20);
+    assert_eq!(Enabled::layout().fields.len(), 2);
+    assert_eq!(Disabled::layout().fields.len(), 1);
+    assert_eq!(Middle::OFFSET_B, 0x18);
+
+    let enabled = Enabled::builder().set_a(1).set_extra(2).build();
+    assert_eq!((enabled.a(), enabled.extra()), (1, 2));
+    assert_eq!(Disabled::builder().set_a(1).build().a(), 1);
+  }
+
+  #[test]
+  fn test_from_bytes_checked() {
+    #[memory_layout(0x10)]
+    pub struct Foo {
+      #[field_offset(0x02)]
+      pub a: u16,
+
+      #[field_offset(0x08)]
+      pub b: u32
+    }
+
+    let mut bytes = [0u8; 0x10];
+    bytes[0x02] = 0x34;
+    bytes[0x08] = 0x78;
+    let foo = Foo::from_bytes_checked(&bytes).unwrap();
+    assert_eq!((foo.a(), foo.b()), (0x34, 0x78));
+
+    bytes[0x05] = 1;
+    assert_eq!(
+      Foo::from_bytes_checked(&bytes).err(),
+      Some(LayoutError::NonZeroPadding { offset: 0x05 })
+    );
+
+    #[memory_layout(0x08)]
+    pub struct Flags {
+      #[field_offset(0x04)]
+      pub flags: u16,
+
+      #[bit_offset(0x05, 4, 4)]
+      pub level: u8
+    }
+
+    let flags = Flags::from_bytes_checked(&[0, 0, 0, 0, 0, 0xF0, 0, 0]).unwrap();
+    assert_eq!(flags.level(), 0xF);
+    assert_eq!(
+      Foo::from_bytes_checked(&bytes[..0x08]).err(),
+      Some(LayoutError::InvalidLength {
+        expected: 0x10,
+        actual:   0x08
+      })
+    );
+  }
+
+  #[test]
+  fn test_view() {
+    #[memory_layout(0x10)]
+    pub struct Foo {
+      #[field_offset(0x03)]
+      pub a: u32
+    }
+
+    #[memory_layout(size = 0x10, align = 0x08)]
+    pub struct Aligned {
+      #[field_offset(0x08)]
+      pub a: u64
+    }
+
+    #[repr(align(8))]
+    struct Buffer([u8; 0x11]);
+
+    let mut buffer = Buffer([0xFF; 0x11]);
+    buffer.0[0x03..0x07].copy_from_slice(&7u32.to_ne_bytes());
+    let foo = Foo::view(&buffer.0[..0x10]).unwrap();
+    assert_eq!(foo.a(), 7);
+    assert!(core::ptr::eq(foo.as_bytes(), &buffer.0[..0x10]));
+
+    assert!(Aligned::view(&buffer.0[..0x10]).is_ok());
+    assert_eq!(
+      Aligned::view(&buffer.0[1..]).err(),
+      Some(LayoutError::Misaligned { align: 0x08 })
+    );
+    assert_eq!(
+      Foo::view(&buffer.0).err(),
+      Some(LayoutError::InvalidLength {
+        expected: 0x10,
+        actual:   0x11
+      })
+    );
+  }
+
+  #[test]
+  fn test_pointer_width() {
+    #[memory_layout(x86 = 0x20, x64 = 0x40, debug)]
+    pub struct CPed {
+      pub vtable: *const (),
+      #[field_offset(x64 = 0x28, x86 = 0x14)]
+      pub health: f32,
+      pub armor:  u32
+    }
+
+    let (health, size) = if cfg!(target_pointer_width = "64") {
+      (0x28, 0x40)
+    } else {
+      (0x14, 0x20)
+    };
+    assert_eq!(CPed::OFFSET_HEALTH, health);
+    assert_eq!(CPed::OFFSET_ARMOR, health + 4);
+    assert_eq!(size_of::<CPed>(), size);
+  }
+
+  #[test]
+  fn test_versions() {
+    #[memory_layout(
+      size(old = 0x100, new = 0x120),
+      versions(old = cfg(not(test)), new = cfg(test)),
+      debug
+    )]
+    pub struct CPed {
+      #[field_offset(0x08)]
+      pub id:     u32,
+      #[field_offset(old = 0x40, new = 0x48)]
+      pub health: f32,
+      pub armor:  u32
+    }
+
+    #[memory_layout(0x20, versions(old = cfg(not(test)), new = cfg(test)))]
+    pub union Value {
+      #[field_offset(old = 0x08, new = 0x10)]
+      pub int: u32
+    }
+
+    assert_eq!(CPed::OFFSET_HEALTH, 0x48);
+    assert_eq!(CPed::OFFSET_ARMOR, 0x4C);
+    assert_eq!(size_of::<CPed>(), 0x120);
+    assert_eq!(Value::OFFSET_INT, 0x10);
+  }
+
+  #[test]
+  fn test_runtime_offsets() {
+    #[memory_layout(0x40, debug)]
+    pub struct CPed {
+      #[field_offset(0x04)]
+      pub id:     u32,
+      #[field_offset(runtime)]
+      pub health: f32,
+      #[field_offset(runtime)]
+      #[cfg(not(test))]
+      pub armor:  f32,
+      #[field_offset(runtime)]
+      pub flags:  u16,
+      pub team:   u8
+    }
+
+    assert_eq!(CPed::OFFSET_TEAM, 0x08);
+    assert_eq!(size_of::<CPed>(), 0x40);
+    assert_eq!(CPed::health_offset(), None);
+    assert_eq!(
+      CPed::set_offsets(0x10, 0x3F),
+      Err(LayoutError::FieldOutOfBounds {
+        field:  "flags",
+        offset: 0x3F,
+        size:   2
+      })
+    );
+    assert_eq!(CPed::health_offset(), None);
+
+    CPed::set_offsets(0x10, 0x3E).unwrap();
+    let mut ped = CPed::zeroed();
+    ped.set_id(1);
+    ped.set_health(100.0);
+    ped.set_flags(0xBEEF);
+    assert_eq!((ped.id(), ped.health(), ped.flags()), (1, 100.0, 0xBEEF));
+    assert_eq!(&ped.as_bytes()[0x10..0x14], &100f32.to_ne_bytes());
+    assert_eq!(&ped.as_bytes()[0x3E..0x40], &0xBEEFu16.to_ne_bytes());
+    assert_eq!(CPed::health_offset(), Some(0x10));
+
+    let mut bytes = ped.as_bytes().to_vec();
+    assert_eq!(CPed::from_bytes_checked(&bytes).unwrap().health(), 100.0);
+    bytes[0x20] = 1;
+    assert_eq!(
+      CPed::from_bytes_checked(&bytes).err(),
+      Some(LayoutError::NonZeroPadding { offset: 0x20 })
+    );
+  }
+
+  #[test]
+  fn test_scan() {
+    use crate::scan::{ScanError, Signature};
+
+    #[memory_layout(0x100)]
+    pub struct CPed {
+      #[field_offset(0x00)]
+      pub id:     u32,
+      // movss xmm0, [rcx + health]
+      #[field_offset(runtime)]
+      #[signature("F3 0F 10 81 ?? ?? ?? ??")]
+      pub health: f32,
+      // mov al, [rcx + team]; cmp al, ??
+      #[field_offset(runtime)]
+      #[signature("8A 41 ?? 3C ??", operand = 2)]
+      pub team:   u8
+    }
+
+    let module = [
+      0x90, 0xF3, 0x0F, 0x10, 0x81, 0x48, 0x00, 0x00, 0x00, 0x8A, 0x41, 0x20, 0x3C, 0x01
+    ];
+    assert_eq!(
+      CPed::scan_offsets(&module[..9]),
+      Err(ScanError::NotFound {
+        pattern: "8A 41 ?? 3C ??"
+      })
+    );
+    assert_eq!(CPed::health_offset(), None);
+    CPed::scan_offsets(&module).unwrap();
+    assert_eq!(
+      (CPed::health_offset(), CPed::team_offset()),
+      (Some(0x48), Some(0x20))
+    );
+
+    let mut too_far = module;
+    too_far[5] = 0xFE;
+    assert!(matches!(
+      CPed::scan_offsets(&too_far),
+      Err(ScanError::Layout(LayoutError::FieldOutOfBounds { .. }))
+    ));
+    assert_eq!(CPed::health_offset(), Some(0x48));
+
+    // call -0x10
+    let call = Signature::new("E8 ?? ?? ?? ??").rip_relative();
+    assert_eq!(
+      call.resolve(&[0x90; 0x20]),
+      Err(ScanError::NotFound {
+        pattern: call.pattern()
+      })
+    );
+    let mut code = [0x90; 0x20];
+    code[0x10..0x15].copy_from_slice(&[0xE8, 0xF0, 0xFF, 0xFF, 0xFF]);
+    assert_eq!(call.find(&code), Ok(0x10));
+    assert_eq!(call.resolve(&code), Ok(0x05));
+    assert_eq!(
+      Signature::new("E8 ?? ??").operand(0).resolve(&code),
+      Err(ScanError::InvalidPattern {
+        pattern: "E8 ?? ??"
+      })
+    );
+    assert!(Signature::new("E8 XY").find(&code).is_err());
+    assert!(Signature::new("").find(&code).is_err());
+  }
+
+  #[test]
+  fn test_field_offset_macro() {
+    #[memory_layout]
+    pub struct Foo<T> {
+      #[field_offset(0x04)]
+      pub a:      u8,
+      #[field_offset(0x10)]
+      pub r#type: T
+    }
+
+    const A: usize = crate::field_offset!(Foo<u32>, a);
+    assert_eq!(A, 0x04);
+    assert_eq!(crate::field_offset!(Foo<u64>, r#type,), 0x10);
+  }
+
+  #[test]
+  fn test_reflect() {
+    use crate::{FieldInfo, RegionKind};
+
+    #[memory_layout(0x10)]
+    pub struct CEntity {
+      #[field_offset(0x08)]
+      pub id: u32
+    }
+
+    #[memory_layout(base = CEntity, size = 0x40, reflect)]
+    pub struct CPed {
+      #[field_offset(0x10)]
+      pub position: [f32; 3],
+      #[overlaps(position)]
+      pub x:        f32,
+      #[gap(0x30)]
+      pub reserved: (),
+      pub owner:    Option<&'static CEntity>
+    }
+
+    assert_eq!(
+      CPed::FIELDS,
+      &[
+        FieldInfo {
+          name:      "base",
+          offset:    0x00,
+          size:      0x10,
+          type_name: "CEntity",
+          kind:      RegionKind::Base
+        },
+        FieldInfo {
+          name:      "position",
+          offset:    0x10,
+          size:      0x0C,
+          type_name: "[f32; 3]",
+          kind:      RegionKind::Data
+        },
+        FieldInfo {
+          name:      "x",
+          offset:    0x10,
+          size:      0x04,
+          type_name: "f32",
+          kind:      RegionKind::Overlay
+        },
+        FieldInfo {
+          name:      "reserved",
+          offset:    0x1C,
+          size:      0x14,
+          type_name: "[u8]",
+          kind:      RegionKind::Gap
+        },
+        FieldInfo {
+          name:      "owner",
+          offset:    0x30,
+          size:      size_of::<usize>(),
+          type_name: "Option<&'static CEntity>",
+          kind:      RegionKind::Data
+        }
+      ]
+    );
+    assert_eq!(CPed::FIELDS[3].end(), 0x30);
+  }
+
+  #[test]
+  fn test_remote() {
+    #[memory_layout(0x10)]
+    pub struct CPed {
+      #[field_offset(0x04)]
+      pub id:     u32,
+      #[field_offset(0x08)]
+      #[endian(big)]
+      pub health: u16,
+      #[access(ro)]
+      pub flags:  u8
+    }
+
+    let process = FakeProcess::<0x40>::new();
+    let mut ped = CPed::zeroed();
+    ped.set_id(7);
+    ped.set_health(100);
+    ped.write_to(&process, 0x20).unwrap();
+    assert_eq!(process.0.borrow()[0x24], 7);
+    assert_eq!(&process.0.borrow()[0x28..0x2A], &[0, 100]);
+
+    CPed::write_health_to(&process, 0x20, 0x0102).unwrap();
+    assert_eq!(CPed::read_health_from(&process, 0x20), Ok(0x0102));
+    assert_eq!(CPed::read_id_from(&process, 0x20), Ok(7));
+    process.0.borrow_mut()[0x2A] = 3;
+    assert_eq!(CPed::read_flags_from(&&process, 0x20), Ok(3));
+    let ped = CPed::read_from(&process, 0x20).unwrap();
+    assert_eq!((ped.id(), ped.health(), ped.flags()), (7, 0x0102, 3));
+    assert_eq!(
+      CPed::read_from(&process, 0x38).map(|ped| ped.id()),
+      Err(0x38)
+    );
+  }
+
+  #[test]
+  fn test_remote_ptr() {
+    use crate::{remote::RemotePtr, Field};
+
+    #[memory_layout(0x10)]
+    pub struct CPed {
+      #[field_offset(0x04)]
+      pub id:    u32,
+      pub flags: [u8; 4]
+    }
+
+    #[memory_layout(0x20)]
+    pub struct CPool {
+      #[field_offset(0x08)]
+      pub peds:  RemotePtr<CPed>,
+      #[field_offset(0x10)]
+      pub count: u32
+    }
+
+    let _: Field<CPool, RemotePtr<CPed>, 0x08> = CPool::PEDS;
+    assert_eq!((CPed::FLAGS.offset(), CPed::FLAGS.size()), (0x08, 4));
+
+    let process = FakeProcess::<0x100>::new();
+    let pool = RemotePtr::<CPool>::new(0x10);
+    let mut value = CPool::zeroed();
+    value.set_peds(RemotePtr::new(0x80));
+    value.set_count(2);
+    pool.write(&process, &value).unwrap();
+    let peds = pool.field(CPool::PEDS).read(&process).unwrap();
+    assert_eq!(peds, RemotePtr::new(0x80));
+    assert_eq!(pool.field(CPool::COUNT).read(&process), Ok(2));
+
+    peds.add(1).field(CPed::ID).write(&process, &7).unwrap();
+    assert_eq!(process.0.borrow()[0x94], 7);
+    assert_eq!(peds.add(1).read(&process).map(|ped| ped.id()), Ok(7));
+    assert_eq!(peds.add(1).field(CPed::FLAGS).address(), 0x98);
+    assert!(RemotePtr::<CPed>::null().is_null());
+    assert_eq!(peds.cast::<u8>().add(3).address(), 0x83);
+    assert_eq!(
+      RemotePtr::<CPed>::new(0xF8).read(&process).map(|_| ()),
+      Err(0xF8)
+    );
+  }
+
+  #[test]
+  fn test_field_handles() {
+    use crate::{AnyBitPattern, Field};
+
+    fn read_field<P: RemoteProcess, S, T: AnyBitPattern, const O: usize>(
+      process: &P,
+      base: usize,
+      field: Field<S, T, O>
+    ) -> Result<T, P::Error> {
+      field.read(process, base)
+    }
+
+    #[memory_layout(0x10)]
+    pub struct CPed {
+      #[field_offset(0x03)]
+      pub id:     u32,
+      #[field_offset(0x08)]
+      #[endian(big)]
+      pub health: u16
+    }
+
+    #[memory_layout(0x08)]
+    pub struct CVehicle {
+      #[field_offset(0x04)]
+      pub id: u32
+    }
+
+    let mut ped = CPed::zeroed();
+    CPed::ID.set(&mut ped, 7);
+    ped.set_health(0x0102);
+    assert_eq!((ped.id(), CPed::ID.get(&ped)), (7, 7));
+    assert_eq!(CPed::HEALTH.get(&ped), 0x0201);
+    assert_eq!(CPed::ID.from_bytes(ped.as_bytes()), Some(7));
+    assert_eq!(CPed::HEALTH.from_bytes(&ped.as_bytes()[..0x09]), None);
+
+    let process = FakeProcess::<0x40>::new();
+    ped.write_to(&process, 0x10).unwrap();
+    CVehicle::ID.write(&process, 0x30, &9).unwrap();
+    assert_eq!(read_field(&process, 0x10, CPed::ID), Ok(7));
+    assert_eq!(read_field(&process, 0x30, CVehicle::ID), Ok(9));
+    assert_eq!(read_field(&process, 0x3E, CVehicle::ID), Err(0x42));
+  }
+
+  #[cfg(all(feature = "process", target_os = "linux"))]
+  #[test]
+  fn test_process() {
+    use crate::remote::Process;
+
+    #[memory_layout(0x10)]
+    pub struct CPed {
+      #[field_offset(0x08)]
+      pub health: f32
+    }
+
+    let mut ped = CPed::zeroed();
+    ped.set_health(50.0);
+    let address = &ped as *const CPed as usize;
+    let process = Process::current();
+    assert_eq!(CPed::read_from(&process, address).unwrap().health(), 50.0);
+    CPed::write_health_to(&process, address, 75.0).unwrap();
+    assert_eq!(unsafe { core::ptr::read_volatile(&ped) }.health(), 75.0);
+  }
+
+  #[test]
+  fn test_cstr_array() {
+    use crate::{CStrArray, CapacityError};
+
+    #[memory_layout(0x20, debug)]
+    pub struct Player {
+      #[field_offset(0x04)]
+      pub name: CStrArray<8>,
+      pub id:   u32
+    }
+
+    let mut player = Player::zeroed();
+    assert!(player.name().is_empty());
+    player.name_mut().set("Niko").unwrap();
+    player.set_id(1);
+    assert_eq!(player.name().as_str(), Ok("Niko"));
+    assert_eq!(player.name(), "Niko");
+    assert_eq!(
+      player.name_mut().set("Roman Bellic"),
+      Err(CapacityError {
+        len:      12,
+        capacity: 7
+      })
+    );
+    assert_eq!(
+      format!("{:?}", player),
+      "Player {name @ 0x4: \"Niko\", id @ 0xC: 1}"
+    );
+
+    player.name_mut().set_bytes(b"Roman").unwrap();
+    assert_eq!(player.name().raw(), b"Roman\0\0\0");
+  }
+
+  #[test]
+  fn test_stride() {
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    #[repr(C)]
+    pub struct Item {
+      pub id:    u32,
+      pub count: u16
+    }
+
+    unsafe impl crate::Zeroable for Item {}
+
+    #[memory_layout(0x100, debug)]
+    pub struct Inventory {
+      #[field_offset(0x40)]
+      #[stride(0x10)]
+      pub items: [Item; 4],
+      pub gold:  u32
+    }
+
+    assert_eq!(Inventory::OFFSET_GOLD, 0x80);
+    assert_eq!(Inventory::SIZE_ITEMS, 0x40);
+
+    let mut inventory = Inventory::zeroed();
+    let item = Item { id: 3, count: 9 };
+    inventory.items_mut().set(1, item);
+    inventory.set_gold(50);
+    assert_eq!(inventory.items().get(1), Some(item));
+    assert_eq!(inventory.items().get(4), None);
+    assert_eq!(inventory.items().len(), 4);
+    assert_eq!(
+      inventory.items().iter().filter(|item| item.id != 0).count(),
+      1
+    );
+    assert_eq!(
+      unsafe {
+        *(&inventory as *const Inventory as *const u8)
+          .add(0x50)
+          .cast::<u32>()
+      },
+      3
+    );
+  }
+
+  #[test]
+  fn test_vtable() {
+    use crate::vtable_layout;
+
+    #[vtable_layout(8)]
+    pub struct VTable {
+      pub id:    fn() -> u32,
+      #[index(3)]
+      pub scale: fn(u32) -> u32,
+      pub name:  fn() -> &'static str
+    }
+
+    #[memory_layout(0x10)]
+    pub struct Object {
+      pub vtable: *const VTable
+    }
+
+    let table: [usize; 8] = [
+      (|| 7) as fn() -> u32 as usize,
+      0,
+      0,
+      (|value| value * 2) as fn(u32) -> u32 as usize,
+      (|| "object") as fn() -> &'static str as usize,
+      0,
+      0,
+      0
+    ];
+    let mut object = Object::zeroed();
+    object.set_vtable(table.as_ptr().cast());
+
+    assert_eq!(size_of::<VTable>(), size_of::<[usize; 8]>());
+    assert_eq!((VTable::INDEX_SCALE, VTable::INDEX_NAME), (3, 4));
+    let vtable = unsafe { VTable::of(&object) };
+    assert_eq!(
+      ((vtable.id)(), (vtable.scale)(21), (vtable.name)()),
+      (7, 42, "object")
+    );
+    assert_eq!(unsafe { VTable::pointer_of(&object) }, object.vtable());
+  }
+
+  #[test]
+  fn test_virtual_calls() {
+    use crate::{virtual_function, vtable_layout};
+
+    #[vtable_layout]
+    pub struct VTable {
+      #[index(1)]
+      pub add: unsafe fn(*mut Counter, u32) -> u32
+    }
+
+    #[memory_layout(0x10)]
+    pub struct Counter {
+      pub vtable: *const VTable,
+      pub count:  u32
+    }
+
+    unsafe fn add(counter: *mut Counter, value: u32) -> u32 {
+      let count = (*counter).count() + value;
+      (*counter).set_count(count);
+      count
+    }
+
+    let table = [0, add as unsafe fn(*mut Counter, u32) -> u32 as usize];
+    let mut counter = Counter::zeroed();
+    counter.set_vtable(table.as_ptr().cast());
+
+    assert_eq!(unsafe { VTable::call_add(&mut counter, 5) }, 5);
+    let add = unsafe {
+      virtual_function::<unsafe fn(*mut Counter, u32) -> u32, _>(&counter, VTable::INDEX_ADD)
+    };
+    assert_eq!(unsafe { add(&mut counter, 2) }, 7);
+  }
+
+  #[test]
+  fn test_base() {
+    #[memory_layout(0x10, debug)]
+    #[derive(Clone, Copy)]
+    pub struct CBase {
+      #[field_offset(0x08)]
+      pub id: u32
+    }
+
+    #[memory_layout(base = CBase, size = 0x20)]
+    pub struct CEntity {
+      pub flags: u32
+    }
+
+    #[memory_layout(base = CEntity, size = 0x40, debug)]
+    pub struct CPed {
+      #[field_offset(0x30)]
+      pub health: f32
+    }
+
+    assert_eq!(CEntity::OFFSET_FLAGS, 0x10);
+    assert_eq!(
+      CPed::layout().field("__base").map(|field| field.size),
+      Some(0x20)
+    );
+
+    let mut ped = CPed::zeroed();
+    ped.set_id(7);
+    ped.set_flags(3);
+    ped.set_health(100.0);
+    assert_eq!((ped.id(), ped.flags(), ped.health()), (7, 3, 100.0));
+    let base: &CBase = &ped;
+    assert_eq!(base.id(), 7);
+  }
+
+  #[test]
+  fn test_nested() {
+    #[memory_layout(0x18)]
+    #[derive(Clone, Copy)]
+    pub struct Vector {
+      pub x: f64
+    }
+
+    #[memory_layout(0x40)]
+    pub struct Entity {
+      #[field_offset(0x10)]
+      #[nested(size = 0x18)]
+      pub position: Vector,
+      #[nested]
+      pub velocity: Vector
+    }
+
+    assert_eq!(<Vector as MemoryLayout>::SIZE, 0x18);
+    assert_eq!(Entity::OFFSET_VELOCITY, 0x28);
+  }
+
+  #[test]
+  fn test_endian() {
+    #[memory_layout(0x08)]
+    pub struct Header {
+      #[endian(big)]
+      pub magic:   u32,
+      #[field_offset(0x04)]
+      #[endian(little)]
+      pub version: u16
+    }
+
+    let mut header = Header::from_bytes([0x7F, 0x45, 0x4C, 0x46, 0x02, 0x01, 0x00, 0x00]);
+    assert_eq!(header.magic(), 0x7F45_4C46);
+    assert_eq!(header.version(), 0x0102);
+
+    header.set_magic(0x1234_5678);
+    header.set_version(0x0304);
+    assert_eq!(
+      header.to_bytes(),
+      [0x12, 0x34, 0x56, 0x78, 0x04, 0x03, 0x00, 0x00]
+    );
+  }
+
+  #[test]
+  fn test_endian_types() {
+    use crate::types::{I16Le, U16Be, U32Le};
+
+    #[memory_layout(0x08)]
+    pub struct Packet {
+      pub kind:   U16Be,
+      #[field_offset(0x02)]
+      pub length: U32Le,
+      #[field_offset(0x06)]
+      pub delta:  I16Le
+    }
+
+    assert_eq!(core::mem::align_of::<U32Le>(), 1);
+    let mut packet = Packet::from_bytes([0x01, 0x02, 0x10, 0x00, 0x00, 0x00, 0xFE, 0xFF]);
+    assert_eq!(packet.kind().get(), 0x0102);
+    assert_eq!(u32::from(packet.length()), 0x10);
+    assert_eq!(packet.delta().get(), -2);
+
+    packet.set_length(U32Le::new(0x0403_0201));
+    assert_eq!(packet.to_bytes()[0x02..0x06], [0x01, 0x02, 0x03, 0x04]);
+    assert_eq!(format!("{:?}", packet.kind()), "258");
+  }
+
+  #[test]
+  fn test_owned_bytes() {
+    #[memory_layout(0x08)]
+    pub struct Foo {
+      #[field_offset(0x01)]
+      pub a: u16
+    }
+
+    let mut bytes = [0u8; 0x08];
+    bytes[1..3].copy_from_slice(&0x1234u16.to_ne_bytes());
+    bytes[7] = 0xFF;
+    let foo = Foo::from_bytes(bytes);
+    assert_eq!(foo.a(), 0x1234);
+    let copied: [u8; 0x08] = foo.to_bytes();
+    assert_eq!(copied, bytes);
+  }
+
+  #[test]
+  fn test_view_mut() {
+    #[memory_layout(0x08)]
+    pub struct Foo {
+      #[field_offset(0x01)]
+      pub a: u16
+    }
+
+    let mut bytes = [0u8; 0x09];
+    Foo::view_mut(&mut bytes[1..]).unwrap().set_a(0xABCD);
+    assert_eq!(&bytes[2..4], &0xABCDu16.to_ne_bytes());
+    assert_eq!(
+      Foo::view_mut(&mut bytes).err(),
+      Some(LayoutError::InvalidLength {
+        expected: 0x08,
+        actual:   0x09
+      })
+    );
+  }
+
+  #[test]
+  fn test_accessor() {
+    #[memory_layout(0x40, builder)]
+    pub struct Foo {
+      #[field_offset(0x10)]
+      #[accessor(name = "flags")]
+      pub field_0x10: u32,
+
+      #[field_offset(0x38)]
+      #[accessor(name = "handle")]
+      pub field_0x38: u64
+    }
+
+    let mut foo = Foo::builder().set_flags(1).set_handle(2).build();
+    foo.set_flags(3);
+    assert_eq!((foo.flags(), foo.handle()), (3, 2));
+    assert_eq!(Foo::OFFSET_FIELD_0X10, 0x10);
+  }
+
+  #[cfg(feature = "alloc")]
+  #[test]
+  fn test_diff_bytes() {
+    use crate::BytesDiff;
+
+    #[memory_layout(0x08)]
+    pub struct Foo {
+      #[field_offset(0x00)]
+      pub a: u16,
+
+      #[field_offset(0x04)]
+      pub b: u32
+    }
+
+    let mut foo = Foo::from_bytes_checked(&[0; 0x08]).unwrap();
+    foo.set_a(0x0102);
+    foo.set_b(0x03);
+
+    let mut dump = [0u8; 0x08];
+    dump[0x00] = 0x02;
+    dump[0x01] = 0x05;
+    dump[0x06] = 0x07;
+    assert_eq!(
+      foo.diff_bytes(&dump),
+      BytesDiff {
+        differences:     vec![(0x01, 0x01, 0x05), (0x04, 0x03, 0x00), (0x06, 0x00, 0x07)],
+        length_mismatch: None
+      }
+    );
+    assert_eq!(
+      foo.diff_bytes(&dump[..0x04]),
+      BytesDiff {
+        differences:     vec![(0x01, 0x01, 0x05)],
+        length_mismatch: Some((0x08, 0x04))
+      }
+    );
+    assert!(foo.diff_bytes(foo.as_bytes()).is_empty());
+  }
+
+  #[cfg(feature = "alloc")]
+  #[test]
+  fn test_layout_diff() {
+    use crate::{FieldChange, FieldDesc, LayoutDiff};
+
+    #[memory_layout(0x20)]
+    pub struct Old {
+      #[field_offset(0x00)]
+      pub id:     u32,
+      #[field_offset(0x08)]
+      pub health: f32,
+      #[field_offset(0x10)]
+      pub flags:  u32
+    }
+
+    #[memory_layout(0x28)]
+    pub struct New {
+      #[field_offset(0x00)]
+      pub id:     u32,
+      #[field_offset(0x0C)]
+      pub health: f64,
+      #[field_offset(0x18)]
+      pub armor:  f32
+    }
+
+    let diff = crate::diff::<Old, New>();
+    assert_eq!(
+      diff,
+      LayoutDiff {
+        size:    Some((0x20, 0x28)),
+        changes: vec![
+          FieldChange::Moved {
+            name: "health",
+            from: 0x08,
+            to:   0x0C
+          },
+          FieldChange::Resized {
+            name: "health",
+            from: 0x04,
+            to:   0x08
+          },
+          FieldChange::Removed(FieldDesc {
+            name:   "flags",
+            offset: 0x10,
+            size:   0x04
+          }),
+          FieldChange::Added(FieldDesc {
+            name:   "armor",
+            offset: 0x18,
+            size:   0x04
+          })
+        ]
+      }
+    );
+    assert_eq!(
+      diff.to_string(),
+      "size: 0x20 -> 0x28\nmoved health: 0x8 -> 0xC\nresized health: 0x4 -> 0x8 bytes\nremoved flags: \
+       0x10 (0x4 bytes)\nadded armor: 0x18 (0x4 bytes)\n"
+    );
+    assert!(crate::diff::<Old, Old>().is_empty());
+  }
+
+  #[cfg(feature = "alloc")]
+  #[test]
+  fn test_export_c_header() {
+    use crate::{export::Exporter, types::U32Be, CStrArray};
+
+    #[memory_layout(0x10, reflect)]
+    pub struct CEntity {
+      #[field_offset(0x08)]
+      pub id: u32
+    }
+
+    #[memory_layout(base = CEntity, size = 0x60, reflect)]
+    pub struct CPed {
+      #[field_offset(0x10)]
+      pub position: [f32; 3],
+      #[overlaps(position)]
+      pub x:        f32,
+      #[gap(0x20)]
+      pub reserved: (),
+      pub owner:    Option<&'static CEntity>,
+      pub children: [*const CPed; 2],
+      pub name:     CStrArray<8>,
+      pub magic:    U32Be,
+      pub range:    core::ops::Range<u32>
+    }
+
+    let header = Exporter::new().add::<CPed>().add::<CEntity>().c_header();
+    assert_eq!(
+      header.lines().collect::<Vec<_>>(),
+      [
+        "#include <stdbool.h>",
+        "#include <stddef.h>",
+        "#include <stdint.h>",
+        "",
+        "#pragma pack(push, 1)",
+        "",
+        "typedef struct CEntity CEntity;",
+        "typedef struct CPed CPed;",
+        "",
+        "struct CEntity {",
+        "  /* 0x0000 */ uint8_t _pad_0000[0x8];",
+        "  /* 0x0008 */ uint32_t id;",
+        "  /* 0x000C */ uint8_t _pad_000C[0x4];",
+        "};",
+        "_Static_assert(sizeof(CEntity) == 0x10, \"size of CEntity\");",
+        "_Static_assert(offsetof(CEntity, id) == 0x8, \"offset of CEntity.id\");",
+        "",
+        "struct CPed {",
+        "  /* 0x0000 */ CEntity base;",
+        "  /* 0x0010 */ float position[0x3];",
+        "  /* 0x001C */ uint8_t reserved[0x4];",
+        "  /* 0x0020 */ CEntity *owner;",
+        "  /* 0x0028 */ CPed *children[0x2];",
+        "  /* 0x0038 */ char name[0x8];",
+        "  /* 0x0040 */ uint32_t magic /* U32Be */;",
+        "  /* 0x0044 */ uint8_t range[0x8] /* core::ops::Range<u32> */;",
+        "  /* 0x004C */ uint8_t _pad_004C[0x14];",
+        "  /* 0x0010 x: f32 (0x4 bytes), overlaps another field */",
+        "};",
+        "_Static_assert(sizeof(CPed) == 0x60, \"size of CPed\");",
+        "_Static_assert(offsetof(CPed, base) == 0x0, \"offset of CPed.base\");",
+        "_Static_assert(offsetof(CPed, position) == 0x10, \"offset of CPed.position\");",
+        "_Static_assert(offsetof(CPed, owner) == 0x20, \"offset of CPed.owner\");",
+        "_Static_assert(offsetof(CPed, children) == 0x28, \"offset of CPed.children\");",
+        "_Static_assert(offsetof(CPed, name) == 0x38, \"offset of CPed.name\");",
+        "_Static_assert(offsetof(CPed, magic) == 0x40, \"offset of CPed.magic\");",
+        "_Static_assert(offsetof(CPed, range) == 0x44, \"offset of CPed.range\");",
+        "",
+        "#pragma pack(pop)"
+      ]
+    );
+  }
+
+  #[cfg(feature = "alloc")]
+  #[test]
+  fn test_export_cpp_header() {
+    use crate::export::Exporter;
+
+    #[memory_layout(0x10, reflect)]
+    pub struct CEntity {
+      #[field_offset(0x08)]
+      pub id: u32
+    }
+
+    #[memory_layout(base = CEntity, size = 0x30, reflect)]
+    pub struct CPed {
+      #[field_offset(0x10)]
+      pub health: f32,
+      pub flags:  [u16; 2],
+      #[field_offset(0x20)]
+      pub target: *mut CEntity,
+      pub vtable: usize
+    }
+
+    let header = Exporter::new().add::<CPed>().add::<CEntity>().cpp_header();
+    assert_eq!(
+      header.lines().collect::<Vec<_>>(),
+      [
+        "#pragma once",
+        "",
+        "#include <cstddef>",
+        "#include <cstdint>",
+        "",
+        "#pragma pack(push, 1)",
+        "",
+        "class CEntity;",
+        "class CPed;",
+        "",
+        "class CEntity {",
+        "public:",
+        "  /* 0x0000 */ std::uint8_t pad_0000[0x8];",
+        "  /* 0x0008 */ std::uint32_t id;",
+        "  /* 0x000C */ std::uint8_t pad_000C[0x4];",
+        "};",
+        "static_assert(sizeof(CEntity) == 0x10, \"size of CEntity\");",
+        "static_assert(offsetof(CEntity, id) == 0x8, \"offset of CEntity.id\");",
+        "",
+        "class CPed {",
+        "public:",
+        "  /* 0x0000 */ CEntity base;",
+        "  /* 0x0010 */ float health;",
+        "  /* 0x0014 */ std::uint16_t flags[0x2];",
+        "  /* 0x0018 */ std::uint8_t pad_0018[0x8];",
+        "  /* 0x0020 */ CEntity *target;",
+        "  /* 0x0028 */ std::uintptr_t vtable;",
+        "};",
+        "static_assert(sizeof(CPed) == 0x30, \"size of CPed\");",
+        "static_assert(offsetof(CPed, base) == 0x0, \"offset of CPed.base\");",
+        "static_assert(offsetof(CPed, health) == 0x10, \"offset of CPed.health\");",
+        "static_assert(offsetof(CPed, flags) == 0x14, \"offset of CPed.flags\");",
+        "static_assert(offsetof(CPed, target) == 0x20, \"offset of CPed.target\");",
+        "static_assert(offsetof(CPed, vtable) == 0x28, \"offset of CPed.vtable\");",
+        "",
+        "#pragma pack(pop)"
+      ]
+    );
+  }
+
+  #[cfg(feature = "alloc")]
+  #[test]
+  fn test_export_reclass() {
+    use crate::{export::Exporter, types::U32Be};
+
+    #[memory_layout(0x10, reflect)]
+    pub struct CEntity {
+      #[field_offset(0x08)]
+      pub id: u32
+    }
+
+    #[memory_layout(base = CEntity, size = 0x38, reflect)]
+    pub struct CPed {
+      #[field_offset(0x10)]
+      pub health: f32,
+      pub pos:    [f32; 3],
+      #[field_offset(0x20)]
+      pub target: *mut CEntity,
+      pub serial: U32Be,
+      pub pair:   (u8, u8)
+    }
+
+    let xml = Exporter::new().add::<CPed>().add::<CEntity>().reclass_xml();
+    assert_eq!(
+      xml.lines().collect::<Vec<_>>(),
+      [
+        r#"<?xml version="1.0" encoding="utf-8"?>"#,
+        r#"<reclass version="65537" type="x64">"#,
+        "  <custom_data />",
+        "  <enums />",
+        "  <classes>",
+        r#"    <class uuid="EZhf3Cp1EOS6jzmWPBgbLg==" name="CEntity" comment="" address="0">"#,
+        r#"      <node type="Hex64Node" name="" comment="" hidden="false" />"#,
+        r#"      <node type="UInt32Node" name="id" comment="" hidden="false" />"#,
+        r#"      <node type="Hex32Node" name="" comment="" hidden="false" />"#,
+        "    </class>",
+        r#"    <class uuid="ZzDW95umhDVuw9ZMQEv8Vw==" name="CPed" comment="" address="0">"#,
+        r#"      <node type="ClassInstanceNode" name="base" comment="" hidden="false" reference="EZhf3Cp1EOS6jzmWPBgbLg==" />"#,
+        r#"      <node type="FloatNode" name="health" comment="" hidden="false" />"#,
+        r#"      <node type="ArrayNode" name="pos" comment="" hidden="false" count="3">"#,
+        r#"        <node type="FloatNode" name="" comment="" hidden="false" />"#,
+        "      </node>",
+        r#"      <node type="PointerNode" name="target" comment="" hidden="false">"#,
+        r#"        <node type="ClassInstanceNode" name="" comment="" hidden="false" reference="EZhf3Cp1EOS6jzmWPBgbLg==" />"#,
+        "      </node>",
+        r#"      <node type="Hex32Node" name="serial" comment="U32Be" hidden="false" />"#,
+        r#"      <node type="ArrayNode" name="pair" comment="(u8, u8)" hidden="false" count="2">"#,
+        r#"        <node type="UInt8Node" name="" comment="" hidden="false" />"#,
+        "      </node>",
+        r#"      <node type="Hex16Node" name="" comment="" hidden="false" />"#,
+        r#"      <node type="Hex64Node" name="" comment="" hidden="false" />"#,
+        "    </class>",
+        "  </classes>",
+        "</reclass>"
+      ]
+    );
+
+    let project = Exporter::new().add::<CEntity>().reclass_project();
+    assert_eq!(&project[..4], b"PK\x03\x04");
+    assert_eq!(&project[30..38], b"Data.xml");
+    // One entry with a 0x36 byte directory entry after the 0x1DC byte local entry.
+    assert!(project.ends_with(&[1, 0, 1, 0, 0x36, 0, 0, 0, 0xDC, 0x1, 0, 0, 0, 0]));
+  }
+  #[cfg(feature = "alloc")]
+  #[test]
+  fn test_export_cheat_engine() {
+    use crate::{export::Exporter, types::U16Be, CStrArray};
+
+    #[memory_layout(0x10, reflect)]
+    pub struct CEntity {
+      #[field_offset(0x08)]
+      pub id:  u32,
+      #[overlaps(id)]
+      pub raw: [u8; 4]
+    }
+
+    #[memory_layout(base = CEntity, size = 0x40, reflect)]
+    pub struct CPed {
+      #[field_offset(0x10)]
+      pub health: f32,
+      pub pos:    [i16; 2],
+      #[field_offset(0x18)]
+      pub target: Option<&'static CEntity>,
+      pub name:   CStrArray<8>,
+      pub serial: U16Be,
+      #[field_offset(0x30)]
+      pub pair:   (u8, u8)
+    }
+
+    let xml = Exporter::new()
+      .add::<CPed>()
+      .add::<CEntity>()
+      .cheat_engine_xml();
+    assert_eq!(
+      xml.lines().collect::<Vec<_>>(),
+      [
+        r#"<?xml version="1.0" encoding="utf-8"?>"#,
+        "<Structures>",
+        r#"  <Structure Name="CEntity" AutoFill="0" AutoCreate="1" DefaultHex="0" AutoDestroy="0" DoNotSaveLocal="0" RLECompression="1" AutoCreateStructsize="16">"#,
+        "    <Elements>",
+        r#"      <Element Offset="8" Vartype="4 Bytes" Bytesize="4" OffsetHex="00000008" Description="id" DisplayMethod="unsigned integer"/>"#,
+        r#"      <Element Offset="8" Vartype="Array of byte" Bytesize="4" OffsetHex="00000008" Description="raw" DisplayMethod="hexadecimal"/>"#,
+        "    </Elements>",
+        "  </Structure>",
+        r#"  <Structure Name="CPed" AutoFill="0" AutoCreate="1" DefaultHex="0" AutoDestroy="0" DoNotSaveLocal="0" RLECompression="1" AutoCreateStructsize="64">"#,
+        "    <Elements>",
+        r#"      <Element Offset="8" Vartype="4 Bytes" Bytesize="4" OffsetHex="00000008" Description="id" DisplayMethod="unsigned integer"/>"#,
+        r#"      <Element Offset="8" Vartype="Array of byte" Bytesize="4" OffsetHex="00000008" Description="raw" DisplayMethod="hexadecimal"/>"#,
+        r#"      <Element Offset="16" Vartype="Float" Bytesize="4" OffsetHex="00000010" Description="health" DisplayMethod="unsigned integer"/>"#,
+        r#"      <Element Offset="20" Vartype="2 Bytes" Bytesize="2" OffsetHex="00000014" Description="pos[0]" DisplayMethod="signed integer"/>"#,
+        r#"      <Element Offset="22" Vartype="2 Bytes" Bytesize="2" OffsetHex="00000016" Description="pos[1]" DisplayMethod="signed integer"/>"#,
+        r#"      <Element Offset="24" Vartype="Pointer" Bytesize="8" OffsetHex="00000018" Description="target" DisplayMethod="hexadecimal" ChildStruct="CEntity"/>"#,
+        r#"      <Element Offset="32" Vartype="String" Bytesize="8" OffsetHex="00000020" Description="name" DisplayMethod="unsigned integer"/>"#,
+        r#"      <Element Offset="40" Vartype="Custom" Bytesize="2" OffsetHex="00000028" Description="serial" DisplayMethod="unsigned integer" CustomType="2 Byte Big Endian"/>"#,
+        r#"      <Element Offset="48" Vartype="Array of byte" Bytesize="2" OffsetHex="00000030" Description="pair" DisplayMethod="hexadecimal"/>"#,
+        "    </Elements>",
+        "  </Structure>",
+        "</Structures>"
+      ]
+    );
+  }
+  #[cfg(feature = "alloc")]
+  #[test]
+  fn test_export_binary_template() {
+    use crate::{export::Exporter, types::U32Be};
+
+    #[memory_layout(0x10, reflect)]
+    pub struct CEntity {
+      #[field_offset(0x08)]
+      pub id:  u32,
+      #[overlaps(id)]
+      pub raw: [u8; 4]
+    }
+
+    #[memory_layout(base = CEntity, size = 0x70, reflect)]
+    pub struct CPed {
+      #[field_offset(0x10)]
+      pub health: f32,
+      pub scale:  [f32; 3],
+      #[field_offset(0x30)]
+      pub target: *const CEntity,
+      pub serial: U32Be,
+      pub pair:   (u8, u8),
+      pub list:   [CEntity; 2]
+    }
+
+    let template = Exporter::new().add::<CEntity>().binary_template::<CPed>();
+    assert!(template.contains("  /* 0x0008 raw: [u8; 4] (0x4 bytes), overlaps another field */\n"));
+    assert!(template.contains(
+      "\
+typedef struct {
+  /* 0x0000 */ CEntity base;
+  /* 0x0010 */ float health;
+  /* 0x0014 */ float scale[0x3];
+  /* 0x0020 */ uchar _pad_0020[0x10];
+  /* 0x0030 */ uint64 target <format=hex, comment=\"* const CEntity\">;
+  /* 0x0038 */ BigEndian(); uint32 serial; LittleEndian();
+  /* 0x003C */ uchar pair[0x2] <format=hex, comment=\"(u8, u8)\">;
+  /* 0x003E */ CEntity list[0x2];
+  /* 0x005E */ uchar _pad_005E[0x12];
+} CPed;
+
+CPed root;
+"
+    ));
+    assert!(template.find("} CEntity;").unwrap() < template.find("} CPed;").unwrap());
+  }
+
+  #[test]
+  fn test_hexdump() {
+    #[memory_layout(0x30)]
+    pub struct Foo {
+      #[field_offset(0x00)]
+      pub a:    u32,
+      #[overlaps(a)]
+      pub a_lo: u16,
+      #[field_offset(0x08)]
+      pub name: [u8; 0x14],
+      pub b:    u8
+    }
+
+    let mut bytes = [0u8; 0x30];
+    for (index, byte) in bytes.iter_mut().enumerate() {
+      *byte = index as u8;
+    }
+    let foo = Foo::from_bytes(bytes);
+    assert_eq!(
+      crate::hexdump(&foo).to_string(),
+      "\
+Foo (0x30 bytes)
+0x0000  00 01 02 03                                      a
+0x0000  00 01                                            a_lo (overlaps)
+0x0004  04 05 06 07                                      <padding>
+0x0008  08 09 0A 0B 0C 0D 0E 0F 10 11 12 13 14 15 16 17  name
+0x0018  18 19 1A 1B
+0x001C  1C                                               b
+0x001D  1D 1E 1F 20 21 22 23 24 25 26 27 28 29 2A 2B 2C  <padding>
+0x002D  2D 2E 2F
+"
+    );
+    assert_eq!(
+      crate::Hexdump::new(Foo::layout(), &bytes[..0x0A]).to_string(),
+      "\
+Foo (0x30 bytes)
+0x0000  00 01 02 03                                      a
+0x0000  00 01                                            a_lo (overlaps)
+0x0004  04 05 06 07                                      <padding>
+0x0008  08 09                                            name
+"
+    );
+  }
+
+  #[cfg(feature = "alloc")]
+  #[test]
+  fn test_layout_diagram() {
+    #[memory_layout(0x54)]
+    pub struct Foo {
+      #[field_offset(0x00)]
+      pub a:    u32,
+      #[overlaps(a)]
+      pub a_lo: u16,
+      #[field_offset(0x08)]
+      pub name: [u8; 0x40],
+      pub b:    u8
+    }
+
+    assert_eq!(
+      Foo::layout_diagram(),
+      "\
+Foo (0x54 bytes)
+        00 01 02 03 04 05 06 07 08 09 0A 0B 0C 0D 0E 0F
+0x0000 |a          |pad        |name                   |
+0x0010 |..name                                         |
+*
+0x0040 |..name                 |b |pad                 |
+0x0050 |..pad      |
+0x0000..0x0002 a_lo overlaps another field
+"
+    );
+  }
+
+  #[cfg(feature = "alloc")]
+  #[test]
+  fn test_export_json() {
+    use crate::export::Exporter;
+
+    #[memory_layout(0x10, reflect)]
+    pub struct CEntity {
+      #[field_offset(0x08)]
+      pub id:  u32,
+      #[overlaps(id)]
+      pub raw: [u8; 4]
+    }
+
+    #[memory_layout(base = CEntity, size = 0x30, reflect)]
+    pub struct CPed {
+      #[gap(0x18)]
+      reserved:   (),
+      #[field_offset(0x20)]
+      pub target: Option<&'static CEntity>
+    }
+
+    let json = Exporter::new().add::<CPed>().add::<CEntity>().json();
+    assert_eq!(
+      json,
+      r#"{
+  "structs": [
+    {
+      "name": "CEntity",
+      "size": 16,
+      "align": 1,
+      "fields": [
+        { "name": "id", "offset": 8, "size": 4, "type": "u32", "kind": "data" },
+        { "name": "raw", "offset": 8, "size": 4, "type": "[u8; 4]", "kind": "overlay" }
+      ]
+    },
+    {
+      "name": "CPed",
+      "size": 48,
+      "align": 1,
+      "fields": [
+        { "name": "base", "offset": 0, "size": 16, "type": "CEntity", "kind": "base" },
+        { "name": "reserved", "offset": 16, "size": 8, "type": "[u8]", "kind": "gap" },
+        { "name": "target", "offset": 32, "size": 8, "type": "Option<&'static CEntity>", "kind": "data" }
+      ]
+    }
+  ]
+}
+"#
+    );
+  }
+
+  #[cfg(feature = "alloc")]
+  #[test]
+  fn test_export_kaitai_schema() {
+    use crate::{export::Exporter, types::U32Be, CStrArray};
+
+    #[memory_layout(0x10, reflect)]
+    pub struct CEntity {
+      #[field_offset(0x08)]
+      pub id:  u32,
+      #[overlaps(id)]
+      pub raw: [u8; 4]
+    }
+
+    #[memory_layout(base = CEntity, size = 0x80, reflect)]
+    pub struct CPed {
+      #[field_offset(0x10)]
+      pub health: f32,
+      pub scale:  [f32; 3],
+      #[field_offset(0x30)]
+      pub target: *const CEntity,
+      pub serial: U32Be,
+      pub pair:   (u8, u8),
+      pub list:   [CEntity; 2],
+      pub name:   CStrArray<16>
+    }
+
+    let schema = Exporter::new().add::<CEntity>().kaitai_schema::<CPed>();
+    assert_eq!(
+      schema,
+      "\
+meta:
+  id: c_ped
+  endian: le
+seq:
+  - id: base
+    type: c_entity
+    size: 0x10
+  - id: health
+    type: f4
+  - id: scale
+    type: f4
+    repeat: expr
+    repeat-expr: 3
+  - id: pad_0020
+    size: 0x10
+  - id: target
+    type: u8
+    doc: \"* const CEntity\"
+  - id: serial
+    type: u4be
+  - id: pair
+    size: 0x2
+    doc: \"(u8, u8)\"
+  - id: list
+    type: c_entity
+    size: 0x10
+    repeat: expr
+    repeat-expr: 2
+  - id: name
+    type: strz
+    size: 0x10
+    encoding: UTF-8
+  - id: pad_006e
+    size: 0x12
+types:
+  c_entity:
+    seq:
+      - id: pad_0000
+        size: 0x8
+      - id: id
+        type: u4
+      - id: pad_000c
+        size: 0x4
+    instances:
+      raw:
+        pos: 0x8
+        size: 0x4
+"
+    );
+  }
+
+  #[cfg(feature = "alloc")]
+  #[test]
+  fn test_export_disassembler_scripts() {
+    use crate::{export::Exporter, types::U32Be};
+
+    #[memory_layout(0x10, reflect)]
+    pub struct CEntity {
+      #[field_offset(0x08)]
+      pub id:  u32,
+      #[overlaps(id)]
+      pub raw: [u8; 4]
+    }
+
+    #[memory_layout(base = CEntity, size = 0x50, reflect)]
+    pub struct CPed {
+      #[field_offset(0x10)]
+      pub health: f32,
+      pub scale:  [f32; 3],
+      #[field_offset(0x30)]
+      pub target: *const CEntity,
+      pub data:   *mut u8,
+      pub serial: U32Be,
+      pub pair:   (u8, u8)
+    }
+
+    let exporter = Exporter::new().add::<CPed>().add::<CEntity>().clone();
+    let ghidra = exporter.ghidra_script();
+    for line in [
+      r#"for name, size in [("CEntity", 0x10), ("CPed", 0x50)]:"#,
+      r#"types["CEntity"].replaceAtOffset(0x8, DWordDataType.dataType, 0x4, "id", None)"#,
+      "# 0x0008 raw: [u8; 4] (0x4 bytes), overlaps another field",
+      r#"types["CPed"].replaceAtOffset(0x0, types["CEntity"], 0x10, "base", None)"#,
+      r#"types["CPed"].replaceAtOffset(0x14, ArrayDataType(FloatDataType.dataType, 3, 4), 0xC, "scale", None)"#,
+      r#"types["CPed"].replaceAtOffset(0x30, PointerDataType(types["CEntity"], 8), 0x8, "target", None)"#,
+      r#"types["CPed"].replaceAtOffset(0x38, PointerDataType(VoidDataType.dataType, 8), 0x8, "data", None)"#,
+      r#"types["CPed"].replaceAtOffset(0x40, DWordDataType.dataType, 0x4, "serial", "U32Be")"#,
+      r#"types["CPed"].replaceAtOffset(0x44, ArrayDataType(ByteDataType.dataType, 2, 1), 0x2, "pair", "(u8, u8)")"#
+    ] {
+      assert!(ghidra.lines().any(|l| l == line), "{}", line);
+    }
+    assert!(ghidra.find("\"id\"").unwrap() < ghidra.find("\"base\"").unwrap());
+
+    let ida = exporter.ida_script();
+    for line in [
+      r#"    ("CEntity", 0x10, ["#,
+      r#"        (0x8, "id", 0x4, "unsigned __int32"),"#,
+      r#"        (0xC, "_pad_000C", 0x4, "unsigned __int8[4]"),"#,
+      "        # 0x0008 raw: [u8; 4] (0x4 bytes), overlaps another field",
+      r#"        (0x0, "base", 0x10, "CEntity"),"#,
+      r#"        (0x10, "health", 0x4, "float"),"#,
+      r#"        (0x14, "scale", 0xC, "float[3]"),"#,
+      r#"        (0x30, "target", 0x8, "CEntity *"),"#,
+      r#"        (0x38, "data", 0x8, "void *"),"#,
+      r#"        (0x46, "_pad_0046", 0xA, "unsigned __int8[10]"),"#
+    ] {
+      assert!(ida.lines().any(|l| l == line), "{}", line);
+    }
+  }
+
+  #[test]
+  fn test_union() {
+    #[memory_layout(0x10)]
+    pub union Foo {
+      #[field_offset(0x00)]
+      pub tag: u32,
+
+      #[field_offset(0x04)]
+      pub health: f32,
+
+      #[field_offset(0x04)]
+      pub raw: u64
+    }
+
+    assert_eq!(size_of::<Foo>(), 0x10);
+    assert_eq!((Foo::OFFSET_HEALTH, Foo::OFFSET_RAW), (0x04, 0x04));
+
+    let mut foo = Foo { __size: [0; 0x10] };
+    foo.set_raw(0);
+    foo.set_health(1.0);
+    foo.set_tag(7);
+    unsafe {
+      assert_eq!(foo.tag(), 7);
+      assert_eq!(foo.health(), 1.0);
+      assert_eq!(foo.raw(), 1.0f32.to_bits() as u64);
+    }
+  }
+
+  #[test]
+  fn test_tuple_struct() {
+    #[memory_layout(0x10)]
+    pub struct Foo(#[field_offset(0x00)] pub u32, #[field_offset(0x04)] pub u64);
+
+    #[memory_layout]
+    pub struct Bar(#[field_offset(0x00)] pub u8, #[field_offset(0x01)] pub u16);
+
+    let mut foo = Foo::new(1, 2);
+    foo.1 = 3;
+    assert_eq!(({ foo.0 }, { foo.1 }), (1, 3));
+    assert_eq!((Foo::OFFSET_1, Foo::END_1), (0x04, 0x0C));
+    assert_eq!(Foo::layout().fields[1].name, "1");
+    assert_eq!(size_of::<Foo>(), 0x10, "`Foo` should be 0x10 bytes in size");
+
+    let bar = Bar::new(1, 2);
+    assert_eq!(({ bar.0 }, { bar.1 }), (1, 2));
+    assert_eq!(size_of::<Bar>(), 0x03, "`Bar` should be 0x03 bytes in size");
+  }
+
+  #[test]
+  fn test_generic_struct() {
+    #[memory_layout(0x20, builder)]
+    pub struct Foo<'a, T> {
+      #[field_offset(0x00)]
+      pub name: &'a str,
+
+      #[field_offset(0x10)]
+      pub next: *const T,
+
+      #[field_offset(0x18)]
+      pub marker: core::marker::PhantomData<T>,
+
+      #[field_offset(0x18)]
+      pub id: u32
+    }
+
+    #[memory_layout]
+    pub struct Bar<T: Copy> {
+      #[field_offset(0x04)]
+      pub value: T
+    }
+
+    let foo = Foo::<u8>::builder()
+      .set_name("foo")
+      .set_next(core::ptr::null())
+      .set_marker(core::marker::PhantomData)
+      .set_id(7)
+      .build();
+    assert_eq!((foo.name(), foo.id()), ("foo", 7));
+    assert_eq!(Foo::<u8>::OFFSET_ID, 0x18);
+    assert_eq!(
+      size_of::<Foo<u64>>(),
+      0x20,
+      "`Foo` should be 0x20 bytes in size"
+    );
+
+    let mut bar = Bar::<u64>::zeroed();
+    bar.set_value(5);
+    assert_eq!(bar.value(), 5);
+    assert_eq!(
+      size_of::<Bar<u16>>(),
+      0x06,
+      "`Bar` should be 0x06 bytes in size"
+    );
+  }
+
+  #[test]
+  fn test_const_generic_reserved() {
+    #[memory_layout]
+    pub struct Foo<const N: usize> {
+      #[field_offset(0x00)]
+      pub len: u32,
+
+      #[field_offset(0x08)]
+      pub reserved: [u8; N]
+    }
+
+    let mut foo = Foo::<4>::zeroed();
+    foo.set_len(4);
+    assert_eq!((foo.len(), foo.reserved()), (4, &[0; 4]));
+    assert_eq!(Foo::<4>::OFFSET_RESERVED, 0x08);
+    assert_eq!(
+      size_of::<Foo<4>>(),
+      0x0C,
+      "`Foo<4>` should be 0x0C bytes in size"
+    );
+    assert_eq!(
+      size_of::<Foo<0x18>>(),
+      0x20,
+      "`Foo<0x18>` should be 0x20 bytes in size"
+    );
+  }
+
+  #[test]
+  fn test_declare_layout() {
+    mod foreign {
+      #[repr(C)]
+      pub struct Player {
+        pub id:    u64,
+        pub score: u32,
+        pub flags: u16
+      }
+    }
+
+    crate::declare_layout! {
+      #[memory_layout(0x10)]
+      pub struct PlayerLayout for foreign::Player {
+        #[field_offset(0x08)]
+        pub score: u32
+      }
+    }
+
+    let mut player = foreign::Player {
+      id:    1,
+      score: 20,
+      flags: 0
+    };
+    assert_eq!(unsafe { PlayerLayout::from_target(&player) }.score(), 20);
+    unsafe { PlayerLayout::from_target_mut(&mut player) }.set_score(30);
+    assert_eq!(player.score, 30);
+
+    let ptr = PlayerLayout::from_target_ptr(&player);
+    assert_eq!(ptr as usize, &player as *const foreign::Player as usize);
+    assert_eq!(PlayerLayout::layout().fields[0].offset, 0x08);
+    assert_eq!(<PlayerLayout as MemoryLayout>::SIZE, 0x10);
+  }
+
+  #[test]
+  fn test_marker_fields() {
+    pub struct Locked;
+
+    #[memory_layout(0x20, sort)]
+    pub struct Foo<S> {
+      #[field_offset(0x10)]
+      pub b: u64,
+
+      #[marker]
+      #[field_offset(0x04)]
+      pub header: Locked,
+
+      #[field_offset(0x04)]
+      pub a: u32,
+
+      #[marker]
+      pub state: core::marker::PhantomData<S>,
+
+      #[marker]
+      #[field_offset(0x20)]
+      pub end: core::marker::PhantomPinned
+    }
+
+    let mut foo = Foo::<u8>::zeroed();
+    foo.set_a(1);
+    foo.set_b(2);
+    assert_eq!((foo.a(), foo.b()), (1, 2));
+    assert_eq!(core::mem::size_of::<Foo<u8>>(), 0x20);
+    assert_eq!(Foo::<u8>::OFFSET_A, 0x04);
+    assert_eq!(Foo::<u8>::layout().fields.len(), 2);
+    assert_eq!(
+      core::ptr::addr_of!(foo.header) as usize - core::ptr::addr_of!(foo) as usize,
+      0x04
+    );
+    assert_eq!(
+      core::ptr::addr_of!(foo.state) as usize - core::ptr::addr_of!(foo) as usize,
+      0x20
+    );
+    assert_eq!(
+      core::ptr::addr_of!(foo.end) as usize - core::ptr::addr_of!(foo) as usize,
+      0x20
+    );
+  }
+
+  #[test]
+  fn test_field_size() {
+    #[derive(Clone, Copy)]
+    pub struct Handle(u64);
+    unsafe impl crate::Zeroable for Handle {}
+
+    #[memory_layout(0x40, strict)]
+    pub struct Entity {
+      #[field_offset(0x08)]
+      #[field_size(0x30)]
+      pub handle: Handle,
+
+      pub id: u32,
+
+      #[field_offset(0x3C)]
+      pub flags: u32
+    }
+
+    let mut entity = Entity::zeroed();
+    entity.set_handle(Handle(7));
+    entity.set_id(3);
+    assert_eq!((entity.handle().0, entity.id()), (7, 3));
+    assert_eq!(Entity::OFFSET_ID, 0x38);
+    assert_eq!(core::mem::size_of::<Entity>(), 0x40);
+  }
+
+  #[cfg(feature = "memoffset")]
+  #[test]
+  fn test_memoffset() {
+    #[memory_layout(0x20)]
+    pub struct Foo {
+      #[field_offset(0x04)]
+      pub a: u32,
+      #[field_offset(0x10)]
+      pub b: [u8; 3]
+    }
+
+    const OFFSET_B: usize = crate::__private::offset_of!(Foo, b);
+    assert_eq!(OFFSET_B, 0x10);
+    assert_eq!(crate::__private::offset_of!(Foo, a), Foo::OFFSET_A);
+  }
+
+  #[test]
+  fn test_tagged_enum() {
+    #[memory_layout(0x20)]
+    #[discriminant(offset = 0x04, ty = u32)]
+    pub enum Shape {
+      Empty  = 0,
+      Circle {
+        #[field_offset(0x10)]
+        radius: f32
+      } = 1,
+      Rect {
+        #[field_offset(0x10)]
+        width:  f32,
+        #[field_offset(0x14)]
+        height: f32
+      } = 2
+    }
+
+    assert_eq!(size_of::<Shape>(), 0x20);
+    assert_eq!(Shape::OFFSET_RECT_HEIGHT, 0x14);
+
+    let mut shape = Shape::new_rect(2.0, 3.0);
+    assert_eq!(shape.discriminant(), 2);
+    assert!(shape.is_rect() && !shape.is_empty());
+    assert_eq!(shape.rect_height(), Some(3.0));
+    assert_eq!(shape.circle_radius(), None);
+    assert!(!shape.set_circle_radius(1.0));
+    assert!(shape.set_rect_width(4.0));
+    assert_eq!(shape.rect_width(), Some(4.0));
+    assert!(Shape::new_empty().is_empty());
+    assert_eq!(
+      Shape::layout()
+        .field("Circle::radius")
+        .map(|field| field.offset),
+      Some(0x10)
+    );
+  }
+
+  #[test]
+  fn test_bit_offset() {
+    #[memory_layout(0x08)]
+    pub struct Foo {
+      #[field_offset(0x04)]
+      pub flags: u16,
+
+      #[bit_offset(0x04, 0, 1)]
+      pub visible: u16,
+
+      #[bit_offset(0x04, 3, 5)]
+      pub mode: u16,
+
+      #[bit_offset(0x05, 7, 1)]
+      pub last: u8
+    }
+
+    assert_eq!(size_of::<Foo>(), 0x08);
+
+    let mut foo = Foo::from_bytes_checked(&[0; 0x08]).unwrap();
+    foo.set_visible(1);
+    foo.set_mode(0x3F);
+    foo.set_last(1);
+    assert_eq!(foo.flags(), 0b1000_0000_1111_1001);
+    assert_eq!((foo.visible(), foo.mode(), foo.last()), (1, 0x1F, 1));
+
+    foo.set_mode(0b10);
+    assert_eq!(foo.flags(), 0b1000_0000_0001_0001);
+
+    #[memory_layout(0x08, sparse)]
+    pub struct Sparse {
+      #[field_offset(0x04)]
+      pub flags: u16,
+
+      #[bit_offset(0x05, 4, 4)]
+      pub level: u8
+    }
+
+    let mut sparse = Sparse::zeroed();
+    sparse.set_level(0xA);
+    assert_eq!(sparse.flags(), 0xA000);
+
+    #[memory_layout(verify)]
+    pub struct Verified {
+      #[field_offset(0x00)]
+      pub kind:  u8,
+      #[field_offset(0x04)]
+      pub flags: u32,
+
+      #[bit_offset(0x04, 28, 4)]
+      pub level: u32
+    }
+
+    let mut verified = Verified::zeroed();
+    verified.set_level(0xF);
+    assert_eq!((verified.flags(), verified.level()), (0xF000_0000, 0xF));
+
+    #[memory_layout]
+    pub struct Generic<T> {
+      #[field_offset(0x00)]
+      pub flags: u16,
+
+      #[bit_offset(0x00, 1, 2)]
+      pub mode: u16,
+
+      #[field_offset(0x02)]
+      pub value: T
+    }
+
+    let mut generic = Generic::<u8>::zeroed();
+    generic.set_mode(0b11);
+    assert_eq!((generic.flags(), generic.mode()), (0b110, 0b11));
+  }
+
+  #[test]
+  fn test_bit_offset_storage() {
+    use std::{
+      collections::hash_map::DefaultHasher,
+      hash::{Hash, Hasher}
+    };
+
+    #[memory_layout(0x10, eq, hash, debug)]
+    pub struct Foo {
+      #[field_offset(0x00)]
+      pub a: u8,
+
+      #[field_offset(0x08)]
+      pub flags: u32,
+
+      #[bit_offset(0x08, 0, 4)]
+      pub mode: u32
+    }
+
+    fn hash(foo: &Foo) -> u64 {
+      let mut hasher = DefaultHasher::new();
+      foo.hash(&mut hasher);
+      hasher.finish()
+    }
+
+    let zeroed = Foo::zeroed();
+    let mut foo = Foo::zeroed();
+    foo.set_mode(5);
+    assert!(foo != zeroed);
+    assert_ne!(hash(&foo), hash(&zeroed));
+    assert_eq!(format!("{:?}", foo), "Foo {a @ 0x0: 0, flags @ 0x8: 5}");
+    assert_eq!(Foo::from_bytes(foo.to_bytes()).mode(), 5);
+    assert_eq!(Foo::from_bytes_checked(&foo.to_bytes()).unwrap().mode(), 5);
+  }
+
+  #[test]
+  fn test_const_expressions() {
+    const BASE: usize = 0x10;
+    const HEADER_SIZE: usize = 0x08;
+
+    #[memory_layout(BASE * 2 + 8, strict)]
+    pub struct Foo {
+      #[field_offset(HEADER_SIZE * 2)]
+      pub a: u32,
+
+      #[field_offset = BASE + 0x8]
+      pub b: u64,
+
+      #[field_offset(end = 0x08)]
+      pub c: u64
+    }
+
+    assert_eq!(size_of::<Foo>(), 0x28);
+    assert_eq!(
+      (Foo::OFFSET_A, Foo::OFFSET_B, Foo::OFFSET_C),
+      (0x10, 0x18, 0x20)
+    );
+
+    pub struct Pair<A, B>(A, B);
+
+    #[memory_layout(size_of::<Pair<u64, u64>>() * 2, strict)]
+    pub struct Generic {
+      #[field_offset(size_of::<Pair<u32, u32>>())]
+      pub a: u32,
+
+      #[field_offset(end = 0x08)]
+      pub b: u64
+    }
+
+    assert_eq!(size_of::<Generic>(), 0x20);
+    assert_eq!((Generic::OFFSET_A, Generic::OFFSET_B), (0x08, 0x18));
+  }
+
+  #[test]
+  fn test_implicit_offset() {
+    #[memory_layout(0x20)]
+    pub struct Foo {
+      pub a: u16,
+      pub b: u32,
+
+      #[field_offset(0x10)]
+      pub c: u64,
+      pub d: u8,
+      pub e: [u8; 3]
+    }
+
+    assert_eq!(
+      (
+        Foo::OFFSET_A,
+        Foo::OFFSET_B,
+        Foo::OFFSET_C,
+        Foo::OFFSET_D,
+        Foo::OFFSET_E
+      ),
+      (0x00, 0x02, 0x10, 0x18, 0x19)
+    );
+    assert_eq!(size_of::<Foo>(), 0x20);
+  }
+
+  #[test]
+  fn test_sort() {
+    #[memory_layout(0x20, sort)]
+    pub struct Foo {
+      #[field_offset(0x10)]
+      pub health:     f32,
+      #[field_offset(0x14)]
+      pub max_health: f32,
+
+      #[field_offset(0x00)]
+      pub id:    u32,
+      #[field_offset(end = 0x04)]
+      pub flags: u32
+    }
+
+    assert_eq!(size_of::<Foo>(), 0x20);
+    let names = Foo::layout()
+      .fields
+      .iter()
+      .map(|field| field.name)
+      .collect::<Vec<_>>();
+    assert_eq!(names, ["id", "health", "max_health", "flags"]);
+    assert_eq!(Foo::OFFSET_FLAGS, 0x1C);
+  }
+
+  #[test]
+  fn test_overlaps() {
+    #[memory_layout(0x10)]
+    pub struct Foo {
+      #[field_offset(0x04)]
+      pub flags: u32,
+
+      #[overlaps(flags)]
+      pub flag_bytes: [u8; 4],
+
+      #[overlaps(flags)]
+      pub low: u16,
+
+      #[field_offset(0x08)]
+      pub b: u32
+    }
+
+    assert_eq!(size_of::<Foo>(), 0x10);
+    assert_eq!((Foo::OFFSET_FLAG_BYTES, Foo::SIZE_LOW), (0x04, 2));
+    assert_eq!((Foo::END_FLAG_BYTES, Foo::END_LOW), (0x08, 0x06));
+
+    let mut foo = Foo::from_bytes_checked(&[0; 0x10]).unwrap();
+    foo.set_flags(0x1234_5678);
+    assert_eq!(foo.flag_bytes(), 0x1234_5678u32.to_ne_bytes());
+    foo.set_flag_bytes([1, 0, 0, 0]);
+    assert_eq!(foo.flags(), u32::from_ne_bytes([1, 0, 0, 0]));
+    foo.set_low(0);
+    assert_eq!(foo.flags(), 0);
+
+    let padding = Foo::layout().padding().collect::<Vec<_>>();
+    assert_eq!(padding, [0x00..0x04, 0x0C..0x10]);
+
+    #[memory_layout]
+    pub struct Generic<T> {
+      #[overlaps(value)]
+      pub value_bytes: [u8; 2],
+
+      #[field_offset(0x02)]
+      pub value: T
+    }
+
+    let mut generic = Generic::<u32>::zeroed();
+    generic.set_value_bytes([0xAA, 0xBB]);
+    assert_eq!(generic.value().to_ne_bytes(), [0xAA, 0xBB, 0, 0]);
+    assert_eq!(generic.value_bytes(), [0xAA, 0xBB]);
+  }
+
+  #[test]
+  fn test_static_assertions() {
+    #[memory_layout]
+    pub struct Foo {
+      #[field_offset(0x04)]
+      pub a: u32,
+
+      #[field_offset(0x10)]
+      pub b: [u8; 3]
+    }
+
+    assert_eq!(size_of::<Foo>(), 0x13);
+  }
+
+  #[test]
+  fn test_verify() {
+    #[memory_layout(0x10, verify)]
+    pub struct Foo {
+      #[field_offset(0x00)]
+      pub a: u32,
+
+      #[field_offset(0x08)]
+      pub b: u64
+    }
+
+    let foo = Foo { a: 1, b: 2 };
+    let b: &u64 = &foo.b;
+    assert_eq!((foo.a(), *b), (1, 2));
+    assert_eq!(core::mem::align_of::<Foo>(), 8);
+    assert_eq!(size_of::<Foo>(), 0x10);
+  }
+
+  #[test]
+  fn test_align() {
+    #[memory_layout(size = 0x40, align = 0x10)]
+    pub struct Foo {
+      #[field_offset(0x10)]
+      pub a: u32,
+
+      #[field_offset(0x20)]
+      pub b: [u8; 3]
+    }
+
+    #[memory_layout(size = 0x20, align = 0x20)]
+    pub union Bar {
+      #[field_offset(0x04)]
+      pub a: u32
+    }
+
+    assert_eq!(core::mem::align_of::<Foo>(), 0x10);
+    assert_eq!(size_of::<Foo>(), 0x40);
+    assert_eq!(Foo::OFFSET_B, 0x20);
+    assert_eq!(core::mem::align_of::<Bar>(), 0x20);
+    assert_eq!(size_of::<Bar>(), 0x20);
+  }
+
+  #[test]
+  fn test_memory_layout_trait() {
+    #[memory_layout(size = 0x20, align = 0x10)]
+    pub struct Foo {
+      #[field_offset(0x04)]
+      pub a: u16,
+
+      #[field_offset(0x10)]
+      pub b: u64
+    }
+
+    #[memory_layout(0x10)]
+    #[discriminant(offset = 0x00, ty = u8)]
+    pub enum Bar {
+      A = 1
+    }
+
+    fn offsets<T: MemoryLayout>() -> (usize, usize, Option<usize>) {
+      (T::SIZE, T::ALIGN, T::offset_of("b"))
+    }
+
+    assert_eq!(offsets::<Foo>(), (0x20, 0x10, Some(0x10)));
+    assert_eq!(offsets::<Bar>(), (0x10, 1, None));
+    assert_eq!(&Foo::LAYOUT, Foo::layout());
+  }
 }
@@ -0,0 +1,28 @@
+/// Declares a type alias for a struct annotated with `#[memory_layout]`.
+///
+/// The alias shares the offset constants, accessors and `layout()` of the aliased struct, so
+/// `MyAlias::OFFSET_A` resolves to the same constant as `Foo::OFFSET_A`. The generated
+/// documentation links back to the aliased struct.
+///
+/// # Example
+/// ```rust
+/// use memory_layout::{memory_layout, reexport_layout};
+///
+/// #[memory_layout]
+/// pub struct Foo {
+///   #[field_offset(0x10)]
+///   a: u32
+/// }
+///
+/// reexport_layout!(pub PlayerInfo = Foo);
+///
+/// assert_eq!(PlayerInfo::OFFSET_A, 0x10);
+/// ```
+#[macro_export]
+macro_rules! reexport_layout {
+  ($(#[$attr:meta])* $vis:vis $alias:ident = $target:ty) => {
+    $(#[$attr])*
+    #[doc = concat!("Alias of [`", stringify!($target), "`], sharing its memory layout.")]
+    $vis type $alias = $target;
+  };
+}
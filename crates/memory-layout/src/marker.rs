@@ -0,0 +1,140 @@
+use core::{marker::PhantomData, mem::MaybeUninit, num::Wrapping, ptr::NonNull};
+
+/// Types for which a value with every byte set to zero is valid.
+///
+/// Structs annotated with `#[memory_layout]` implement it when all of their fields do, which makes
+/// their `zeroed()` constructor available.
+///
+/// # Safety
+///
+/// An all-zero bit pattern must be a valid value of the implementing type.
+pub unsafe trait Zeroable {}
+
+macro_rules! impl_zeroable {
+  ($($ty:ty),*) => {
+    $(unsafe impl Zeroable for $ty {})*
+  };
+}
+
+impl_zeroable!(
+  u8,
+  u16,
+  u32,
+  u64,
+  u128,
+  usize,
+  i8,
+  i16,
+  i32,
+  i64,
+  i128,
+  isize,
+  f32,
+  f64,
+  bool,
+  char,
+  ()
+);
+
+unsafe impl<T: Zeroable, const N: usize> Zeroable for [T; N] {}
+unsafe impl<T: Zeroable> Zeroable for Wrapping<T> {}
+unsafe impl<T> Zeroable for MaybeUninit<T> {}
+unsafe impl<T: ?Sized> Zeroable for PhantomData<T> {}
+unsafe impl<T> Zeroable for *const T {}
+unsafe impl<T> Zeroable for *mut T {}
+unsafe impl<T> Zeroable for Option<NonNull<T>> {}
+unsafe impl<T> Zeroable for Option<&T> {}
+unsafe impl<T> Zeroable for Option<&mut T> {}
+
+/// Types for which every bit pattern is a valid value, so they can be read from arbitrary bytes.
+///
+/// Structs annotated with `#[memory_layout]` implement it when all of their fields do, which makes
+/// their `view()` function available.
+///
+/// `MaybeUninit<T>` implements it as well, since it accepts any bytes, even uninitialized ones. It
+/// isn't `NoUninit` though, so the bytes of a struct containing it can't be viewed or written
+/// through functions that need initialized memory.
+///
+/// # Safety
+///
+/// Every bit pattern of the size of the implementing type must be a valid value of it.
+pub unsafe trait AnyBitPattern: Zeroable {}
+
+macro_rules! impl_any_bit_pattern {
+  ($($ty:ty),*) => {
+    $(unsafe impl AnyBitPattern for $ty {})*
+  };
+}
+
+impl_any_bit_pattern!(
+  u8,
+  u16,
+  u32,
+  u64,
+  u128,
+  usize,
+  i8,
+  i16,
+  i32,
+  i64,
+  i128,
+  isize,
+  f32,
+  f64,
+  ()
+);
+
+unsafe impl<T: AnyBitPattern, const N: usize> AnyBitPattern for [T; N] {}
+unsafe impl<T: AnyBitPattern> AnyBitPattern for Wrapping<T> {}
+// Holding uninitialized bytes is fine to read into, but rules out `NoUninit`.
+unsafe impl<T> AnyBitPattern for MaybeUninit<T> {}
+unsafe impl<T: ?Sized> AnyBitPattern for PhantomData<T> {}
+
+/// Types without uninitialized bytes, so their raw bytes can be viewed and copied.
+///
+/// Structs annotated with `#[memory_layout]` implement it when all of their fields do and their
+/// padding is initialized, which makes `as_bytes()`, `to_bytes()` and `write_to()` available.
+///
+/// # Safety
+///
+/// Every byte of every value of the implementing type must be initialized, so it can't have
+/// padding or fields like `MaybeUninit` or `Option<u16>`.
+pub unsafe trait NoUninit {}
+
+macro_rules! impl_no_uninit {
+  ($($ty:ty),*) => {
+    $(unsafe impl NoUninit for $ty {})*
+  };
+}
+
+impl_no_uninit!(
+  u8,
+  u16,
+  u32,
+  u64,
+  u128,
+  usize,
+  i8,
+  i16,
+  i32,
+  i64,
+  i128,
+  isize,
+  f32,
+  f64,
+  bool,
+  char,
+  ()
+);
+
+unsafe impl<T: NoUninit, const N: usize> NoUninit for [T; N] {}
+unsafe impl<T: NoUninit> NoUninit for Wrapping<T> {}
+unsafe impl<T: ?Sized> NoUninit for PhantomData<T> {}
+unsafe impl<T> NoUninit for *const T {}
+unsafe impl<T> NoUninit for *mut T {}
+unsafe impl<T> NoUninit for NonNull<T> {}
+unsafe impl<T> NoUninit for Option<NonNull<T>> {}
+unsafe impl<T> NoUninit for &T {}
+unsafe impl<T> NoUninit for &mut T {}
+unsafe impl<T> NoUninit for Option<&T> {}
+unsafe impl<T> NoUninit for Option<&mut T> {}
@@ -0,0 +1,430 @@
+//! Reading and writing structs in the memory of another process.
+//!
+//! Structs annotated with `#[memory_layout]` get `read_from` and `write_to` functions, which copy
+//! the whole struct, and `read_<field>_from` and `write_<field>_to` functions for single fields.
+//! They work with any [`RemoteProcess`], the `process` feature provides `Process` for Linux and
+//! Windows.
+//! ```
+//! use std::cell::RefCell;
+//!
+//! use memory_layout::{memory_layout, remote::RemoteProcess};
+//!
+//! /// Memory of a process, faked by a buffer.
+//! struct Fake(RefCell<Vec<u8>>);
+//!
+//! impl RemoteProcess for Fake {
+//!   type Error = ();
+//!
+//!   fn read_bytes(&self, address: usize, buffer: &mut [u8]) -> Result<(), ()> {
+//!     let memory = self.0.borrow();
+//!     buffer.copy_from_slice(memory.get(address..address + buffer.len()).ok_or(())?);
+//!     Ok(())
+//!   }
+//!
+//!   fn write_bytes(&self, address: usize, bytes: &[u8]) -> Result<(), ()> {
+//!     let mut memory = self.0.borrow_mut();
+//!     memory.get_mut(address..address + bytes.len()).ok_or(())?.copy_from_slice(bytes);
+//!     Ok(())
+//!   }
+//! }
+//!
+//! #[memory_layout(0x10)]
+//! pub struct CPed {
+//!   #[field_offset(0x08)]
+//!   health: f32
+//! }
+//!
+//! let process = Fake(RefCell::new(vec![0; 0x100]));
+//! CPed::write_health_to(&process, 0x40, 100.0).unwrap();
+//! assert_eq!(CPed::read_from(&process, 0x40).unwrap().health(), 100.0);
+//! ```
+
+use core::{fmt, hash, marker::PhantomData, mem, mem::MaybeUninit, slice};
+
+use crate::{AnyBitPattern, Field, NoUninit, Zeroable};
+
+/// Memory of a process that can be read and written by address.
+pub trait RemoteProcess {
+  /// Error returned when memory can't be accessed.
+  type Error;
+
+  /// Fills `buffer` with the bytes starting at `address`.
+  fn read_bytes(&self, address: usize, buffer: &mut [u8]) -> Result<(), Self::Error>;
+
+  /// Writes `bytes` starting at `address`.
+  fn write_bytes(&self, address: usize, bytes: &[u8]) -> Result<(), Self::Error>;
+}
+
+impl<P: RemoteProcess + ?Sized> RemoteProcess for &P {
+  type Error = P::Error;
+
+  fn read_bytes(&self, address: usize, buffer: &mut [u8]) -> Result<(), Self::Error> {
+    (**self).read_bytes(address, buffer)
+  }
+
+  fn write_bytes(&self, address: usize, bytes: &[u8]) -> Result<(), Self::Error> {
+    (**self).write_bytes(address, bytes)
+  }
+}
+
+/// Address of a `T` in the memory of another process.
+///
+/// Field handles project it to the fields of a `#[memory_layout]` struct, and pointers stored in
+/// the process can be declared as `RemotePtr` fields to follow them.
+/// ```
+/// # use std::cell::RefCell;
+/// # use memory_layout::{memory_layout, remote::{RemoteProcess, RemotePtr}};
+/// # struct Fake(RefCell<Vec<u8>>);
+/// # impl RemoteProcess for Fake {
+/// #   type Error = ();
+/// #   fn read_bytes(&self, address: usize, buffer: &mut [u8]) -> Result<(), ()> {
+/// #     buffer.copy_from_slice(self.0.borrow().get(address..address + buffer.len()).ok_or(())?);
+/// #     Ok(())
+/// #   }
+/// #   fn write_bytes(&self, address: usize, bytes: &[u8]) -> Result<(), ()> {
+/// #     let mut memory = self.0.borrow_mut();
+/// #     memory.get_mut(address..address + bytes.len()).ok_or(())?.copy_from_slice(bytes);
+/// #     Ok(())
+/// #   }
+/// # }
+/// #[memory_layout(0x10)]
+/// pub struct CPed {
+///   #[field_offset(0x08)]
+///   health: f32
+/// }
+///
+/// #[memory_layout(0x10)]
+/// pub struct CWorld {
+///   #[field_offset(0x08)]
+///   player: RemotePtr<CPed>
+/// }
+///
+/// let process = Fake(RefCell::new(vec![0; 0x100]));
+/// let world = RemotePtr::<CWorld>::new(0x40);
+/// world.field(CWorld::PLAYER).write(&process, &RemotePtr::new(0x80)).unwrap();
+/// let health = world.field(CWorld::PLAYER).read(&process).unwrap().field(CPed::HEALTH);
+/// assert_eq!(health.address(), 0x88);
+/// health.write(&process, &100.0).unwrap();
+/// assert_eq!(health.read(&process), Ok(100.0));
+/// ```
+#[repr(transparent)]
+pub struct RemotePtr<T> {
+  address: usize,
+  marker:  PhantomData<fn() -> T>
+}
+
+impl<T> RemotePtr<T> {
+  /// Pointer to `address`.
+  pub const fn new(address: usize) -> Self {
+    Self {
+      address,
+      marker: PhantomData
+    }
+  }
+
+  /// Pointer to address 0.
+  pub const fn null() -> Self {
+    Self::new(0)
+  }
+
+  /// Whether the address is 0.
+  pub const fn is_null(self) -> bool {
+    self.address == 0
+  }
+
+  /// The address.
+  pub const fn address(self) -> usize {
+    self.address
+  }
+
+  /// Pointer to the same address as another type.
+  pub const fn cast<U>(self) -> RemotePtr<U> {
+    RemotePtr::new(self.address)
+  }
+
+  /// Pointer to the element `count` elements after this one, wrapping around the address space.
+  pub const fn add(self, count: usize) -> Self {
+    Self::new(
+      self
+        .address
+        .wrapping_add(count.wrapping_mul(mem::size_of::<T>()))
+    )
+  }
+
+  /// Pointer to a field of the struct this points to.
+  pub const fn field<U, const OFFSET: usize>(self, _field: Field<T, U, OFFSET>) -> RemotePtr<U> {
+    RemotePtr::new(self.address.wrapping_add(OFFSET))
+  }
+
+  /// Reads the value from `process`. `T` must be valid for any bit pattern.
+  pub fn read<P: RemoteProcess + ?Sized>(self, process: &P) -> Result<T, P::Error>
+  where
+    T: AnyBitPattern
+  {
+    let mut value = MaybeUninit::<T>::zeroed();
+    process.read_bytes(self.address, unsafe {
+      slice::from_raw_parts_mut(value.as_mut_ptr() as *mut u8, mem::size_of::<T>())
+    })?;
+    Ok(unsafe { value.assume_init() })
+  }
+
+  /// Writes `value` to `process`. `T` must not contain uninitialized bytes, such as padding.
+  pub fn write<P: RemoteProcess + ?Sized>(self, process: &P, value: &T) -> Result<(), P::Error>
+  where
+    T: NoUninit
+  {
+    process.write_bytes(self.address, unsafe {
+      slice::from_raw_parts(value as *const T as *const u8, mem::size_of::<T>())
+    })
+  }
+}
+
+impl<T> Clone for RemotePtr<T> {
+  fn clone(&self) -> Self {
+    *self
+  }
+}
+
+impl<T> Copy for RemotePtr<T> {}
+
+impl<T> PartialEq for RemotePtr<T> {
+  fn eq(&self, other: &Self) -> bool {
+    self.address == other.address
+  }
+}
+
+impl<T> Eq for RemotePtr<T> {}
+
+impl<T> hash::Hash for RemotePtr<T> {
+  fn hash<H: hash::Hasher>(&self, state: &mut H) {
+    self.address.hash(state);
+  }
+}
+
+impl<T> fmt::Debug for RemotePtr<T> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "RemotePtr(0x{:X})", self.address)
+  }
+}
+
+unsafe impl<T> Zeroable for RemotePtr<T> {}
+unsafe impl<T> AnyBitPattern for RemotePtr<T> {}
+unsafe impl<T> NoUninit for RemotePtr<T> {}
+
+#[cfg(all(feature = "process", target_os = "linux"))]
+pub use linux::Process;
+#[cfg(all(feature = "process", windows))]
+pub use windows::Process;
+
+#[cfg(all(feature = "process", target_os = "linux"))]
+mod linux {
+  use std::{ffi::c_void, io};
+
+  use super::RemoteProcess;
+
+  #[repr(C)]
+  struct IoVec {
+    base: *mut c_void,
+    len:  usize
+  }
+
+  extern "C" {
+    fn getpid() -> i32;
+    fn process_vm_readv(
+      pid: i32,
+      local: *const IoVec,
+      local_count: usize,
+      remote: *const IoVec,
+      remote_count: usize,
+      flags: usize
+    ) -> isize;
+    fn process_vm_writev(
+      pid: i32,
+      local: *const IoVec,
+      local_count: usize,
+      remote: *const IoVec,
+      remote_count: usize,
+      flags: usize
+    ) -> isize;
+  }
+
+  /// A process accessed through `process_vm_readv` and `process_vm_writev`, which requires
+  /// permission to ptrace it.
+  #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+  pub struct Process {
+    pid: i32
+  }
+
+  impl Process {
+    /// The process with the id `pid`.
+    pub const fn new(pid: i32) -> Self {
+      Self { pid }
+    }
+
+    /// The calling process.
+    pub fn current() -> Self {
+      Self::new(unsafe { getpid() })
+    }
+
+    /// Id of the process.
+    pub const fn pid(&self) -> i32 {
+      self.pid
+    }
+
+    fn check(transferred: isize, expected: usize) -> io::Result<()> {
+      if transferred < 0 {
+        Err(io::Error::last_os_error())
+      } else if transferred as usize != expected {
+        Err(io::Error::new(
+          io::ErrorKind::UnexpectedEof,
+          "partial transfer of process memory"
+        ))
+      } else {
+        Ok(())
+      }
+    }
+  }
+
+  impl RemoteProcess for Process {
+    type Error = io::Error;
+
+    fn read_bytes(&self, address: usize, buffer: &mut [u8]) -> io::Result<()> {
+      let local = IoVec {
+        base: buffer.as_mut_ptr().cast(),
+        len:  buffer.len()
+      };
+      let remote = IoVec {
+        base: address as *mut c_void,
+        len:  buffer.len()
+      };
+      Self::check(
+        unsafe { process_vm_readv(self.pid, &local, 1, &remote, 1, 0) },
+        buffer.len()
+      )
+    }
+
+    fn write_bytes(&self, address: usize, bytes: &[u8]) -> io::Result<()> {
+      let local = IoVec {
+        base: bytes.as_ptr() as *mut c_void,
+        len:  bytes.len()
+      };
+      let remote = IoVec {
+        base: address as *mut c_void,
+        len:  bytes.len()
+      };
+      Self::check(
+        unsafe { process_vm_writev(self.pid, &local, 1, &remote, 1, 0) },
+        bytes.len()
+      )
+    }
+  }
+}
+
+#[cfg(all(feature = "process", windows))]
+mod windows {
+  use std::{ffi::c_void, io};
+
+  use super::RemoteProcess;
+
+  const PROCESS_VM_OPERATION: u32 = 0x0008;
+  const PROCESS_VM_READ: u32 = 0x0010;
+  const PROCESS_VM_WRITE: u32 = 0x0020;
+
+  #[link(name = "kernel32")]
+  extern "system" {
+    fn OpenProcess(access: u32, inherit: i32, pid: u32) -> *mut c_void;
+    fn CloseHandle(handle: *mut c_void) -> i32;
+    fn ReadProcessMemory(
+      process: *mut c_void,
+      address: *const c_void,
+      buffer: *mut c_void,
+      size: usize,
+      read: *mut usize
+    ) -> i32;
+    fn WriteProcessMemory(
+      process: *mut c_void,
+      address: *mut c_void,
+      buffer: *const c_void,
+      size: usize,
+      written: *mut usize
+    ) -> i32;
+  }
+
+  /// A process accessed through `ReadProcessMemory` and `WriteProcessMemory`, the handle is closed
+  /// when it's dropped.
+  #[derive(Debug)]
+  pub struct Process {
+    handle: *mut c_void
+  }
+
+  impl Process {
+    /// Opens the process with the id `pid` for reading and writing its memory.
+    pub fn open(pid: u32) -> io::Result<Self> {
+      let handle = unsafe {
+        OpenProcess(
+          PROCESS_VM_OPERATION | PROCESS_VM_READ | PROCESS_VM_WRITE,
+          0,
+          pid
+        )
+      };
+      if handle.is_null() {
+        return Err(io::Error::last_os_error());
+      }
+      Ok(Self { handle })
+    }
+
+    fn check(success: i32, transferred: usize, expected: usize) -> io::Result<()> {
+      if success == 0 {
+        Err(io::Error::last_os_error())
+      } else if transferred != expected {
+        Err(io::Error::new(
+          io::ErrorKind::UnexpectedEof,
+          "partial transfer of process memory"
+        ))
+      } else {
+        Ok(())
+      }
+    }
+  }
+
+  impl Drop for Process {
+    fn drop(&mut self) {
+      unsafe { CloseHandle(self.handle) };
+    }
+  }
+
+  impl RemoteProcess for Process {
+    type Error = io::Error;
+
+    fn read_bytes(&self, address: usize, buffer: &mut [u8]) -> io::Result<()> {
+      let mut read = 0;
+      let success = unsafe {
+        ReadProcessMemory(
+          self.handle,
+          address as *const c_void,
+          buffer.as_mut_ptr().cast(),
+          buffer.len(),
+          &mut read
+        )
+      };
+      Self::check(success, read, buffer.len())
+    }
+
+    fn write_bytes(&self, address: usize, bytes: &[u8]) -> io::Result<()> {
+      let mut written = 0;
+      let success = unsafe {
+        WriteProcessMemory(
+          self.handle,
+          address as *mut c_void,
+          bytes.as_ptr().cast(),
+          bytes.len(),
+          &mut written
+        )
+      };
+      Self::check(success, written, bytes.len())
+    }
+  }
+
+  unsafe impl Send for Process {}
+  unsafe impl Sync for Process {}
+}
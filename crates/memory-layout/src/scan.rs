@@ -0,0 +1,200 @@
+//! Signature scanning, finding offsets and addresses in a module's code by a byte pattern.
+//!
+//! Fields declared as `#[field_offset(runtime)]` can be given a `#[signature(..)]` of an
+//! instruction accessing them, the generated `scan_offsets` then finds every signature in the
+//! module and sets the offsets.
+//! ```
+//! use memory_layout::memory_layout;
+//!
+//! #[memory_layout(0x200)]
+//! pub struct CPed {
+//!   // movss xmm0, [rcx + health]
+//!   #[field_offset(runtime)]
+//!   #[signature("F3 0F 10 81 ?? ?? ?? ?? C3")]
+//!   health: f32
+//! }
+//!
+//! let module = [0xCC, 0xF3, 0x0F, 0x10, 0x81, 0x48, 0x01, 0x00, 0x00, 0xC3];
+//! CPed::scan_offsets(&module).unwrap();
+//! assert_eq!(CPed::health_offset(), Some(0x148));
+//! ```
+//!
+//! Addresses of globals are found with a [`Signature`] directly, `rip_relative` resolves the
+//! operand relative to the end of the operand:
+//! ```
+//! use memory_layout::scan::Signature;
+//!
+//! // mov rax, [rip + 0x10]
+//! const PLAYERS: Signature = Signature::new("48 8B 05 ?? ?? ?? ??").rip_relative();
+//!
+//! let module = [0x90, 0x48, 0x8B, 0x05, 0x10, 0x00, 0x00, 0x00];
+//! assert_eq!(PLAYERS.resolve(&module), Ok(0x18));
+//! ```
+//!
+//! The module is passed as the bytes it occupies in memory, results are relative to its start.
+
+use core::fmt;
+
+use crate::LayoutError;
+
+/// Longest supported pattern, in bytes.
+const MAX_PATTERN_LEN: usize = 128;
+
+/// Error returned when a signature can't be resolved.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ScanError {
+  /// The pattern isn't a sequence of hex bytes and `??` wildcards, or has no operand to read.
+  InvalidPattern {
+    /// The pattern.
+    pattern: &'static str
+  },
+  /// The pattern doesn't occur in the module.
+  NotFound {
+    /// The pattern.
+    pattern: &'static str
+  },
+  /// An offset that was found doesn't fit the struct.
+  Layout(LayoutError)
+}
+
+impl fmt::Display for ScanError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      ScanError::InvalidPattern { pattern } => write!(f, "invalid pattern \"{}\"", pattern),
+      ScanError::NotFound { pattern } => write!(f, "pattern \"{}\" not found", pattern),
+      ScanError::Layout(err) => fmt::Display::fmt(err, f)
+    }
+  }
+}
+
+impl From<LayoutError> for ScanError {
+  fn from(err: LayoutError) -> Self {
+    ScanError::Layout(err)
+  }
+}
+
+/// A byte pattern like `"48 8B 05 ?? ?? ?? ??"`, with `??` matching any byte, and how to read the
+/// value it locates.
+///
+/// The value is read from the operand, the run of wildcards starting at `operand`, which defaults
+/// to the first wildcard. Operands of 1, 2, 4 or 8 bytes are read as little-endian integers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Signature {
+  pattern:      &'static str,
+  operand:      Option<usize>,
+  rip_relative: bool
+}
+
+impl Signature {
+  /// Creates a signature reading the operand at the first wildcard.
+  pub const fn new(pattern: &'static str) -> Self {
+    Self {
+      pattern,
+      operand: None,
+      rip_relative: false
+    }
+  }
+
+  /// Reads the operand starting at byte `position` of the pattern.
+  pub const fn operand(self, position: usize) -> Self {
+    Self {
+      operand: Some(position),
+      ..self
+    }
+  }
+
+  /// Resolves the operand as a 4 byte displacement relative to the end of the operand, like the
+  /// target of `lea rax, [rip + x]` or `call x`. This is the end of the instruction unless an
+  /// immediate follows the displacement, as in `cmp dword ptr [rip + x], 1`, where the result is
+  /// off by the size of the immediate.
+  pub const fn rip_relative(self) -> Self {
+    Self {
+      rip_relative: true,
+      ..self
+    }
+  }
+
+  /// The pattern.
+  pub const fn pattern(&self) -> &'static str {
+    self.pattern
+  }
+
+  fn parse(&self) -> Result<([Option<u8>; MAX_PATTERN_LEN], usize), ScanError> {
+    let invalid = ScanError::InvalidPattern {
+      pattern: self.pattern
+    };
+    let mut bytes = [None; MAX_PATTERN_LEN];
+    let mut len = 0;
+    for token in self.pattern.split_ascii_whitespace() {
+      let byte = match token {
+        "?" | "??" => None,
+        _ if token.len() == 2 && token.bytes().all(|byte| byte.is_ascii_hexdigit()) => {
+          Some(u8::from_str_radix(token, 16).map_err(|_| invalid)?)
+        }
+        _ => return Err(invalid)
+      };
+      *bytes.get_mut(len).ok_or(invalid)? = byte;
+      len += 1;
+    }
+
+    if len == 0 {
+      return Err(invalid);
+    }
+    Ok((bytes, len))
+  }
+
+  /// Position of the first match of the pattern in `module`.
+  pub fn find(&self, module: &[u8]) -> Result<usize, ScanError> {
+    let (bytes, len) = self.parse()?;
+    let pattern = &bytes[..len];
+    module
+      .windows(len)
+      .position(|window| {
+        window
+          .iter()
+          .zip(pattern)
+          .all(|(byte, expected)| expected.map_or(true, |expected| *byte == expected))
+      })
+      .ok_or(ScanError::NotFound {
+        pattern: self.pattern
+      })
+  }
+
+  /// Finds the pattern in `module` and reads its operand. For `rip_relative` signatures, the result
+  /// is the offset into the module the operand points to.
+  pub fn resolve(&self, module: &[u8]) -> Result<usize, ScanError> {
+    let invalid = ScanError::InvalidPattern {
+      pattern: self.pattern
+    };
+    let (bytes, len) = self.parse()?;
+    let operand = match self.operand {
+      Some(operand) if operand < len => operand,
+      Some(_) => return Err(invalid),
+      None => {
+        bytes[..len]
+          .iter()
+          .position(Option::is_none)
+          .ok_or(invalid)?
+      }
+    };
+    let width = bytes[operand..len]
+      .iter()
+      .take_while(|byte| byte.is_none())
+      .count();
+    if !matches!(width, 1 | 2 | 4 | 8) || (self.rip_relative && width != 4) {
+      return Err(invalid);
+    }
+
+    let start = self.find(module)? + operand;
+    let value = module[start..start + width]
+      .iter()
+      .rev()
+      .fold(0u64, |value, byte| value << 8 | u64::from(*byte));
+    if self.rip_relative {
+      let displacement = value as u32 as i32 as isize;
+      Ok((start + width).wrapping_add(displacement as usize))
+    } else {
+      Ok(value as usize)
+    }
+  }
+}
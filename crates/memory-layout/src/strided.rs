@@ -0,0 +1,120 @@
+use core::{fmt, marker::PhantomData, mem, ptr};
+
+use crate::{AnyBitPattern, NoUninit, Zeroable};
+
+/// `N` elements of type `T` placed `STRIDE` bytes apart, for arrays with padding after every
+/// element.
+///
+/// Fields annotated with `#[stride(0x30)]` have this type instead of `[T; N]`. The elements are
+/// stored as bytes with an alignment of 1, so they are read and written without requiring
+/// alignment.
+/// ```rust
+/// use memory_layout::{memory_layout, Strided};
+///
+/// #[memory_layout(0x80)]
+/// pub struct Inventory {
+///   #[field_offset(0x10)]
+///   #[stride(0x0C)]
+///   items: [u32; 4]
+/// }
+///
+/// let mut inventory = Inventory::zeroed();
+/// inventory.items_mut().set(2, 7);
+/// assert_eq!(inventory.items().get(2), Some(7));
+/// assert_eq!(inventory.items().as_ptr(3) as usize - inventory.items().as_ptr(2) as usize, 0x0C);
+/// ```
+#[repr(transparent)]
+pub struct Strided<T, const STRIDE: usize, const N: usize> {
+  bytes:  [[u8; STRIDE]; N],
+  marker: PhantomData<T>
+}
+
+impl<T, const STRIDE: usize, const N: usize> Strided<T, STRIDE, N> {
+  const FITS: () = assert!(
+    mem::size_of::<T>() <= STRIDE,
+    "The element type is larger than the stride."
+  );
+
+  /// Number of elements.
+  pub const fn len(&self) -> usize {
+    N
+  }
+
+  /// Whether there are no elements.
+  pub const fn is_empty(&self) -> bool {
+    N == 0
+  }
+
+  /// Pointer to the element at `index`, which may be unaligned.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `index` is out of bounds.
+  pub fn as_ptr(&self, index: usize) -> *const T {
+    #[allow(clippy::let_unit_value)]
+    let () = Self::FITS;
+    self.bytes[index].as_ptr().cast()
+  }
+
+  /// Mutable pointer to the element at `index`, which may be unaligned.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `index` is out of bounds.
+  pub fn as_mut_ptr(&mut self, index: usize) -> *mut T {
+    #[allow(clippy::let_unit_value)]
+    let () = Self::FITS;
+    self.bytes[index].as_mut_ptr().cast()
+  }
+
+  /// Reads the element at `index`, returns `None` if it's out of bounds.
+  pub fn get(&self, index: usize) -> Option<T>
+  where
+    T: Copy
+  {
+    (index < N).then(|| unsafe { ptr::read_unaligned(self.as_ptr(index)) })
+  }
+
+  /// Writes the element at `index`, leaving the padding after it untouched.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `index` is out of bounds.
+  pub fn set(&mut self, index: usize, value: T)
+  where
+    T: Copy
+  {
+    unsafe { ptr::write_unaligned(self.as_mut_ptr(index), value) }
+  }
+
+  /// Iterates over copies of the elements.
+  pub fn iter(&self) -> impl Iterator<Item = T> + '_
+  where
+    T: Copy
+  {
+    (0..N).map(move |index| unsafe { ptr::read_unaligned(self.as_ptr(index)) })
+  }
+}
+
+impl<T: Copy, const STRIDE: usize, const N: usize> Clone for Strided<T, STRIDE, N> {
+  fn clone(&self) -> Self {
+    *self
+  }
+}
+
+impl<T: Copy, const STRIDE: usize, const N: usize> Copy for Strided<T, STRIDE, N> {}
+
+impl<T: Copy + fmt::Debug, const STRIDE: usize, const N: usize> fmt::Debug
+  for Strided<T, STRIDE, N>
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_list().entries(self.iter()).finish()
+  }
+}
+
+unsafe impl<T: Zeroable, const STRIDE: usize, const N: usize> Zeroable for Strided<T, STRIDE, N> {}
+unsafe impl<T: AnyBitPattern, const STRIDE: usize, const N: usize> AnyBitPattern
+  for Strided<T, STRIDE, N>
+{
+}
+unsafe impl<T: NoUninit, const STRIDE: usize, const N: usize> NoUninit for Strided<T, STRIDE, N> {}
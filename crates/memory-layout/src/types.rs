@@ -0,0 +1,109 @@
+//! Integers stored in a fixed byte order, converting on access.
+//!
+//! The wrappers are byte arrays with an alignment of 1, so they can be used as field types of
+//! packed structs. Unlike `#[endian(..)]`, the byte order is part of the field's type.
+//! ```
+//! use memory_layout::{memory_layout, types::U32Be};
+//!
+//! #[memory_layout(0x08)]
+//! pub struct Header {
+//!   pub magic: U32Be
+//! }
+//!
+//! let header = Header::from_bytes([0x7F, 0x45, 0x4C, 0x46, 0, 0, 0, 0]);
+//! assert_eq!(header.magic().get(), 0x7F45_4C46);
+//! ```
+
+use core::fmt;
+
+use crate::{AnyBitPattern, NoUninit, Zeroable};
+
+macro_rules! endian_types {
+  ($($name:ident($int:ty, $to_bytes:ident, $from_bytes:ident, $order:literal);)*) => {
+    $(
+      #[doc = concat!("`", stringify!($int), "` stored in ", $order, " byte order.")]
+      #[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+      #[repr(transparent)]
+      pub struct $name([u8; core::mem::size_of::<$int>()]);
+
+      impl $name {
+        /// Stores `value` in this byte order.
+        #[inline]
+        pub const fn new(value: $int) -> Self {
+          Self(value.$to_bytes())
+        }
+
+        /// Reads the value in the native byte order.
+        #[inline]
+        pub const fn get(self) -> $int {
+          <$int>::$from_bytes(self.0)
+        }
+
+        /// Replaces the stored value.
+        #[inline]
+        pub fn set(&mut self, value: $int) {
+          *self = Self::new(value);
+        }
+
+        /// Raw bytes in this byte order.
+        #[inline]
+        pub const fn to_bytes(self) -> [u8; core::mem::size_of::<$int>()] {
+          self.0
+        }
+
+        /// Wraps bytes already in this byte order.
+        #[inline]
+        pub const fn from_bytes(bytes: [u8; core::mem::size_of::<$int>()]) -> Self {
+          Self(bytes)
+        }
+      }
+
+      impl From<$int> for $name {
+        fn from(value: $int) -> Self {
+          Self::new(value)
+        }
+      }
+
+      impl From<$name> for $int {
+        fn from(value: $name) -> Self {
+          value.get()
+        }
+      }
+
+      impl fmt::Debug for $name {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+          fmt::Debug::fmt(&self.get(), f)
+        }
+      }
+
+      impl fmt::Display for $name {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+          fmt::Display::fmt(&self.get(), f)
+        }
+      }
+
+      unsafe impl Zeroable for $name {}
+      unsafe impl AnyBitPattern for $name {}
+      unsafe impl NoUninit for $name {}
+    )*
+  };
+}
+
+endian_types! {
+  U16Be(u16, to_be_bytes, from_be_bytes, "big-endian");
+  U32Be(u32, to_be_bytes, from_be_bytes, "big-endian");
+  U64Be(u64, to_be_bytes, from_be_bytes, "big-endian");
+  U128Be(u128, to_be_bytes, from_be_bytes, "big-endian");
+  I16Be(i16, to_be_bytes, from_be_bytes, "big-endian");
+  I32Be(i32, to_be_bytes, from_be_bytes, "big-endian");
+  I64Be(i64, to_be_bytes, from_be_bytes, "big-endian");
+  I128Be(i128, to_be_bytes, from_be_bytes, "big-endian");
+  U16Le(u16, to_le_bytes, from_le_bytes, "little-endian");
+  U32Le(u32, to_le_bytes, from_le_bytes, "little-endian");
+  U64Le(u64, to_le_bytes, from_le_bytes, "little-endian");
+  U128Le(u128, to_le_bytes, from_le_bytes, "little-endian");
+  I16Le(i16, to_le_bytes, from_le_bytes, "little-endian");
+  I32Le(i32, to_le_bytes, from_le_bytes, "little-endian");
+  I64Le(i64, to_le_bytes, from_le_bytes, "little-endian");
+  I128Le(i128, to_le_bytes, from_le_bytes, "little-endian");
+}
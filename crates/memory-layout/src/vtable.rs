@@ -0,0 +1,41 @@
+use core::mem;
+
+/// Reads entry `index` of the vtable `object` points to with its first field, as an `F`.
+///
+/// Useful to call a virtual function without describing the vtable with `#[vtable_layout]`:
+/// ```rust
+/// use memory_layout::virtual_function;
+///
+/// #[repr(C)]
+/// struct Object {
+///   vtable: *const usize
+/// }
+///
+/// fn double(object: *const Object, value: u32) -> u32 {
+///   value * 2
+/// }
+///
+/// let table = [0, double as fn(*const Object, u32) -> u32 as usize];
+/// let object = Object { vtable: table.as_ptr() };
+/// let double = unsafe { virtual_function::<fn(*const Object, u32) -> u32, _>(&object, 1) };
+/// assert_eq!(double(&object, 21), 42);
+/// ```
+///
+/// # Safety
+///
+/// `object` must start with a valid pointer to a table with at least `index + 1` entries, and the
+/// entry must be a valid value of `F`.
+///
+/// # Panics
+///
+/// Panics if `F` isn't pointer sized.
+#[inline]
+pub unsafe fn virtual_function<F: Copy, T>(object: *const T, index: usize) -> F {
+  assert_eq!(
+    mem::size_of::<F>(),
+    mem::size_of::<usize>(),
+    "vtable entries are pointer sized"
+  );
+  let table = (object as *const *const F).read_unaligned();
+  table.add(index).read()
+}